@@ -0,0 +1,30 @@
+# Messages of the lillinput binary, in English.
+
+settings-error = Unable to process settings: { $error }. Attempting to proceed with defaults ...
+initialization-error = Unable to initialize: { $error }
+devices-found = Found { $count } gesture-capable devices
+devices-found-seat = Found { $count } gesture-capable devices on seat { $seat }
+devices-error = Unable to find devices: { $error }
+unknown-seat-section = Ignoring the section of seat { $seat }, as it is not an additional seat
+seat-error = Unable to use seat { $seat }: { $error }
+no-devices = No gesture-capable devices found on any seat, waiting for one to be connected on seat { $seat } ...
+dbus-name-claimed = Claimed D-Bus name { $name }
+dbus-name-taken = D-Bus name { $name } is owned by another instance, exiting ...
+dbus-name-error = Unable to claim D-Bus name { $name }: { $error }
+dbus-name-lost = D-Bus name { $name } claimed by another instance, exiting ...
+dbus-disabled = Ignoring the D-Bus name policy, as the dbus feature is not enabled
+invalid-action = Invalid action for { $event }: { $action }: { $error }
+actions-valid = All the actions were validated successfully
+listening = Listening for events ...
+reloading = Reloading the configuration ...
+reloading-change = Setting changed: { $change }
+reloading-unchanged = The settings did not change
+reloading-devices = The devices changed, recreating the processor ...
+reloading-devices-error = Unable to recreate the processor, keeping the previous devices: { $error }
+main-loop-error = Unhandled error during the main loop: { $error }
+latency-summary = Latency of { $count } gestures: { $mean }ms on average, { $max }ms at most
+unexpected-failure = Unexpected failure, please include the following information when reporting it: { $state }
+state-unavailable = controller state unavailable
+sway-import-error = Unable to read the sway configuration { $path }: { $error }
+threshold-suggestion = Suggested threshold for { $event }: { $suggested } (currently { $current }, from { $accepted } accepted and { $rejected } discarded swipes)
+threshold-suggestions-none = Not enough swipes performed for suggesting thresholds
@@ -0,0 +1,30 @@
+# Messages of the lillinput binary, in Spanish.
+
+settings-error = No se pudo procesar la configuración: { $error }. Intentando continuar con los valores por defecto ...
+initialization-error = No se pudo inicializar: { $error }
+devices-found = Encontrados { $count } dispositivos con soporte de gestos
+devices-found-seat = Encontrados { $count } dispositivos con soporte de gestos en el asiento { $seat }
+devices-error = No se pudieron encontrar dispositivos: { $error }
+unknown-seat-section = Ignorando la sección del asiento { $seat }, ya que no es un asiento adicional
+seat-error = No se pudo usar el asiento { $seat }: { $error }
+no-devices = No se encontraron dispositivos con soporte de gestos en ningún asiento, esperando a que se conecte uno en el asiento { $seat } ...
+dbus-name-claimed = Reclamado el nombre de D-Bus { $name }
+dbus-name-taken = El nombre de D-Bus { $name } pertenece a otra instancia, saliendo ...
+dbus-name-error = No se pudo reclamar el nombre de D-Bus { $name }: { $error }
+dbus-name-lost = El nombre de D-Bus { $name } ha sido reclamado por otra instancia, saliendo ...
+dbus-disabled = Ignorando la política del nombre de D-Bus, ya que la característica dbus no está habilitada
+invalid-action = Acción no válida para { $event }: { $action }: { $error }
+actions-valid = Todas las acciones se validaron correctamente
+listening = Escuchando eventos ...
+reloading = Recargando la configuración ...
+reloading-change = Opción cambiada: { $change }
+reloading-unchanged = La configuración no cambió
+reloading-devices = Los dispositivos cambiaron, recreando el procesador ...
+reloading-devices-error = No se pudo recrear el procesador, manteniendo los dispositivos anteriores: { $error }
+main-loop-error = Error no gestionado en el bucle principal: { $error }
+latency-summary = Latencia de { $count } gestos: { $mean } ms de media, { $max } ms como máximo
+unexpected-failure = Fallo inesperado, por favor incluya la siguiente información al reportarlo: { $state }
+state-unavailable = estado del controlador no disponible
+sway-import-error = No se pudo leer la configuración de sway { $path }: { $error }
+threshold-suggestion = Umbral sugerido para { $event }: { $suggested } (actualmente { $current }, a partir de { $accepted } gestos aceptados y { $rejected } descartados)
+threshold-suggestions-none = No se realizaron suficientes gestos para sugerir umbrales
@@ -0,0 +1,125 @@
+//! Ownership of the well-known D-Bus name of the `lillinput` binary.
+
+use std::env;
+
+use strum::{Display, EnumString, EnumVariantNames};
+
+#[cfg(feature = "dbus")]
+use crate::i18n::tr;
+#[cfg(feature = "dbus")]
+use log::{info, warn};
+#[cfg(feature = "dbus")]
+use std::process;
+#[cfg(feature = "dbus")]
+use std::thread;
+#[cfg(feature = "dbus")]
+use zbus::blocking::fdo::DBusProxy;
+#[cfg(feature = "dbus")]
+use zbus::blocking::Connection;
+#[cfg(feature = "dbus")]
+use zbus::fdo::{RequestNameFlags, RequestNameReply};
+
+/// Well-known name claimed on the D-Bus session bus.
+pub const BUS_NAME: &str = "org.lillinput";
+
+/// Possible policies for claiming the well-known D-Bus name.
+///
+/// When claimed, the name is always owned allowing replacement, so a new
+/// instance with the [`NamePolicy::Replace`] policy can take it over. The
+/// instance that loses the name exits.
+#[derive(Copy, Clone, Debug, Default, Display, EnumString, EnumVariantNames, Eq, PartialEq)]
+#[strum(serialize_all = "kebab_case")]
+pub enum NamePolicy {
+    /// Do not claim the name.
+    #[default]
+    Disabled,
+    /// Claim the name, exiting if it is owned by another instance.
+    Exclusive,
+    /// Claim the name, replacing the instance that owns it.
+    Replace,
+}
+
+/// Claim the well-known D-Bus name on the session bus.
+///
+/// Along with the name, a thread is spawned that keeps the connection to the
+/// bus, and exits the process if the name is lost to another instance.
+///
+/// # Arguments
+///
+/// * `policy` - the policy for claiming the name.
+///
+/// # Errors
+///
+/// Returns `Err` if the connection to the session bus failed, or
+/// [`zbus::Error::NameTaken`] if the name is owned by another instance that
+/// could not be replaced.
+#[cfg(feature = "dbus")]
+pub fn claim_bus_name(policy: NamePolicy) -> Result<(), zbus::Error> {
+    if policy == NamePolicy::Disabled {
+        return Ok(());
+    }
+
+    // Subscribe to the signals before requesting the name, in order to avoid
+    // missing them.
+    let connection = Connection::session()?;
+    let proxy = DBusProxy::new(&connection)?;
+    let name_lost = proxy.receive_name_lost()?;
+
+    let mut flags = RequestNameFlags::AllowReplacement | RequestNameFlags::DoNotQueue;
+    if policy == NamePolicy::Replace {
+        flags |= RequestNameFlags::ReplaceExisting;
+    }
+    match connection.request_name_with_flags(BUS_NAME, flags)? {
+        RequestNameReply::PrimaryOwner | RequestNameReply::AlreadyOwner => (),
+        RequestNameReply::InQueue | RequestNameReply::Exists => return Err(zbus::Error::NameTaken),
+    }
+
+    thread::spawn(move || {
+        // Keep the connection open while the name is owned.
+        let _connection = connection;
+        for signal in name_lost {
+            match signal.args() {
+                Ok(args) if args.name() == BUS_NAME => {
+                    info!("{}", tr!("dbus-name-lost", name = BUS_NAME));
+                    process::exit(0);
+                }
+                Ok(_) => (),
+                Err(e) => warn!("Unable to parse D-Bus signal: {e}"),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Return the D-Bus service file for activating `lillinput` on demand.
+///
+/// The activated instance uses the current executable, claiming the name with
+/// the [`NamePolicy::Exclusive`] policy.
+#[must_use]
+pub fn dbus_service_file() -> String {
+    let executable =
+        env::current_exe().map_or_else(|_| String::from("lillinput"), |x| x.display().to_string());
+
+    format!(
+        "[D-BUS Service]\nName={BUS_NAME}\nExec={executable} --dbus-name-policy {}\n",
+        NamePolicy::Exclusive
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::{dbus_service_file, BUS_NAME};
+
+    #[test]
+    /// Test the generation of the D-Bus service file.
+    fn test_dbus_service_file() {
+        let service_file = dbus_service_file();
+        let lines: Vec<&str> = service_file.lines().collect();
+
+        assert_eq!(lines[0], "[D-BUS Service]");
+        assert_eq!(lines[1], format!("Name={BUS_NAME}"));
+        assert!(lines[2].starts_with("Exec="));
+        assert!(lines[2].ends_with(" --dbus-name-policy exclusive"));
+    }
+}
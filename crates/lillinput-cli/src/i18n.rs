@@ -0,0 +1,172 @@
+//! Localization of the user-facing messages of the `lillinput` binary.
+//!
+//! The messages are defined as [Fluent] resources, bundled in the binary. The
+//! locale is detected from the environment (`LC_ALL`, `LC_MESSAGES` and `LANG`
+//! on Unix), falling back to English if no translation is available for it.
+//! The messages are retrieved via the [`tr!`] macro.
+//!
+//! [Fluent]: https://projectfluent.org
+
+use std::sync::OnceLock;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use log::warn;
+use unic_langid::LanguageIdentifier;
+
+/// Locale used if no translation is available for the detected one.
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+/// Bundled translations, along with their locale.
+const TRANSLATIONS: [(&str, &str); 2] = [
+    (DEFAULT_LOCALE, include_str!("../locales/en-US.ftl")),
+    ("es", include_str!("../locales/es.ftl")),
+];
+
+/// Bundles for the detected locale and the default one.
+static BUNDLES: OnceLock<Vec<FluentBundle<FluentResource>>> = OnceLock::new();
+
+/// Return the translated message for an identifier.
+///
+/// Prefer the [`tr!`] macro, which builds the arguments.
+///
+/// # Arguments
+///
+/// * `id` - the identifier of the message.
+/// * `args` - the arguments of the message, if any.
+///
+/// # Returns
+///
+/// The message in the detected locale if translated, in the default locale
+/// otherwise, or the identifier itself if the message does not exist.
+pub fn translate(id: &str, args: Option<&FluentArgs>) -> String {
+    let bundles = BUNDLES.get_or_init(|| {
+        let locale = sys_locale::get_locale().unwrap_or_default();
+        let mut locales = vec![negotiate_locale(&locale)];
+        if locales[0] != DEFAULT_LOCALE {
+            locales.push(DEFAULT_LOCALE);
+        }
+        locales.into_iter().filter_map(create_bundle).collect()
+    });
+
+    for bundle in bundles {
+        if let Some(pattern) = bundle.get_message(id).and_then(|x| x.value()) {
+            let mut errors = vec![];
+            let message = bundle.format_pattern(pattern, args, &mut errors);
+            if !errors.is_empty() {
+                warn!("Unable to format message {id}: {errors:?}");
+            }
+            return message.into_owned();
+        }
+    }
+
+    id.to_string()
+}
+
+/// Return the bundled locale that best matches a locale.
+///
+/// The locales are matched by their language, ignoring the region and other
+/// variants (for example, `es-AR` uses the `es` translation).
+///
+/// # Arguments
+///
+/// * `locale` - the locale, as a language tag (`es-ES`) or in POSIX form
+///   (`es_ES.UTF-8`).
+fn negotiate_locale(locale: &str) -> &'static str {
+    let tag = locale.split(['.', '@']).next().unwrap_or_default();
+    let Ok(langid) = tag.replace('_', "-").parse::<LanguageIdentifier>() else {
+        return DEFAULT_LOCALE;
+    };
+
+    TRANSLATIONS
+        .iter()
+        .map(|(bundled, _)| *bundled)
+        .find(|bundled| {
+            bundled
+                .parse::<LanguageIdentifier>()
+                .is_ok_and(|x| x.language == langid.language)
+        })
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+/// Create the bundle for a bundled locale.
+///
+/// # Arguments
+///
+/// * `locale` - the bundled locale.
+fn create_bundle(locale: &str) -> Option<FluentBundle<FluentResource>> {
+    let (_, source) = TRANSLATIONS.iter().find(|(x, _)| *x == locale)?;
+    let langid = locale.parse::<LanguageIdentifier>().ok()?;
+    let resource = FluentResource::try_new((*source).to_string())
+        .map_err(|(_, e)| warn!("Unable to parse the {locale} translation: {e:?}"))
+        .ok()?;
+
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // Avoid the Unicode isolation marks around the arguments, as the messages
+    // are written to terminals.
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(resource)
+        .map_err(|e| warn!("Unable to load the {locale} translation: {e:?}"))
+        .ok()?;
+
+    Some(bundle)
+}
+
+/// Return the translated message for an identifier, with its arguments.
+///
+/// The arguments are specified as `name = value` pairs, with the values
+/// converted via [`fluent_bundle::FluentValue::from`]:
+///
+/// ```ignore
+/// tr!("seat-error", seat = seat.as_str(), error = e.to_string())
+/// ```
+macro_rules! tr {
+    ($id:expr) => {
+        $crate::i18n::translate($id, None)
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::translate($id, Some(&args))
+    }};
+}
+
+pub(crate) use tr;
+
+#[cfg(test)]
+mod test {
+    use super::{create_bundle, negotiate_locale, TRANSLATIONS};
+
+    #[test]
+    /// Test matching the detected locale with the bundled ones.
+    fn test_negotiate_locale() {
+        assert_eq!(negotiate_locale("es_ES.UTF-8"), "es");
+        assert_eq!(negotiate_locale("es-AR"), "es");
+        assert_eq!(negotiate_locale("en_GB"), "en-US");
+        assert_eq!(negotiate_locale("fr_FR.UTF-8"), "en-US");
+        assert_eq!(negotiate_locale("C"), "en-US");
+        assert_eq!(negotiate_locale(""), "en-US");
+    }
+
+    #[test]
+    /// Test that the translations are valid and define the same messages.
+    fn test_translations() {
+        let bundles: Vec<_> = TRANSLATIONS
+            .iter()
+            .map(|(locale, _)| create_bundle(locale).unwrap())
+            .collect();
+        let ids: Vec<&str> = TRANSLATIONS[0]
+            .1
+            .lines()
+            .filter_map(|x| x.split_once(" = ").map(|(id, _)| id))
+            .collect();
+
+        assert!(!ids.is_empty());
+        for bundle in &bundles {
+            for id in &ids {
+                assert!(bundle.has_message(id), "missing message {id}");
+            }
+        }
+    }
+}
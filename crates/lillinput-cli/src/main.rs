@@ -18,7 +18,91 @@
 //! $ lillinput -e i3 -e command --three-finger-swipe-up "i3:workspace next" --three-finger-swipe-up "command:touch /tmp/myfile"
 //! ```
 //!
-//! Currently, the available action types are `i3` and `command`.
+//! Currently, the available action types are:
+//!
+//! * `i3`: commands for the `i3` IPC interface. Requires the `i3` feature
+//!   (enabled by default).
+//! * `i3!`: builtin commands that perform several requests over the `i3` IPC
+//!   interface: `mark-toggle {mark}`, `goto-mark {mark}`,
+//!   `swap-with-mark {mark}`, and `output-next {focus|move}` and
+//!   `output-prev {focus|move}` (focus or move the focused window to the
+//!   adjacent output, ordered by their position). Requires the `i3` feature
+//!   (enabled by default).
+//! * `command`: shell commands. The context of the gesture is exposed to the
+//!   command via the `LILLINPUT_EVENT`, `LILLINPUT_FINGERS`, `LILLINPUT_DX`,
+//!   `LILLINPUT_DY` and `LILLINPUT_DEVICE` environment variables, allowing a
+//!   single script to handle several gestures. Requires the `command` feature
+//!   (enabled by default).
+//! * `tmux`: commands for a `tmux` server, using the socket specified by the
+//!   `--tmux-socket` argument if provided. Requires the `tmux` feature (enabled
+//!   by default).
+//! * `obs`: requests for `OBS` via `obs-websocket` (`set-scene {scene}`,
+//!   `toggle-record`, `toggle-stream`), using the server specified by the
+//!   `--obs-url` argument and the `obs_password` configuration option. Requires
+//!   the `obs` feature.
+//! * `rest`: `REST` requests defined in the `rest.{name}` configuration section,
+//!   with a method, url, headers and an optional body (`{event}` is replaced
+//!   with the gesture in the url and body). Requires the `rest` feature.
+//! * `record-macro`: starts or stops recording a macro, binding the gestures
+//!   performed while recording to the gesture specified as the command (for
+//!   example, `record-macro:four-finger-swipe-down`). The macros are persisted
+//!   in the file specified by the `--macros-file` argument if provided.
+//! * `internal`: commands that act on `lillinput` itself: `pause` and `resume`
+//!   (stop and restart the execution of the rest of the actions, for example
+//!   before gaming), `toggle-pause` (allowing a single gesture to lock and unlock
+//!   the gestures), `reload-config` (read the configuration again, recreating
+//!   the actions and updating the thresholds; the devices and seats used are
+//!   kept until restarting) and `set-profile <name>` (select a profile, see
+//!   below).
+//! * `log`: emits a log entry with the gesture and the command as the message,
+//!   without any other effect (for example, `log:next workspace`). Useful for
+//!   verifying the bindings and thresholds before configuring the actual
+//!   actions.
+//! * `clipboard`: places the command as text on the Wayland clipboard, using
+//!   the `data-control` protocol of the compositor (`{event}` is replaced with
+//!   the gesture). Requires the `clipboard` feature.
+//! * `uinput`: emits the gesture specified as the command on a virtual touchpad
+//!   (for example, `uinput:four-finger-swipe-up`), allowing gestures to be
+//!   remapped to the gestures handled by the compositor. Requires the `uinput`
+//!   feature and write access to `/dev/uinput`.
+//! * `pointer`: emits a scroll or a button click on a virtual pointer, one of
+//!   `scroll-up`, `scroll-down`, `scroll-left`, `scroll-right`, `left-click`,
+//!   `middle-click`, `right-click`, `back` or `forward` (for example,
+//!   `pointer:back` for navigating back in a browser on Wayland, where `xdotool`
+//!   is not available). Requires the `uinput` feature and write access to
+//!   `/dev/uinput`.
+//! * `xdo`: injects key combinations (`key`, for example `xdo:key ctrl+Tab`)
+//!   or button clicks (`click`, for example `xdo:click 8`) on X11 via the
+//!   `XTest` extension, without spawning an `xdotool` process for each gesture.
+//!   The keys are specified by their X keysym name (such as `Left` or
+//!   `XF86Back`), by a single character, or by their hexadecimal keysym.
+//!   Requires the `xdo` feature.
+//! * `wtype`: injects key combinations (`key`, with the same syntax as `xdo`)
+//!   or text (`type`, for example `wtype:type hello`) on Wayland via the
+//!   `virtual-keyboard` protocol, supported by `wlroots`-based compositors
+//!   such as Sway. Unlike `uinput`, it does not require any privileges.
+//!   Requires the `wtype` feature.
+//! * `volume`: adjusts the volume of the default audio sink, either raising or
+//!   lowering it by a step in percent (`raise [step]` or `lower [step]`, 5 by
+//!   default, for example `volume:raise 2`) or toggling muting it (`mute`).
+//!   It speaks to `PulseAudio` (or `PipeWire`, via `pipewire-pulse`) over a
+//!   connection kept open between gestures, which makes it suitable for the
+//!   progress events of continuous swipes. Requires the `volume` feature.
+//! * `plugin`: executes an action provided by a plugin, specified by the name
+//!   of the plugin followed by its command (for example,
+//!   `plugin:hue lights off`). Requires the `plugins` feature.
+//! * `helper`: sends the gesture to a long-running helper process (see
+//!   below), along with the command of the action (for example,
+//!   `helper:next-track`). Requires the `helper` feature.
+//! * `river`: runs a command of the `River` compositor, accepting the same
+//!   commands as `riverctl` (for example, `river:focus-view next` or
+//!   `river:set-focused-tags 2`) without spawning a process for each gesture.
+//!   Requires the `river` feature.
+//!
+//! The action types that require a feature can be left out of the build via
+//! `--no-default-features` (selecting the desired ones via `--features`). The
+//! actions using an action type that is not compiled in are reported and
+//! disabled at startup.
 //!
 //! ### Using a configuration file
 //!
@@ -34,6 +118,603 @@
 //! specific options rather than the full range of options), and each option can be
 //! overridden individually by later config files or command line arguments,
 //! falling back to their default values if not provided.
+//!
+//! ### Importing the gestures of `sway`
+//!
+//! The `--import-sway-config` argument prints the `bindgesture` bindings of a
+//! `sway` configuration file as a `lillinput` configuration, converting the
+//! commands into `i3` actions, and exits. Only swipes with three or four
+//! fingers can be converted, and the rest of the bindings are listed as
+//! comments:
+//!
+//! ```text
+//! lillinput --import-sway-config ~/.config/sway/config > lillinput.toml
+//! ```
+//!
+//! Conversely, `--export sway` prints the gesture bindings of the configuration
+//! as `bindgesture` lines, translating the `i3` actions into `sway` commands and
+//! the `command` actions into `exec` commands, and exits. The bindings that
+//! cannot be translated (such as long gestures, gestures with modifiers or
+//! other action types) are listed as comments.
+//!
+//! ### Gestures with keyboard modifiers
+//!
+//! The entries in the `actions` section of the configuration file can be
+//! prefixed with one or several keyboard modifiers (`shift`, `ctrl`, `alt` and
+//! `super`), separated by `+`. These actions are only triggered if the modifiers
+//! are held when the gesture starts, taking precedence over the actions without
+//! modifiers:
+//!
+//! ```toml
+//! [actions]
+//! three-finger-swipe-right = ["i3:workspace next"]
+//! "super+three-finger-swipe-right" = ["i3:move container to workspace next"]
+//! ```
+//!
+//! Pointer buttons can be used as modifiers as well (`left-button`,
+//! `right-button`, `middle-button`, `side-button`, `extra-button`,
+//! `forward-button` and `back-button`), allowing combinations such as a swipe
+//! while holding the thumb button of a mouse
+//! (`"side-button+three-finger-swipe-left"`).
+//!
+//! ### Configuring gestures individually
+//!
+//! Alternatively to the `actions` section, the gestures can be configured in the
+//! nested `gestures` section, indexed by kind (`swipe`), number of fingers and
+//! direction. Along with the actions, each gesture can override the `threshold`,
+//! the execution `mode` (`sequential`, `parallel` or `chain`) and the `cooldown` (in
+//! milliseconds) between two occurrences of the gesture:
+//!
+//! ```toml
+//! [gestures.swipe.3.up]
+//! actions = ["i3:fullscreen toggle"]
+//! threshold = 40.0
+//! cooldown = 500
+//! ```
+//!
+//! The number of fingers can also be a minimum (`"3+"`) or a range (`"3-4"`),
+//! applying the entry to each matching gesture. The entries for a specific number
+//! of fingers take precedence over the ones for ranges, and the entries of the
+//! `gestures` section take precedence over the ones in the `actions` and
+//! `execution_modes` sections for the same gesture:
+//!
+//! ```toml
+//! [gestures.swipe."3+".left]
+//! actions = ["i3:workspace prev"]
+//! ```
+//!
+//! ### Long gestures
+//!
+//! Each gesture can define a second, larger `long_threshold`. Gestures that reach
+//! it trigger the long variant of the event instead (for example,
+//! `three-finger-swipe-right-long`), which can be bound to different actions:
+//!
+//! ```toml
+//! [gestures.swipe.3.right]
+//! actions = ["i3:workspace next"]
+//! long_threshold = 100.0
+//!
+//! [gestures.swipe.3.right-long]
+//! actions = ["i3:move container to workspace next; workspace next"]
+//! ```
+//!
+//! ### Fast gestures
+//!
+//! Similarly, each gesture can define a `fast_velocity` (in threshold units per
+//! second). Gestures performed at least that fast trigger the fast variant of
+//! the event instead (for example, `three-finger-swipe-right-fast`), allowing a
+//! quick flick to be bound to different actions than a slow swipe. The long
+//! variant takes precedence if both are reached:
+//!
+//! ```toml
+//! [gestures.swipe.3.right]
+//! actions = ["i3:workspace next"]
+//! fast_velocity = 400.0
+//!
+//! [gestures.swipe.3.right-fast]
+//! actions = ["i3:workspace number 10"]
+//! ```
+//!
+//! ### Swipe and hold gestures
+//!
+//! Each gesture can also define a `hold_time` (in milliseconds). If the fingers
+//! are held still for at least that time at the end of the swipe before being
+//! lifted, the hold variant of the event is triggered instead (for example,
+//! `three-finger-swipe-up-hold`). The hold variant takes precedence over the
+//! fast one, and the long variant over both:
+//!
+//! ```toml
+//! [gestures.swipe.3.up]
+//! actions = ["i3:fullscreen toggle"]
+//! hold_time = 500
+//!
+//! [gestures.swipe.3.up-hold]
+//! actions = ["i3:floating toggle"]
+//! ```
+//!
+//! If `max_gesture_duration` is set, it must be long enough to include the
+//! time the fingers are held.
+//!
+//! ### Continuous gestures
+//!
+//! By default, the actions of a gesture are executed once the fingers are
+//! lifted. Setting a `progress_step` for a gesture enables its continuous mode:
+//! once the threshold is reached, its event is emitted every `progress_step` of
+//! displacement while the fingers move (and not when they are lifted), allowing
+//! the actions to follow the fingers:
+//!
+//! ```toml
+//! [gestures.swipe.3.up]
+//! actions = ["command:pactl set-sink-volume @DEFAULT_SINK@ +5%"]
+//! progress_step = 30.0
+//!
+//! [gestures.swipe.3.down]
+//! actions = ["command:pactl set-sink-volume @DEFAULT_SINK@ -5%"]
+//! progress_step = 30.0
+//! ```
+//!
+//! Setting `repeat = true` for a gesture instead emits its event each time the
+//! displacement reaches another multiple of its threshold, so that a single
+//! long swipe repeats the actions (for example, for switching several
+//! workspaces at once):
+//!
+//! ```toml
+//! [gestures.swipe.3.right]
+//! actions = ["i3:workspace next"]
+//! repeat = true
+//! ```
+//!
+//! ### Rotation gestures
+//!
+//! Rotating two fingers on the touchpad (as in a pinch) triggers the
+//! `two-finger-rotate-clockwise` and `two-finger-rotate-counter-clockwise`
+//! events, once the rotation reaches the `rotation_threshold` (in degrees):
+//!
+//! ```toml
+//! rotation_threshold = 45.0
+//!
+//! [gestures.rotate.2.clockwise]
+//! actions = ["command:brightnessctl set +10%"]
+//!
+//! [gestures.rotate.2.counter-clockwise]
+//! actions = ["command:brightnessctl set 10%-"]
+//! ```
+//!
+//! ### Pinch gestures
+//!
+//! Pinches with two, three or four fingers that are not recognized as
+//! rotations are classified by their final scale (the ratio between the
+//! distance of the fingers at the end and at the start of the pinch):
+//! `<n>-finger-pinch-in` below `pinch_in_threshold` (`0.8` by default) and
+//! `<n>-finger-pinch-out` above `pinch_out_threshold` (`1.25` by default).
+//! The optional `strong_pinch_in_threshold` and `strong_pinch_out_threshold`
+//! enable a second tier of events (`<n>-finger-pinch-in-strong` and
+//! `<n>-finger-pinch-out-strong`) for wider pinches:
+//!
+//! ```toml
+//! strong_pinch_out_threshold = 2.0
+//!
+//! [gestures.pinch.2.out]
+//! actions = ["command:ydotool key ctrl+plus"]
+//!
+//! [gestures.pinch.2.out-strong]
+//! actions = ["command:ydotool key ctrl+0"]
+//!
+//! [gestures.pinch.2.in]
+//! actions = ["command:ydotool key ctrl+minus"]
+//! ```
+//!
+//! ### Touchscreen edge swipes
+//!
+//! On touchscreens, single-finger swipes starting at an edge of the screen
+//! trigger the `edge-swipe-from-left`, `edge-swipe-from-right`,
+//! `edge-swipe-from-top` and `edge-swipe-from-bottom` events. The touch has to
+//! start within `edge_margin` of the edge, and travel at least
+//! `edge_threshold` away from it, both expressed as percentages of the size of
+//! the touchscreen:
+//!
+//! ```toml
+//! edge_margin = 5.0
+//!
+//! [actions]
+//! edge-swipe-from-right = ["command:swaync-client -t"]
+//! ```
+//!
+//! The touchscreens can be disabled via the `device_classes` option, by
+//! leaving out the `touchscreen` class.
+//!
+//! ### Lid and tablet mode switches
+//!
+//! Besides the gestures, the toggles of the switches reported by `libinput` can
+//! trigger actions: `lid-closed` and `lid-opened` for the lid of a laptop, and
+//! `tablet-mode-on` and `tablet-mode-off` for convertible devices. The switches
+//! are processed regardless of the devices selected for the gestures:
+//!
+//! ```toml
+//! [actions]
+//! tablet-mode-on = ["command:wvkbd-mobintl --hidden"]
+//! tablet-mode-off = ["command:pkill wvkbd-mobintl"]
+//! ```
+//!
+//! ### Reusing groups of actions
+//!
+//! Lists of actions used by several gestures can be defined once in the `groups`
+//! section of the configuration file, and referenced from any list of actions
+//! (including the command line arguments) as `@{group}`. The references are
+//! replaced by the actions of the group, and groups cannot reference other groups:
+//!
+//! ```toml
+//! [groups.workspace-nav]
+//! actions = ["i3:workspace next", "command:notify-send workspace"]
+//!
+//! [actions]
+//! three-finger-swipe-right = ["@workspace-nav"]
+//! four-finger-swipe-right = ["@workspace-nav", "i3:fullscreen toggle"]
+//! ```
+//!
+//! ### Options of the command actions
+//!
+//! In the configuration file, the `command` actions can be specified as a table
+//! instead of a string, along with the working directory of the command
+//! (`working_directory`), additional environment variables (`env`) and whether
+//! to execute it via `sh -c` (`shell`, allowing pipes and redirections):
+//!
+//! ```toml
+//! [actions]
+//! three-finger-swipe-up = [
+//!     { type = "command", command = "./notify.sh | logger", shell = true, working_directory = "/home/user/scripts", env = { LEVEL = "info" } },
+//! ]
+//! ```
+//!
+//! The output of the commands is captured and logged (truncated to a kilobyte)
+//! at debug level, and the standard error of the commands that exit with a
+//! non-zero status is included in the warning about their failure. The output
+//! can be discarded instead via `log_command_output = false`.
+//!
+//! ### Chains of actions
+//!
+//! Any action can be specified as a table with a `delay` (in milliseconds) to
+//! wait before executing it. Along with the `chain` execution mode, which
+//! executes the actions in order and skips the remaining ones if an action
+//! fails, this allows declaring chains of steps without relying on `sleep`
+//! commands:
+//!
+//! ```toml
+//! [gestures.swipe.3.left]
+//! actions = [
+//!     "i3:focus left",
+//!     { type = "xdo", command = "key ctrl+t", delay = 100 },
+//! ]
+//! mode = "chain"
+//! ```
+//!
+//! ### Helper process
+//!
+//! The `helper` actions send the gestures to a single long-running process,
+//! specified via `helper_command`, avoiding the startup cost of a process per
+//! gesture (for example, for scripts in interpreted languages). The process is
+//! started on the first gesture, and receives each gesture on its standard input
+//! as a line of JSON, with the same fields as the environment variables of the
+//! `command` actions along with the command of the action:
+//!
+//! ```json
+//! {"event": "three-finger-swipe-up", "fingers": 3, "dx": 0.0, "dy": -42.5, "device": "Touchpad", "command": "next-track"}
+//! ```
+//!
+//! Each gesture is acknowledged by writing a line to its standard output: `ok`
+//! on success, or the reason of the failure otherwise. If the process exits or
+//! does not acknowledge a gesture within a second, it is started again on the
+//! next gesture.
+//!
+//! ### Plugins
+//!
+//! Custom action types can be provided by plugins: dynamic libraries (`.so`
+//! files) exporting a factory of actions via `lillinput::export_plugin!`, built
+//! against the same version of `lillinput` and with the same compiler. The
+//! plugins are loaded at startup from the `plugins_directory` (by default,
+//! `${XDG_CONFIG_HOME}/lillinput/plugins`), and the libraries that are not
+//! compatible are reported and skipped. As loading a plugin runs its code, only
+//! trusted libraries should be placed in the directory.
+//!
+//! ### Scheduling profiles
+//!
+//! Alternative sets of actions ("profiles") can be defined in the `profiles`
+//! section of the configuration file, and activated during specific time windows
+//! via the `schedules` section. While a profile is active, its actions take
+//! precedence over the ones in the `actions` section:
+//!
+//! ```toml
+//! [profiles.work]
+//! three-finger-swipe-right = ["i3:workspace next_on_output"]
+//!
+//! [[schedules]]
+//! profile = "work"
+//! start = "09:00"
+//! end = "17:00"
+//! days = ["mon", "tue", "wed", "thu", "fri"]
+//! ```
+//!
+//! The schedules are evaluated in order when a gesture is performed, and the
+//! `days` can be omitted in order to activate the profile every day.
+//! By default, the active profile is only updated when a gesture is performed:
+//! the `tick_interval` option (in milliseconds) can be used for updating it
+//! periodically instead.
+//!
+//! A profile can also be selected via the `internal:set-profile <name>` action,
+//! taking precedence over the schedules until the configuration is reloaded
+//! (`internal:set-profile default` selecting the actions outside of any
+//! profile).
+//!
+//! Profiles can also contain any of the settings, allowing entire setups to be
+//! switched without separate configuration files. The profile selected via the
+//! `--profile` argument (or the `profile` option of the configuration file) is
+//! merged over the base settings, including its actions; the settings of the
+//! rest of the profiles are ignored:
+//!
+//! ```toml
+//! [profiles.docked]
+//! threshold = 10.0
+//! three-finger-swipe-right = ["i3:workspace next_on_output"]
+//! ```
+//!
+//! ```bash
+//! $ lillinput --profile docked
+//! ```
+//!
+//! ### Settings for specific seats
+//!
+//! The `threshold` and the actions can be overridden for a specific `libinput`
+//! seat in the `seats` section of the configuration file. The section of the
+//! seat in use is merged over the base settings (and the selected profile):
+//!
+//! ```toml
+//! [seats.seat1]
+//! threshold = 30.0
+//!
+//! [seats.seat1.actions]
+//! three-finger-swipe-up = ["i3:fullscreen toggle"]
+//! ```
+//!
+//! ### Several seats
+//!
+//! The gestures of additional seats can be processed along with the main one
+//! by listing them in the `extra_seats` setting (or the `--extra-seats`
+//! argument). Each seat keeps the state of its own gestures, and uses the
+//! `threshold` and the actions of its own section in `seats`, falling back to
+//! the settings of the main seat:
+//!
+//! ```toml
+//! seat = "seat0"
+//! extra_seats = ["seat1"]
+//!
+//! [seats.seat1]
+//! threshold = 30.0
+//! ```
+//!
+//! ### Actions for specific devices
+//!
+//! When using several touchpads (for example, an external one along with the
+//! built-in one), the actions can be overridden for the gestures of a specific
+//! device in the `device_actions` section of the configuration file. Devices are
+//! matched by their name or their system name (as listed by
+//! `libinput list-devices`), ignoring the case:
+//!
+//! ```toml
+//! [device_actions."Apple Magic Trackpad"]
+//! three-finger-swipe-up = ["i3:fullscreen toggle"]
+//!
+//! [device_actions.event5]
+//! four-finger-swipe-left = ["i3:workspace prev"]
+//! ```
+//!
+//! The actions of the device take precedence over the ones of the active
+//! profile and the `actions` section, but not over the ones bound with
+//! modifiers.
+//!
+//! ### Undoing gestures
+//!
+//! Actions for reverting the effects of a gesture can be defined in the
+//! `undo_actions` section of the configuration file. If the gesture in the
+//! opposite direction (with the same number of fingers) is performed within the
+//! `undo_window` (in milliseconds, `500` by default), the undo actions are
+//! executed instead of the actions of the opposite gesture:
+//!
+//! ```toml
+//! undo_window = 500
+//!
+//! [undo_actions]
+//! three-finger-swipe-up = ["command:xdotool key ctrl+z"]
+//! ```
+//!
+//! ### Sequences of gestures
+//!
+//! Gestures performed in quick succession can be bound to their own actions
+//! via the `sequences` section of the configuration file. When the last gesture
+//! of a sequence is performed, with each gesture following the previous one
+//! within the `timeout` (in milliseconds, `500` by default), the actions of the
+//! sequence are executed instead of the actions of the last gesture:
+//!
+//! ```toml
+//! [[sequences]]
+//! gestures = ["three-finger-swipe-up", "three-finger-swipe-down"]
+//! timeout = 500
+//! actions = ["i3:kill"]
+//! ```
+//!
+//! The actions of the preceding gestures are executed as usual. If several
+//! sequences are completed by a gesture, the longest one is used.
+//!
+//! ### Discarding resting fingers
+//!
+//! Resting the fingers on the touchpad and lifting them much later can end up
+//! being detected as a swipe. The `--max-gesture-duration` argument (in
+//! milliseconds) discards the swipes lasting longer than it.
+//!
+//! ### Stabilizing the direction of gestures
+//!
+//! Gestures that end near the boundary between two directions can be detected
+//! in a different direction than the one they started in. The
+//! `--direction-margin` argument (in degrees) keeps the direction detected when
+//! the gesture first reaches the threshold, unless the final angle deviates from
+//! it by more than the margin.
+//!
+//! If only the left, up, right and down gestures are bound, the `--directions 4`
+//! argument disables the diagonals, splitting their angles between the
+//! neighbouring directions so that imprecise swipes are not lost.
+//!
+//! The angle covered by each direction can also be adjusted in the
+//! `sector_widths` section of the configuration file, for example widening the
+//! main directions at the expense of the diagonals. The sectors are laid out
+//! clock-wise starting from the left one (centered on the horizontal axis), and
+//! their widths (in degrees) must add up to `360`. With `--directions 4`, the
+//! widths of the diagonals must be `0`:
+//!
+//! ```toml
+//! [sector_widths]
+//! left = 60.0
+//! left-up = 30.0
+//! up = 60.0
+//! right-up = 30.0
+//! right = 60.0
+//! right-down = 30.0
+//! down = 60.0
+//! left-down = 30.0
+//! ```
+//!
+//! Swipes that end close to the boundary between two directions can also be
+//! discarded as ambiguous, instead of picking either of them: the
+//! `--direction-deadzone` argument (in degrees) sets the width of the deadzone
+//! around each boundary. Gestures whose initial direction is kept due to the
+//! `--direction-margin` are not discarded.
+//!
+//! ### Thresholds relative to the touchpad
+//!
+//! By default, the thresholds are displacements in `libinput` units. With the
+//! `--threshold-mode relative` argument, they are interpreted as percentages of
+//! the width and height of the touchpad performing the gesture instead, so that
+//! the same settings behave consistently across touchpads of different sizes.
+//! If the size of a touchpad is not reported, its thresholds are interpreted as
+//! absolute displacements.
+//!
+//! ### Thresholds per axis
+//!
+//! The `--threshold-x` and `--threshold-y` arguments override the threshold
+//! for the horizontal and vertical displacements respectively (for example,
+//! requiring longer horizontal swipes on wide touchpads). The diagonal swipes
+//! use a threshold in between, and the thresholds of individual gestures take
+//! precedence over both.
+//!
+//! ### Validating the actions
+//!
+//! The `--validate-actions` argument checks the actions on startup, before the
+//! first gesture is performed, reporting the ones that would fail. As `i3` cannot
+//! parse a command without executing it, the `i3` commands are checked
+//! heuristically: the connection must be established, the quotes and criteria
+//! balanced, and each of the chained commands must be a known `i3` command.
+//!
+//! ### Bounded runs
+//!
+//! The `--max-events` and `--duration` arguments make `lillinput-cli` exit
+//! cleanly after processing a number of gestures or after running for a number
+//! of seconds, whichever comes first. Useful for scripted tests, demos and for
+//! calibrating the thresholds.
+//!
+//! The latency of each gesture (the time from the end of the gesture, as
+//! reported by `libinput`, until it is recognized and until its actions
+//! complete) is logged with the `-v` verbosity, and a summary is logged when
+//! a bounded run finishes.
+//!
+//! ### Tuning the thresholds
+//!
+//! The `--learn-thresholds` argument records the magnitude of the swipes
+//! performed, both the ones reaching the threshold and the ones discarded for
+//! being below it. Once a bounded run finishes, a threshold is suggested for
+//! each finger count and direction with enough swipes, to be set in the
+//! `gestures` section of the configuration file:
+//!
+//! ```text
+//! lillinput --learn-thresholds true --duration 600 -v
+//! ```
+//!
+//! ### Keeping a history of the actions
+//!
+//! The `--audit-log` argument (or the `audit_log` option) appends every action
+//! executed to a file, separate from the regular logging. Each action is recorded
+//! as a tab-separated line with the time of the execution, the gesture that
+//! triggered it, the action and its result (`ok`, or the error message).
+//!
+//! ### Finding the running instance via D-Bus
+//!
+//! When built with the `dbus` feature, the `--dbus-name-policy` argument claims
+//! the `org.lillinput` name on the session bus, allowing other tools to find the
+//! running instance:
+//! * `exclusive`: exits if the name is owned by another instance.
+//! * `replace`: replaces the instance that owns the name, which then exits.
+//!
+//! The `--print-dbus-service` argument prints a service file for starting
+//! `lillinput` on demand when the name is requested, to be installed in the
+//! D-Bus services directory (for example, `~/.local/share/dbus-1/services`).
+//!
+//! ### Localization
+//!
+//! The messages about the startup and the main loop of `lillinput-cli` are
+//! translated according to the locale of the environment (`LC_ALL`,
+//! `LC_MESSAGES` or `LANG`), currently to Spanish, falling back to English. The
+//! rest of the log messages are kept in English. Translations are defined in the
+//! `locales` directory as [Fluent](https://projectfluent.org) files.
+//!
+//! ### Connecting devices while running
+//!
+//! If no gesture-capable devices are found at startup (for example, if a
+//! Bluetooth touchpad is not connected yet), `lillinput-cli` keeps running,
+//! waiting for the devices added to the seat and processing their gestures once
+//! connected. The devices connected and disconnected are reported in the logs.
+//! Devices specified via the `--devices` argument are only opened at startup.
+//!
+//! ### Passing gestures through to the compositor
+//!
+//! The `--passthrough` argument (or `passthrough = true` in the configuration
+//! file) re-emits the gestures without actions on a virtual touchpad, via
+//! `uinput` actions, for the compositor to handle them. The gestures performed
+//! on the virtual touchpad are ignored by `lillinput-cli` itself. Note that
+//! `libinput` does not grab the devices, so compositors reading the touchpad
+//! directly already receive every gesture: the passthrough is meant for
+//! compositors and tools that only receive the gestures via the virtual
+//! touchpad (for example, when the touchpad is grabbed by another tool).
+//!
+//! ### Enabling gestures per class of device
+//!
+//! The devices are classified via `libinput` as `internal-touchpad`,
+//! `external-touchpad` (for example, a USB or Bluetooth trackpad), `trackpoint`,
+//! `touchscreen` or `other`. The `--device-classes` argument restricts the gestures processed
+//! to the devices of the given classes (by default, all of them), for example
+//! for ignoring an external trackpad handled by a different tool:
+//!
+//! ```toml
+//! device_classes = ["internal-touchpad"]
+//! ```
+//!
+//! Specific devices can be ignored via the `deny_devices` option, or the gestures
+//! restricted to specific devices via the `allow_devices` option. Devices are
+//! matched by their name or their system name (as listed by
+//! `libinput list-devices`), or by a `udev` property in the `KEY=VALUE` form. For
+//! example, for ignoring a drawing tablet while still using the touchpad:
+//!
+//! ```toml
+//! deny_devices = ["ID_INPUT_TABLET=1"]
+//! ```
+//!
+//! ### Device permissions and confined environments
+//!
+//! `lillinput-cli` reads the gestures directly from the input devices via
+//! `libinput`, and as such requires read access to them (typically, by adding the
+//! user to the `input` group). If access is denied, the offending device is
+//! reported at startup.
+//!
+//! When running confined (for example, as a Flatpak), access to the devices needs
+//! to be granted explicitly (`--device=all`): the XDG desktop portals do not
+//! provide an alternative, as the `RemoteDesktop` portal is limited to emulating
+//! input and the `InputCapture` portal does not forward touchpad gestures.
 
 #![warn(
     missing_docs,
@@ -43,55 +724,661 @@
     clippy::doc_markdown
 )]
 
+pub mod dbus;
+pub mod i18n;
 pub mod opts;
 pub mod settings;
+pub mod sway;
 
+#[cfg(feature = "dbus")]
+use crate::dbus::{claim_bus_name, BUS_NAME};
+use crate::dbus::{dbus_service_file, NamePolicy};
+use crate::i18n::tr;
 use crate::opts::Opts;
-use crate::settings::{extract_action_map, setup_application, Settings};
-use lillinput::controllers::{Controller, DefaultController};
-use lillinput::events::DefaultProcessor;
+use crate::settings::{
+    default_config_template, diff_settings, extract_action_map, extract_audit_log,
+    extract_dbus_name_policy, extract_device_classes, extract_directions, extract_event_thresholds,
+    extract_execution_modes, extract_fast_velocities, extract_filters, extract_hold_times,
+    extract_long_thresholds, extract_progress_steps, extract_repeat_events, extract_schedules,
+    extract_sector_widths, extract_threshold_mode, setup_application, Settings,
+};
+use crate::sway::{export_bindgestures, import_bindgestures, render_import};
+use lillinput::controllers::{Controller, DefaultController, SharedControllerState};
+use lillinput::events::libinput::available_seats;
+use lillinput::events::{
+    DefaultProcessor, DeviceFilter, EdgeSwipeThresholds, LibinputError, MultiSeatProcessor,
+    PinchThresholds, Processor, ProcessorSettings, SharedThresholdLearner, ThresholdLearner,
+};
 
 use clap::Parser;
-use log::{error, info};
+use log::{debug, error, info, warn};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::iter;
+use std::mem;
+use std::panic;
 use std::process;
+use std::sync::{Arc, Mutex, PoisonError, TryLockError};
+use std::time::Duration;
 
 #[cfg(test)]
 mod test_utils;
 
+/// Create the [`DefaultProcessor`], detecting the seat if needed.
+///
+/// If devices are specified in the settings, they are used directly instead
+/// of the seat. Otherwise, if the seat from the settings has no
+/// gesture-capable devices, the rest of the available seats are tried in
+/// order. If none of them has gesture-capable devices, the seat from the
+/// settings is used, processing the gestures once a device is connected.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+///
+/// # Errors
+///
+/// Returns `Err` if the `libinput` initialization failed for the seat from
+/// the settings.
+fn create_processor(settings: &Settings) -> Result<DefaultProcessor, LibinputError> {
+    let new_processor = |seat: &str| {
+        DefaultProcessor::new(
+            settings.threshold,
+            seat,
+            settings.invert_x,
+            settings.invert_y,
+        )
+    };
+
+    if !settings.devices.is_empty() {
+        let mut processor = DefaultProcessor::new_from_path(
+            settings.threshold,
+            &settings.devices,
+            settings.invert_x,
+            settings.invert_y,
+        )?;
+        match processor.check_devices() {
+            Ok(device_count) => info!("{}", tr!("devices-found", count = device_count)),
+            Err(e) => warn!("{}", tr!("devices-error", error = e.to_string())),
+        }
+        return Ok(processor);
+    }
+
+    let fallback_seats = available_seats()
+        .into_iter()
+        .filter(|seat| *seat != settings.seat);
+    for seat in iter::once(settings.seat.clone()).chain(fallback_seats) {
+        match new_processor(&seat).and_then(|mut processor| {
+            processor
+                .check_devices()
+                .map(|device_count| (processor, device_count))
+        }) {
+            Ok((processor, device_count)) => {
+                info!(
+                    "{}",
+                    tr!(
+                        "devices-found-seat",
+                        count = device_count,
+                        seat = seat.as_str()
+                    )
+                );
+                return Ok(processor);
+            }
+            Err(e) => warn!(
+                "{}",
+                tr!("seat-error", seat = seat.as_str(), error = e.to_string())
+            ),
+        }
+    }
+
+    warn!("{}", tr!("no-devices", seat = settings.seat.as_str()));
+    new_processor(&settings.seat)
+}
+
+/// Create the processors of the additional seats from the settings.
+///
+/// The seats that fail to initialize are skipped, as well as the seats
+/// already in use. The sections of `seats` that do not match any of the
+/// additional seats are reported.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+/// * `primary_seat` - the seat of the main processor.
+fn create_extra_processors(settings: &Settings, primary_seat: &str) -> Vec<DefaultProcessor> {
+    for seat in settings.seats.keys() {
+        if !settings.extra_seats.contains(seat) {
+            warn!("{}", tr!("unknown-seat-section", seat = seat.as_str()));
+        }
+    }
+
+    let mut seats: Vec<&str> = vec![primary_seat];
+    let mut processors = vec![];
+    for seat in &settings.extra_seats {
+        if seats.contains(&seat.as_str()) {
+            continue;
+        }
+        seats.push(seat);
+
+        match DefaultProcessor::new(
+            settings.threshold,
+            seat,
+            settings.invert_x,
+            settings.invert_y,
+        )
+        .and_then(|mut processor| {
+            processor
+                .check_devices()
+                .map(|device_count| (processor, device_count))
+        }) {
+            Ok((processor, device_count)) => {
+                info!(
+                    "{}",
+                    tr!(
+                        "devices-found-seat",
+                        count = device_count,
+                        seat = seat.as_str()
+                    )
+                );
+                processors.push(processor);
+            }
+            Err(e) => warn!(
+                "{}",
+                tr!("seat-error", seat = seat.as_str(), error = e.to_string())
+            ),
+        }
+    }
+
+    processors
+}
+
+/// Combine the main processor with the processors of the additional seats.
+///
+/// # Arguments
+///
+/// * `processor` - the configured processor of the main seat.
+/// * `settings` - application settings.
+/// * `threshold_learner` - the recorder of the magnitudes of the swipes.
+///
+/// # Returns
+///
+/// The main processor if no additional seats are available, or a
+/// [`MultiSeatProcessor`] combining all the seats otherwise.
+fn combine_seats(
+    processor: DefaultProcessor,
+    settings: &Settings,
+    threshold_learner: Option<&SharedThresholdLearner>,
+) -> Box<dyn Processor> {
+    let extra_processors = create_extra_processors(settings, &processor.seat_id);
+    if extra_processors.is_empty() {
+        return Box::new(processor);
+    }
+
+    let mut multi_seat_processor = MultiSeatProcessor::new(processor);
+    for mut extra_processor in extra_processors {
+        configure_processor(&mut extra_processor, settings);
+        extra_processor.threshold_learner = threshold_learner.cloned();
+        let threshold = settings
+            .seats
+            .get(&extra_processor.seat_id)
+            .and_then(|x| x.threshold);
+        multi_seat_processor.add_processor(extra_processor, threshold);
+    }
+    Box::new(multi_seat_processor)
+}
+
+/// Create the processor for the settings, along with the processors of the
+/// additional seats.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+/// * `threshold_learner` - the recorder of the magnitudes of the swipes.
+///
+/// # Errors
+///
+/// Returns `Err` if the `libinput` initialization failed for the seat from
+/// the settings.
+fn setup_processor(
+    settings: &Settings,
+    threshold_learner: Option<&SharedThresholdLearner>,
+) -> Result<Box<dyn Processor>, LibinputError> {
+    let mut processor = create_processor(settings)?;
+    configure_processor(&mut processor, settings);
+    processor.threshold_learner = threshold_learner.cloned();
+    Ok(combine_seats(processor, settings, threshold_learner))
+}
+
+/// Return `true` if the processor needs to be recreated for applying new
+/// settings.
+///
+/// The settings that select the devices (and the edge swipes of the
+/// touchscreens), as well as the thresholds of the additional seats, are only
+/// applied when the processor is created, in contrast to the parameters for
+/// recognizing gestures.
+///
+/// # Arguments
+///
+/// * `current` - the settings the processor was created with.
+/// * `new` - the new settings.
+fn requires_new_processor(current: &Settings, new: &Settings) -> bool {
+    current.seat != new.seat
+        || current.extra_seats != new.extra_seats
+        || seat_thresholds(current) != seat_thresholds(new)
+        || current.devices != new.devices
+        || current.allow_devices != new.allow_devices
+        || current.deny_devices != new.deny_devices
+        || current.device_classes != new.device_classes
+        || extract_edge_swipe_thresholds(current) != extract_edge_swipe_thresholds(new)
+}
+
+/// Return the thresholds of the additional seats, by seat.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+fn seat_thresholds(settings: &Settings) -> BTreeMap<&str, f64> {
+    settings
+        .seats
+        .iter()
+        .filter_map(|(seat, x)| Some((seat.as_str(), x.threshold?)))
+        .collect()
+}
+
+/// Read the settings again, applying them to the processor.
+///
+/// The changes are reported, and the processor is recreated if the devices
+/// changed. The current settings are kept if the new ones cannot be read.
+///
+/// # Arguments
+///
+/// * `settings` - the current application settings, to be replaced.
+/// * `processor` - the processor of the controller.
+/// * `threshold_learner` - the recorder of the magnitudes of the swipes.
+fn reload_settings(
+    settings: &mut Settings,
+    processor: &mut Box<dyn Processor>,
+    threshold_learner: Option<&SharedThresholdLearner>,
+) {
+    info!("{}", tr!("reloading"));
+    match setup_application(Opts::parse(), false) {
+        Ok(new_settings) => {
+            // Report the settings changed by the reload.
+            let changes = diff_settings(settings, &new_settings);
+            if changes.is_empty() {
+                info!("{}", tr!("reloading-unchanged"));
+            }
+            for change in changes {
+                info!("{}", tr!("reloading-change", change = change));
+            }
+            if requires_new_processor(settings, &new_settings) {
+                info!("{}", tr!("reloading-devices"));
+                match setup_processor(&new_settings, threshold_learner) {
+                    Ok(new_processor) => *processor = new_processor,
+                    Err(e) => {
+                        error!("{}", tr!("reloading-devices-error", error = e.to_string()));
+                    }
+                }
+            }
+            *settings = new_settings;
+        }
+        Err(e) => error!("{}", tr!("settings-error", error = e.to_string())),
+    }
+    processor.update_settings(extract_processor_settings(settings, &processor.settings()));
+}
+
+/// Install a panic hook that logs the latest activity of the controller,
+/// before delegating to the default hook.
+///
+/// The state is not waited for if it is locked, as the panic might have
+/// happened while holding it.
+///
+/// # Arguments
+///
+/// * `state` - the shared controller state.
+fn install_panic_hook(state: SharedControllerState) {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let snapshot = match state.try_lock() {
+            Ok(state) => Some(format!("{state:?}")),
+            Err(TryLockError::Poisoned(e)) => Some(format!("{:?}", e.get_ref())),
+            Err(TryLockError::WouldBlock) => None,
+        };
+        let state = snapshot.unwrap_or_else(|| tr!("state-unavailable"));
+        error!("{}", tr!("unexpected-failure", state = state));
+        default_hook(info);
+    }));
+}
+
+/// Log the summary of the latency of the gestures processed, if any.
+///
+/// # Arguments
+///
+/// * `state` - the shared controller state.
+fn log_latency_summary(state: &SharedControllerState) {
+    let latency = state.lock().unwrap_or_else(PoisonError::into_inner).latency;
+    if let Some(mean) = latency.mean() {
+        info!(
+            "{}",
+            tr!(
+                "latency-summary",
+                count = latency.count,
+                mean = format!("{:.1}", mean.as_secs_f64() * 1000.0),
+                max = format!("{:.1}", latency.max.as_secs_f64() * 1000.0)
+            )
+        );
+    }
+}
+
+/// Apply the settings for recognizing gestures to a processor.
+///
+/// # Arguments
+///
+/// * `processor` - the processor to be configured.
+/// * `settings` - application settings.
+fn configure_processor(processor: &mut DefaultProcessor, settings: &Settings) {
+    processor.update_settings(extract_processor_settings(settings, &processor.settings()));
+    processor.device_classes = extract_device_classes(settings);
+    processor.device_filter = DeviceFilter {
+        allow: settings.allow_devices.clone(),
+        deny: settings.deny_devices.clone(),
+    };
+    processor.edge_swipe_thresholds = extract_edge_swipe_thresholds(settings);
+}
+
+/// Return the thresholds for recognizing the edge swipes of touchscreens.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+fn extract_edge_swipe_thresholds(settings: &Settings) -> EdgeSwipeThresholds {
+    EdgeSwipeThresholds {
+        margin: settings.edge_margin,
+        distance: settings.edge_threshold,
+    }
+}
+
+/// Return the settings for recognizing gestures of a processor.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+/// * `current` - current settings of the processor, for the parameters that
+///   are detected from the devices.
+fn extract_processor_settings(
+    settings: &Settings,
+    current: &ProcessorSettings,
+) -> ProcessorSettings {
+    ProcessorSettings {
+        threshold: settings.threshold,
+        invert_x: settings.invert_x,
+        invert_y: settings.invert_y,
+        disable_while_typing: settings
+            .disable_while_typing
+            .map(|x| Duration::from_millis(x.into())),
+        max_duration: settings
+            .max_gesture_duration
+            .map(|x| Duration::from_millis(x.into())),
+        threshold_x: settings.threshold_x,
+        threshold_y: settings.threshold_y,
+        direction_margin: settings.direction_margin.map(f64::from),
+        directions: extract_directions(settings),
+        sector_widths: extract_sector_widths(settings),
+        direction_deadzone: settings.direction_deadzone.map(f64::from),
+        event_thresholds: extract_event_thresholds(settings),
+        long_thresholds: extract_long_thresholds(settings),
+        fast_velocities: extract_fast_velocities(settings),
+        hold_times: extract_hold_times(settings),
+        progress_steps: extract_progress_steps(settings),
+        repeat_events: extract_repeat_events(settings),
+        threshold_mode: extract_threshold_mode(settings),
+        rotation_threshold: settings.rotation_threshold,
+        pinch_thresholds: PinchThresholds {
+            pinch_in: settings.pinch_in_threshold,
+            pinch_out: settings.pinch_out_threshold,
+            strong_pinch_in: settings.strong_pinch_in_threshold,
+            strong_pinch_out: settings.strong_pinch_out_threshold,
+        },
+        device_size: current.device_size,
+    }
+}
+
+/// Create the controller for the actions of the settings.
+///
+/// # Arguments
+///
+/// * `processor` - the processor of the controller.
+/// * `settings` - application settings.
+/// * `state` - the shared controller state, kept across reloads.
+fn create_controller(
+    processor: Box<dyn Processor>,
+    settings: &Settings,
+    state: &SharedControllerState,
+) -> DefaultController {
+    let extracted_actions = extract_action_map(settings, state);
+    let filters = extract_filters(settings, &extracted_actions);
+    let mut controller: DefaultController =
+        DefaultController::new(processor, extracted_actions.actions);
+    controller.modifier_actions = extracted_actions.modifier_actions;
+    controller.profiles = extracted_actions.profiles;
+    controller.device_actions = extracted_actions.device_actions;
+    controller.seat_actions = extracted_actions.seat_actions;
+    controller.undo_actions = extracted_actions.undo_actions;
+    controller.undo_window = Duration::from_millis(settings.undo_window.into());
+    controller.sequences = extracted_actions.sequences;
+    controller.schedules = extract_schedules(settings);
+    controller.macro_recorder = Some(extracted_actions.macro_recorder);
+    controller.state = Arc::clone(state);
+    controller.filters = filters;
+    controller.execution_modes = extract_execution_modes(settings);
+    controller.audit_log = extract_audit_log(settings);
+    controller.max_repeated_events = settings
+        .max_repeated_events
+        .and_then(|x| usize::try_from(x).ok());
+    controller.max_events = settings.max_events.and_then(|x| usize::try_from(x).ok());
+    controller.run_duration = settings.duration.map(|x| Duration::from_secs(x.into()));
+
+    controller
+}
+
+/// Log the actions that cannot be executed, as reported by the controller.
+///
+/// # Arguments
+///
+/// * `controller` - the controller whose actions are validated.
+fn log_invalid_actions(controller: &DefaultController) {
+    let invalid_actions = controller.validate_actions();
+    for (action_event, action, e) in &invalid_actions {
+        warn!(
+            "{}",
+            tr!(
+                "invalid-action",
+                event = action_event.to_string(),
+                action = action.as_str(),
+                error = e.to_string()
+            )
+        );
+    }
+    if invalid_actions.is_empty() {
+        info!("{}", tr!("actions-valid"));
+    }
+}
+
+/// Log the thresholds suggested from the magnitude of the swipes performed.
+///
+/// # Arguments
+///
+/// * `threshold_learner` - the recorder of the magnitudes of the swipes.
+/// * `processor_settings` - the parameters used for recognizing gestures.
+fn log_threshold_suggestions(
+    threshold_learner: &SharedThresholdLearner,
+    processor_settings: &ProcessorSettings,
+) {
+    let suggestions = threshold_learner
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .suggestions(processor_settings);
+    if suggestions.is_empty() {
+        info!("{}", tr!("threshold-suggestions-none"));
+    }
+    for suggestion in suggestions {
+        info!(
+            "{}",
+            tr!(
+                "threshold-suggestion",
+                event = suggestion.action_event.to_string(),
+                current = suggestion.current,
+                suggested = suggestion.suggested,
+                accepted = suggestion.accepted,
+                rejected = suggestion.rejected
+            )
+        );
+    }
+}
+
+/// Print the output requested via the arguments that print and exit (such as
+/// the default configuration), if any.
+///
+/// # Arguments
+///
+/// * `opts` - command line arguments.
+///
+/// # Returns
+///
+/// `true` if an output was requested, and the application should exit.
+fn print_requested_output(opts: &Opts) -> bool {
+    if opts.print_default_config {
+        print!("{}", default_config_template());
+        return true;
+    }
+    if opts.print_dbus_service {
+        print!("{}", dbus_service_file());
+        return true;
+    }
+    if let Some(path) = &opts.import_sway_config {
+        match fs::read_to_string(path) {
+            Ok(config) => print!("{}", render_import(&import_bindgestures(&config))),
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    tr!(
+                        "sway-import-error",
+                        path = path.as_str(),
+                        error = e.to_string()
+                    )
+                );
+                process::exit(1);
+            }
+        }
+        return true;
+    }
+
+    false
+}
+
 /// Main entry point.
 pub fn main() {
     // Retrieve the application settings and setup logging.
     let opts = Opts::parse();
-    let settings = match setup_application(opts, true) {
+    if print_requested_output(&opts) {
+        return;
+    }
+    let export = opts.export.is_some();
+    let settings = match setup_application(opts, !export) {
         Ok(settings) => settings,
         Err(e) => {
-            error!("Unable to process settings: {e}. Attempting to proceed with defaults ...");
+            error!("{}", tr!("settings-error", error = e.to_string()));
             Settings::default()
         }
     };
+    if export {
+        print!("{}", export_bindgestures(&settings));
+        return;
+    }
+
+    // Report the settings that differ from the defaults.
+    for line in diff_settings(&Settings::default(), &settings) {
+        debug!("Settings differing from the defaults: {line}");
+    }
+
+    // Claim the D-Bus name, exiting if another instance owns it.
+    let name_policy = extract_dbus_name_policy(&settings);
+    #[cfg(feature = "dbus")]
+    match claim_bus_name(name_policy) {
+        Ok(()) if name_policy != NamePolicy::Disabled => {
+            info!("{}", tr!("dbus-name-claimed", name = BUS_NAME));
+        }
+        Ok(()) => (),
+        Err(zbus::Error::NameTaken) => {
+            error!("{}", tr!("dbus-name-taken", name = BUS_NAME));
+            process::exit(1);
+        }
+        Err(e) => warn!(
+            "{}",
+            tr!("dbus-name-error", name = BUS_NAME, error = e.to_string())
+        ),
+    }
+    #[cfg(not(feature = "dbus"))]
+    if name_policy != NamePolicy::Disabled {
+        warn!("{}", tr!("dbus-disabled"));
+    }
 
     // Create the Processor.
-    let processor = match DefaultProcessor::new(
-        settings.threshold,
-        &settings.seat,
-        settings.invert_x,
-        settings.invert_y,
-    ) {
+    let threshold_learner = settings
+        .learn_thresholds
+        .then(|| Arc::new(Mutex::new(ThresholdLearner::default())));
+    let mut processor = match setup_processor(&settings, threshold_learner.as_ref()) {
         Ok(processor) => processor,
         Err(e) => {
-            error!("Unable to initialize: {e}");
+            error!("{}", tr!("initialization-error", error = e.to_string()));
             process::exit(1);
         }
     };
+    let mut settings = settings;
+
+    // Report the latest activity of the controller on unexpected failures,
+    // sharing the state across the reloads.
+    let state = SharedControllerState::default();
+    install_panic_hook(Arc::clone(&state));
+
+    let controller = loop {
+        // Create the controller.
+        let mut controller = create_controller(processor, &settings, &state);
 
-    // Create the controller.
-    let (actions, _) = extract_action_map(&settings);
-    let mut controller: DefaultController = DefaultController::new(Box::new(processor), actions);
+        // Report the invalid actions, if requested.
+        if settings.validate_actions {
+            log_invalid_actions(&controller);
+        }
+
+        // Summarize the configured gestures, coloring the summary if the log
+        // messages are written to a terminal.
+        controller.log_status_info(io::stdout().is_terminal());
+
+        // Start the main loop.
+        info!("{}", tr!("listening"));
+        if let Err(e) = controller.run() {
+            error!("{}", tr!("main-loop-error", error = e.to_string()));
+            process::exit(1);
+        }
+
+        // Recreate the controller with the settings read again, if a reload
+        // was requested, keeping the processor unless the devices changed.
+        if !mem::take(
+            &mut state
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .reload_requested,
+        ) {
+            break controller;
+        }
+        processor = controller.processor;
+        reload_settings(&mut settings, &mut processor, threshold_learner.as_ref());
+    };
 
-    // Start the main loop.
-    info!("Listening for events ...");
-    if let Err(e) = controller.run() {
-        error!("Unhandled error during the main loop: {e}");
-        process::exit(1);
+    // Summarize the latency of the gestures, once a bounded run finishes.
+    log_latency_summary(&controller.state);
+    if let Some(threshold_learner) = threshold_learner {
+        log_threshold_suggestions(&threshold_learner, &controller.processor.settings());
     }
 }
@@ -1,25 +1,77 @@
 //! Arguments and utils for the `lillinput` binary.
 
-use lillinput::actions::ActionType;
-use lillinput::events::ActionEvent;
+use crate::dbus::NamePolicy;
+use lillinput::actions::{ActionError, ActionType, ACTION_TYPES};
+use lillinput::events::{ActionEvent, ThresholdMode};
 
 use clap::error::ErrorKind;
 use clap::Parser;
 use clap_verbosity_flag::{InfoLevel, Verbosity};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
 use strum::VariantNames;
 
+/// Prefix of the action strings that reference an action group.
+pub const GROUP_PREFIX: char = '@';
+
 /// Representation of an action.
+///
+/// In the configuration file, an action can be specified either as an action
+/// string (`{type}:{command}`), or as a table with the `type` and `command`
+/// keys along with the [`ActionOptions`] and the `delay` (in milliseconds)
+/// before executing the action.
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
-#[serde(try_from = "String")]
-#[serde(into = "String")]
+#[serde(try_from = "ActionValue")]
+#[serde(into = "ActionValue")]
 pub struct StringifiedAction {
     /// Action type.
     pub type_: String,
     /// Action command.
     pub command: String,
+    /// Options of the action, if specified via the table form.
+    pub options: Option<ActionOptions>,
+    /// Time (in milliseconds) to wait before executing the action.
+    pub delay: Option<u32>,
+}
+
+/// Options of an action, specified via the table form of the action.
+///
+/// The options are only supported by the `command` actions.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ActionOptions {
+    /// Working directory of the command.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_directory: Option<String>,
+    /// Additional environment variables of the command.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+    /// Whether to execute the command via `sh -c`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub shell: bool,
+}
+
+/// Serialized form of a [`StringifiedAction`].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+enum ActionValue {
+    /// Action string, in the `{type}:{command}` format.
+    String(String),
+    /// Table with the type, the command and the options of the action.
+    Table {
+        /// Action type.
+        #[serde(rename = "type")]
+        type_: String,
+        /// Action command.
+        command: String,
+        /// Time (in milliseconds) to wait before executing the action.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        delay: Option<u32>,
+        /// Options of the action.
+        #[serde(flatten)]
+        options: ActionOptions,
+    },
 }
 
 impl StringifiedAction {
@@ -29,27 +81,67 @@ impl StringifiedAction {
         Self {
             type_: type_.to_string(),
             command: command.to_string(),
+            options: None,
+            delay: None,
         }
     }
+
+    /// Return the name of the referenced action group, if the action is a
+    /// reference to a group.
+    #[must_use]
+    pub fn group(&self) -> Option<&str> {
+        (self.type_ == GROUP_PREFIX.to_string()).then_some(self.command.as_str())
+    }
 }
 
-/// Convert a [`StringifiedAction`] into a [`String`].
+/// Convert a [`StringifiedAction`] into an [`ActionValue`].
 ///
 /// The [`Into`] trait is implemented manually instead of [`From`], as the
 /// conversion in one direction can fail - and as serde serialization derive
 /// does not provide of specifying `try_into` currently.
 #[allow(clippy::from_over_into)]
-impl Into<String> for StringifiedAction {
-    fn into(self) -> String {
-        format!("{self}")
+impl Into<ActionValue> for StringifiedAction {
+    fn into(self) -> ActionValue {
+        if self.options.is_none() && self.delay.is_none() {
+            return ActionValue::String(format!("{self}"));
+        }
+
+        ActionValue::Table {
+            type_: self.type_,
+            command: self.command,
+            delay: self.delay,
+            options: self.options.unwrap_or_default(),
+        }
     }
 }
 
-impl TryFrom<String> for StringifiedAction {
+impl TryFrom<ActionValue> for StringifiedAction {
     type Error = clap::Error;
 
-    fn try_from(value: String) -> Result<Self, Self::Error> {
-        Self::from_str(&value)
+    fn try_from(value: ActionValue) -> Result<Self, Self::Error> {
+        match value {
+            ActionValue::String(value) => Self::from_str(&value),
+            ActionValue::Table {
+                type_,
+                command,
+                delay,
+                options,
+            } => {
+                let is_command = type_ == "command";
+                if !is_command && options != ActionOptions::default() {
+                    return Err(clap::Error::raw(
+                        ErrorKind::ValueValidation,
+                        "The options of the actions (other than `delay`) are only supported by the `command` actions",
+                    ));
+                }
+
+                Ok(Self {
+                    options: is_command.then_some(options),
+                    delay,
+                    ..Self::from_str(&format!("{type_}:{command}"))?
+                })
+            }
+        }
     }
 }
 
@@ -60,26 +152,43 @@ impl FromStr for StringifiedAction {
     ///
     /// A string that specifies an action must conform to the following format:
     /// * `{action choice}:{value}`.
+    ///
+    /// Alternatively, a string can reference an action group, in the format
+    /// `@{group}`.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(group) = s.strip_prefix(GROUP_PREFIX) {
+            return if group.is_empty() {
+                Err(clap::Error::raw(
+                    ErrorKind::ValueValidation,
+                    "The value does not conform to the group reference pattern `@{group}`",
+                ))
+            } else {
+                Ok(Self::new(&GROUP_PREFIX.to_string(), group))
+            };
+        }
+
         match s.split_once(':') {
             None | Some((_, "") | ("", _)) => Err(clap::Error::raw(
                 ErrorKind::ValueValidation,
                 "The value does not conform to the action string pattern `{type}:{command}`",
             )),
             Some((action_type, action_command)) => {
-                if ActionType::VARIANTS.iter().any(|s| s == &action_type) {
-                    Ok(Self {
-                        type_: action_type.into(),
-                        command: action_command.into(),
-                    })
-                } else {
-                    Err(clap::Error::raw(
+                // Action types that are not compiled in are accepted, in
+                // order to be reported as disabled when pruning the settings.
+                match ActionType::parse(action_type) {
+                    Ok(_) | Err(ActionError::DisabledType { .. }) => {
+                        Ok(Self::new(action_type, action_command))
+                    }
+                    Err(_) => Err(clap::Error::raw(
                         ErrorKind::ValueValidation,
                         format!(
                             "The value does not start with a valid action ({:?})",
-                            ActionType::VARIANTS
+                            ACTION_TYPES
+                                .iter()
+                                .map(|(name, _)| name)
+                                .collect::<Vec<_>>()
                         ),
-                    ))
+                    )),
                 }
             }
         }
@@ -88,7 +197,10 @@ impl FromStr for StringifiedAction {
 
 impl fmt::Display for StringifiedAction {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}:{}", self.type_, self.command)
+        match self.group() {
+            Some(group) => write!(f, "{GROUP_PREFIX}{group}"),
+            None => write!(f, "{}:{}", self.type_, self.command),
+        }
     }
 }
 
@@ -106,7 +218,7 @@ pub struct Opts {
     #[arg(short, long)]
     pub seat: Option<String>,
     /// enabled action types
-    #[arg(short, long, value_parser = clap::builder::PossibleValuesParser::new(ActionType::VARIANTS))]
+    #[arg(short, long, value_parser = clap::builder::PossibleValuesParser::new(ACTION_TYPES.iter().map(|(name, _)| name)))]
     pub enabled_action_types: Option<Vec<String>>,
     /// minimum threshold for displacement changes
     #[arg(short, long)]
@@ -159,17 +271,357 @@ pub struct Opts {
     /// actions for the "four-finger swipe left-down" event
     #[arg(long)]
     pub four_finger_swipe_left_down: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger long swipe left" event
+    #[arg(long)]
+    pub three_finger_swipe_left_long: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger long swipe left-up" event
+    #[arg(long)]
+    pub three_finger_swipe_left_up_long: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger long swipe up" event
+    #[arg(long)]
+    pub three_finger_swipe_up_long: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger long swipe right-up" event
+    #[arg(long)]
+    pub three_finger_swipe_right_up_long: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger long swipe right" event
+    #[arg(long)]
+    pub three_finger_swipe_right_long: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger long swipe right-down" event
+    #[arg(long)]
+    pub three_finger_swipe_right_down_long: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger long swipe down" event
+    #[arg(long)]
+    pub three_finger_swipe_down_long: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger long swipe left-down" event
+    #[arg(long)]
+    pub three_finger_swipe_left_down_long: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger long swipe left" event
+    #[arg(long)]
+    pub four_finger_swipe_left_long: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger long swipe left-up" event
+    #[arg(long)]
+    pub four_finger_swipe_left_up_long: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger long swipe up" event
+    #[arg(long)]
+    pub four_finger_swipe_up_long: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger long swipe right-up" event
+    #[arg(long)]
+    pub four_finger_swipe_right_up_long: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger long swipe right" event
+    #[arg(long)]
+    pub four_finger_swipe_right_long: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger long swipe right-down" event
+    #[arg(long)]
+    pub four_finger_swipe_right_down_long: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger long swipe down" event
+    #[arg(long)]
+    pub four_finger_swipe_down_long: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger long swipe left-down" event
+    #[arg(long)]
+    pub four_finger_swipe_left_down_long: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger fast swipe left" event
+    #[arg(long)]
+    pub three_finger_swipe_left_fast: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger fast swipe left-up" event
+    #[arg(long)]
+    pub three_finger_swipe_left_up_fast: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger fast swipe up" event
+    #[arg(long)]
+    pub three_finger_swipe_up_fast: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger fast swipe right-up" event
+    #[arg(long)]
+    pub three_finger_swipe_right_up_fast: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger fast swipe right" event
+    #[arg(long)]
+    pub three_finger_swipe_right_fast: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger fast swipe right-down" event
+    #[arg(long)]
+    pub three_finger_swipe_right_down_fast: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger fast swipe down" event
+    #[arg(long)]
+    pub three_finger_swipe_down_fast: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger fast swipe left-down" event
+    #[arg(long)]
+    pub three_finger_swipe_left_down_fast: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger fast swipe left" event
+    #[arg(long)]
+    pub four_finger_swipe_left_fast: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger fast swipe left-up" event
+    #[arg(long)]
+    pub four_finger_swipe_left_up_fast: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger fast swipe up" event
+    #[arg(long)]
+    pub four_finger_swipe_up_fast: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger fast swipe right-up" event
+    #[arg(long)]
+    pub four_finger_swipe_right_up_fast: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger fast swipe right" event
+    #[arg(long)]
+    pub four_finger_swipe_right_fast: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger fast swipe right-down" event
+    #[arg(long)]
+    pub four_finger_swipe_right_down_fast: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger fast swipe down" event
+    #[arg(long)]
+    pub four_finger_swipe_down_fast: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger fast swipe left-down" event
+    #[arg(long)]
+    pub four_finger_swipe_left_down_fast: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger swipe left and hold" event
+    #[arg(long)]
+    pub three_finger_swipe_left_hold: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger swipe left-up and hold" event
+    #[arg(long)]
+    pub three_finger_swipe_left_up_hold: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger swipe up and hold" event
+    #[arg(long)]
+    pub three_finger_swipe_up_hold: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger swipe right-up and hold" event
+    #[arg(long)]
+    pub three_finger_swipe_right_up_hold: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger swipe right and hold" event
+    #[arg(long)]
+    pub three_finger_swipe_right_hold: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger swipe right-down and hold" event
+    #[arg(long)]
+    pub three_finger_swipe_right_down_hold: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger swipe down and hold" event
+    #[arg(long)]
+    pub three_finger_swipe_down_hold: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger swipe left-down and hold" event
+    #[arg(long)]
+    pub three_finger_swipe_left_down_hold: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger swipe left and hold" event
+    #[arg(long)]
+    pub four_finger_swipe_left_hold: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger swipe left-up and hold" event
+    #[arg(long)]
+    pub four_finger_swipe_left_up_hold: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger swipe up and hold" event
+    #[arg(long)]
+    pub four_finger_swipe_up_hold: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger swipe right-up and hold" event
+    #[arg(long)]
+    pub four_finger_swipe_right_up_hold: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger swipe right and hold" event
+    #[arg(long)]
+    pub four_finger_swipe_right_hold: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger swipe right-down and hold" event
+    #[arg(long)]
+    pub four_finger_swipe_right_down_hold: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger swipe down and hold" event
+    #[arg(long)]
+    pub four_finger_swipe_down_hold: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger swipe left-down and hold" event
+    #[arg(long)]
+    pub four_finger_swipe_left_down_hold: Option<Vec<StringifiedAction>>,
+    /// actions for the "two-finger rotate clockwise" event
+    #[arg(long)]
+    pub two_finger_rotate_clockwise: Option<Vec<StringifiedAction>>,
+    /// actions for the "two-finger rotate counter-clockwise" event
+    #[arg(long)]
+    pub two_finger_rotate_counter_clockwise: Option<Vec<StringifiedAction>>,
+    /// actions for the "two-finger pinch in" event
+    #[arg(long)]
+    pub two_finger_pinch_in: Option<Vec<StringifiedAction>>,
+    /// actions for the "two-finger strong pinch in" event
+    #[arg(long)]
+    pub two_finger_pinch_in_strong: Option<Vec<StringifiedAction>>,
+    /// actions for the "two-finger pinch out" event
+    #[arg(long)]
+    pub two_finger_pinch_out: Option<Vec<StringifiedAction>>,
+    /// actions for the "two-finger strong pinch out" event
+    #[arg(long)]
+    pub two_finger_pinch_out_strong: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger pinch in" event
+    #[arg(long)]
+    pub three_finger_pinch_in: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger strong pinch in" event
+    #[arg(long)]
+    pub three_finger_pinch_in_strong: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger pinch out" event
+    #[arg(long)]
+    pub three_finger_pinch_out: Option<Vec<StringifiedAction>>,
+    /// actions for the "three-finger strong pinch out" event
+    #[arg(long)]
+    pub three_finger_pinch_out_strong: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger pinch in" event
+    #[arg(long)]
+    pub four_finger_pinch_in: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger strong pinch in" event
+    #[arg(long)]
+    pub four_finger_pinch_in_strong: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger pinch out" event
+    #[arg(long)]
+    pub four_finger_pinch_out: Option<Vec<StringifiedAction>>,
+    /// actions for the "four-finger strong pinch out" event
+    #[arg(long)]
+    pub four_finger_pinch_out_strong: Option<Vec<StringifiedAction>>,
+    /// actions for the "edge swipe from left" event
+    #[arg(long)]
+    pub edge_swipe_from_left: Option<Vec<StringifiedAction>>,
+    /// actions for the "edge swipe from right" event
+    #[arg(long)]
+    pub edge_swipe_from_right: Option<Vec<StringifiedAction>>,
+    /// actions for the "edge swipe from top" event
+    #[arg(long)]
+    pub edge_swipe_from_top: Option<Vec<StringifiedAction>>,
+    /// actions for the "edge swipe from bottom" event
+    #[arg(long)]
+    pub edge_swipe_from_bottom: Option<Vec<StringifiedAction>>,
+    /// actions for the "tablet mode on" event
+    #[arg(long)]
+    pub tablet_mode_on: Option<Vec<StringifiedAction>>,
+    /// actions for the "tablet mode off" event
+    #[arg(long)]
+    pub tablet_mode_off: Option<Vec<StringifiedAction>>,
+    /// actions for the "lid closed" event
+    #[arg(long)]
+    pub lid_closed: Option<Vec<StringifiedAction>>,
+    /// actions for the "lid opened" event
+    #[arg(long)]
+    pub lid_opened: Option<Vec<StringifiedAction>>,
     /// invert the X axis (considering positive displacement as "left")
     #[arg(long)]
     pub invert_x: Option<bool>,
     /// invert the Y axis (considering positive displacement as "up")
     #[arg(long)]
     pub invert_y: Option<bool>,
+    /// maximum number of consecutive identical events to process at once, discarding the rest
+    #[arg(long)]
+    pub max_repeated_events: Option<u32>,
+    /// path to the tmux server socket
+    #[arg(long)]
+    pub tmux_socket: Option<String>,
+    /// URL of the obs-websocket server
+    #[arg(long)]
+    pub obs_url: Option<String>,
+    /// time (in milliseconds) after a key press during which gestures are discarded
+    #[arg(long)]
+    pub disable_while_typing: Option<u32>,
+    /// discard gestures while the focused i3 window is fullscreen
+    #[arg(long)]
+    pub ignore_fullscreen: Option<bool>,
+    /// path to the file for persisting the recorded macros
+    #[arg(long)]
+    pub macros_file: Option<String>,
+    /// maximum time (in milliseconds) between a gesture and its opposite for undoing it
+    #[arg(long)]
+    pub undo_window: Option<u32>,
+    /// minimum time (in milliseconds) between two occurrences of the same gesture
+    #[arg(long)]
+    pub rate_limit: Option<u32>,
+    /// device nodes to read gestures from, instead of the devices of the seat
+    #[arg(long)]
+    pub devices: Option<Vec<String>>,
+    /// interval (in milliseconds) between periodic ticks, such as for switching scheduled profiles
+    #[arg(long)]
+    pub tick_interval: Option<u32>,
+    /// margin (in degrees) beyond its sector that a gesture can deviate before changing its initial direction
+    #[arg(long)]
+    pub direction_margin: Option<u32>,
+    /// print the default configuration as a commented template, and exit
+    #[arg(long)]
+    pub print_default_config: bool,
+    /// print the D-Bus service file for activating lillinput on demand, and exit
+    #[arg(long)]
+    pub print_dbus_service: bool,
+    /// print the gesture bindings of a sway configuration file as a lillinput configuration, and exit
+    #[arg(long)]
+    pub import_sway_config: Option<String>,
+    /// print the gesture bindings of the configuration in the given format, and exit
+    #[arg(long, value_parser = clap::builder::PossibleValuesParser::new(["sway"]))]
+    pub export: Option<String>,
+    /// validate the actions (such as the syntax of the i3 commands) on startup, reporting the invalid ones
+    #[arg(long)]
+    pub validate_actions: Option<bool>,
+    /// name of the profile of the configuration file applied over the base settings
+    #[arg(long)]
+    pub profile: Option<String>,
+    /// mode for interpreting the thresholds, either as absolute displacements or as percentages of the touchpad size
+    #[arg(long, value_parser = clap::builder::PossibleValuesParser::new(ThresholdMode::VARIANTS))]
+    pub threshold_mode: Option<String>,
+    /// file for recording every executed action, along with its gesture and result
+    #[arg(long)]
+    pub audit_log: Option<String>,
+    /// policy for claiming the org.lillinput name on the D-Bus session bus, for finding the running instance
+    #[arg(long, value_parser = clap::builder::PossibleValuesParser::new(NamePolicy::VARIANTS))]
+    pub dbus_name_policy: Option<String>,
+    /// exit after processing this number of gestures
+    #[arg(long)]
+    pub max_events: Option<u32>,
+    /// exit after running for this number of seconds
+    #[arg(long)]
+    pub duration: Option<u32>,
+    /// re-emit the gestures without actions on a virtual touchpad, passing them through to the compositor
+    #[arg(long)]
+    pub passthrough: Option<bool>,
+    /// classes of devices to process gestures from (all by default)
+    #[arg(long)]
+    pub device_classes: Option<Vec<String>>,
+    /// record the magnitude of the swipes, suggesting a threshold for each gesture on exit
+    #[arg(long)]
+    pub learn_thresholds: Option<bool>,
+    /// minimum rotation (in degrees) of a two-finger pinch for triggering the rotation events
+    #[arg(long)]
+    pub rotation_threshold: Option<f64>,
+    /// maximum distance (in percentage of the touchscreen) from an edge for a touch to start an edge swipe
+    #[arg(long)]
+    pub edge_margin: Option<f64>,
+    /// minimum distance (in percentage of the touchscreen) travelled away from the edge for triggering an edge swipe
+    #[arg(long)]
+    pub edge_threshold: Option<f64>,
+    /// number of directions recognized for the swipes (4 or 8)
+    #[arg(long)]
+    pub directions: Option<u8>,
+    /// angle (in degrees) around the boundaries between directions in which the swipes are discarded as ambiguous
+    #[arg(long)]
+    pub direction_deadzone: Option<u32>,
+    /// minimum threshold for displacement changes on the `X` axis, overriding the global one
+    #[arg(long)]
+    pub threshold_x: Option<f64>,
+    /// minimum threshold for displacement changes on the `Y` axis, overriding the global one
+    #[arg(long)]
+    pub threshold_y: Option<f64>,
+    /// maximum time (in milliseconds) between the start and the end of a swipe, beyond which it is discarded
+    #[arg(long)]
+    pub max_gesture_duration: Option<u32>,
+    /// additional seats to process gestures from, along with the main seat
+    #[arg(long)]
+    pub extra_seats: Option<Vec<String>>,
+    /// devices to process gestures from, by name or udev property (all by default)
+    #[arg(long)]
+    pub allow_devices: Option<Vec<String>>,
+    /// devices to ignore gestures from, by name or udev property
+    #[arg(long)]
+    pub deny_devices: Option<Vec<String>>,
+    /// capture the output of the command actions, logging it (truncated) at debug level or on failure
+    #[arg(long)]
+    pub log_command_output: Option<bool>,
+    /// directory to load the plugins (.so files) providing custom action types from
+    #[arg(long)]
+    pub plugins_directory: Option<String>,
+    /// command of the long-running helper process receiving the gestures of the helper actions
+    #[arg(long)]
+    pub helper_command: Option<String>,
+    /// scale below which a pinch triggers the pinch-in events
+    #[arg(long)]
+    pub pinch_in_threshold: Option<f64>,
+    /// scale above which a pinch triggers the pinch-out events
+    #[arg(long)]
+    pub pinch_out_threshold: Option<f64>,
+    /// scale below which a pinch triggers the strong pinch-in events (disabled by default)
+    #[arg(long)]
+    pub strong_pinch_in_threshold: Option<f64>,
+    /// scale above which a pinch triggers the strong pinch-out events (disabled by default)
+    #[arg(long)]
+    pub strong_pinch_out_threshold: Option<f64>,
 }
 
 impl Opts {
     /// Return the actions registered with an event.
     #[must_use]
+    #[allow(clippy::too_many_lines)]
     pub fn get_actions_for_event(
         &self,
         action_event: ActionEvent,
@@ -191,6 +643,120 @@ impl Opts {
             ActionEvent::FourFingerSwipeRightDown => self.four_finger_swipe_right_down.as_ref(),
             ActionEvent::FourFingerSwipeDown => self.four_finger_swipe_down.as_ref(),
             ActionEvent::FourFingerSwipeLeftDown => self.four_finger_swipe_left_down.as_ref(),
+            ActionEvent::ThreeFingerSwipeLeftLong => self.three_finger_swipe_left_long.as_ref(),
+            ActionEvent::ThreeFingerSwipeLeftUpLong => {
+                self.three_finger_swipe_left_up_long.as_ref()
+            }
+            ActionEvent::ThreeFingerSwipeUpLong => self.three_finger_swipe_up_long.as_ref(),
+            ActionEvent::ThreeFingerSwipeRightUpLong => {
+                self.three_finger_swipe_right_up_long.as_ref()
+            }
+            ActionEvent::ThreeFingerSwipeRightLong => self.three_finger_swipe_right_long.as_ref(),
+            ActionEvent::ThreeFingerSwipeRightDownLong => {
+                self.three_finger_swipe_right_down_long.as_ref()
+            }
+            ActionEvent::ThreeFingerSwipeDownLong => self.three_finger_swipe_down_long.as_ref(),
+            ActionEvent::ThreeFingerSwipeLeftDownLong => {
+                self.three_finger_swipe_left_down_long.as_ref()
+            }
+            ActionEvent::FourFingerSwipeLeftLong => self.four_finger_swipe_left_long.as_ref(),
+            ActionEvent::FourFingerSwipeLeftUpLong => self.four_finger_swipe_left_up_long.as_ref(),
+            ActionEvent::FourFingerSwipeUpLong => self.four_finger_swipe_up_long.as_ref(),
+            ActionEvent::FourFingerSwipeRightUpLong => {
+                self.four_finger_swipe_right_up_long.as_ref()
+            }
+            ActionEvent::FourFingerSwipeRightLong => self.four_finger_swipe_right_long.as_ref(),
+            ActionEvent::FourFingerSwipeRightDownLong => {
+                self.four_finger_swipe_right_down_long.as_ref()
+            }
+            ActionEvent::FourFingerSwipeDownLong => self.four_finger_swipe_down_long.as_ref(),
+            ActionEvent::FourFingerSwipeLeftDownLong => {
+                self.four_finger_swipe_left_down_long.as_ref()
+            }
+            ActionEvent::ThreeFingerSwipeLeftFast => self.three_finger_swipe_left_fast.as_ref(),
+            ActionEvent::ThreeFingerSwipeLeftUpFast => {
+                self.three_finger_swipe_left_up_fast.as_ref()
+            }
+            ActionEvent::ThreeFingerSwipeUpFast => self.three_finger_swipe_up_fast.as_ref(),
+            ActionEvent::ThreeFingerSwipeRightUpFast => {
+                self.three_finger_swipe_right_up_fast.as_ref()
+            }
+            ActionEvent::ThreeFingerSwipeRightFast => self.three_finger_swipe_right_fast.as_ref(),
+            ActionEvent::ThreeFingerSwipeRightDownFast => {
+                self.three_finger_swipe_right_down_fast.as_ref()
+            }
+            ActionEvent::ThreeFingerSwipeDownFast => self.three_finger_swipe_down_fast.as_ref(),
+            ActionEvent::ThreeFingerSwipeLeftDownFast => {
+                self.three_finger_swipe_left_down_fast.as_ref()
+            }
+            ActionEvent::FourFingerSwipeLeftFast => self.four_finger_swipe_left_fast.as_ref(),
+            ActionEvent::FourFingerSwipeLeftUpFast => self.four_finger_swipe_left_up_fast.as_ref(),
+            ActionEvent::FourFingerSwipeUpFast => self.four_finger_swipe_up_fast.as_ref(),
+            ActionEvent::FourFingerSwipeRightUpFast => {
+                self.four_finger_swipe_right_up_fast.as_ref()
+            }
+            ActionEvent::FourFingerSwipeRightFast => self.four_finger_swipe_right_fast.as_ref(),
+            ActionEvent::FourFingerSwipeRightDownFast => {
+                self.four_finger_swipe_right_down_fast.as_ref()
+            }
+            ActionEvent::FourFingerSwipeDownFast => self.four_finger_swipe_down_fast.as_ref(),
+            ActionEvent::FourFingerSwipeLeftDownFast => {
+                self.four_finger_swipe_left_down_fast.as_ref()
+            }
+            ActionEvent::ThreeFingerSwipeLeftHold => self.three_finger_swipe_left_hold.as_ref(),
+            ActionEvent::ThreeFingerSwipeLeftUpHold => {
+                self.three_finger_swipe_left_up_hold.as_ref()
+            }
+            ActionEvent::ThreeFingerSwipeUpHold => self.three_finger_swipe_up_hold.as_ref(),
+            ActionEvent::ThreeFingerSwipeRightUpHold => {
+                self.three_finger_swipe_right_up_hold.as_ref()
+            }
+            ActionEvent::ThreeFingerSwipeRightHold => self.three_finger_swipe_right_hold.as_ref(),
+            ActionEvent::ThreeFingerSwipeRightDownHold => {
+                self.three_finger_swipe_right_down_hold.as_ref()
+            }
+            ActionEvent::ThreeFingerSwipeDownHold => self.three_finger_swipe_down_hold.as_ref(),
+            ActionEvent::ThreeFingerSwipeLeftDownHold => {
+                self.three_finger_swipe_left_down_hold.as_ref()
+            }
+            ActionEvent::FourFingerSwipeLeftHold => self.four_finger_swipe_left_hold.as_ref(),
+            ActionEvent::FourFingerSwipeLeftUpHold => self.four_finger_swipe_left_up_hold.as_ref(),
+            ActionEvent::FourFingerSwipeUpHold => self.four_finger_swipe_up_hold.as_ref(),
+            ActionEvent::FourFingerSwipeRightUpHold => {
+                self.four_finger_swipe_right_up_hold.as_ref()
+            }
+            ActionEvent::FourFingerSwipeRightHold => self.four_finger_swipe_right_hold.as_ref(),
+            ActionEvent::FourFingerSwipeRightDownHold => {
+                self.four_finger_swipe_right_down_hold.as_ref()
+            }
+            ActionEvent::FourFingerSwipeDownHold => self.four_finger_swipe_down_hold.as_ref(),
+            ActionEvent::FourFingerSwipeLeftDownHold => {
+                self.four_finger_swipe_left_down_hold.as_ref()
+            }
+            ActionEvent::TwoFingerRotateClockwise => self.two_finger_rotate_clockwise.as_ref(),
+            ActionEvent::TwoFingerRotateCounterClockwise => {
+                self.two_finger_rotate_counter_clockwise.as_ref()
+            }
+            ActionEvent::TwoFingerPinchIn => self.two_finger_pinch_in.as_ref(),
+            ActionEvent::TwoFingerPinchInStrong => self.two_finger_pinch_in_strong.as_ref(),
+            ActionEvent::TwoFingerPinchOut => self.two_finger_pinch_out.as_ref(),
+            ActionEvent::TwoFingerPinchOutStrong => self.two_finger_pinch_out_strong.as_ref(),
+            ActionEvent::ThreeFingerPinchIn => self.three_finger_pinch_in.as_ref(),
+            ActionEvent::ThreeFingerPinchInStrong => self.three_finger_pinch_in_strong.as_ref(),
+            ActionEvent::ThreeFingerPinchOut => self.three_finger_pinch_out.as_ref(),
+            ActionEvent::ThreeFingerPinchOutStrong => self.three_finger_pinch_out_strong.as_ref(),
+            ActionEvent::FourFingerPinchIn => self.four_finger_pinch_in.as_ref(),
+            ActionEvent::FourFingerPinchInStrong => self.four_finger_pinch_in_strong.as_ref(),
+            ActionEvent::FourFingerPinchOut => self.four_finger_pinch_out.as_ref(),
+            ActionEvent::FourFingerPinchOutStrong => self.four_finger_pinch_out_strong.as_ref(),
+            ActionEvent::EdgeSwipeFromLeft => self.edge_swipe_from_left.as_ref(),
+            ActionEvent::EdgeSwipeFromRight => self.edge_swipe_from_right.as_ref(),
+            ActionEvent::EdgeSwipeFromTop => self.edge_swipe_from_top.as_ref(),
+            ActionEvent::EdgeSwipeFromBottom => self.edge_swipe_from_bottom.as_ref(),
+            ActionEvent::TabletModeOn => self.tablet_mode_on.as_ref(),
+            ActionEvent::TabletModeOff => self.tablet_mode_off.as_ref(),
+            ActionEvent::LidClosed => self.lid_closed.as_ref(),
+            ActionEvent::LidOpened => self.lid_opened.as_ref(),
         }
     }
 }
@@ -198,11 +764,19 @@ impl Opts {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::settings::{setup_application, Settings};
+    use crate::settings::{
+        setup_application, GestureSettings, RestSettings, ScheduleSettings, SeatSettings,
+        SequenceSettings, Settings,
+    };
+    #[cfg(feature = "i3")]
     use crate::test_utils::default_test_settings;
     use clap::Parser;
+    #[cfg(feature = "i3")]
     use simplelog::LevelFilter;
+    use std::collections::HashMap;
+    #[cfg(feature = "i3")]
     use std::env;
+    #[cfg(feature = "i3")]
     use std::fs::{create_dir, File};
     use std::io::Write;
     use tempfile::Builder;
@@ -221,6 +795,16 @@ mod test {
         Opts::try_parse_from(["lillinput", "--three-finger-swipe-left", "invalid:bar"]).unwrap();
     }
 
+    #[test]
+    /// Test parsing and formatting references to action groups.
+    fn test_group_reference() {
+        let action = StringifiedAction::from_str("@nav").unwrap();
+        assert_eq!(action.group(), Some("nav"));
+        assert_eq!(action.to_string(), "@nav");
+        assert_eq!(StringifiedAction::new("i3", "foo").group(), None);
+        assert!(StringifiedAction::from_str("@").is_err());
+    }
+
     #[test]
     /// Test passing an action string as a parameter.
     fn test_action_argument_valid_action_string() {
@@ -239,6 +823,7 @@ mod test {
     }
 
     #[test]
+    #[cfg(feature = "i3")]
     /// Test conversion of `Opts` to `Settings`.
     fn test_opts_to_settings() {
         let opts: Opts = Opts::parse_from([
@@ -253,6 +838,12 @@ mod test {
             "i3",
             "--threshold",
             "20",
+            "--max-repeated-events",
+            "2",
+            "--disable-while-typing",
+            "300",
+            "--devices",
+            "/dev/input/event5",
             "--three-finger-swipe-left",
             "command:bar",
             "--three-finger-swipe-left",
@@ -300,6 +891,9 @@ mod test {
         expected_settings.seat = String::from("some.seat");
         expected_settings.enabled_action_types = vec![ActionType::I3.to_string()];
         expected_settings.threshold = 20.0;
+        expected_settings.max_repeated_events = Some(2);
+        expected_settings.disable_while_typing = Some(300);
+        expected_settings.devices = vec![String::from("/dev/input/event5")];
         for (event, command) in vec![
             (ActionEvent::ThreeFingerSwipeLeft.to_string(), "3left"),
             (ActionEvent::ThreeFingerSwipeLeftUp.to_string(), "3left-up"),
@@ -342,6 +936,7 @@ mod test {
     }
 
     #[test]
+    #[cfg(feature = "i3")]
     /// Test using a config file.
     fn test_config_file() {
         let mut file = Builder::new().suffix(".toml").tempfile().unwrap();
@@ -388,6 +983,587 @@ four-finger-swipe-right = ["i3:bar", "command:baz"]
     }
 
     #[test]
+    /// Test using a config file with `REST` request definitions.
+    fn test_config_file_rest() {
+        let mut file = Builder::new().suffix(".toml").tempfile().unwrap();
+        let file_path = String::from(file.path().to_str().unwrap());
+
+        writeln!(
+            file,
+            r#"
+[rest.notify]
+method = "POST"
+url = "https://example.com/notify"
+headers = {{ authorization = "Bearer token" }}
+body = '{{"gesture": "{{event}}"}}'
+
+[rest.ping]
+url = "https://example.com/ping"
+"#
+        )
+        .unwrap();
+
+        let opts: Opts = Opts::parse_from(["lillinput", "--config-file", &file_path]);
+        let converted_settings: Settings = setup_application(opts, false).unwrap();
+
+        // Build expected settings:
+        // * the method should fall back to `GET` if not provided.
+        let expected_rest = HashMap::from([
+            (
+                String::from("notify"),
+                RestSettings {
+                    method: String::from("POST"),
+                    url: String::from("https://example.com/notify"),
+                    headers: HashMap::from([(
+                        String::from("authorization"),
+                        String::from("Bearer token"),
+                    )]),
+                    body: Some(String::from(r#"{"gesture": "{event}"}"#)),
+                },
+            ),
+            (
+                String::from("ping"),
+                RestSettings {
+                    method: String::from("GET"),
+                    url: String::from("https://example.com/ping"),
+                    headers: HashMap::new(),
+                    body: None,
+                },
+            ),
+        ]);
+
+        assert_eq!(converted_settings.rest, expected_rest);
+    }
+
+    #[test]
+    /// Test using a config file with profiles and schedules.
+    fn test_config_file_profiles() {
+        let mut file = Builder::new().suffix(".toml").tempfile().unwrap();
+        let file_path = String::from(file.path().to_str().unwrap());
+
+        writeln!(
+            file,
+            r#"
+enabled_action_types = ["command"]
+
+[profiles.work]
+three-finger-swipe-up = ["command:foo", "i3:bar"]
+
+[[schedules]]
+profile = "work"
+start = "09:00"
+end = "17:00"
+days = ["mon", "tue"]
+"#
+        )
+        .unwrap();
+
+        let opts: Opts = Opts::parse_from(["lillinput", "--config-file", &file_path]);
+        let converted_settings: Settings = setup_application(opts, false).unwrap();
+
+        // Assert that the disabled action types are pruned from the profiles.
+        assert_eq!(
+            converted_settings.profiles,
+            HashMap::from([(
+                String::from("work"),
+                HashMap::from([(
+                    ActionEvent::ThreeFingerSwipeUp.to_string(),
+                    vec![StringifiedAction::new("command", "foo")]
+                )])
+            )])
+        );
+        assert_eq!(
+            converted_settings.schedules,
+            vec![ScheduleSettings {
+                profile: String::from("work"),
+                start: String::from("09:00"),
+                end: String::from("17:00"),
+                days: vec![String::from("mon"), String::from("tue")],
+            }]
+        );
+    }
+
+    #[test]
+    /// Test using a config file with actions for specific devices.
+    fn test_config_file_device_actions() {
+        let mut file = Builder::new().suffix(".toml").tempfile().unwrap();
+        let file_path = String::from(file.path().to_str().unwrap());
+
+        writeln!(
+            file,
+            r#"
+enabled_action_types = ["command"]
+
+[device_actions."Apple Magic Trackpad"]
+three-finger-swipe-up = ["command:foo", "i3:bar"]
+three-finger-swipe-down = ["i3:baz"]
+
+[device_actions.event5]
+four-finger-swipe-up = ["command:qux"]
+"#
+        )
+        .unwrap();
+
+        let opts: Opts = Opts::parse_from(["lillinput", "--config-file", &file_path]);
+        let converted_settings: Settings = setup_application(opts, false).unwrap();
+
+        // Assert that the disabled action types are pruned from the devices.
+        let mut device_actions: Vec<_> = converted_settings.device_actions.into_iter().collect();
+        device_actions.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(device_actions.len(), 2);
+        assert!(device_actions[0]
+            .0
+            .eq_ignore_ascii_case("Apple Magic Trackpad"));
+        assert_eq!(
+            device_actions[0].1,
+            HashMap::from([(
+                ActionEvent::ThreeFingerSwipeUp.to_string(),
+                vec![StringifiedAction::new("command", "foo")]
+            )])
+        );
+        assert_eq!(
+            device_actions[1],
+            (
+                String::from("event5"),
+                HashMap::from([(
+                    ActionEvent::FourFingerSwipeUp.to_string(),
+                    vec![StringifiedAction::new("command", "qux")]
+                )])
+            )
+        );
+    }
+
+    #[test]
+    /// Test overriding the settings for the seat in use.
+    fn test_config_file_seats() {
+        let mut file = Builder::new().suffix(".toml").tempfile().unwrap();
+        let file_path = String::from(file.path().to_str().unwrap());
+
+        writeln!(
+            file,
+            r#"
+enabled_action_types = ["command"]
+threshold = 10.0
+seat = "seat1"
+extra_seats = ["seat2"]
+
+[actions]
+three-finger-swipe-up = ["command:foo"]
+three-finger-swipe-down = ["command:bar"]
+
+[seats.seat1]
+threshold = 30.0
+invert_x = true
+
+[seats.seat1.actions]
+three-finger-swipe-up = ["command:baz"]
+
+[seats.seat2]
+threshold = 50.0
+"#
+        )
+        .unwrap();
+
+        // Assert that the section of the seat is merged over the base
+        // settings, ignoring the unsupported settings.
+        let opts: Opts = Opts::parse_from(["lillinput", "--config-file", &file_path]);
+        let converted_settings: Settings = setup_application(opts, false).unwrap();
+        assert!((converted_settings.threshold - 30.0).abs() < f64::EPSILON);
+        assert!(!converted_settings.invert_x);
+        assert_eq!(converted_settings.extra_seats, vec![String::from("seat2")]);
+        assert_eq!(
+            converted_settings.actions,
+            HashMap::from([
+                (
+                    ActionEvent::ThreeFingerSwipeUp.to_string(),
+                    vec![StringifiedAction::new("command", "baz")]
+                ),
+                (
+                    ActionEvent::ThreeFingerSwipeDown.to_string(),
+                    vec![StringifiedAction::new("command", "bar")]
+                ),
+            ])
+        );
+
+        // Assert that the sections of the rest of the seats are kept for the
+        // additional seats.
+        assert_eq!(
+            converted_settings.seats,
+            HashMap::from([(
+                String::from("seat2"),
+                SeatSettings {
+                    threshold: Some(50.0),
+                    actions: HashMap::new(),
+                }
+            )])
+        );
+
+        // Assert that the seat from the arguments selects the section, and
+        // the arguments take precedence over it.
+        let opts: Opts =
+            Opts::parse_from(["lillinput", "--config-file", &file_path, "--seat", "seat2"]);
+        let converted_settings: Settings = setup_application(opts, false).unwrap();
+        assert!((converted_settings.threshold - 50.0).abs() < f64::EPSILON);
+        assert_eq!(
+            converted_settings.seats,
+            HashMap::from([(
+                String::from("seat1"),
+                SeatSettings {
+                    threshold: Some(30.0),
+                    actions: HashMap::from([(
+                        ActionEvent::ThreeFingerSwipeUp.to_string(),
+                        vec![StringifiedAction::new("command", "baz")]
+                    )]),
+                }
+            )])
+        );
+        let opts: Opts = Opts::parse_from([
+            "lillinput",
+            "--config-file",
+            &file_path,
+            "--threshold",
+            "20.0",
+        ]);
+        let converted_settings: Settings = setup_application(opts, false).unwrap();
+        assert!((converted_settings.threshold - 20.0).abs() < f64::EPSILON);
+
+        // Assert that the additional seats from the arguments take
+        // precedence over the ones from the config file.
+        let opts: Opts = Opts::parse_from([
+            "lillinput",
+            "--config-file",
+            &file_path,
+            "--extra-seats",
+            "seat3",
+        ]);
+        let converted_settings: Settings = setup_application(opts, false).unwrap();
+        assert_eq!(converted_settings.extra_seats, vec![String::from("seat3")]);
+    }
+
+    #[test]
+    /// Test selecting a profile of the config file.
+    fn test_config_file_selected_profile() {
+        let mut file = Builder::new().suffix(".toml").tempfile().unwrap();
+        let file_path = String::from(file.path().to_str().unwrap());
+
+        writeln!(
+            file,
+            r#"
+enabled_action_types = ["command"]
+threshold = 10.0
+profile = "docked"
+
+[actions]
+three-finger-swipe-up = ["command:foo"]
+three-finger-swipe-down = ["command:bar"]
+
+[profiles.docked]
+threshold = 30.0
+
+[profiles.travel]
+invert_x = true
+three-finger-swipe-up = ["command:baz"]
+"#
+        )
+        .unwrap();
+
+        // Assert that the profile from the config file is applied.
+        let opts: Opts = Opts::parse_from(["lillinput", "--config-file", &file_path]);
+        let converted_settings: Settings = setup_application(opts, false).unwrap();
+        assert!((converted_settings.threshold - 30.0).abs() < f64::EPSILON);
+        assert!(!converted_settings.invert_x);
+
+        // Assert that the profile from the arguments is merged over the base
+        // settings, and its settings are removed from the profiles.
+        let opts: Opts = Opts::parse_from([
+            "lillinput",
+            "--config-file",
+            &file_path,
+            "--profile",
+            "travel",
+        ]);
+        let converted_settings: Settings = setup_application(opts, false).unwrap();
+        assert!((converted_settings.threshold - 10.0).abs() < f64::EPSILON);
+        assert!(converted_settings.invert_x);
+        assert_eq!(
+            converted_settings.actions,
+            HashMap::from([
+                (
+                    ActionEvent::ThreeFingerSwipeUp.to_string(),
+                    vec![StringifiedAction::new("command", "baz")]
+                ),
+                (
+                    ActionEvent::ThreeFingerSwipeDown.to_string(),
+                    vec![StringifiedAction::new("command", "bar")]
+                ),
+            ])
+        );
+        assert_eq!(
+            converted_settings.profiles,
+            HashMap::from([
+                (String::from("docked"), HashMap::new()),
+                (
+                    String::from("travel"),
+                    HashMap::from([(
+                        ActionEvent::ThreeFingerSwipeUp.to_string(),
+                        vec![StringifiedAction::new("command", "baz")]
+                    )])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    /// Test using a config file with action groups.
+    fn test_config_file_groups() {
+        let mut file = Builder::new().suffix(".toml").tempfile().unwrap();
+        let file_path = String::from(file.path().to_str().unwrap());
+
+        writeln!(
+            file,
+            r#"
+enabled_action_types = ["command"]
+
+[actions]
+three-finger-swipe-up = ["@nav", "command:baz", "@unknown"]
+
+[groups.nav]
+actions = ["command:foo", "i3:bar", "@nav"]
+
+[gestures.swipe.4.up]
+actions = ["@nav"]
+"#
+        )
+        .unwrap();
+
+        let opts: Opts = Opts::parse_from([
+            "lillinput",
+            "--config-file",
+            &file_path,
+            "--three-finger-swipe-down",
+            "@nav",
+        ]);
+        let converted_settings: Settings = setup_application(opts, false).unwrap();
+
+        // Assert that the references are expanded, and the invalid ones
+        // removed.
+        assert_eq!(
+            converted_settings.actions,
+            HashMap::from([
+                (
+                    ActionEvent::ThreeFingerSwipeUp.to_string(),
+                    vec![
+                        StringifiedAction::new("command", "foo"),
+                        StringifiedAction::new("command", "baz")
+                    ]
+                ),
+                (
+                    ActionEvent::ThreeFingerSwipeDown.to_string(),
+                    vec![StringifiedAction::new("command", "foo")]
+                ),
+            ])
+        );
+        assert_eq!(
+            converted_settings.gestures["swipe"]["4"]["up"].actions,
+            vec![StringifiedAction::new("command", "foo")]
+        );
+    }
+
+    #[test]
+    /// Test using a config file with the nested gestures format.
+    fn test_config_file_gestures() {
+        let mut file = Builder::new().suffix(".toml").tempfile().unwrap();
+        let file_path = String::from(file.path().to_str().unwrap());
+
+        writeln!(
+            file,
+            r#"
+enabled_action_types = ["command"]
+
+[gestures.swipe.3.up]
+actions = ["command:foo", "i3:bar"]
+threshold = 30.0
+mode = "parallel"
+cooldown = 500
+
+[gestures.swipe.5.up]
+actions = ["command:baz"]
+"#
+        )
+        .unwrap();
+
+        let opts: Opts = Opts::parse_from(["lillinput", "--config-file", &file_path]);
+        let converted_settings: Settings = setup_application(opts, false).unwrap();
+
+        // Assert that the unknown gestures and disabled action types are pruned.
+        assert_eq!(
+            converted_settings.gestures,
+            HashMap::from([(
+                String::from("swipe"),
+                HashMap::from([
+                    (
+                        String::from("3"),
+                        HashMap::from([(
+                            String::from("up"),
+                            GestureSettings {
+                                actions: vec![StringifiedAction::new("command", "foo")],
+                                threshold: Some(30.0),
+                                long_threshold: None,
+                                fast_velocity: None,
+                                hold_time: None,
+                                progress_step: None,
+                                repeat: None,
+                                mode: Some(String::from("parallel")),
+                                cooldown: Some(500),
+                            }
+                        )])
+                    ),
+                    (String::from("5"), HashMap::new())
+                ])
+            )])
+        );
+    }
+
+    #[test]
+    /// Test using a config file with undo actions.
+    fn test_config_file_undo_actions() {
+        let mut file = Builder::new().suffix(".toml").tempfile().unwrap();
+        let file_path = String::from(file.path().to_str().unwrap());
+
+        writeln!(
+            file,
+            r#"
+enabled_action_types = ["command"]
+undo_window = 300
+
+[undo_actions]
+three-finger-swipe-up = ["command:foo", "i3:bar"]
+three-finger-swipe-down = ["i3:baz"]
+"#
+        )
+        .unwrap();
+
+        let opts: Opts = Opts::parse_from(["lillinput", "--config-file", &file_path]);
+        let converted_settings: Settings = setup_application(opts, false).unwrap();
+
+        // Assert that the disabled action types are pruned from the undo actions.
+        assert_eq!(
+            converted_settings.undo_actions,
+            HashMap::from([(
+                ActionEvent::ThreeFingerSwipeUp.to_string(),
+                vec![StringifiedAction::new("command", "foo")]
+            )])
+        );
+        assert_eq!(converted_settings.undo_window, 300);
+    }
+
+    #[test]
+    /// Test using a config file with actions in the table form.
+    fn test_config_file_action_tables() {
+        let mut file = Builder::new().suffix(".toml").tempfile().unwrap();
+        let file_path = String::from(file.path().to_str().unwrap());
+
+        writeln!(
+            file,
+            r#"
+enabled_action_types = ["command", "log"]
+
+[actions]
+three-finger-swipe-up = [
+    "command:foo",
+    {{ type = "command", command = "bar | baz", shell = true, working_directory = "/tmp", env = {{ FOO = "1" }} }},
+    {{ type = "log", command = "qux", delay = 100 }},
+]
+"#
+        )
+        .unwrap();
+
+        let opts: Opts = Opts::parse_from(["lillinput", "--config-file", &file_path]);
+        let converted_settings: Settings = setup_application(opts, false).unwrap();
+
+        // Assert that the options are parsed.
+        assert_eq!(
+            converted_settings.actions,
+            HashMap::from([(
+                ActionEvent::ThreeFingerSwipeUp.to_string(),
+                vec![
+                    StringifiedAction::new("command", "foo"),
+                    StringifiedAction {
+                        options: Some(ActionOptions {
+                            working_directory: Some(String::from("/tmp")),
+                            env: HashMap::from([(String::from("FOO"), String::from("1"))]),
+                            shell: true,
+                        }),
+                        ..StringifiedAction::new("command", "bar | baz")
+                    },
+                    StringifiedAction {
+                        delay: Some(100),
+                        ..StringifiedAction::new("log", "qux")
+                    }
+                ]
+            )])
+        );
+
+        // Assert that the options are only accepted for the command actions,
+        // reverting to the default settings otherwise.
+        let mut file = Builder::new().suffix(".toml").tempfile().unwrap();
+        let file_path = String::from(file.path().to_str().unwrap());
+        writeln!(
+            file,
+            r#"
+[actions]
+three-finger-swipe-up = [{{ type = "i3", command = "foo", shell = true }}]
+"#
+        )
+        .unwrap();
+        let opts: Opts = Opts::parse_from(["lillinput", "--config-file", &file_path]);
+        let converted_settings: Settings = setup_application(opts, false).unwrap();
+        assert_eq!(converted_settings.actions, Settings::default().actions);
+    }
+
+    #[test]
+    /// Test using a config file with sequences of gestures.
+    fn test_config_file_sequences() {
+        let mut file = Builder::new().suffix(".toml").tempfile().unwrap();
+        let file_path = String::from(file.path().to_str().unwrap());
+
+        writeln!(
+            file,
+            r#"
+enabled_action_types = ["command"]
+
+[[sequences]]
+gestures = ["three-finger-swipe-up", "three-finger-swipe-down"]
+actions = ["command:foo", "i3:bar"]
+
+[[sequences]]
+gestures = ["four-finger-swipe-left", "four-finger-swipe-right"]
+timeout = 300
+actions = ["i3:baz"]
+"#
+        )
+        .unwrap();
+
+        let opts: Opts = Opts::parse_from(["lillinput", "--config-file", &file_path]);
+        let converted_settings: Settings = setup_application(opts, false).unwrap();
+
+        // Assert that the disabled action types are pruned, along with the
+        // sequences left without actions.
+        assert_eq!(
+            converted_settings.sequences,
+            vec![SequenceSettings {
+                gestures: vec![
+                    ActionEvent::ThreeFingerSwipeUp.to_string(),
+                    ActionEvent::ThreeFingerSwipeDown.to_string()
+                ],
+                timeout: 500,
+                actions: vec![StringifiedAction::new("command", "foo")]
+            }]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "i3")]
     /// Test using a config file from the default set (at `XDG_CONFIG_HOME`).
     fn test_config_file_from_xdg_config_home() {
         // Create a temporary dir.
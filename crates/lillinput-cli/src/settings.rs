@@ -1,23 +1,72 @@
 //! Functionality related to application settings and related tooling.
 
-use std::cell::RefCell;
-use std::collections::HashMap;
-use std::rc::Rc;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::string::ToString;
+#[cfg(feature = "i3")]
+use std::sync::PoisonError;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+use crate::dbus::NamePolicy;
 use crate::opts::{Opts, StringifiedAction};
-use config::{Config, ConfigError, File, Map, Source, Value};
+use chrono::{NaiveTime, Weekday};
+use clap::CommandFactory;
+use config::{Config, ConfigError, File, Map, Source, Value, ValueKind};
+#[cfg(feature = "i3")]
 use i3ipc::I3Connection;
-use lillinput::actions::{Action, ActionType, CommandAction, I3Action, SharedConnection};
-use lillinput::events::ActionEvent;
+#[cfg(feature = "clipboard")]
+use lillinput::actions::ClipboardAction;
+#[cfg(feature = "tmux")]
+use lillinput::actions::TmuxAction;
+#[cfg(feature = "i3")]
+use lillinput::actions::{watch_i3_shutdown, I3Action, I3BuiltinAction, SharedConnection};
+use lillinput::actions::{
+    Action, ActionType, DelayedAction, InternalAction, LogAction, MacroRecorder, RecordMacroAction,
+    SharedMacroRecorder, ACTION_TYPES,
+};
+#[cfg(feature = "command")]
+use lillinput::actions::{CommandAction, CommandOptions};
+#[cfg(feature = "helper")]
+use lillinput::actions::{
+    HelperAction, HelperProcess, SharedHelperProcess, DEFAULT_HELPER_TIMEOUT,
+};
+#[cfg(feature = "obs")]
+use lillinput::actions::{ObsAction, ObsConnection, SharedObsConnection};
+#[cfg(feature = "plugins")]
+use lillinput::actions::{PluginAction, PluginRegistry};
+#[cfg(feature = "uinput")]
+use lillinput::actions::{
+    PointerAction, SharedVirtualPointer, SharedVirtualTouchpad, UinputAction, VirtualPointer,
+    VirtualTouchpad,
+};
+#[cfg(feature = "rest")]
+use lillinput::actions::{RestAction, RestRequest};
+#[cfg(feature = "river")]
+use lillinput::actions::{RiverAction, RiverConnection, SharedRiverConnection};
+#[cfg(feature = "wtype")]
+use lillinput::actions::{SharedVirtualKeyboard, VirtualKeyboard, WtypeAction};
+#[cfg(feature = "volume")]
+use lillinput::actions::{SharedVolumeConnection, VolumeAction, VolumeConnection};
+#[cfg(feature = "xdo")]
+use lillinput::actions::{SharedXdoConnection, XdoAction, XdoConnection};
+#[cfg(feature = "i3")]
+use lillinput::controllers::FullscreenFilter;
+use lillinput::controllers::{
+    ActionMap, AuditLog, EventFilter, ExecutionMode, GestureSequence, RateLimitFilter, Schedule,
+    SharedControllerState,
+};
+use lillinput::events::{ActionEvent, DeviceClass, Modifier, ThresholdMode, SWIPE_DIRECTIONS};
 use log::{info, warn, SetLoggerError};
 use serde::{Deserialize, Serialize};
 use simplelog::{ColorChoice, Config as LogConfig, Level, LevelFilter, TermLogger, TerminalMode};
-use strum::IntoEnumIterator;
+use strum::{IntoEnumIterator, VariantNames};
 
 /// Application settings.
 #[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Settings {
     /// Level of verbosity.
     pub verbose: LevelFilter,
@@ -33,6 +82,295 @@ pub struct Settings {
     pub invert_x: bool,
     /// Invert the `Y` axis (considering positive displacement as "up")
     pub invert_y: bool,
+    /// Mode for executing the actions of each action event.
+    #[serde(default)]
+    pub execution_modes: HashMap<String, String>,
+    /// Maximum number of consecutive identical events processed from a single dispatch.
+    #[serde(default)]
+    pub max_repeated_events: Option<u32>,
+    /// Path to the `tmux` server socket.
+    #[serde(default)]
+    pub tmux_socket: Option<String>,
+    /// URL of the `obs-websocket` server.
+    pub obs_url: String,
+    /// Password for the `obs-websocket` server.
+    #[serde(default)]
+    pub obs_password: Option<String>,
+    /// Definitions of `REST` requests, indexed by name.
+    #[serde(default)]
+    pub rest: HashMap<String, RestSettings>,
+    /// Time (in milliseconds) after a key press during which gestures are discarded.
+    #[serde(default)]
+    pub disable_while_typing: Option<u32>,
+    /// Discard gestures while the focused `i3` window is fullscreen.
+    pub ignore_fullscreen: bool,
+    /// List of action for each action event, for each profile.
+    #[serde(default)]
+    pub profiles: HashMap<String, HashMap<String, Vec<StringifiedAction>>>,
+    /// Schedules for activating the profiles, in order of precedence.
+    #[serde(default)]
+    pub schedules: Vec<ScheduleSettings>,
+    /// Path to the file for persisting the recorded macros.
+    #[serde(default)]
+    pub macros_file: Option<String>,
+    /// List of actions for undoing each action event, if followed by its opposite event.
+    #[serde(default)]
+    pub undo_actions: HashMap<String, Vec<StringifiedAction>>,
+    /// Maximum time (in milliseconds) between an event and its opposite for undoing it.
+    pub undo_window: u32,
+    /// Minimum time (in milliseconds) between two occurrences of the same event.
+    #[serde(default)]
+    pub rate_limit: Option<u32>,
+    /// Device nodes to read gestures from, instead of the devices of the seat.
+    #[serde(default)]
+    pub devices: Vec<String>,
+    /// Interval (in milliseconds) between periodic ticks of the controller.
+    #[serde(default)]
+    pub tick_interval: Option<u32>,
+    /// Margin (in degrees) beyond its sector that a gesture can deviate before changing its initial direction.
+    #[serde(default)]
+    pub direction_margin: Option<u32>,
+    /// Settings for each gesture, indexed by kind, number of fingers and direction.
+    #[serde(default)]
+    pub gestures: HashMap<String, HashMap<String, HashMap<String, GestureSettings>>>,
+    /// Validate the actions (such as the syntax of the `i3` commands) on startup.
+    pub validate_actions: bool,
+    /// Name of the profile of the configuration file applied over the base settings.
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Reusable groups of actions, referenced from the lists of actions as `@{group}`.
+    #[serde(default)]
+    pub groups: HashMap<String, GroupSettings>,
+    /// Mode for interpreting the thresholds, either as absolute displacements or as percentages of the touchpad size.
+    pub threshold_mode: String,
+    /// File for recording every executed action, along with its gesture and result.
+    #[serde(default)]
+    pub audit_log: Option<String>,
+    /// Policy for claiming the `org.lillinput` name on the D-Bus session bus, for finding the running instance.
+    pub dbus_name_policy: String,
+    /// Exit after processing this number of gestures.
+    #[serde(default)]
+    pub max_events: Option<u32>,
+    /// Exit after running for this number of seconds.
+    #[serde(default)]
+    pub duration: Option<u32>,
+    /// Re-emit the gestures without actions on a virtual touchpad.
+    pub passthrough: bool,
+    /// Classes of devices to process gestures from (all if empty).
+    #[serde(default)]
+    pub device_classes: Vec<String>,
+    /// Record the magnitude of the swipes, suggesting a threshold for each gesture on exit.
+    pub learn_thresholds: bool,
+    /// Minimum rotation (in degrees) of a two-finger pinch for triggering the rotation events.
+    pub rotation_threshold: f64,
+    /// Maximum distance (in percentage of the touchscreen) from an edge for a touch to start an edge swipe.
+    pub edge_margin: f64,
+    /// Minimum distance (in percentage of the touchscreen) travelled away from the edge for triggering an edge swipe.
+    pub edge_threshold: f64,
+    /// Number of directions recognized for the swipes (`4` or `8`).
+    pub directions: u8,
+    /// Width (in degrees) of the sector of each swipe direction.
+    #[serde(default)]
+    pub sector_widths: HashMap<String, f64>,
+    /// Angle (in degrees) around the boundaries between directions in which the swipes are discarded as ambiguous.
+    #[serde(default)]
+    pub direction_deadzone: Option<u32>,
+    /// Minimum threshold for displacement changes on the `X` axis, overriding the global one.
+    #[serde(default)]
+    pub threshold_x: Option<f64>,
+    /// Minimum threshold for displacement changes on the `Y` axis, overriding the global one.
+    #[serde(default)]
+    pub threshold_y: Option<f64>,
+    /// Maximum time (in milliseconds) between the start and the end of a swipe, beyond which it is discarded.
+    #[serde(default)]
+    pub max_gesture_duration: Option<u32>,
+    /// Sequences of gestures with their own actions, performed in quick succession.
+    #[serde(default)]
+    pub sequences: Vec<SequenceSettings>,
+    /// List of action for each action event, for each device (by name or system name).
+    #[serde(default)]
+    pub device_actions: HashMap<String, HashMap<String, Vec<StringifiedAction>>>,
+    /// Additional seats to process gestures from, along with the main seat.
+    #[serde(default)]
+    pub extra_seats: Vec<String>,
+    /// Settings of each additional seat, overriding the ones of the main seat.
+    #[serde(default)]
+    pub seats: HashMap<String, SeatSettings>,
+    /// Devices to process gestures from, by name or `udev` property (all if empty).
+    #[serde(default)]
+    pub allow_devices: Vec<String>,
+    /// Devices to ignore gestures from, by name or `udev` property.
+    #[serde(default)]
+    pub deny_devices: Vec<String>,
+    /// Capture the output of the command actions, logging it (truncated).
+    pub log_command_output: bool,
+    /// Directory to load the plugins (`.so` files) providing custom action types from (by default, `plugins` in the configuration directory).
+    #[serde(default)]
+    pub plugins_directory: Option<String>,
+    /// Command of the long-running helper process receiving the gestures of the `helper` actions.
+    #[serde(default)]
+    pub helper_command: Option<String>,
+    /// Scale below which a pinch triggers the pinch-in events.
+    pub pinch_in_threshold: f64,
+    /// Scale above which a pinch triggers the pinch-out events.
+    pub pinch_out_threshold: f64,
+    /// Scale below which a pinch triggers the strong pinch-in events, if enabled.
+    #[serde(default)]
+    pub strong_pinch_in_threshold: Option<f64>,
+    /// Scale above which a pinch triggers the strong pinch-out events, if enabled.
+    #[serde(default)]
+    pub strong_pinch_out_threshold: Option<f64>,
+}
+
+/// Settings for a gesture in the nested `gestures` section.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct GestureSettings {
+    /// List of actions for the gesture.
+    #[serde(default)]
+    pub actions: Vec<StringifiedAction>,
+    /// Minimum threshold for displacement changes, overriding the global one.
+    #[serde(default)]
+    pub threshold: Option<f64>,
+    /// Minimum threshold for displacement changes in order to trigger the
+    /// long variant of the gesture.
+    #[serde(default)]
+    pub long_threshold: Option<f64>,
+    /// Minimum velocity (displacement per second) in order to trigger the
+    /// fast variant of the gesture.
+    #[serde(default)]
+    pub fast_velocity: Option<f64>,
+    /// Minimum time (in milliseconds) the fingers are held still at the end
+    /// of the gesture in order to trigger its hold variant.
+    #[serde(default)]
+    pub hold_time: Option<u32>,
+    /// Displacement between the progress events emitted while the gesture is
+    /// in progress, enabling the continuous mode for the gesture.
+    #[serde(default)]
+    pub progress_step: Option<f64>,
+    /// Whether the gesture is emitted again each time the displacement
+    /// reaches another multiple of its threshold while in progress.
+    #[serde(default)]
+    pub repeat: Option<bool>,
+    /// Mode for executing the actions of the gesture.
+    #[serde(default)]
+    pub mode: Option<String>,
+    /// Minimum time (in milliseconds) between two occurrences of the gesture.
+    #[serde(default)]
+    pub cooldown: Option<u32>,
+}
+
+/// Settings for a reusable group of actions.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct GroupSettings {
+    /// List of actions of the group.
+    #[serde(default)]
+    pub actions: Vec<StringifiedAction>,
+}
+
+/// Settings overridden for an additional seat.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct SeatSettings {
+    /// Minimum threshold for displacement changes, overriding the global one.
+    #[serde(default)]
+    pub threshold: Option<f64>,
+    /// List of actions for each action event, overriding the global ones.
+    #[serde(default)]
+    pub actions: HashMap<String, Vec<StringifiedAction>>,
+}
+
+/// Settings for a `REST` request.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct RestSettings {
+    /// HTTP method of the request.
+    #[serde(default = "RestSettings::default_method")]
+    pub method: String,
+    /// URL of the request.
+    pub url: String,
+    /// Headers of the request.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Optional body template of the request.
+    #[serde(default)]
+    pub body: Option<String>,
+}
+
+impl RestSettings {
+    /// Return the default HTTP method for a request.
+    fn default_method() -> String {
+        String::from("GET")
+    }
+}
+
+/// Settings for a profile schedule.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct ScheduleSettings {
+    /// Name of the profile activated by the schedule.
+    pub profile: String,
+    /// Start of the time window, in `HH:MM` format.
+    pub start: String,
+    /// End of the time window, in `HH:MM` format.
+    pub end: String,
+    /// Days of the week in which the schedule is active (all if empty).
+    #[serde(default)]
+    pub days: Vec<String>,
+}
+
+/// Settings for a sequence of gestures.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct SequenceSettings {
+    /// Gestures of the sequence, in order.
+    pub gestures: Vec<String>,
+    /// Maximum time (in milliseconds) between two consecutive gestures of the sequence.
+    #[serde(default = "SequenceSettings::default_timeout")]
+    pub timeout: u32,
+    /// List of actions executed when the sequence is performed.
+    #[serde(default)]
+    pub actions: Vec<StringifiedAction>,
+}
+
+impl SequenceSettings {
+    /// Return the default maximum time between the gestures of a sequence.
+    fn default_timeout() -> u32 {
+        500
+    }
+}
+
+impl TryFrom<&ScheduleSettings> for Schedule {
+    type Error = String;
+
+    fn try_from(schedule_settings: &ScheduleSettings) -> Result<Self, Self::Error> {
+        let parse_time = |value: &str| {
+            NaiveTime::parse_from_str(value, "%H:%M")
+                .map_err(|e| format!("invalid time {value}: {e}"))
+        };
+
+        Ok(Schedule {
+            profile: schedule_settings.profile.clone(),
+            start: parse_time(&schedule_settings.start)?,
+            end: parse_time(&schedule_settings.end)?,
+            days: schedule_settings
+                .days
+                .iter()
+                .map(|x| Weekday::from_str(x).map_err(|_| format!("invalid day {x}")))
+                .collect::<Result<Vec<Weekday>, String>>()?,
+        })
+    }
+}
+
+#[cfg(feature = "rest")]
+impl From<&RestSettings> for RestRequest {
+    fn from(rest_settings: &RestSettings) -> Self {
+        RestRequest {
+            method: rest_settings.method.clone(),
+            url: rest_settings.url.clone(),
+            headers: rest_settings
+                .headers
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            body: rest_settings.body.clone(),
+        }
+    }
 }
 
 impl Default for Settings {
@@ -40,8 +378,16 @@ impl Default for Settings {
         Settings {
             verbose: LevelFilter::Info,
             seat: "seat0".to_string(),
+            #[cfg(feature = "i3")]
             enabled_action_types: vec![ActionType::I3.to_string()],
+            #[cfg(all(not(feature = "i3"), feature = "command"))]
+            enabled_action_types: vec![ActionType::Command.to_string()],
+            #[cfg(not(any(feature = "i3", feature = "command")))]
+            enabled_action_types: vec![],
             threshold: 20.0,
+            #[cfg(not(feature = "i3"))]
+            actions: HashMap::new(),
+            #[cfg(feature = "i3")]
             actions: HashMap::from([
                 (
                     ActionEvent::ThreeFingerSwipeLeft.to_string(),
@@ -54,6 +400,57 @@ impl Default for Settings {
             ]),
             invert_x: false,
             invert_y: false,
+            execution_modes: HashMap::new(),
+            max_repeated_events: None,
+            tmux_socket: None,
+            obs_url: String::from("ws://localhost:4455"),
+            obs_password: None,
+            rest: HashMap::new(),
+            disable_while_typing: None,
+            ignore_fullscreen: false,
+            profiles: HashMap::new(),
+            schedules: vec![],
+            macros_file: None,
+            undo_actions: HashMap::new(),
+            undo_window: 500,
+            rate_limit: None,
+            devices: vec![],
+            tick_interval: None,
+            direction_margin: None,
+            gestures: HashMap::new(),
+            validate_actions: false,
+            profile: None,
+            groups: HashMap::new(),
+            threshold_mode: ThresholdMode::default().to_string(),
+            audit_log: None,
+            dbus_name_policy: NamePolicy::default().to_string(),
+            max_events: None,
+            duration: None,
+            passthrough: false,
+            device_classes: vec![],
+            learn_thresholds: false,
+            rotation_threshold: 30.0,
+            edge_margin: 3.0,
+            edge_threshold: 10.0,
+            directions: 8,
+            sector_widths: HashMap::new(),
+            direction_deadzone: None,
+            threshold_x: None,
+            threshold_y: None,
+            max_gesture_duration: None,
+            sequences: vec![],
+            device_actions: HashMap::new(),
+            extra_seats: vec![],
+            seats: HashMap::new(),
+            allow_devices: vec![],
+            deny_devices: vec![],
+            log_command_output: true,
+            plugins_directory: None,
+            helper_command: None,
+            pinch_in_threshold: 0.8,
+            pinch_out_threshold: 1.25,
+            strong_pinch_in_threshold: None,
+            strong_pinch_out_threshold: None,
         }
     }
 }
@@ -91,6 +488,262 @@ fn setup_logging(verbosity: LevelFilter) -> Result<(), SetLoggerError> {
     )
 }
 
+/// Expand the references to action groups, replacing them with the actions
+/// of the groups.
+///
+/// References to unknown groups, as well as references from a group to
+/// another group, are removed.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+/// * `log_entries` - list of log entries, to be extended with the removed
+///   references.
+fn expand_groups(settings: &mut Settings, log_entries: &mut Vec<LogEntry>) {
+    // Remove the references between groups, avoiding cycles.
+    for (group, group_settings) in &mut settings.groups {
+        group_settings.actions.retain(|entry| {
+            let nested = entry.group().is_some();
+            if nested {
+                log_entries.push(LogEntry::warn(format!(
+                    "Removing nested group reference in group {group}: {entry}",
+                )));
+            }
+            !nested
+        });
+    }
+
+    let groups = &settings.groups;
+    let mut expand = |key: &str, actions: &mut Vec<StringifiedAction>| {
+        *actions = actions
+            .drain(..)
+            .flat_map(|entry| {
+                let Some(group) = entry.group() else {
+                    return vec![entry];
+                };
+                if let Some(group_settings) = groups.get(group) {
+                    group_settings.actions.clone()
+                } else {
+                    log_entries.push(LogEntry::warn(format!(
+                        "Removing reference to unknown group in {key}: {entry}",
+                    )));
+                    vec![]
+                }
+            })
+            .collect();
+    };
+
+    for (key, actions) in &mut settings.actions {
+        expand(key, actions);
+    }
+    for (profile, bindings) in &mut settings.profiles {
+        for (key, actions) in bindings {
+            expand(&format!("{profile}.{key}"), actions);
+        }
+    }
+    for (device, bindings) in &mut settings.device_actions {
+        for (key, actions) in bindings {
+            expand(&format!("device_actions.{device}.{key}"), actions);
+        }
+    }
+    for (seat, seat_settings) in &mut settings.seats {
+        for (key, actions) in &mut seat_settings.actions {
+            expand(&format!("seats.{seat}.actions.{key}"), actions);
+        }
+    }
+    for (key, actions) in &mut settings.undo_actions {
+        expand(key, actions);
+    }
+    for (index, sequence) in settings.sequences.iter_mut().enumerate() {
+        expand(&format!("sequences.{index}"), &mut sequence.actions);
+    }
+    for (kind, fingers) in &mut settings.gestures {
+        for (finger_count, directions) in fingers {
+            for (direction, gesture) in directions {
+                expand(
+                    &format!("gestures.{kind}.{finger_count}.{direction}"),
+                    &mut gesture.actions,
+                );
+            }
+        }
+    }
+}
+
+/// Prune the action strings of a section of bindings, removing the items that
+/// are malformed or using not enabled action types, along with the bindings
+/// left without actions.
+///
+/// # Arguments
+///
+/// * `bindings` - the actions of each binding of the section.
+/// * `section` - the name of the section, for the log messages.
+/// * `enabled_action_types` - the enabled action types.
+/// * `log_entries` - list of log entries, to be extended with the pruned entries.
+fn prune_bindings(
+    bindings: &mut HashMap<String, Vec<StringifiedAction>>,
+    section: &str,
+    enabled_action_types: &[String],
+    log_entries: &mut Vec<LogEntry>,
+) {
+    for (key, value) in &mut *bindings {
+        value.retain(|entry| {
+            let enabled = enabled_action_types.contains(&entry.type_);
+            if !enabled {
+                log_entries.push(LogEntry::warn(format!(
+                    "Removing malformed or disabled action in {section}.{key}: {entry}",
+                )));
+            }
+            enabled
+        });
+    }
+    bindings.retain(|_, v| !v.is_empty());
+}
+
+/// Prune the settings, removing the entries that are malformed or disabled.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+/// * `log_entries` - list of log entries, to be extended with the pruned entries.
+fn prune_settings(settings: &mut Settings, log_entries: &mut Vec<LogEntry>) {
+    // Prune enabled action types, removing the ones that are not compiled in.
+    settings
+        .enabled_action_types
+        .retain(|type_| match ActionType::parse(type_) {
+            Ok(_) => true,
+            Err(e) => {
+                log_entries.push(LogEntry::warn(format!("Removing enabled action type: {e}")));
+                false
+            }
+        });
+
+    expand_groups(settings, log_entries);
+
+    // Prune action strings, removing the items that are malformed or using
+    // not enabled action types.
+    let enabled_action_types = settings.enabled_action_types.as_slice();
+    for (key, value) in &mut settings.actions {
+        let mut prune = false;
+        // Check each action string, for debugging purposes.
+        for entry in &*value {
+            if !enabled_action_types.contains(&entry.type_) {
+                log_entries.push(LogEntry::warn(format!(
+                    "Removing malformed or disabled action in {key}: {entry}",
+                )));
+                prune = true;
+            }
+        }
+
+        if prune {
+            value.retain(|x| enabled_action_types.contains(&x.type_));
+        }
+    }
+
+    // Prune events that do not have actions.
+    settings.actions.retain(|_, v| !v.is_empty());
+
+    // Prune the action strings of the profiles, devices and seats, as above.
+    for (profile, actions) in &mut settings.profiles {
+        prune_bindings(actions, profile, enabled_action_types, log_entries);
+    }
+    for (device, actions) in &mut settings.device_actions {
+        let section = format!("device_actions.{device}");
+        prune_bindings(actions, &section, enabled_action_types, log_entries);
+    }
+    for (seat, seat_settings) in &mut settings.seats {
+        let section = format!("seats.{seat}.actions");
+        prune_bindings(
+            &mut seat_settings.actions,
+            &section,
+            enabled_action_types,
+            log_entries,
+        );
+    }
+
+    // Prune the undo action strings, as above.
+    settings.undo_actions.retain(|key, value| {
+        value.retain(|entry| {
+            let enabled = enabled_action_types.contains(&entry.type_);
+            if !enabled {
+                log_entries.push(LogEntry::warn(format!(
+                    "Removing malformed or disabled undo action in {key}: {entry}",
+                )));
+            }
+            enabled
+        });
+        !value.is_empty()
+    });
+
+    // Prune the action strings of the sequences, as above.
+    settings.sequences.retain_mut(|sequence| {
+        sequence.actions.retain(|entry| {
+            let enabled = enabled_action_types.contains(&entry.type_);
+            if !enabled {
+                log_entries.push(LogEntry::warn(format!(
+                    "Removing malformed or disabled sequence action in {}: {entry}",
+                    sequence.gestures.join(", "),
+                )));
+            }
+            enabled
+        });
+        !sequence.actions.is_empty()
+    });
+
+    prune_gestures(settings, log_entries);
+
+    // Prune execution modes, removing the items that are malformed.
+    settings.execution_modes.retain(|key, value| {
+        let valid = ActionEvent::from_str(key).is_ok() && ExecutionMode::from_str(value).is_ok();
+        if !valid {
+            log_entries.push(LogEntry::warn(format!(
+                "Removing malformed execution mode for {key}: {value}",
+            )));
+        }
+        valid
+    });
+}
+
+/// Prune the nested `gestures` section, removing the unknown gestures and
+/// the entries that are malformed or disabled.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+/// * `log_entries` - list of log entries, to be extended with the pruned entries.
+fn prune_gestures(settings: &mut Settings, log_entries: &mut Vec<LogEntry>) {
+    let enabled_action_types = settings.enabled_action_types.as_slice();
+    for (kind, fingers) in &mut settings.gestures {
+        for (finger_count, directions) in fingers.iter_mut() {
+            directions.retain(|direction, gesture| {
+                let key = format!("{kind}.{finger_count}.{direction}");
+                if parse_gesture(kind, finger_count, direction).is_empty() {
+                    log_entries.push(LogEntry::warn(format!("Removing unknown gesture {key}")));
+                    return false;
+                }
+
+                gesture.actions.retain(|entry| {
+                    let enabled = enabled_action_types.contains(&entry.type_);
+                    if !enabled {
+                        log_entries.push(LogEntry::warn(format!(
+                            "Removing malformed or disabled action in {key}: {entry}",
+                        )));
+                    }
+                    enabled
+                });
+                if let Some(mode) = &gesture.mode {
+                    if ExecutionMode::from_str(mode).is_err() {
+                        log_entries.push(LogEntry::warn(format!(
+                            "Removing malformed execution mode for {key}: {mode}",
+                        )));
+                        gesture.mode = None;
+                    }
+                }
+                true
+            });
+        }
+    }
+}
+
 /// Setup the application logging and return the application settings.
 ///
 /// The application settings are merged from:
@@ -160,30 +813,35 @@ pub fn setup_application(opts: Opts, initialize_logging: bool) -> Result<Setting
             None
         };
 
-    // Parse the settings, defaulting in case of errors.
-    let mut final_settings = match Config::builder()
-        .add_source(Settings::default())
-        .add_source(files)
-        .add_source(opts)
-        .set_override_option(String::from("verbose"), verbosity_override)
-    {
-        Ok(final_builder) => match final_builder.build() {
-            Ok(merged_config) => match merged_config.try_deserialize::<Settings>() {
-                Ok(merged_settings) => merged_settings,
-                Err(e) => {
-                    log_entries.push(LogEntry::warn(format!(
-                        "Unable to parse settings: {e}. Reverting to default settings",
-                    )));
-                    Settings::default()
-                }
-            },
-            Err(e) => {
-                log_entries.push(LogEntry::warn(format!(
-                    "Unable to parse settings: {e}. Reverting to default settings",
-                )));
-                Settings::default()
-            }
+    // Parse the settings, applying the selected profile and seat, and
+    // defaulting in case of errors.
+    let profile = opts.profile.clone();
+    let mut final_settings = match split_profiles(files, profile, &mut log_entries).and_then(
+        |(mut base, profile_overrides)| {
+            // Apply the section of the seat in use, if any.
+            let seat = opts
+                .seat
+                .clone()
+                .or_else(|| {
+                    [&profile_overrides.0, &base.0]
+                        .iter()
+                        .find_map(|x| x.get("seat")?.clone().into_string().ok())
+                })
+                .unwrap_or_else(|| default_settings.seat.clone());
+            let seat_overrides = split_seats(&mut base.0, &seat, &mut log_entries);
+
+            Config::builder()
+                .add_source(Settings::default())
+                .add_source(base)
+                .add_source(profile_overrides)
+                .add_source(seat_overrides)
+                .add_source(opts)
+                .set_override_option(String::from("verbose"), verbosity_override)?
+                .build()?
+                .try_deserialize::<Settings>()
         },
+    ) {
+        Ok(merged_settings) => merged_settings,
         Err(e) => {
             log_entries.push(LogEntry::warn(format!(
                 "Unable to parse settings: {e}. Reverting to default settings",
@@ -192,28 +850,8 @@ pub fn setup_application(opts: Opts, initialize_logging: bool) -> Result<Setting
         }
     };
 
-    // Prune action strings, removing the items that are malformed or using
-    // not enabled action types.
-    let enabled_action_types = final_settings.enabled_action_types.as_slice();
-    for (key, value) in &mut final_settings.actions {
-        let mut prune = false;
-        // Check each action string, for debugging purposes.
-        for entry in &*value {
-            if !enabled_action_types.contains(&entry.type_) {
-                log_entries.push(LogEntry::warn(format!(
-                    "Removing malformed or disabled action in {key}: {entry}",
-                )));
-                prune = true;
-            }
-        }
-
-        if prune {
-            value.retain(|x| enabled_action_types.contains(&x.type_));
-        }
-    }
-
-    // Prune events that do not have actions.
-    final_settings.actions.retain(|_, v| !v.is_empty());
+    // Prune the settings, removing invalid entries.
+    prune_settings(&mut final_settings, &mut log_entries);
 
     // Initialize logging, setting the logger and the verbosity.
     if initialize_logging {
@@ -233,6 +871,239 @@ pub fn setup_application(opts: Opts, initialize_logging: bool) -> Result<Setting
     Ok(final_settings)
 }
 
+/// Source for settings already collected into a map.
+#[derive(Clone, Debug, Default)]
+struct MapSource(Map<String, Value>);
+
+impl Source for MapSource {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new((*self).clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+        Ok(self.0.clone())
+    }
+}
+
+/// Split the settings of the profiles from the configuration files.
+///
+/// Along with the actions for each gesture, the profiles can contain any of
+/// the settings. These settings are removed from the profiles and, for the
+/// selected profile, returned as overrides for the base settings, along with
+/// its actions.
+///
+/// # Arguments
+///
+/// * `files` - configuration files.
+/// * `profile` - name of the selected profile, taking precedence over the
+///   one from the configuration files.
+/// * `log_entries` - list of pending log entries.
+///
+/// # Errors
+///
+/// Returns `Err` if the configuration files could not be parsed.
+fn split_profiles<T>(
+    files: Vec<T>,
+    profile: Option<String>,
+    log_entries: &mut Vec<LogEntry>,
+) -> Result<(MapSource, MapSource), ConfigError>
+where
+    T: Source + Clone + Send + Sync + 'static,
+{
+    let mut base = Config::builder().add_source(files).build()?.collect()?;
+    let profile = profile.or_else(|| {
+        base.get("profile")
+            .and_then(|x| x.clone().into_string().ok())
+    });
+    let mut overrides = Map::new();
+    let mut found = false;
+
+    if let Some(Value {
+        kind: ValueKind::Table(profiles),
+        ..
+    }) = base.get_mut("profiles")
+    {
+        for (name, entries) in profiles.iter_mut() {
+            let ValueKind::Table(entries) = &mut entries.kind else {
+                continue;
+            };
+            let selected = profile.as_ref() == Some(name);
+            found |= selected;
+
+            // Move the settings out of the profile.
+            let setting_keys: Vec<String> = entries
+                .keys()
+                .filter(|key| ActionEvent::from_str(key).is_err())
+                .cloned()
+                .collect();
+            for key in setting_keys {
+                if let Some(value) = entries.remove(&key) {
+                    if selected {
+                        overrides.insert(key, value);
+                    }
+                }
+            }
+
+            // Merge the actions of the selected profile over the base ones.
+            if selected && !entries.is_empty() {
+                let mut actions = match overrides.remove("actions").map(Value::into_table) {
+                    Some(Ok(actions)) => actions,
+                    _ => Map::new(),
+                };
+                actions.extend(entries.iter().map(|(k, v)| (k.clone(), v.clone())));
+                overrides.insert(String::from("actions"), Value::from(actions));
+            }
+        }
+    }
+
+    if let Some(profile) = profile.filter(|_| !found) {
+        log_entries.push(LogEntry::warn(format!(
+            "Unknown profile {profile}: using the base settings",
+        )));
+    }
+
+    Ok((MapSource(base), MapSource(overrides)))
+}
+
+/// Settings that can be overridden for specific seats.
+const SEAT_SETTINGS: [&str; 2] = ["threshold", "actions"];
+
+/// Split the settings of the seats from the configuration files.
+///
+/// The section of the seat in use is removed from the `seats` section and its
+/// entries are returned as overrides for the base settings, while the
+/// sections of the rest of the seats are kept for the additional seats. The
+/// unsupported entries are removed from all the sections.
+///
+/// # Arguments
+///
+/// * `settings` - settings from the configuration files.
+/// * `seat` - `libinput` seat in use.
+/// * `log_entries` - list of pending log entries.
+fn split_seats(
+    settings: &mut Map<String, Value>,
+    seat: &str,
+    log_entries: &mut Vec<LogEntry>,
+) -> MapSource {
+    let mut overrides = Map::new();
+    let Some(seats) = settings.remove("seats") else {
+        return MapSource(overrides);
+    };
+    let Ok(seats) = seats.into_table() else {
+        log_entries.push(LogEntry::warn(String::from(
+            "Invalid seats section: ignoring it",
+        )));
+        return MapSource(overrides);
+    };
+
+    let mut extra_seats = Map::new();
+    for (name, entries) in seats {
+        let Ok(entries) = entries.into_table() else {
+            log_entries.push(LogEntry::warn(format!(
+                "Invalid section for seat {name}: ignoring it",
+            )));
+            continue;
+        };
+        let mut supported = Map::new();
+        for (key, value) in entries {
+            if SEAT_SETTINGS.contains(&key.as_str()) {
+                supported.insert(key, value);
+            } else {
+                log_entries.push(LogEntry::warn(format!(
+                    "Unsupported setting {key} for seat {name}: ignoring it",
+                )));
+            }
+        }
+
+        if name == seat {
+            overrides = supported;
+        } else {
+            extra_seats.insert(name, Value::from(supported));
+        }
+    }
+    settings.insert(String::from("seats"), Value::from(extra_seats));
+
+    MapSource(overrides)
+}
+
+impl Opts {
+    /// Collect the arguments for recognizing gestures into a map.
+    ///
+    /// # Arguments
+    ///
+    /// * `m` - the map to be extended with the arguments.
+    fn collect_thresholds(&self, m: &mut Map<String, Value>) {
+        self.threshold
+            .as_ref()
+            .map(|x| m.insert(String::from("threshold"), Value::from(*x)));
+        self.threshold_x
+            .as_ref()
+            .map(|x| m.insert(String::from("threshold_x"), Value::from(*x)));
+        self.threshold_y
+            .as_ref()
+            .map(|x| m.insert(String::from("threshold_y"), Value::from(*x)));
+        self.direction_margin
+            .as_ref()
+            .map(|x| m.insert(String::from("direction_margin"), Value::from(*x)));
+        self.directions
+            .as_ref()
+            .map(|x| m.insert(String::from("directions"), Value::from(*x)));
+        self.direction_deadzone
+            .as_ref()
+            .map(|x| m.insert(String::from("direction_deadzone"), Value::from(*x)));
+        self.threshold_mode
+            .as_ref()
+            .map(|x| m.insert(String::from("threshold_mode"), Value::from(x.clone())));
+        self.rotation_threshold
+            .as_ref()
+            .map(|x| m.insert(String::from("rotation_threshold"), Value::from(*x)));
+        self.pinch_in_threshold
+            .as_ref()
+            .map(|x| m.insert(String::from("pinch_in_threshold"), Value::from(*x)));
+        self.pinch_out_threshold
+            .as_ref()
+            .map(|x| m.insert(String::from("pinch_out_threshold"), Value::from(*x)));
+        self.strong_pinch_in_threshold
+            .as_ref()
+            .map(|x| m.insert(String::from("strong_pinch_in_threshold"), Value::from(*x)));
+        self.strong_pinch_out_threshold
+            .as_ref()
+            .map(|x| m.insert(String::from("strong_pinch_out_threshold"), Value::from(*x)));
+        self.edge_margin
+            .as_ref()
+            .map(|x| m.insert(String::from("edge_margin"), Value::from(*x)));
+        self.edge_threshold
+            .as_ref()
+            .map(|x| m.insert(String::from("edge_threshold"), Value::from(*x)));
+    }
+
+    /// Collect the arguments that select the devices into a map.
+    ///
+    /// # Arguments
+    ///
+    /// * `m` - the map to be extended with the arguments.
+    fn collect_devices(&self, m: &mut Map<String, Value>) {
+        self.seat
+            .as_ref()
+            .map(|x| m.insert(String::from("seat"), Value::from(x.clone())));
+        self.devices
+            .as_ref()
+            .map(|x| m.insert(String::from("devices"), Value::from(x.clone())));
+        self.device_classes
+            .as_ref()
+            .map(|x| m.insert(String::from("device_classes"), Value::from(x.clone())));
+        self.extra_seats
+            .as_ref()
+            .map(|x| m.insert(String::from("extra_seats"), Value::from(x.clone())));
+        self.allow_devices
+            .as_ref()
+            .map(|x| m.insert(String::from("allow_devices"), Value::from(x.clone())));
+        self.deny_devices
+            .as_ref()
+            .map(|x| m.insert(String::from("deny_devices"), Value::from(x.clone())));
+    }
+}
+
 impl Source for Opts {
     fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
         Box::new((*self).clone())
@@ -240,20 +1111,16 @@ impl Source for Opts {
 
     fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
         let mut m = Map::new();
+        self.collect_thresholds(&mut m);
+        self.collect_devices(&mut m);
 
         m.insert(
             String::from("verbose"),
             Value::from(self.verbose.log_level_filter().to_string()),
         );
-        self.seat
-            .as_ref()
-            .map(|x| m.insert(String::from("seat"), Value::from(x.clone())));
         self.enabled_action_types
             .as_ref()
             .map(|x| m.insert(String::from("enabled_action_types"), Value::from(x.clone())));
-        self.threshold
-            .as_ref()
-            .map(|x| m.insert(String::from("threshold"), Value::from(*x)));
 
         for action_event in ActionEvent::iter() {
             let actions = self.get_actions_for_event(action_event);
@@ -271,66 +1138,610 @@ impl Source for Opts {
         self.invert_y
             .as_ref()
             .map(|x| m.insert(String::from("invert_y"), Value::from(*x)));
+        self.max_repeated_events
+            .as_ref()
+            .map(|x| m.insert(String::from("max_repeated_events"), Value::from(*x)));
+        self.tmux_socket
+            .as_ref()
+            .map(|x| m.insert(String::from("tmux_socket"), Value::from(x.clone())));
+        self.obs_url
+            .as_ref()
+            .map(|x| m.insert(String::from("obs_url"), Value::from(x.clone())));
 
+        self.disable_while_typing
+            .as_ref()
+            .map(|x| m.insert(String::from("disable_while_typing"), Value::from(*x)));
+        self.ignore_fullscreen
+            .as_ref()
+            .map(|x| m.insert(String::from("ignore_fullscreen"), Value::from(*x)));
+        self.macros_file
+            .as_ref()
+            .map(|x| m.insert(String::from("macros_file"), Value::from(x.clone())));
+        self.undo_window
+            .as_ref()
+            .map(|x| m.insert(String::from("undo_window"), Value::from(*x)));
+        self.rate_limit
+            .as_ref()
+            .map(|x| m.insert(String::from("rate_limit"), Value::from(*x)));
+        self.tick_interval
+            .as_ref()
+            .map(|x| m.insert(String::from("tick_interval"), Value::from(*x)));
+        self.validate_actions
+            .as_ref()
+            .map(|x| m.insert(String::from("validate_actions"), Value::from(*x)));
+        self.profile
+            .as_ref()
+            .map(|x| m.insert(String::from("profile"), Value::from(x.clone())));
+        self.audit_log
+            .as_ref()
+            .map(|x| m.insert(String::from("audit_log"), Value::from(x.clone())));
+        self.dbus_name_policy
+            .as_ref()
+            .map(|x| m.insert(String::from("dbus_name_policy"), Value::from(x.clone())));
+        self.max_events
+            .map(|x| m.insert(String::from("max_events"), Value::from(x)));
+        self.duration
+            .map(|x| m.insert(String::from("duration"), Value::from(x)));
+        self.passthrough
+            .as_ref()
+            .map(|x| m.insert(String::from("passthrough"), Value::from(*x)));
+        self.learn_thresholds
+            .as_ref()
+            .map(|x| m.insert(String::from("learn_thresholds"), Value::from(*x)));
+        self.max_gesture_duration
+            .as_ref()
+            .map(|x| m.insert(String::from("max_gesture_duration"), Value::from(*x)));
+        self.log_command_output
+            .as_ref()
+            .map(|x| m.insert(String::from("log_command_output"), Value::from(*x)));
+        self.plugins_directory
+            .as_ref()
+            .map(|x| m.insert(String::from("plugins_directory"), Value::from(x.clone())));
+        self.helper_command
+            .as_ref()
+            .map(|x| m.insert(String::from("helper_command"), Value::from(x.clone())));
         Ok(m)
     }
 }
 
+impl Settings {
+    /// Collect the settings that are tables keyed by event into a map.
+    ///
+    /// # Arguments
+    ///
+    /// * `m` - the map to be extended with the settings.
+    fn collect_tables(&self, m: &mut Map<String, Value>) {
+        for (action_event, actions) in &self.actions {
+            m.insert(
+                String::from(&format!("actions.{action_event}")),
+                actions_to_value(actions),
+            );
+        }
+        for (direction, width) in &self.sector_widths {
+            m.insert(format!("sector_widths.{direction}"), Value::from(*width));
+        }
+        for (seat, seat_settings) in &self.seats {
+            seat_settings
+                .threshold
+                .map(|x| m.insert(format!("seats.{seat}.threshold"), Value::from(x)));
+        }
+        for (action_event, execution_mode) in &self.execution_modes {
+            m.insert(
+                String::from(&format!("execution_modes.{action_event}")),
+                Value::from(execution_mode.clone()),
+            );
+        }
+        for (profile, actions) in &self.profiles {
+            for (action_event, actions) in actions {
+                m.insert(
+                    format!("profiles.{profile}.{action_event}"),
+                    actions_to_value(actions),
+                );
+            }
+        }
+        for (device, actions) in &self.device_actions {
+            for (action_event, actions) in actions {
+                m.insert(
+                    format!("device_actions.{device}.{action_event}"),
+                    actions_to_value(actions),
+                );
+            }
+        }
+        for (action_event, actions) in &self.undo_actions {
+            m.insert(
+                format!("undo_actions.{action_event}"),
+                actions_to_value(actions),
+            );
+        }
+        if !self.gestures.is_empty() {
+            m.insert(String::from("gestures"), gestures_to_value(&self.gestures));
+        }
+        if !self.sequences.is_empty() {
+            m.insert(
+                String::from("sequences"),
+                sequences_to_value(&self.sequences),
+            );
+        }
+        for (group, group_settings) in &self.groups {
+            m.insert(
+                format!("groups.{group}.actions"),
+                actions_to_value(&group_settings.actions),
+            );
+        }
+    }
+
+    /// Collect the settings for recognizing gestures into a map.
+    ///
+    /// # Arguments
+    ///
+    /// * `m` - the map to be extended with the settings.
+    fn collect_thresholds(&self, m: &mut Map<String, Value>) {
+        m.insert(String::from("threshold"), Value::from(self.threshold));
+        if let Some(threshold_x) = self.threshold_x {
+            m.insert(String::from("threshold_x"), Value::from(threshold_x));
+        }
+        if let Some(threshold_y) = self.threshold_y {
+            m.insert(String::from("threshold_y"), Value::from(threshold_y));
+        }
+        if let Some(direction_margin) = self.direction_margin {
+            m.insert(
+                String::from("direction_margin"),
+                Value::from(direction_margin),
+            );
+        }
+        m.insert(String::from("directions"), Value::from(self.directions));
+        if let Some(direction_deadzone) = self.direction_deadzone {
+            m.insert(
+                String::from("direction_deadzone"),
+                Value::from(direction_deadzone),
+            );
+        }
+        m.insert(
+            String::from("threshold_mode"),
+            Value::from(self.threshold_mode.clone()),
+        );
+        m.insert(
+            String::from("rotation_threshold"),
+            Value::from(self.rotation_threshold),
+        );
+        m.insert(
+            String::from("pinch_in_threshold"),
+            Value::from(self.pinch_in_threshold),
+        );
+        m.insert(
+            String::from("pinch_out_threshold"),
+            Value::from(self.pinch_out_threshold),
+        );
+        if let Some(strong_pinch_in_threshold) = self.strong_pinch_in_threshold {
+            m.insert(
+                String::from("strong_pinch_in_threshold"),
+                Value::from(strong_pinch_in_threshold),
+            );
+        }
+        if let Some(strong_pinch_out_threshold) = self.strong_pinch_out_threshold {
+            m.insert(
+                String::from("strong_pinch_out_threshold"),
+                Value::from(strong_pinch_out_threshold),
+            );
+        }
+        m.insert(String::from("edge_margin"), Value::from(self.edge_margin));
+        m.insert(
+            String::from("edge_threshold"),
+            Value::from(self.edge_threshold),
+        );
+    }
+
+    /// Collect the settings that select the devices into a map.
+    ///
+    /// # Arguments
+    ///
+    /// * `m` - the map to be extended with the settings.
+    fn collect_devices(&self, m: &mut Map<String, Value>) {
+        m.insert(String::from("seat"), Value::from(self.seat.clone()));
+        if !self.devices.is_empty() {
+            m.insert(String::from("devices"), Value::from(self.devices.clone()));
+        }
+        if !self.extra_seats.is_empty() {
+            m.insert(
+                String::from("extra_seats"),
+                Value::from(self.extra_seats.clone()),
+            );
+        }
+        if !self.device_classes.is_empty() {
+            m.insert(
+                String::from("device_classes"),
+                Value::from(self.device_classes.clone()),
+            );
+        }
+        if !self.allow_devices.is_empty() {
+            m.insert(
+                String::from("allow_devices"),
+                Value::from(self.allow_devices.clone()),
+            );
+        }
+        if !self.deny_devices.is_empty() {
+            m.insert(
+                String::from("deny_devices"),
+                Value::from(self.deny_devices.clone()),
+            );
+        }
+    }
+
+    /// Return the settings of the known gestures in the nested `gestures`
+    /// section, along with their [`ActionEvent`]s.
+    ///
+    /// The gestures with finger count ranges are expanded into an entry for
+    /// each of their events, preceding the more specific entries so the
+    /// latter take precedence.
+    pub fn gesture_settings(&self) -> impl Iterator<Item = (ActionEvent, &GestureSettings)> {
+        let mut gestures: Vec<(usize, ActionEvent, &GestureSettings)> = self
+            .gestures
+            .iter()
+            .flat_map(|(kind, fingers)| {
+                fingers.iter().flat_map(move |(finger_count, directions)| {
+                    directions.iter().flat_map(move |(direction, gesture)| {
+                        let action_events = parse_gesture(kind, finger_count, direction);
+                        let width = action_events.len();
+                        action_events
+                            .into_iter()
+                            .map(move |action_event| (width, action_event, gesture))
+                    })
+                })
+            })
+            .collect();
+        gestures.sort_by_key(|(width, ..)| Reverse(*width));
+
+        gestures
+            .into_iter()
+            .map(|(_, action_event, gesture)| (action_event, gesture))
+    }
+}
+
 impl Source for Settings {
     fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
         Box::new((*self).clone())
     }
 
+    #[allow(clippy::too_many_lines)]
     fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
         let mut m = Map::new();
+        self.collect_tables(&mut m);
+        self.collect_devices(&mut m);
+        self.collect_thresholds(&mut m);
 
         m.insert(
             String::from("verbose"),
             Value::from(self.verbose.to_string()),
         );
-        m.insert(String::from("seat"), Value::from(self.seat.clone()));
         m.insert(
             String::from("enabled_action_types"),
             Value::from(self.enabled_action_types.clone()),
         );
-        m.insert(String::from("threshold"), Value::from(self.threshold));
-        for (action_event, actions) in &self.actions {
+        m.insert(String::from("invert_x"), Value::from(self.invert_x));
+        m.insert(String::from("invert_y"), Value::from(self.invert_y));
+        if let Some(max_repeated_events) = self.max_repeated_events {
             m.insert(
-                String::from(&format!("actions.{action_event}")),
-                Value::from(
-                    actions
-                        .iter()
-                        .map(ToString::to_string)
-                        .collect::<Vec<String>>(),
-                ),
+                String::from("max_repeated_events"),
+                Value::from(max_repeated_events),
             );
         }
-        m.insert(String::from("invert_x"), Value::from(self.invert_x));
-        m.insert(String::from("invert_y"), Value::from(self.invert_y));
+        if let Some(tmux_socket) = &self.tmux_socket {
+            m.insert(
+                String::from("tmux_socket"),
+                Value::from(tmux_socket.clone()),
+            );
+        }
+        m.insert(String::from("obs_url"), Value::from(self.obs_url.clone()));
 
+        if let Some(disable_while_typing) = self.disable_while_typing {
+            m.insert(
+                String::from("disable_while_typing"),
+                Value::from(disable_while_typing),
+            );
+        }
+        m.insert(
+            String::from("ignore_fullscreen"),
+            Value::from(self.ignore_fullscreen),
+        );
+        if !self.schedules.is_empty() {
+            m.insert(
+                String::from("schedules"),
+                schedules_to_value(&self.schedules),
+            );
+        }
+        if let Some(macros_file) = &self.macros_file {
+            m.insert(
+                String::from("macros_file"),
+                Value::from(macros_file.clone()),
+            );
+        }
+        m.insert(String::from("undo_window"), Value::from(self.undo_window));
+        if let Some(rate_limit) = self.rate_limit {
+            m.insert(String::from("rate_limit"), Value::from(rate_limit));
+        }
+        if let Some(tick_interval) = self.tick_interval {
+            m.insert(String::from("tick_interval"), Value::from(tick_interval));
+        }
+        m.insert(
+            String::from("validate_actions"),
+            Value::from(self.validate_actions),
+        );
+        if let Some(profile) = &self.profile {
+            m.insert(String::from("profile"), Value::from(profile.clone()));
+        }
+        if let Some(audit_log) = &self.audit_log {
+            m.insert(String::from("audit_log"), Value::from(audit_log.clone()));
+        }
+        m.insert(
+            String::from("dbus_name_policy"),
+            Value::from(self.dbus_name_policy.clone()),
+        );
+        if let Some(max_events) = self.max_events {
+            m.insert(String::from("max_events"), Value::from(max_events));
+        }
+        if let Some(duration) = self.duration {
+            m.insert(String::from("duration"), Value::from(duration));
+        }
+        m.insert(String::from("passthrough"), Value::from(self.passthrough));
+        m.insert(
+            String::from("learn_thresholds"),
+            Value::from(self.learn_thresholds),
+        );
+        if let Some(max_gesture_duration) = self.max_gesture_duration {
+            m.insert(
+                String::from("max_gesture_duration"),
+                Value::from(max_gesture_duration),
+            );
+        }
+        m.insert(
+            String::from("log_command_output"),
+            Value::from(self.log_command_output),
+        );
+        if let Some(plugins_directory) = &self.plugins_directory {
+            m.insert(
+                String::from("plugins_directory"),
+                Value::from(plugins_directory.clone()),
+            );
+        }
+        if let Some(helper_command) = &self.helper_command {
+            m.insert(
+                String::from("helper_command"),
+                Value::from(helper_command.clone()),
+            );
+        }
         Ok(m)
     }
 }
 
-/// Generate [`Action`]s from application settings.
+/// Convert a list of [`ScheduleSettings`] into a configuration [`Value`].
 ///
 /// # Arguments
 ///
-/// * `settings` - application settings.
+/// * `schedules` - the list of schedules.
+fn schedules_to_value(schedules: &[ScheduleSettings]) -> Value {
+    Value::from(
+        schedules
+            .iter()
+            .map(|x| {
+                Value::from(Map::from([
+                    (String::from("profile"), Value::from(x.profile.clone())),
+                    (String::from("start"), Value::from(x.start.clone())),
+                    (String::from("end"), Value::from(x.end.clone())),
+                    (String::from("days"), Value::from(x.days.clone())),
+                ]))
+            })
+            .collect::<Vec<Value>>(),
+    )
+}
+
+/// Convert a list of [`SequenceSettings`] into a configuration [`Value`].
+///
+/// # Arguments
+///
+/// * `sequences` - the list of sequences.
+fn sequences_to_value(sequences: &[SequenceSettings]) -> Value {
+    Value::from(
+        sequences
+            .iter()
+            .map(|x| {
+                Value::from(Map::from([
+                    (String::from("gestures"), Value::from(x.gestures.clone())),
+                    (String::from("timeout"), Value::from(x.timeout)),
+                    (String::from("actions"), actions_to_value(&x.actions)),
+                ]))
+            })
+            .collect::<Vec<Value>>(),
+    )
+}
+
+/// Convert a list of [`StringifiedAction`]s into a configuration [`Value`].
+///
+/// # Arguments
+///
+/// * `actions` - the list of actions.
+fn actions_to_value(actions: &[StringifiedAction]) -> Value {
+    Value::from(
+        actions
+            .iter()
+            .map(|action| {
+                if action.options.is_none() && action.delay.is_none() {
+                    return Value::from(action.to_string());
+                }
+
+                let mut table = Map::from([
+                    (String::from("type"), Value::from(action.type_.clone())),
+                    (String::from("command"), Value::from(action.command.clone())),
+                ]);
+                if let Some(delay) = action.delay {
+                    table.insert(String::from("delay"), Value::from(delay));
+                }
+                if let Some(options) = &action.options {
+                    table.insert(String::from("shell"), Value::from(options.shell));
+                    table.insert(String::from("env"), Value::from(options.env.clone()));
+                    if let Some(working_directory) = &options.working_directory {
+                        table.insert(
+                            String::from("working_directory"),
+                            Value::from(working_directory.clone()),
+                        );
+                    }
+                }
+                Value::from(table)
+            })
+            .collect::<Vec<Value>>(),
+    )
+}
+
+/// Convert the nested `gestures` section into a configuration [`Value`].
+///
+/// # Arguments
+///
+/// * `gestures` - the settings for each gesture.
+fn gestures_to_value(
+    gestures: &HashMap<String, HashMap<String, HashMap<String, GestureSettings>>>,
+) -> Value {
+    let gesture_to_value = |gesture: &GestureSettings| {
+        let mut m = Map::from([(String::from("actions"), actions_to_value(&gesture.actions))]);
+        if let Some(threshold) = gesture.threshold {
+            m.insert(String::from("threshold"), Value::from(threshold));
+        }
+        if let Some(fast_velocity) = gesture.fast_velocity {
+            m.insert(String::from("fast_velocity"), Value::from(fast_velocity));
+        }
+        if let Some(hold_time) = gesture.hold_time {
+            m.insert(String::from("hold_time"), Value::from(hold_time));
+        }
+        if let Some(progress_step) = gesture.progress_step {
+            m.insert(String::from("progress_step"), Value::from(progress_step));
+        }
+        if let Some(repeat) = gesture.repeat {
+            m.insert(String::from("repeat"), Value::from(repeat));
+        }
+        if let Some(mode) = &gesture.mode {
+            m.insert(String::from("mode"), Value::from(mode.clone()));
+        }
+        if let Some(cooldown) = gesture.cooldown {
+            m.insert(String::from("cooldown"), Value::from(cooldown));
+        }
+        Value::from(m)
+    };
+
+    Value::from(
+        gestures
+            .iter()
+            .map(|(kind, fingers)| {
+                let fingers = fingers
+                    .iter()
+                    .map(|(finger_count, directions)| {
+                        let directions = directions
+                            .iter()
+                            .map(|(direction, gesture)| {
+                                (direction.clone(), gesture_to_value(gesture))
+                            })
+                            .collect::<Map<String, Value>>();
+                        (finger_count.clone(), Value::from(directions))
+                    })
+                    .collect::<Map<String, Value>>();
+                (kind.clone(), Value::from(fingers))
+            })
+            .collect::<Map<String, Value>>(),
+    )
+}
+
+/// Number of fingers supported in gestures, along with their names.
+const FINGER_COUNTS: [(u32, &str); 3] = [(2, "two"), (3, "three"), (4, "four")];
+
+/// Parse a gesture of the nested `gestures` section into its [`ActionEvent`]s.
+///
+/// Gestures are identified by their kind (`swipe`, `rotate` or `pinch`),
+/// number of fingers and direction (for example, `left-up`, `clockwise` or
+/// `in-strong`). The number of fingers can be a single count (`3`), a minimum
+/// count (`3+`) or a range of counts (`3-4`), expanding into an event for each
+/// count supported by the kind.
+///
+/// # Arguments
+///
+/// * `kind` - the kind of the gesture.
+/// * `finger_count` - the number of fingers of the gesture.
+/// * `direction` - the direction of the gesture.
+///
+/// # Returns
+///
+/// The events of the gesture, empty if the gesture is unknown.
 #[must_use]
-pub fn extract_action_map(
-    settings: &Settings,
-) -> (HashMap<ActionEvent, Vec<Box<dyn Action>>>, SharedConnection) {
-    let mut action_map: HashMap<ActionEvent, Vec<Box<dyn Action>>> = HashMap::new();
-    let connection = Rc::new(RefCell::new(None));
-    let mut connection_exists = false;
-
-    // Create the I3 connection if needed.
-    if settings
-        .actions
-        .values()
-        .flatten()
-        .any(|s| s.type_ == ActionType::I3.to_string())
+pub fn parse_gesture(kind: &str, finger_count: &str, direction: &str) -> Vec<ActionEvent> {
+    let parse_count = |value: &str| value.parse::<u32>().ok();
+    let range = if let Some(min) = finger_count.strip_suffix('+') {
+        parse_count(min).map(|min| (min, u32::MAX))
+    } else if let Some((min, max)) = finger_count.split_once('-') {
+        parse_count(min).zip(parse_count(max))
+    } else {
+        parse_count(finger_count).map(|count| (count, count))
+    };
+    let Some((min, max)) = range else {
+        return vec![];
+    };
+
+    FINGER_COUNTS
+        .iter()
+        .filter(|(count, _)| (min..=max).contains(count))
+        .filter_map(|(_, name)| {
+            ActionEvent::from_str(&format!("{name}-finger-{kind}-{direction}")).ok()
+        })
+        .collect()
+}
+
+/// Parse a gesture binding into its modifiers and [`ActionEvent`].
+///
+/// Bindings are in the form `{modifier}+...+{action event}`, with the
+/// modifiers being optional (for example, `super+three-finger-swipe-right`).
+///
+/// # Arguments
+///
+/// * `binding` - the gesture binding.
+#[must_use]
+pub fn parse_binding(binding: &str) -> Option<(BTreeSet<Modifier>, ActionEvent)> {
+    let (modifiers, action_event) = match binding.rsplit_once('+') {
+        Some((modifiers, action_event)) => (modifiers.split('+').collect(), action_event),
+        None => (vec![], binding),
+    };
+
+    let modifiers = modifiers
+        .into_iter()
+        .map(Modifier::from_str)
+        .collect::<Result<BTreeSet<Modifier>, _>>()
+        .ok()?;
+
+    Some((modifiers, ActionEvent::from_str(action_event).ok()?))
+}
+
+/// Create the `i3` connection, if any of the actions or settings requires it.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+#[cfg(feature = "i3")]
+fn create_i3_connection(settings: &Settings) -> SharedConnection {
+    let connection = Arc::new(Mutex::new(None));
+
+    if settings.ignore_fullscreen
+        || settings
+            .actions
+            .values()
+            .chain(settings.profiles.values().flat_map(HashMap::values))
+            .chain(settings.device_actions.values().flat_map(HashMap::values))
+            .chain(settings.seats.values().flat_map(|x| x.actions.values()))
+            .chain(settings.undo_actions.values())
+            .chain(settings.sequences.iter().map(|x| &x.actions))
+            .chain(
+                settings
+                    .gesture_settings()
+                    .map(|(_, gesture)| &gesture.actions),
+            )
+            .flatten()
+            .any(|s| {
+                s.type_ == ActionType::I3.to_string()
+                    || s.type_ == ActionType::I3Builtin.to_string()
+            })
     {
         let new_connection = match I3Connection::connect() {
             Ok(mut conn) => {
@@ -343,7 +1754,6 @@ pub fn extract_action_map(
                 };
 
                 info!("i3: connection opened (with version {version})",);
-                connection_exists = true;
 
                 Some(conn)
             }
@@ -353,64 +1763,1302 @@ pub fn extract_action_map(
             }
         };
 
-        // Update the connection.
-        let connection_option = &mut *connection.borrow_mut();
-        *connection_option = new_connection;
+        // Update the connection, keeping it usable across restarts of i3.
+        let connected = new_connection.is_some();
+        *connection.lock().unwrap_or_else(PoisonError::into_inner) = new_connection;
+        if connected {
+            if let Err(e) = watch_i3_shutdown(Arc::clone(&connection)) {
+                warn!("i3: could not watch for restarts: {e}");
+            }
+        }
     }
 
-    // Populate the fields for each `ActionEvent`.
-    for action_event in ActionEvent::iter() {
-        if let Some(arguments) = settings.actions.get(&action_event.to_string()) {
-            let mut actions_list: Vec<Box<dyn Action>> = vec![];
-
-            for value in arguments {
-                // Create the new actions.
-                match ActionType::from_str(&value.type_) {
-                    Ok(ActionType::Command) => {
-                        actions_list.push(Box::new(CommandAction::new(value.command.clone())));
-                    }
-                    Ok(ActionType::I3) => {
-                        if connection_exists {
-                            actions_list.push(Box::new(I3Action::new(
-                                value.command.clone(),
-                                Rc::clone(&connection),
-                            )));
-                        } else {
-                            warn!("Disabling action as i3 connection could not be established: {value}");
-                        }
-                    }
-                    Err(_) => {
-                        warn!("Unknown action type: '{}", value.type_);
-                    }
-                }
+    connection
+}
+
+/// Create the macro recorder, loading the persisted macros if needed.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+fn create_macro_recorder(settings: &Settings) -> MacroRecorder {
+    let path = settings.macros_file.as_ref().map(PathBuf::from);
+    match MacroRecorder::new(path.clone()) {
+        Ok(macro_recorder) => macro_recorder,
+        Err(e) => {
+            warn!("Unable to load the recorded macros: {e}");
+            let mut macro_recorder = MacroRecorder::default();
+            macro_recorder.path = path;
+            macro_recorder
+        }
+    }
+}
+
+/// Create the plugin registry, loading the plugins from the plugins
+/// directory.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+#[cfg(feature = "plugins")]
+fn create_plugin_registry(settings: &Settings) -> PluginRegistry {
+    let mut registry = PluginRegistry::default();
+    let directory = if let Some(directory) = &settings.plugins_directory {
+        PathBuf::from(directory)
+    } else {
+        // The default directory is only used if it exists.
+        match xdg::BaseDirectories::with_prefix("lillinput") {
+            Ok(xdg_dir) if xdg_dir.get_config_home().join("plugins").is_dir() => {
+                xdg_dir.get_config_home().join("plugins")
             }
+            _ => return registry,
+        }
+    };
 
-            action_map.insert(action_event, actions_list);
+    // SAFETY: the libraries in the plugins directory are installed by the
+    // user, and trusted to be plugins built against this version.
+    match unsafe { registry.load_directory(&directory) } {
+        Ok(results) => {
+            for result in results {
+                match result {
+                    Ok(name) => info!("Loaded plugin {name}"),
+                    Err(e) => warn!("Skipping plugin: {e}"),
+                }
+            }
         }
+        Err(e) => warn!("Unable to load the plugins: {e}"),
     }
 
-    (action_map, connection)
+    registry
 }
 
-#[cfg(test)]
-mod test {
-    use std::env;
-
-    use super::*;
-    use crate::test_utils::default_test_settings;
-    use lillinput::controllers::DefaultController;
-    use lillinput::events::DefaultProcessor;
+/// Connections shared between the [`Action`]s.
+struct ActionConnections {
+    /// `i3` connection.
+    #[cfg(feature = "i3")]
+    i3: SharedConnection,
+    /// Whether the `i3` connection could be established.
+    #[cfg(feature = "i3")]
+    i3_exists: bool,
+    /// `obs-websocket` connection.
+    #[cfg(feature = "obs")]
+    obs: SharedObsConnection,
+    /// Virtual touchpad for emitting gestures.
+    #[cfg(feature = "uinput")]
+    touchpad: SharedVirtualTouchpad,
+    /// Virtual pointer for emitting scrolls and button clicks.
+    #[cfg(feature = "uinput")]
+    pointer: SharedVirtualPointer,
+    /// Connection to the X server for injecting events.
+    #[cfg(feature = "xdo")]
+    xdo: SharedXdoConnection,
+    /// Virtual keyboard for injecting key presses on Wayland.
+    #[cfg(feature = "wtype")]
+    keyboard: SharedVirtualKeyboard,
+    /// Connection to the audio server for adjusting the volume.
+    #[cfg(feature = "volume")]
+    volume: SharedVolumeConnection,
+    /// Registry of the plugins providing custom action types.
+    #[cfg(feature = "plugins")]
+    plugins: PluginRegistry,
+    /// Long-running helper process, if configured.
+    #[cfg(feature = "helper")]
+    helper: Option<SharedHelperProcess>,
+    /// Connection to `River` for running commands.
+    #[cfg(feature = "river")]
+    river: SharedRiverConnection,
+    /// Macro recorder.
+    macro_recorder: SharedMacroRecorder,
+    /// Shared controller state.
+    controller_state: SharedControllerState,
+}
 
-    use serial_test::serial;
+/// Create the [`Action`]s for a list of action strings.
+///
+/// # Arguments
+///
+/// * `arguments` - list of action strings.
+/// * `action_event` - the event that triggers the actions.
+/// * `settings` - application settings.
+/// * `connections` - connections shared between the actions.
+#[cfg_attr(not(feature = "rest"), allow(unused_variables))]
+#[allow(clippy::too_many_lines)]
+fn create_actions(
+    arguments: &[StringifiedAction],
+    action_event: ActionEvent,
+    settings: &Settings,
+    connections: &ActionConnections,
+) -> Vec<Box<dyn Action>> {
+    let mut actions_list: Vec<Box<dyn Action>> = vec![];
 
-    #[test]
-    #[serial]
-    ///Test graceful handling of unavailable i3 connection.
-    fn test_i3_not_available() {
-        // Initialize the command line options.
-        let mut settings: Settings = default_test_settings();
-        settings.enabled_action_types = vec!["i3".to_string()];
-        settings.actions.insert(
+    for value in arguments {
+        // Create the new actions.
+        let created = actions_list.len();
+        match ActionType::parse(&value.type_) {
+            #[cfg(feature = "command")]
+            Ok(ActionType::Command) => {
+                let mut options =
+                    value
+                        .options
+                        .as_ref()
+                        .map_or_else(CommandOptions::default, |x| CommandOptions {
+                            working_directory: x.working_directory.as_ref().map(PathBuf::from),
+                            env: x.env.clone(),
+                            shell: x.shell,
+                            discard_output: false,
+                        });
+                options.discard_output = !settings.log_command_output;
+                actions_list.push(Box::new(CommandAction::with_options(
+                    value.command.clone(),
+                    options,
+                )));
+            }
+            #[cfg(feature = "i3")]
+            Ok(ActionType::I3) => {
+                if connections.i3_exists {
+                    actions_list.push(Box::new(I3Action::new(
+                        value.command.clone(),
+                        Arc::clone(&connections.i3),
+                    )));
+                } else {
+                    warn!("Disabling action as i3 connection could not be established: {value}");
+                }
+            }
+            Ok(ActionType::RecordMacro) => {
+                match RecordMacroAction::new(
+                    &value.command,
+                    Arc::clone(&connections.macro_recorder),
+                ) {
+                    Ok(action) => actions_list.push(Box::new(action)),
+                    Err(e) => warn!("Disabling action {value}: {e}"),
+                }
+            }
+            Ok(ActionType::Internal) => {
+                match InternalAction::new(&value.command, Arc::clone(&connections.controller_state))
+                {
+                    Ok(action) => actions_list.push(Box::new(action)),
+                    Err(e) => warn!("Disabling action {value}: {e}"),
+                }
+            }
+            Ok(ActionType::Log) => {
+                actions_list.push(Box::new(LogAction::new(
+                    value.command.clone(),
+                    action_event,
+                )));
+            }
+            #[cfg(feature = "tmux")]
+            Ok(ActionType::Tmux) => {
+                actions_list.push(Box::new(TmuxAction::new(
+                    value.command.clone(),
+                    settings.tmux_socket.clone(),
+                )));
+            }
+            #[cfg(feature = "obs")]
+            Ok(ActionType::Obs) => {
+                match ObsAction::new(&value.command, Arc::clone(&connections.obs)) {
+                    Ok(action) => actions_list.push(Box::new(action)),
+                    Err(e) => warn!("Disabling action {value}: {e}"),
+                }
+            }
+            #[cfg(feature = "rest")]
+            Ok(ActionType::Rest) => match settings.rest.get(&value.command) {
+                Some(rest_settings) => actions_list.push(Box::new(RestAction::new(
+                    value.command.clone(),
+                    rest_settings.into(),
+                    action_event,
+                ))),
+                None => {
+                    warn!("Disabling action as the REST request is not defined: {value}");
+                }
+            },
+            #[cfg(feature = "clipboard")]
+            Ok(ActionType::Clipboard) => {
+                actions_list.push(Box::new(ClipboardAction::new(
+                    value.command.clone(),
+                    action_event,
+                )));
+            }
+            #[cfg(feature = "uinput")]
+            Ok(ActionType::Uinput) => {
+                match UinputAction::new(&value.command, Arc::clone(&connections.touchpad)) {
+                    Ok(action) => actions_list.push(Box::new(action)),
+                    Err(e) => warn!("Disabling action {value}: {e}"),
+                }
+            }
+            #[cfg(feature = "uinput")]
+            Ok(ActionType::Pointer) => {
+                match PointerAction::new(&value.command, Arc::clone(&connections.pointer)) {
+                    Ok(action) => actions_list.push(Box::new(action)),
+                    Err(e) => warn!("Disabling action {value}: {e}"),
+                }
+            }
+            #[cfg(feature = "xdo")]
+            Ok(ActionType::Xdo) => {
+                match XdoAction::new(&value.command, Arc::clone(&connections.xdo)) {
+                    Ok(action) => actions_list.push(Box::new(action)),
+                    Err(e) => warn!("Disabling action {value}: {e}"),
+                }
+            }
+            #[cfg(feature = "wtype")]
+            Ok(ActionType::Wtype) => {
+                match WtypeAction::new(&value.command, Arc::clone(&connections.keyboard)) {
+                    Ok(action) => actions_list.push(Box::new(action)),
+                    Err(e) => warn!("Disabling action {value}: {e}"),
+                }
+            }
+            #[cfg(feature = "volume")]
+            Ok(ActionType::Volume) => {
+                match VolumeAction::new(&value.command, Arc::clone(&connections.volume)) {
+                    Ok(action) => actions_list.push(Box::new(action)),
+                    Err(e) => warn!("Disabling action {value}: {e}"),
+                }
+            }
+            #[cfg(feature = "helper")]
+            Ok(ActionType::Helper) => match &connections.helper {
+                Some(helper) => actions_list.push(Box::new(HelperAction::new(
+                    &value.command,
+                    Arc::clone(helper),
+                ))),
+                None => warn!("Disabling action as no helper_command is configured: {value}"),
+            },
+            #[cfg(feature = "river")]
+            Ok(ActionType::River) => {
+                match RiverAction::new(&value.command, Arc::clone(&connections.river)) {
+                    Ok(action) => actions_list.push(Box::new(action)),
+                    Err(e) => warn!("Disabling action {value}: {e}"),
+                }
+            }
+            #[cfg(feature = "plugins")]
+            Ok(ActionType::Plugin) => match PluginAction::new(&value.command, &connections.plugins)
+            {
+                Ok(action) => actions_list.push(Box::new(action)),
+                Err(e) => warn!("Disabling action {value}: {e}"),
+            },
+            #[cfg(feature = "i3")]
+            Ok(ActionType::I3Builtin) => {
+                if connections.i3_exists {
+                    match I3BuiltinAction::new(&value.command, Arc::clone(&connections.i3)) {
+                        Ok(action) => actions_list.push(Box::new(action)),
+                        Err(e) => warn!("Disabling action {value}: {e}"),
+                    }
+                } else {
+                    warn!("Disabling action as i3 connection could not be established: {value}");
+                }
+            }
+            Err(e) => {
+                warn!("Disabling action {value}: {e}");
+            }
+        }
+
+        // Wait for the delay of the action before executing it.
+        if let Some(delay) = value.delay {
+            let delayed = actions_list.split_off(created);
+            actions_list.extend(delayed.into_iter().map(|action| {
+                Box::new(DelayedAction::new(
+                    Duration::from_millis(u64::from(delay)),
+                    action,
+                )) as Box<dyn Action>
+            }));
+        }
+    }
+
+    actions_list
+}
+
+/// Add the actions for re-emitting the gestures without actions on a virtual
+/// touchpad, passing them through to the compositor.
+///
+/// # Arguments
+///
+/// * `action_map` - the actions for each [`ActionEvent`].
+/// * `connections` - connections shared between the actions.
+#[cfg_attr(not(feature = "uinput"), allow(unused_variables))]
+fn add_passthrough_actions(action_map: &mut ActionMap, connections: &ActionConnections) {
+    #[cfg(feature = "uinput")]
+    for action_event in ActionEvent::iter() {
+        let actions_list = action_map.entry(action_event).or_default();
+        if actions_list.is_empty() {
+            if let Ok(action) =
+                UinputAction::new(&action_event.to_string(), Arc::clone(&connections.touchpad))
+            {
+                actions_list.push(Box::new(action));
+            }
+        }
+    }
+
+    #[cfg(not(feature = "uinput"))]
+    warn!("Ignoring the passthrough setting, as the `uinput` feature is not compiled in");
+}
+
+/// [`Action`]s generated from application settings.
+pub struct ExtractedActions {
+    /// Actions for each [`ActionEvent`].
+    pub actions: ActionMap,
+    /// Actions for each [`ActionEvent`], for each set of keyboard modifiers.
+    pub modifier_actions: HashMap<BTreeSet<Modifier>, ActionMap>,
+    /// Actions for each [`ActionEvent`], for each profile.
+    pub profiles: HashMap<String, ActionMap>,
+    /// Actions for each [`ActionEvent`], for each device.
+    pub device_actions: HashMap<String, ActionMap>,
+    /// Actions for each [`ActionEvent`], for each additional seat.
+    pub seat_actions: HashMap<String, ActionMap>,
+    /// Actions for undoing each [`ActionEvent`].
+    pub undo_actions: ActionMap,
+    /// Sequences of [`ActionEvent`]s, along with their actions.
+    pub sequences: Vec<GestureSequence>,
+    /// `i3` connection.
+    #[cfg(feature = "i3")]
+    pub connection: SharedConnection,
+    /// Macro recorder.
+    pub macro_recorder: SharedMacroRecorder,
+}
+
+/// Generate [`Action`]s from application settings.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+/// * `controller_state` - the shared controller state, acted upon by the
+///   internal actions.
+#[must_use]
+pub fn extract_action_map(
+    settings: &Settings,
+    controller_state: &SharedControllerState,
+) -> ExtractedActions {
+    let mut action_map: ActionMap = HashMap::new();
+    let mut modifier_action_map: HashMap<BTreeSet<Modifier>, ActionMap> = HashMap::new();
+    #[cfg(feature = "i3")]
+    let connection = create_i3_connection(settings);
+    let connections = ActionConnections {
+        #[cfg(feature = "i3")]
+        i3: Arc::clone(&connection),
+        #[cfg(feature = "i3")]
+        i3_exists: connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_some(),
+        #[cfg(feature = "obs")]
+        obs: Arc::new(Mutex::new(ObsConnection::new(
+            settings.obs_url.clone(),
+            settings.obs_password.clone(),
+        ))),
+        #[cfg(feature = "uinput")]
+        touchpad: Arc::new(Mutex::new(VirtualTouchpad::default())),
+        #[cfg(feature = "uinput")]
+        pointer: Arc::new(Mutex::new(VirtualPointer::default())),
+        #[cfg(feature = "xdo")]
+        xdo: Arc::new(Mutex::new(XdoConnection::default())),
+        #[cfg(feature = "wtype")]
+        keyboard: Arc::new(Mutex::new(VirtualKeyboard::default())),
+        #[cfg(feature = "volume")]
+        volume: Arc::new(Mutex::new(VolumeConnection::default())),
+        #[cfg(feature = "plugins")]
+        plugins: create_plugin_registry(settings),
+        #[cfg(feature = "helper")]
+        helper: settings.helper_command.clone().map(|command| {
+            Arc::new(Mutex::new(HelperProcess::new(
+                command,
+                DEFAULT_HELPER_TIMEOUT,
+            )))
+        }),
+        #[cfg(feature = "river")]
+        river: Arc::new(Mutex::new(RiverConnection::default())),
+        macro_recorder: Arc::new(Mutex::new(create_macro_recorder(settings))),
+        controller_state: Arc::clone(controller_state),
+    };
+
+    // Populate the fields for each binding.
+    for (binding, arguments) in &settings.actions {
+        let Some((modifiers, action_event)) = parse_binding(binding) else {
+            warn!("Unknown gesture binding: '{binding}'");
+            continue;
+        };
+
+        let actions_list = create_actions(arguments, action_event, settings, &connections);
+        if modifiers.is_empty() {
+            action_map.insert(action_event, actions_list);
+        } else {
+            modifier_action_map
+                .entry(modifiers)
+                .or_default()
+                .insert(action_event, actions_list);
+        }
+    }
+
+    // Populate the fields for each gesture of the nested section, taking
+    // precedence over the bindings without modifiers.
+    for (action_event, gesture) in settings.gesture_settings() {
+        if !gesture.actions.is_empty() {
+            action_map.insert(
+                action_event,
+                create_actions(&gesture.actions, action_event, settings, &connections),
+            );
+        }
+    }
+
+    // Pass the gestures without actions through to the compositor.
+    if settings.passthrough {
+        add_passthrough_actions(&mut action_map, &connections);
+    }
+
+    // Populate the fields for each profile, device and seat.
+    let profiles = create_action_maps(&settings.profiles, "profile", settings, &connections);
+    let device_actions =
+        create_action_maps(&settings.device_actions, "device", settings, &connections);
+    let seat_bindings = settings
+        .seats
+        .iter()
+        .map(|(seat, seat_settings)| (seat.clone(), seat_settings.actions.clone()))
+        .collect();
+    let seat_actions = create_action_maps(&seat_bindings, "seat", settings, &connections);
+
+    // Populate the undo actions.
+    let mut undo_actions: ActionMap = HashMap::new();
+    for (binding, arguments) in &settings.undo_actions {
+        let Ok(action_event) = ActionEvent::from_str(binding) else {
+            warn!("Unknown gesture binding in undo actions: '{binding}'");
+            continue;
+        };
+
+        undo_actions.insert(
+            action_event,
+            create_actions(arguments, action_event, settings, &connections),
+        );
+    }
+
+    let sequences = create_sequences(settings, &connections);
+
+    ExtractedActions {
+        actions: action_map,
+        modifier_actions: modifier_action_map,
+        profiles,
+        device_actions,
+        seat_actions,
+        undo_actions,
+        sequences,
+        #[cfg(feature = "i3")]
+        connection,
+        macro_recorder: connections.macro_recorder,
+    }
+}
+
+/// Create the [`ActionMap`]s of a section with named sets of bindings, such as
+/// the profiles or the devices.
+///
+/// # Arguments
+///
+/// * `sets` - the bindings of each set, by name.
+/// * `kind` - the kind of the sets, for the log messages.
+/// * `settings` - application settings.
+/// * `connections` - connections shared between the actions.
+fn create_action_maps(
+    sets: &HashMap<String, HashMap<String, Vec<StringifiedAction>>>,
+    kind: &str,
+    settings: &Settings,
+    connections: &ActionConnections,
+) -> HashMap<String, ActionMap> {
+    let mut action_maps: HashMap<String, ActionMap> = HashMap::new();
+    for (name, bindings) in sets {
+        let mut action_map: ActionMap = HashMap::new();
+        for (binding, arguments) in bindings {
+            let Ok(action_event) = ActionEvent::from_str(binding) else {
+                warn!("Unknown gesture binding in {kind} {name}: '{binding}'");
+                continue;
+            };
+
+            action_map.insert(
+                action_event,
+                create_actions(arguments, action_event, settings, connections),
+            );
+        }
+        action_maps.insert(name.clone(), action_map);
+    }
+
+    action_maps
+}
+
+/// Create the [`GestureSequence`]s from application settings.
+///
+/// The sequences with unknown gestures are discarded.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+/// * `connections` - connections shared between the actions.
+fn create_sequences(settings: &Settings, connections: &ActionConnections) -> Vec<GestureSequence> {
+    let mut sequences = vec![];
+    for sequence_settings in &settings.sequences {
+        let Ok(events) = sequence_settings
+            .gestures
+            .iter()
+            .map(|x| ActionEvent::from_str(x))
+            .collect::<Result<Vec<ActionEvent>, _>>()
+        else {
+            warn!(
+                "Unknown gestures in sequence: '{}'",
+                sequence_settings.gestures.join(", ")
+            );
+            continue;
+        };
+        let Some(action_event) = events.last().copied() else {
+            warn!("Ignoring sequence without gestures");
+            continue;
+        };
+
+        sequences.push(GestureSequence {
+            events,
+            timeout: Duration::from_millis(sequence_settings.timeout.into()),
+            actions: create_actions(
+                &sequence_settings.actions,
+                action_event,
+                settings,
+                connections,
+            ),
+        });
+    }
+
+    sequences
+}
+
+/// Generate the [`Schedule`]s for activating profiles from application
+/// settings.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+#[must_use]
+pub fn extract_schedules(settings: &Settings) -> Vec<Schedule> {
+    settings
+        .schedules
+        .iter()
+        .filter_map(
+            |schedule_settings| match Schedule::try_from(schedule_settings) {
+                Ok(schedule) => Some(schedule),
+                Err(e) => {
+                    warn!(
+                        "Ignoring schedule for profile {}: {e}",
+                        schedule_settings.profile
+                    );
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+/// Generate the [`EventFilter`]s from application settings.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+/// * `extracted_actions` - actions generated from the settings, along with
+///   their connections.
+#[must_use]
+#[cfg_attr(not(feature = "i3"), allow(unused_variables))]
+pub fn extract_filters(
+    settings: &Settings,
+    extracted_actions: &ExtractedActions,
+) -> Vec<Box<dyn EventFilter>> {
+    let mut filters: Vec<Box<dyn EventFilter>> = vec![];
+
+    if settings.ignore_fullscreen {
+        #[cfg(feature = "i3")]
+        filters.push(Box::new(FullscreenFilter::new(Arc::clone(
+            &extracted_actions.connection,
+        ))));
+        #[cfg(not(feature = "i3"))]
+        warn!("Ignoring fullscreen windows requires the i3 feature");
+    }
+    let cooldowns: Vec<(ActionEvent, u32)> = settings
+        .gesture_settings()
+        .filter_map(|(action_event, gesture)| Some((action_event, gesture.cooldown?)))
+        .collect();
+    if settings.rate_limit.is_some() || !cooldowns.is_empty() {
+        let mut filter = RateLimitFilter::new(Duration::from_millis(
+            settings.rate_limit.unwrap_or(0).into(),
+        ));
+        for (action_event, cooldown) in cooldowns {
+            filter.set_event_interval(action_event, Duration::from_millis(cooldown.into()));
+        }
+        filters.push(Box::new(filter));
+    }
+
+    filters
+}
+
+/// Generate the [`AuditLog`] for recording the executed actions from
+/// application settings.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+#[must_use]
+pub fn extract_audit_log(settings: &Settings) -> Option<Arc<AuditLog>> {
+    let path = settings.audit_log.as_ref()?;
+    match AuditLog::open(path) {
+        Ok(audit_log) => Some(Arc::new(audit_log)),
+        Err(e) => {
+            warn!("Unable to open the audit log {path}: {e}. Skipping it.");
+            None
+        }
+    }
+}
+
+/// Generate the [`ExecutionMode`] for each [`ActionEvent`] from application
+/// settings.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+#[must_use]
+pub fn extract_execution_modes(settings: &Settings) -> HashMap<ActionEvent, ExecutionMode> {
+    let mut execution_modes: HashMap<ActionEvent, ExecutionMode> = settings
+        .execution_modes
+        .iter()
+        .filter_map(|(action_event, execution_mode)| {
+            match (
+                ActionEvent::from_str(action_event),
+                ExecutionMode::from_str(execution_mode),
+            ) {
+                (Ok(action_event), Ok(execution_mode)) => Some((action_event, execution_mode)),
+                _ => None,
+            }
+        })
+        .collect();
+
+    // Add the modes of the gestures of the nested section, taking precedence.
+    for (action_event, gesture) in settings.gesture_settings() {
+        if let Some(Ok(execution_mode)) = gesture.mode.as_deref().map(ExecutionMode::from_str) {
+            execution_modes.insert(action_event, execution_mode);
+        }
+    }
+
+    execution_modes
+}
+
+/// Convert a configuration [`Value`] into its `TOML` representation.
+///
+/// # Arguments
+///
+/// * `value` - the value to be converted.
+///
+/// # Returns
+///
+/// `None` if the value is empty or a table.
+fn value_to_toml(value: &Value) -> Option<String> {
+    Some(match &value.kind {
+        ValueKind::String(x) => format!("{x:?}"),
+        ValueKind::Boolean(x) => x.to_string(),
+        ValueKind::I64(x) => x.to_string(),
+        ValueKind::I128(x) => x.to_string(),
+        ValueKind::U64(x) => x.to_string(),
+        ValueKind::U128(x) => x.to_string(),
+        ValueKind::Float(x) => format!("{x:?}"),
+        ValueKind::Array(x) => format!(
+            "[{}]",
+            x.iter()
+                .map(value_to_toml)
+                .collect::<Option<Vec<String>>>()?
+                .join(", ")
+        ),
+        ValueKind::Nil | ValueKind::Table(_) => return None,
+    })
+}
+
+/// Sections of the settings that contain bindings, compared per action in
+/// [`diff_settings`].
+const BINDING_SECTIONS: [&str; 6] = [
+    "actions",
+    "profiles",
+    "device_actions",
+    "undo_actions",
+    "groups",
+    "gestures",
+];
+
+/// Return the bindings of the settings, as the actions for each binding.
+///
+/// The bindings are keyed by the section they belong to (for example,
+/// `actions.three-finger-swipe-up` or `profiles.work.four-finger-swipe-down`),
+/// with the gestures of the nested section merged into the `actions` section.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+fn collect_bindings(settings: &Settings) -> BTreeMap<String, Vec<String>> {
+    let to_strings =
+        |actions: &[StringifiedAction]| actions.iter().map(ToString::to_string).collect();
+    let mut bindings = BTreeMap::new();
+
+    for (binding, actions) in &settings.actions {
+        bindings.insert(format!("actions.{binding}"), to_strings(actions));
+    }
+    for (action_event, gesture) in settings.gesture_settings() {
+        if !gesture.actions.is_empty() {
+            bindings.insert(
+                format!("actions.{action_event}"),
+                to_strings(&gesture.actions),
+            );
+        }
+    }
+    for (profile, profile_bindings) in &settings.profiles {
+        for (binding, actions) in profile_bindings {
+            bindings.insert(format!("profiles.{profile}.{binding}"), to_strings(actions));
+        }
+    }
+    for (device, device_bindings) in &settings.device_actions {
+        for (binding, actions) in device_bindings {
+            bindings.insert(
+                format!("device_actions.{device}.{binding}"),
+                to_strings(actions),
+            );
+        }
+    }
+    for (seat, seat_settings) in &settings.seats {
+        for (binding, actions) in &seat_settings.actions {
+            bindings.insert(
+                format!("seats.{seat}.actions.{binding}"),
+                to_strings(actions),
+            );
+        }
+    }
+    for (binding, actions) in &settings.undo_actions {
+        bindings.insert(format!("undo_actions.{binding}"), to_strings(actions));
+    }
+    for (group, group_settings) in &settings.groups {
+        bindings.insert(
+            format!("groups.{group}"),
+            to_strings(&group_settings.actions),
+        );
+    }
+
+    bindings
+}
+
+/// Return the values of the settings that are not bindings, including the
+/// thresholds of the gestures.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+fn collect_values(settings: &Settings) -> BTreeMap<String, String> {
+    let mut values: BTreeMap<String, String> = settings
+        .collect()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(key, _)| {
+            !BINDING_SECTIONS
+                .iter()
+                .any(|section| key == section || key.starts_with(&format!("{section}.")))
+        })
+        .map(|(key, value)| {
+            let text = value_to_toml(&value).unwrap_or_else(|| value.to_string());
+            (key, text)
+        })
+        .collect();
+
+    for (action_event, threshold) in extract_event_thresholds(settings) {
+        values.insert(
+            format!("gestures.{action_event}.threshold"),
+            threshold.to_string(),
+        );
+    }
+    for (action_event, threshold) in extract_long_thresholds(settings) {
+        values.insert(
+            format!("gestures.{action_event}.long_threshold"),
+            threshold.to_string(),
+        );
+    }
+    for (action_event, velocity) in extract_fast_velocities(settings) {
+        values.insert(
+            format!("gestures.{action_event}.fast_velocity"),
+            velocity.to_string(),
+        );
+    }
+    for (action_event, hold_time) in extract_hold_times(settings) {
+        values.insert(
+            format!("gestures.{action_event}.hold_time"),
+            hold_time.as_millis().to_string(),
+        );
+    }
+    for (action_event, progress_step) in extract_progress_steps(settings) {
+        values.insert(
+            format!("gestures.{action_event}.progress_step"),
+            progress_step.to_string(),
+        );
+    }
+    for action_event in extract_repeat_events(settings) {
+        values.insert(
+            format!("gestures.{action_event}.repeat"),
+            String::from("true"),
+        );
+    }
+
+    values
+}
+
+/// Return a human-readable summary of the differences between two settings.
+///
+/// The bindings are compared per action, reporting the actions added and
+/// removed for each binding, and the rest of the settings (including the
+/// thresholds of the gestures) are compared per value.
+///
+/// # Arguments
+///
+/// * `old` - the previous settings.
+/// * `new` - the updated settings.
+///
+/// # Returns
+///
+/// A line for each difference, sorted by setting, or an empty list if the
+/// settings are equivalent.
+#[must_use]
+pub fn diff_settings(old: &Settings, new: &Settings) -> Vec<String> {
+    let mut lines = vec![];
+
+    // Compare the bindings, per action.
+    let old_bindings = collect_bindings(old);
+    let new_bindings = collect_bindings(new);
+    let keys: BTreeSet<&String> = old_bindings.keys().chain(new_bindings.keys()).collect();
+    for key in keys {
+        let line_count = lines.len();
+        let old_actions = old_bindings.get(key).map(Vec::as_slice).unwrap_or_default();
+        let new_actions = new_bindings.get(key).map(Vec::as_slice).unwrap_or_default();
+        for action in new_actions.iter().filter(|x| !old_actions.contains(x)) {
+            lines.push(format!("{key}: added action {action}"));
+        }
+        for action in old_actions.iter().filter(|x| !new_actions.contains(x)) {
+            lines.push(format!("{key}: removed action {action}"));
+        }
+        if lines.len() == line_count && old_actions != new_actions {
+            lines.push(format!("{key}: reordered actions"));
+        }
+    }
+
+    // Compare the rest of the settings, per value.
+    let old_values = collect_values(old);
+    let new_values = collect_values(new);
+    let keys: BTreeSet<&String> = old_values.keys().chain(new_values.keys()).collect();
+    for key in keys {
+        match (old_values.get(key), new_values.get(key)) {
+            (Some(old_value), Some(new_value)) if old_value != new_value => {
+                lines.push(format!("{key}: changed from {old_value} to {new_value}"));
+            }
+            (None, Some(new_value)) => lines.push(format!("{key}: set to {new_value}")),
+            (Some(old_value), None) => lines.push(format!("{key}: unset (was {old_value})")),
+            _ => (),
+        }
+    }
+
+    lines
+}
+
+/// Convert a help text into a sentence, capitalizing it and ending it with a
+/// period.
+///
+/// # Arguments
+///
+/// * `text` - the help text.
+fn to_sentence(text: &str) -> String {
+    let mut chars = text.trim_end_matches('.').chars();
+    match chars.next() {
+        Some(first) => format!("{}{}.", first.to_uppercase(), chars.as_str()),
+        None => String::new(),
+    }
+}
+
+/// Generate the D-Bus [`NamePolicy`] from application settings.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+#[must_use]
+pub fn extract_dbus_name_policy(settings: &Settings) -> NamePolicy {
+    NamePolicy::from_str(&settings.dbus_name_policy).unwrap_or_else(|_| {
+        warn!(
+            "Invalid D-Bus name policy {}, using {}",
+            settings.dbus_name_policy,
+            NamePolicy::default()
+        );
+        NamePolicy::default()
+    })
+}
+
+/// Return the default settings as a commented configuration file.
+///
+/// The template is generated from the command line arguments (for the
+/// descriptions and possible values) and the default settings (for the
+/// values), listing every gesture in the `actions` section.
+#[must_use]
+pub fn default_config_template() -> String {
+    let defaults = Settings::default().collect().unwrap_or_default();
+    let mut lines = vec![
+        String::from("# Default configuration for lillinput."),
+        String::from("#"),
+        String::from("# Uncomment and edit the entries in order to override the defaults."),
+        String::new(),
+        format!(
+            "# Level of verbosity (possible values: {}).",
+            LevelFilter::iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        format!("# verbose = \"{}\"", Settings::default().verbose),
+    ];
+
+    // Add the options, skipping the ones that are not settings.
+    for arg in Opts::command().get_arguments() {
+        let key = arg.get_id().as_str();
+        if [
+            "config_file",
+            "print_default_config",
+            "print_dbus_service",
+            "import_sway_config",
+            "export",
+            "verbose",
+            "quiet",
+            "help",
+            "version",
+        ]
+        .contains(&key)
+            || ActionEvent::from_str(&key.replace('_', "-")).is_ok()
+        {
+            continue;
+        }
+
+        let mut description = arg.get_help().map(ToString::to_string).unwrap_or_default();
+        let possible_values: Vec<String> = arg
+            .get_possible_values()
+            .iter()
+            .map(|x| x.get_name().to_string())
+            .collect();
+        if !possible_values.is_empty() {
+            description = format!(
+                "{description} (possible values: {})",
+                possible_values.join(", ")
+            );
+        }
+
+        lines.push(String::new());
+        if let Some(value) = defaults.get(key).and_then(value_to_toml) {
+            lines.push(format!("# {}", to_sentence(&description)));
+            lines.push(format!("# {key} = {value}"));
+        } else {
+            lines.push(format!(
+                "# {} Not set by default.",
+                to_sentence(&description)
+            ));
+            lines.push(format!("# {key} ="));
+        }
+    }
+
+    // Add the actions of every gesture.
+    lines.push(String::new());
+    lines.push(String::from("[actions]"));
+    lines.push(format!(
+        "# Actions for each gesture, as `{{type}}:{{command}}` strings (possible types: {}).",
+        ACTION_TYPES
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<&str>>()
+            .join(", ")
+    ));
+    for action_event in ActionEvent::iter() {
+        let value = defaults
+            .get(&format!("actions.{action_event}"))
+            .and_then(value_to_toml)
+            .unwrap_or_else(|| String::from("[]"));
+        lines.push(format!("# {action_event} = {value}"));
+    }
+
+    // Add pointers to the rest of the sections.
+    lines.extend(
+        [
+            "",
+            "# Other sections, empty by default:",
+            "# * [execution_modes]: mode for executing the actions of each gesture",
+            &format!(
+                "#   (possible values: {}).",
+                ExecutionMode::VARIANTS.join(", ")
+            ),
+            "# * [sector_widths]: width (in degrees) of each swipe direction, adding up to 360.",
+            "# * [undo_actions]: actions for undoing each gesture.",
+            "# * [[sequences]]: gestures performed in quick succession, with their own actions.",
+            "# * [device_actions.{device}]: actions for the gestures of a specific device.",
+            "# * [profiles.{name}] and [[schedules]]: sets of actions and settings, selected",
+            "#   via the `profile` option or activated on a schedule.",
+            "# * [gestures.{kind}.{fingers}.{direction}]: settings of individual gestures.",
+            "# * [rest.{name}]: definitions of REST requests.",
+            "# * [groups.{name}]: reusable groups of actions, referenced as `@{name}`.",
+            "# * [seats.{seat}]: threshold and actions overridden for a specific seat.",
+        ]
+        .map(String::from),
+    );
+
+    lines.join("\n") + "\n"
+}
+
+/// Generate the minimum threshold of specific [`ActionEvent`]s from
+/// application settings.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+#[must_use]
+pub fn extract_event_thresholds(settings: &Settings) -> HashMap<ActionEvent, f64> {
+    settings
+        .gesture_settings()
+        .filter_map(|(action_event, gesture)| Some((action_event, gesture.threshold?)))
+        .collect()
+}
+
+/// Generate the minimum threshold for triggering the long variant of specific
+/// [`ActionEvent`]s from application settings.
+///
+/// The long thresholds of the gestures that are already long variants are
+/// ignored.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+#[must_use]
+pub fn extract_long_thresholds(settings: &Settings) -> HashMap<ActionEvent, f64> {
+    settings
+        .gesture_settings()
+        .filter(|(action_event, _)| !action_event.is_long())
+        .filter_map(|(action_event, gesture)| Some((action_event, gesture.long_threshold?)))
+        .collect()
+}
+
+/// Generate the minimum velocity for triggering the fast variant of specific
+/// [`ActionEvent`]s from application settings.
+///
+/// Only the velocities of the regular swipes are used, as the long variant
+/// takes precedence over the fast one.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+#[must_use]
+pub fn extract_fast_velocities(settings: &Settings) -> HashMap<ActionEvent, f64> {
+    settings
+        .gesture_settings()
+        .filter(|(action_event, _)| *action_event == action_event.short())
+        .filter(|(action_event, _)| action_event.is_swipe())
+        .filter_map(|(action_event, gesture)| Some((action_event, gesture.fast_velocity?)))
+        .collect()
+}
+
+/// Generate the minimum time the fingers are held still for triggering the
+/// hold variant of specific [`ActionEvent`]s from application settings.
+///
+/// Only the times of the regular swipes are used, as the long variant takes
+/// precedence over the hold one.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+#[must_use]
+pub fn extract_hold_times(settings: &Settings) -> HashMap<ActionEvent, Duration> {
+    settings
+        .gesture_settings()
+        .filter(|(action_event, _)| *action_event == action_event.short())
+        .filter(|(action_event, _)| action_event.is_swipe())
+        .filter_map(|(action_event, gesture)| {
+            Some((
+                action_event,
+                Duration::from_millis(gesture.hold_time?.into()),
+            ))
+        })
+        .collect()
+}
+
+/// Generate the enabled [`DeviceClass`]es from application settings.
+///
+/// All the classes are enabled if none are specified.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+#[must_use]
+pub fn extract_device_classes(settings: &Settings) -> BTreeSet<DeviceClass> {
+    if settings.device_classes.is_empty() {
+        return DeviceClass::iter().collect();
+    }
+
+    settings
+        .device_classes
+        .iter()
+        .filter_map(|name| {
+            let device_class = DeviceClass::from_str(name).ok();
+            if device_class.is_none() {
+                warn!("Ignoring unknown device class: {name}");
+            }
+            device_class
+        })
+        .collect()
+}
+
+/// Generate the displacement between the progress events of specific
+/// [`ActionEvent`]s from application settings.
+///
+/// The progress steps of the gestures that are not regular swipes are
+/// ignored, as only the swipes are tracked while in progress.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+#[must_use]
+pub fn extract_progress_steps(settings: &Settings) -> HashMap<ActionEvent, f64> {
+    settings
+        .gesture_settings()
+        .filter(|(action_event, _)| {
+            *action_event == action_event.short() && action_event.is_swipe()
+        })
+        .filter_map(|(action_event, gesture)| Some((action_event, gesture.progress_step?)))
+        .collect()
+}
+
+/// Generate the [`ActionEvent`]s repeated while in progress from application
+/// settings.
+///
+/// As with the progress steps, the gestures that are not regular swipes are
+/// ignored.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+#[must_use]
+pub fn extract_repeat_events(settings: &Settings) -> HashSet<ActionEvent> {
+    settings
+        .gesture_settings()
+        .filter(|(action_event, gesture)| {
+            gesture.repeat == Some(true)
+                && *action_event == action_event.short()
+                && action_event.is_swipe()
+        })
+        .map(|(action_event, _)| action_event)
+        .collect()
+}
+
+/// Generate the number of directions recognized for the swipes from
+/// application settings.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+#[must_use]
+pub fn extract_directions(settings: &Settings) -> u8 {
+    match settings.directions {
+        4 | 8 => settings.directions,
+        directions => {
+            warn!("Invalid number of directions {directions}, using 8");
+            8
+        }
+    }
+}
+
+/// Generate the width of the sector of each swipe direction from application
+/// settings.
+///
+/// The widths are keyed by direction (for example, `left-up`), and are only
+/// used if all the directions are present and their widths add up to `360`
+/// degrees. With `4` directions, the widths of the diagonals must be `0`.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+#[must_use]
+pub fn extract_sector_widths(settings: &Settings) -> Option<[f64; 8]> {
+    if settings.sector_widths.is_empty() {
+        return None;
+    }
+    let invalid = |reason: String| {
+        warn!("Invalid sector widths ({reason}), using equal sectors");
+        None
+    };
+
+    if let Some(direction) = settings
+        .sector_widths
+        .keys()
+        .find(|direction| !SWIPE_DIRECTIONS.contains(&direction.as_str()))
+    {
+        return invalid(format!("unknown direction {direction}"));
+    }
+
+    let mut widths = [0.0; 8];
+    for (width, direction) in widths.iter_mut().zip(SWIPE_DIRECTIONS) {
+        match settings.sector_widths.get(direction) {
+            Some(value) if *value >= 0.0 => *width = *value,
+            Some(value) => return invalid(format!("negative width {value} for {direction}")),
+            None => return invalid(format!("missing direction {direction}")),
+        }
+    }
+    let total: f64 = widths.iter().sum();
+    if (total - 360.0).abs() > 1e-6 {
+        return invalid(format!("widths adding up to {total} degrees"));
+    }
+    if extract_directions(settings) == 4 {
+        // The diagonals are odd octants.
+        if let Some((width, direction)) = widths
+            .iter()
+            .zip(SWIPE_DIRECTIONS)
+            .skip(1)
+            .step_by(2)
+            .find(|(width, _)| **width != 0.0)
+        {
+            return invalid(format!(
+                "width {width} for {direction}, with only 4 directions"
+            ));
+        }
+    }
+
+    Some(widths)
+}
+
+/// Generate the [`ThresholdMode`] from application settings.
+///
+/// # Arguments
+///
+/// * `settings` - application settings.
+#[must_use]
+pub fn extract_threshold_mode(settings: &Settings) -> ThresholdMode {
+    ThresholdMode::from_str(&settings.threshold_mode).unwrap_or_else(|_| {
+        warn!(
+            "Invalid threshold mode {}, using {}",
+            settings.threshold_mode,
+            ThresholdMode::default()
+        );
+        ThresholdMode::default()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(all(feature = "i3", feature = "command"))]
+    use std::env;
+
+    use super::*;
+    use crate::test_utils::default_test_settings;
+    use config::FileFormat;
+    #[cfg(all(feature = "i3", feature = "command"))]
+    use lillinput::controllers::{ControllerState, DefaultController};
+    #[cfg(all(feature = "i3", feature = "command"))]
+    use lillinput::events::DefaultProcessor;
+
+    #[cfg(all(feature = "i3", feature = "command"))]
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    #[cfg(all(feature = "i3", feature = "command"))]
+    ///Test graceful handling of unavailable i3 connection.
+    fn test_i3_not_available() {
+        // Initialize the command line options.
+        let mut settings: Settings = default_test_settings();
+        settings.enabled_action_types = vec!["i3".to_string()];
+        settings.actions.insert(
             ActionEvent::ThreeFingerSwipeRight.to_string(),
             vec![
                 StringifiedAction::new("i3", "swipe right"),
@@ -420,7 +3068,8 @@ mod test {
 
         // Create the controller.
         env::set_var("I3SOCK", "/tmp/non-existing-socket");
-        let (actions, _) = extract_action_map(&settings);
+        let ExtractedActions { actions, .. } =
+            extract_action_map(&settings, &Arc::new(Mutex::new(ControllerState::default())));
         let processor = DefaultProcessor::default();
         let controller = DefaultController::new(Box::new(processor), actions);
 
@@ -434,4 +3083,418 @@ mod test {
             1
         );
     }
+
+    #[test]
+    /// Test pruning the action types that are not compiled in.
+    fn test_prune_disabled_action_types() {
+        let mut settings: Settings = default_test_settings();
+        settings.enabled_action_types = vec!["internal".to_string(), "obs".to_string()];
+        settings.actions.insert(
+            ActionEvent::ThreeFingerSwipeRight.to_string(),
+            vec![
+                StringifiedAction::new("internal", "pause"),
+                StringifiedAction::new("obs", "toggle-record"),
+            ],
+        );
+
+        let mut log_entries = vec![];
+        prune_settings(&mut settings, &mut log_entries);
+
+        // Assert that the obs action type is only kept if compiled in.
+        let obs_enabled = cfg!(feature = "obs");
+        assert_eq!(
+            settings.enabled_action_types.contains(&"obs".to_string()),
+            obs_enabled
+        );
+        assert_eq!(
+            settings.actions[&ActionEvent::ThreeFingerSwipeRight.to_string()].len(),
+            if obs_enabled { 2 } else { 1 }
+        );
+    }
+
+    #[test]
+    /// Test the conversion of execution modes from settings.
+    fn test_extract_execution_modes() {
+        let mut settings: Settings = default_test_settings();
+        settings.execution_modes.insert(
+            ActionEvent::ThreeFingerSwipeRight.to_string(),
+            ExecutionMode::Parallel.to_string(),
+        );
+        settings.execution_modes.insert(
+            ActionEvent::ThreeFingerSwipeLeft.to_string(),
+            String::from("invalid"),
+        );
+
+        let execution_modes = extract_execution_modes(&settings);
+
+        // Assert that only the valid execution mode is extracted.
+        assert_eq!(
+            execution_modes,
+            HashMap::from([(ActionEvent::ThreeFingerSwipeRight, ExecutionMode::Parallel)])
+        );
+    }
+
+    #[test]
+    /// Test the validation of the sector widths from settings.
+    fn test_extract_sector_widths() {
+        let mut settings: Settings = default_test_settings();
+        assert_eq!(extract_sector_widths(&settings), None);
+
+        settings.sector_widths = ["left", "up", "right", "down"]
+            .into_iter()
+            .map(|direction| (String::from(direction), 60.0))
+            .chain(
+                ["left-up", "right-up", "right-down", "left-down"]
+                    .into_iter()
+                    .map(|direction| (String::from(direction), 30.0)),
+            )
+            .collect();
+        assert_eq!(
+            extract_sector_widths(&settings),
+            Some([60.0, 30.0, 60.0, 30.0, 60.0, 30.0, 60.0, 30.0])
+        );
+
+        // Assert that invalid widths are discarded.
+        settings.sector_widths.insert(String::from("up"), 90.0);
+        assert_eq!(extract_sector_widths(&settings), None);
+        settings.sector_widths.remove("up");
+        assert_eq!(extract_sector_widths(&settings), None);
+        settings.sector_widths.insert(String::from("upwards"), 60.0);
+        assert_eq!(extract_sector_widths(&settings), None);
+
+        // Assert that the diagonals are rejected with only 4 directions.
+        settings.sector_widths = SWIPE_DIRECTIONS
+            .into_iter()
+            .map(|direction| (String::from(direction), 45.0))
+            .collect();
+        settings.directions = 4;
+        assert_eq!(extract_sector_widths(&settings), None);
+        settings.sector_widths = SWIPE_DIRECTIONS
+            .into_iter()
+            .enumerate()
+            .map(|(octant, direction)| {
+                let width = if octant % 2 == 0 { 90.0 } else { 0.0 };
+                (String::from(direction), width)
+            })
+            .collect();
+        assert_eq!(
+            extract_sector_widths(&settings),
+            Some([90.0, 0.0, 90.0, 0.0, 90.0, 0.0, 90.0, 0.0])
+        );
+    }
+
+    #[test]
+    /// Test the parsing of gesture bindings.
+    fn test_parse_binding() {
+        assert_eq!(
+            parse_binding("three-finger-swipe-right"),
+            Some((BTreeSet::new(), ActionEvent::ThreeFingerSwipeRight))
+        );
+        assert_eq!(
+            parse_binding("super+shift+four-finger-swipe-up"),
+            Some((
+                BTreeSet::from([Modifier::Shift, Modifier::Super]),
+                ActionEvent::FourFingerSwipeUp
+            ))
+        );
+        assert_eq!(
+            parse_binding("side-button+three-finger-swipe-left"),
+            Some((
+                BTreeSet::from([Modifier::SideButton]),
+                ActionEvent::ThreeFingerSwipeLeft
+            ))
+        );
+
+        // Assert that unknown modifiers or events are rejected.
+        assert_eq!(parse_binding("hyper+three-finger-swipe-right"), None);
+        assert_eq!(parse_binding("super+three-finger-tap"), None);
+    }
+
+    #[test]
+    /// Test the conversion of device classes from settings.
+    fn test_extract_device_classes() {
+        let mut settings: Settings = default_test_settings();
+        assert_eq!(
+            extract_device_classes(&settings),
+            DeviceClass::iter().collect()
+        );
+
+        // Assert that unknown classes are ignored.
+        settings.device_classes = vec![
+            String::from("internal-touchpad"),
+            String::from("foo"),
+            String::from("trackpoint"),
+        ];
+        assert_eq!(
+            extract_device_classes(&settings),
+            BTreeSet::from([DeviceClass::InternalTouchpad, DeviceClass::Trackpoint])
+        );
+    }
+
+    #[test]
+    /// Test the conversion of schedules from settings.
+    fn test_extract_schedules() {
+        let mut settings: Settings = default_test_settings();
+        settings.schedules = vec![
+            ScheduleSettings {
+                profile: String::from("work"),
+                start: String::from("09:00"),
+                end: String::from("17:30"),
+                days: vec![String::from("mon"), String::from("friday")],
+            },
+            ScheduleSettings {
+                profile: String::from("invalid"),
+                start: String::from("25:00"),
+                end: String::from("17:30"),
+                days: vec![],
+            },
+        ];
+
+        // Assert that only the valid schedule is extracted.
+        assert_eq!(
+            extract_schedules(&settings),
+            vec![Schedule {
+                profile: String::from("work"),
+                start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                end: NaiveTime::from_hms_opt(17, 30, 0).unwrap(),
+                days: vec![Weekday::Mon, Weekday::Fri],
+            }]
+        );
+    }
+
+    #[test]
+    /// Test the conversion of the nested gestures section from settings.
+    fn test_extract_gestures() {
+        let mut settings: Settings = default_test_settings();
+        settings.execution_modes.insert(
+            ActionEvent::ThreeFingerSwipeUp.to_string(),
+            ExecutionMode::Sequential.to_string(),
+        );
+        settings.gestures = HashMap::from([(
+            String::from("swipe"),
+            HashMap::from([(
+                String::from("3"),
+                HashMap::from([
+                    (
+                        String::from("up"),
+                        GestureSettings {
+                            threshold: Some(30.0),
+                            long_threshold: Some(80.0),
+                            fast_velocity: Some(200.0),
+                            hold_time: Some(400),
+                            progress_step: Some(15.0),
+                            repeat: Some(true),
+                            mode: Some(ExecutionMode::Parallel.to_string()),
+                            ..GestureSettings::default()
+                        },
+                    ),
+                    (
+                        String::from("up-long"),
+                        GestureSettings {
+                            long_threshold: Some(90.0),
+                            fast_velocity: Some(300.0),
+                            hold_time: Some(600),
+                            progress_step: Some(20.0),
+                            repeat: Some(true),
+                            ..GestureSettings::default()
+                        },
+                    ),
+                    (
+                        String::from("sideways"),
+                        GestureSettings {
+                            threshold: Some(10.0),
+                            repeat: Some(true),
+                            ..GestureSettings::default()
+                        },
+                    ),
+                ]),
+            )]),
+        )]);
+
+        // Assert that only the known gestures are extracted, taking
+        // precedence over the flat sections.
+        assert_eq!(
+            extract_event_thresholds(&settings),
+            HashMap::from([(ActionEvent::ThreeFingerSwipeUp, 30.0)])
+        );
+        assert_eq!(
+            extract_long_thresholds(&settings),
+            HashMap::from([(ActionEvent::ThreeFingerSwipeUp, 80.0)])
+        );
+        assert_eq!(
+            extract_fast_velocities(&settings),
+            HashMap::from([(ActionEvent::ThreeFingerSwipeUp, 200.0)])
+        );
+        assert_eq!(
+            extract_hold_times(&settings),
+            HashMap::from([(ActionEvent::ThreeFingerSwipeUp, Duration::from_millis(400))])
+        );
+        assert_eq!(
+            extract_progress_steps(&settings),
+            HashMap::from([(ActionEvent::ThreeFingerSwipeUp, 15.0)])
+        );
+        assert_eq!(
+            extract_repeat_events(&settings),
+            HashSet::from([ActionEvent::ThreeFingerSwipeUp])
+        );
+        assert_eq!(
+            extract_execution_modes(&settings),
+            HashMap::from([(ActionEvent::ThreeFingerSwipeUp, ExecutionMode::Parallel)])
+        );
+        assert!(parse_gesture("swipe", "3", "sideways").is_empty());
+    }
+
+    #[test]
+    /// Test the parsing of gestures with finger count ranges.
+    fn test_parse_gesture_ranges() {
+        assert_eq!(
+            parse_gesture("swipe", "4", "left-down"),
+            vec![ActionEvent::FourFingerSwipeLeftDown]
+        );
+        assert_eq!(
+            parse_gesture("swipe", "3+", "up"),
+            vec![
+                ActionEvent::ThreeFingerSwipeUp,
+                ActionEvent::FourFingerSwipeUp
+            ]
+        );
+        assert_eq!(
+            parse_gesture("swipe", "1-3", "up"),
+            vec![ActionEvent::ThreeFingerSwipeUp]
+        );
+        assert!(parse_gesture("swipe", "5+", "up").is_empty());
+        assert!(parse_gesture("swipe", "three", "up").is_empty());
+        assert_eq!(
+            parse_gesture("rotate", "2+", "counter-clockwise"),
+            vec![ActionEvent::TwoFingerRotateCounterClockwise]
+        );
+        assert_eq!(
+            parse_gesture("pinch", "2-3", "out-strong"),
+            vec![
+                ActionEvent::TwoFingerPinchOutStrong,
+                ActionEvent::ThreeFingerPinchOutStrong
+            ]
+        );
+
+        // Assert that the specific finger counts take precedence.
+        let mut settings: Settings = default_test_settings();
+        settings.gestures = HashMap::from([(
+            String::from("swipe"),
+            HashMap::from([
+                (
+                    String::from("3+"),
+                    HashMap::from([(
+                        String::from("up"),
+                        GestureSettings {
+                            threshold: Some(30.0),
+                            ..GestureSettings::default()
+                        },
+                    )]),
+                ),
+                (
+                    String::from("4"),
+                    HashMap::from([(
+                        String::from("up"),
+                        GestureSettings {
+                            threshold: Some(10.0),
+                            ..GestureSettings::default()
+                        },
+                    )]),
+                ),
+            ]),
+        )]);
+        assert_eq!(
+            extract_event_thresholds(&settings),
+            HashMap::from([
+                (ActionEvent::ThreeFingerSwipeUp, 30.0),
+                (ActionEvent::FourFingerSwipeUp, 10.0)
+            ])
+        );
+    }
+
+    #[test]
+    /// Test summarizing the differences between two settings.
+    fn test_diff_settings() {
+        let mut old_settings: Settings = default_test_settings();
+        old_settings.actions.insert(
+            String::from("three-finger-swipe-up"),
+            vec![
+                StringifiedAction::new("i3", "foo"),
+                StringifiedAction::new("command", "bar"),
+            ],
+        );
+        old_settings.actions.insert(
+            String::from("four-finger-swipe-up"),
+            vec![StringifiedAction::new("i3", "baz")],
+        );
+        assert!(diff_settings(&old_settings, &old_settings.clone()).is_empty());
+
+        let mut new_settings = old_settings.clone();
+        new_settings.threshold = 10.0;
+        new_settings.audit_log = Some(String::from("audit.log"));
+        new_settings.actions.insert(
+            String::from("three-finger-swipe-up"),
+            vec![
+                StringifiedAction::new("i3", "foo"),
+                StringifiedAction::new("command", "qux"),
+            ],
+        );
+        new_settings.actions.insert(
+            String::from("four-finger-swipe-up"),
+            vec![StringifiedAction::new("i3", "baz")],
+        );
+        new_settings.gestures = HashMap::from([(
+            String::from("swipe"),
+            HashMap::from([(
+                String::from("4"),
+                HashMap::from([(
+                    String::from("down"),
+                    GestureSettings {
+                        threshold: Some(20.0),
+                        ..GestureSettings::default()
+                    },
+                )]),
+            )]),
+        )]);
+
+        assert_eq!(
+            diff_settings(&old_settings, &new_settings),
+            vec![
+                "actions.three-finger-swipe-up: added action command:qux",
+                "actions.three-finger-swipe-up: removed action command:bar",
+                "audit_log: set to \"audit.log\"",
+                "gestures.four-finger-swipe-down.threshold: set to 20",
+                "threshold: changed from 5.0 to 10.0",
+            ]
+        );
+    }
+
+    #[test]
+    /// Test that the default config template matches the default settings.
+    fn test_default_config_template() {
+        // Uncomment the entries that have a value.
+        let template = default_config_template();
+        let contents = template
+            .lines()
+            .map(|line| match line.strip_prefix("# ") {
+                Some(entry) if entry.contains(" = ") => entry,
+                _ => line,
+            })
+            .collect::<Vec<&str>>()
+            .join("\n");
+
+        let mut settings: Settings = Config::builder()
+            .add_source(File::from_str(&contents, FileFormat::Toml))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap();
+        prune_settings(&mut settings, &mut vec![]);
+
+        // Assert that every gesture is listed, and the values are the defaults.
+        for action_event in ActionEvent::iter() {
+            assert!(template.contains(&format!("# {action_event} = ")));
+        }
+        assert_eq!(settings, Settings::default());
+    }
 }
@@ -0,0 +1,499 @@
+//! Conversion of `sway` `bindgesture` bindings.
+//!
+//! `sway` binds gestures via `bindgesture <gesture>[:<fingers>][:<directions>]
+//! <command>`, with the directions combined via `+`. As `sway` commands are
+//! compatible with `i3`, the bindings are converted from and into `i3`
+//! actions.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use lillinput::events::ActionEvent;
+
+use crate::opts::StringifiedAction;
+use crate::settings::{parse_binding, Settings};
+
+/// Swipe directions of `sway`, along with the corresponding direction of the
+/// [`ActionEvent`]s.
+///
+/// The diagonals are expressed as two directions combined via `+`.
+const DIRECTIONS: [(&[&str], &str); 8] = [
+    (&["left"], "left"),
+    (&["left", "up"], "left-up"),
+    (&["up"], "up"),
+    (&["right", "up"], "right-up"),
+    (&["right"], "right"),
+    (&["down", "right"], "right-down"),
+    (&["down"], "down"),
+    (&["down", "left"], "left-down"),
+];
+
+/// Result of importing the bindings of a `sway` configuration.
+#[derive(Debug, Default, PartialEq)]
+pub struct SwayImport {
+    /// Actions for each [`ActionEvent`], keyed by the name of the event.
+    pub actions: BTreeMap<String, Vec<StringifiedAction>>,
+    /// Bindings that could not be converted, along with the reason.
+    pub skipped: Vec<(String, String)>,
+}
+
+/// Convert a `sway` gesture into the [`ActionEvent`]s it matches.
+///
+/// Gestures without a finger count match both three and four finger swipes,
+/// and gestures without directions match every direction. Pinches are only
+/// supported as two-finger rotations (for example, `pinch:2:clockwise`).
+///
+/// # Arguments
+///
+/// * `gesture` - the gesture (for example, `swipe:3:right`).
+///
+/// # Errors
+///
+/// Returns `Err` with the reason if the gesture cannot be converted.
+pub fn parse_gesture(gesture: &str) -> Result<Vec<ActionEvent>, String> {
+    let mut fields = gesture.split(':');
+    match fields.next() {
+        Some("swipe") => {}
+        Some("pinch") => return parse_rotation(fields),
+        Some(kind) => return Err(format!("unsupported gesture type: {kind}")),
+        None => return Err(String::from("empty gesture")),
+    }
+
+    let mut finger_counts = vec!["three", "four"];
+    let mut directions: Vec<&str> = DIRECTIONS.iter().map(|(_, x)| *x).collect();
+    for field in fields {
+        if let Ok(finger_count) = field.parse::<u32>() {
+            finger_counts = match finger_count {
+                3 => vec!["three"],
+                4 => vec!["four"],
+                _ => return Err(format!("unsupported finger count: {finger_count}")),
+            };
+        } else {
+            let mut names: Vec<&str> = field.split('+').collect();
+            names.sort_unstable();
+            names.dedup();
+            let direction = DIRECTIONS
+                .iter()
+                .find(|(sway_names, _)| *sway_names == names.as_slice())
+                .ok_or_else(|| format!("unsupported directions: {field}"))?;
+            directions = vec![direction.1];
+        }
+    }
+
+    finger_counts
+        .iter()
+        .flat_map(|finger_count| {
+            directions
+                .iter()
+                .map(move |direction| format!("{finger_count}-finger-swipe-{direction}"))
+        })
+        .map(|name| ActionEvent::from_str(&name).map_err(|e| e.to_string()))
+        .collect()
+}
+
+/// Convert the fields of a `sway` pinch gesture into its rotation
+/// [`ActionEvent`].
+///
+/// # Arguments
+///
+/// * `fields` - the fields following the gesture type.
+///
+/// # Errors
+///
+/// Returns `Err` with the reason if the pinch is not a two-finger rotation.
+fn parse_rotation<'a>(fields: impl Iterator<Item = &'a str>) -> Result<Vec<ActionEvent>, String> {
+    let mut action_event = None;
+    for field in fields {
+        match field.parse::<u32>() {
+            Ok(2) => {}
+            Ok(finger_count) => return Err(format!("unsupported finger count: {finger_count}")),
+            Err(_) => {
+                action_event = Some(match field {
+                    "clockwise" => ActionEvent::TwoFingerRotateClockwise,
+                    "counterclockwise" => ActionEvent::TwoFingerRotateCounterClockwise,
+                    _ => return Err(format!("unsupported directions: {field}")),
+                });
+            }
+        }
+    }
+
+    action_event
+        .map(|action_event| vec![action_event])
+        .ok_or_else(|| String::from("pinch gestures are only supported as rotations"))
+}
+
+/// Return the statements of a `sway` configuration.
+///
+/// Comments are removed, continued lines are joined, variables defined via
+/// `set` are expanded, and the statements inside blocks (such as
+/// `bindgesture { ... }`) are prefixed with the command of the block.
+///
+/// # Arguments
+///
+/// * `config` - the contents of the configuration file.
+fn statements(config: &str) -> Vec<String> {
+    let mut variables: Vec<(String, String)> = vec![];
+    let mut block: Option<String> = None;
+    let mut statements = vec![];
+
+    let joined = config.replace("\\\n", " ");
+    for line in joined.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        // Expand the variables, longest first in order to avoid replacing
+        // the prefixes of other variables.
+        let mut line = line.to_string();
+        for (name, value) in &variables {
+            line = line.replace(name.as_str(), value);
+        }
+
+        if let Some(definition) = line.strip_prefix("set ") {
+            if let Some((name, value)) = definition.trim().split_once(char::is_whitespace) {
+                variables.push((name.to_string(), value.trim().to_string()));
+                variables.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+            }
+        } else if let Some(command) = line.strip_suffix('{') {
+            block = Some(command.trim().to_string());
+        } else if line == "}" {
+            block = None;
+        } else if let Some(command) = &block {
+            statements.push(format!("{command} {line}"));
+        } else {
+            statements.push(line);
+        }
+    }
+
+    statements
+}
+
+/// Import the `bindgesture` bindings of a `sway` configuration.
+///
+/// The commands are converted into `i3` actions. Later bindings of a gesture
+/// replace the earlier ones, as in `sway`.
+///
+/// # Arguments
+///
+/// * `config` - the contents of the configuration file.
+#[must_use]
+pub fn import_bindgestures(config: &str) -> SwayImport {
+    let mut import = SwayImport::default();
+
+    for statement in statements(config) {
+        let Some(arguments) = statement.strip_prefix("bindgesture ") else {
+            continue;
+        };
+
+        // Skip the flags, which have no equivalent.
+        let mut arguments = arguments.trim_start();
+        while let Some(rest) = arguments.strip_prefix("--") {
+            arguments = rest
+                .split_once(char::is_whitespace)
+                .map_or("", |(_, rest)| rest.trim_start());
+        }
+
+        let Some((gesture, command)) = arguments.split_once(char::is_whitespace) else {
+            import
+                .skipped
+                .push((statement.clone(), String::from("missing command")));
+            continue;
+        };
+
+        match parse_gesture(gesture) {
+            Ok(action_events) => {
+                for action_event in action_events {
+                    import.actions.insert(
+                        action_event.to_string(),
+                        vec![StringifiedAction::new("i3", command.trim())],
+                    );
+                }
+            }
+            Err(reason) => import.skipped.push((statement.clone(), reason)),
+        }
+    }
+
+    import
+}
+
+/// Render an imported `sway` configuration as a `lillinput` configuration.
+///
+/// The bindings that could not be converted are included as comments.
+///
+/// # Arguments
+///
+/// * `import` - the imported bindings.
+#[must_use]
+pub fn render_import(import: &SwayImport) -> String {
+    let mut lines = vec![
+        String::from("# Configuration for lillinput, imported from sway."),
+        String::new(),
+        String::from("[actions]"),
+    ];
+
+    for (binding, actions) in &import.actions {
+        let actions: Vec<String> = actions
+            .iter()
+            .map(|x| format!("{:?}", x.to_string()))
+            .collect();
+        lines.push(format!("{binding} = [{}]", actions.join(", ")));
+    }
+
+    if !import.skipped.is_empty() {
+        lines.push(String::new());
+        lines.push(String::from("# Bindings that could not be imported:"));
+        for (statement, reason) in &import.skipped {
+            lines.push(format!("# {statement} ({reason})"));
+        }
+    }
+
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Convert an [`ActionEvent`] into a `sway` gesture.
+///
+/// # Arguments
+///
+/// * `action_event` - the event.
+///
+/// # Errors
+///
+/// Returns `Err` with the reason if the event has no equivalent.
+fn to_gesture(action_event: ActionEvent) -> Result<String, String> {
+    if action_event.is_long() {
+        return Err(String::from("long gestures are not supported by sway"));
+    }
+    if action_event.is_fast() {
+        return Err(String::from("fast gestures are not supported by sway"));
+    }
+    if action_event.is_hold() {
+        return Err(String::from("hold gestures are not supported by sway"));
+    }
+
+    match action_event {
+        ActionEvent::TwoFingerRotateClockwise => return Ok(String::from("pinch:2:clockwise")),
+        ActionEvent::TwoFingerRotateCounterClockwise => {
+            return Ok(String::from("pinch:2:counterclockwise"));
+        }
+        _ => {}
+    }
+
+    let name = action_event.to_string();
+    let (finger_count, direction) =
+        if let Some(direction) = name.strip_prefix("three-finger-swipe-") {
+            (3, direction)
+        } else if let Some(direction) = name.strip_prefix("four-finger-swipe-") {
+            (4, direction)
+        } else {
+            return Err(format!("unsupported gesture: {name}"));
+        };
+    let (sway_names, _) = DIRECTIONS
+        .iter()
+        .find(|(_, x)| *x == direction)
+        .ok_or_else(|| format!("unsupported gesture: {name}"))?;
+
+    Ok(format!("swipe:{finger_count}:{}", sway_names.join("+")))
+}
+
+/// Convert an action into a `sway` command.
+///
+/// # Arguments
+///
+/// * `action` - the action.
+///
+/// # Errors
+///
+/// Returns `Err` with the reason if the action has no equivalent.
+fn to_command(action: &StringifiedAction) -> Result<String, String> {
+    match action.type_.as_str() {
+        "i3" => Ok(action.command.clone()),
+        "command" => Ok(format!("exec {}", action.command)),
+        type_ => Err(format!("{type_} actions are not supported by sway")),
+    }
+}
+
+/// Export the bindings of the settings as `sway` `bindgesture` lines.
+///
+/// The `i3` actions are exported as `sway` commands, and the `command`
+/// actions as `exec` commands. The bindings that cannot be translated (such
+/// as the ones with modifiers, or the ones with other actions) are included
+/// as comments.
+///
+/// # Arguments
+///
+/// * `settings` - application settings, with the action groups expanded.
+#[must_use]
+pub fn export_bindgestures(settings: &Settings) -> String {
+    // Merge the gestures of the nested section into the bindings.
+    let mut bindings: BTreeMap<String, &Vec<StringifiedAction>> = settings
+        .actions
+        .iter()
+        .map(|(binding, actions)| (binding.clone(), actions))
+        .collect();
+    for (action_event, gesture) in settings.gesture_settings() {
+        if !gesture.actions.is_empty() {
+            bindings.insert(action_event.to_string(), &gesture.actions);
+        }
+    }
+
+    let mut lines = vec![String::from("# Gesture bindings exported from lillinput.")];
+    let mut skipped = vec![];
+    for (binding, actions) in bindings {
+        if actions.is_empty() {
+            continue;
+        }
+
+        let line = match parse_binding(&binding) {
+            None => Err(String::from("unknown gesture")),
+            Some((modifiers, _)) if !modifiers.is_empty() => {
+                Err(String::from("modifiers are not supported by sway"))
+            }
+            Some((_, action_event)) => to_gesture(action_event).and_then(|gesture| {
+                let commands = actions
+                    .iter()
+                    .map(to_command)
+                    .collect::<Result<Vec<String>, String>>()?;
+                Ok(format!(
+                    "bindgesture --exact {gesture} {}",
+                    commands.join("; ")
+                ))
+            }),
+        };
+        match line {
+            Ok(line) => lines.push(line),
+            Err(reason) => skipped.push(format!("# {binding} ({reason})")),
+        }
+    }
+
+    if !skipped.is_empty() {
+        lines.push(String::new());
+        lines.push(String::from("# Bindings that could not be exported:"));
+        lines.extend(skipped);
+    }
+
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use lillinput::events::ActionEvent;
+
+    use super::{export_bindgestures, import_bindgestures, parse_gesture};
+    use crate::opts::StringifiedAction;
+    use crate::test_utils::default_test_settings;
+
+    #[test]
+    /// Test the conversion of `sway` gestures.
+    fn test_parse_gesture() {
+        assert_eq!(
+            parse_gesture("swipe:3:right"),
+            Ok(vec![ActionEvent::ThreeFingerSwipeRight])
+        );
+        assert_eq!(
+            parse_gesture("swipe:4:up+left"),
+            Ok(vec![ActionEvent::FourFingerSwipeLeftUp])
+        );
+        assert_eq!(
+            parse_gesture("swipe:down"),
+            Ok(vec![
+                ActionEvent::ThreeFingerSwipeDown,
+                ActionEvent::FourFingerSwipeDown
+            ])
+        );
+        assert_eq!(parse_gesture("swipe:3").map(|x| x.len()), Ok(8));
+        assert_eq!(
+            parse_gesture("pinch:2:counterclockwise"),
+            Ok(vec![ActionEvent::TwoFingerRotateCounterClockwise])
+        );
+
+        // Assert that gestures without an equivalent are rejected.
+        assert!(parse_gesture("pinch:2:inward").is_err());
+        assert!(parse_gesture("pinch:3:clockwise").is_err());
+        assert!(parse_gesture("swipe:5:left").is_err());
+        assert!(parse_gesture("swipe:3:up+down").is_err());
+    }
+
+    #[test]
+    /// Test importing the bindings of a `sway` configuration.
+    fn test_import_bindgestures() {
+        let config = "
+# Workspaces.
+set $ws workspace
+bindgesture swipe:3:right $ws next
+bindgesture --exact swipe:3:left \\
+    $ws prev
+bindgesture {
+    swipe:4:up fullscreen toggle
+    hold:3 kill
+}
+bindsym $mod+Return exec foot
+";
+        let import = import_bindgestures(config);
+        assert_eq!(
+            import.actions.get("three-finger-swipe-right"),
+            Some(&vec![StringifiedAction::new("i3", "workspace next")])
+        );
+        assert_eq!(
+            import.actions.get("three-finger-swipe-left"),
+            Some(&vec![StringifiedAction::new("i3", "workspace prev")])
+        );
+        assert_eq!(
+            import.actions.get("four-finger-swipe-up"),
+            Some(&vec![StringifiedAction::new("i3", "fullscreen toggle")])
+        );
+        assert_eq!(import.actions.len(), 3);
+        assert_eq!(import.skipped.len(), 1);
+        assert_eq!(import.skipped[0].0, "bindgesture hold:3 kill");
+    }
+
+    #[test]
+    /// Test exporting the bindings as `sway` `bindgesture` lines.
+    fn test_export_bindgestures() {
+        let mut settings = default_test_settings();
+        settings.actions = HashMap::from([
+            (
+                String::from("three-finger-swipe-left-up"),
+                vec![
+                    StringifiedAction::new("i3", "workspace prev"),
+                    StringifiedAction::new("command", "notify-send foo"),
+                ],
+            ),
+            (
+                String::from("four-finger-swipe-down"),
+                vec![StringifiedAction::new("log", "foo")],
+            ),
+            (
+                String::from("two-finger-rotate-clockwise"),
+                vec![StringifiedAction::new("command", "brightnessctl set +10%")],
+            ),
+            (
+                String::from("super+three-finger-swipe-right"),
+                vec![StringifiedAction::new("i3", "workspace next")],
+            ),
+        ]);
+
+        let exported = export_bindgestures(&settings);
+        assert!(exported.contains(
+            "bindgesture --exact swipe:3:left+up workspace prev; exec notify-send foo\n"
+        ));
+        assert!(exported
+            .contains("bindgesture --exact pinch:2:clockwise exec brightnessctl set +10%\n"));
+        assert!(exported.contains("# four-finger-swipe-down (log actions are not supported"));
+        assert!(exported.contains("# super+three-finger-swipe-right (modifiers"));
+
+        // Assert that the exported bindings can be imported back.
+        let import = import_bindgestures(&exported);
+        assert_eq!(
+            import.actions.get("three-finger-swipe-left-up"),
+            Some(&vec![StringifiedAction::new(
+                "i3",
+                "workspace prev; exec notify-send foo"
+            )])
+        );
+        assert_eq!(import.actions.len(), 2);
+    }
+}
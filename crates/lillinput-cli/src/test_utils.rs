@@ -14,5 +14,56 @@ pub fn default_test_settings() -> Settings {
         verbose: LevelFilter::Info,
         invert_x: false,
         invert_y: false,
+        execution_modes: HashMap::new(),
+        max_repeated_events: None,
+        tmux_socket: None,
+        obs_url: String::from("ws://localhost:4455"),
+        obs_password: None,
+        rest: HashMap::new(),
+        disable_while_typing: None,
+        ignore_fullscreen: false,
+        profiles: HashMap::new(),
+        schedules: vec![],
+        macros_file: None,
+        undo_actions: HashMap::new(),
+        undo_window: 500,
+        rate_limit: None,
+        devices: vec![],
+        tick_interval: None,
+        direction_margin: None,
+        gestures: HashMap::new(),
+        validate_actions: false,
+        profile: None,
+        groups: HashMap::new(),
+        threshold_mode: String::from("absolute"),
+        audit_log: None,
+        dbus_name_policy: String::from("disabled"),
+        max_events: None,
+        duration: None,
+        passthrough: false,
+        device_classes: vec![],
+        learn_thresholds: false,
+        rotation_threshold: 30.0,
+        edge_margin: 3.0,
+        edge_threshold: 10.0,
+        directions: 8,
+        sector_widths: HashMap::new(),
+        direction_deadzone: None,
+        threshold_x: None,
+        threshold_y: None,
+        max_gesture_duration: None,
+        sequences: vec![],
+        device_actions: HashMap::new(),
+        extra_seats: vec![],
+        seats: HashMap::new(),
+        allow_devices: vec![],
+        deny_devices: vec![],
+        log_command_output: true,
+        plugins_directory: None,
+        helper_command: None,
+        pinch_in_threshold: 0.8,
+        pinch_out_threshold: 1.25,
+        strong_pinch_in_threshold: None,
+        strong_pinch_out_threshold: None,
     }
 }
@@ -0,0 +1,63 @@
+//! Benchmarks replaying a corpus of swipe events through the processor and
+//! the controller.
+//!
+//! A synthetic corpus is used by default. A recorded corpus can be replayed
+//! instead by setting the `LILLINPUT_CORPUS` environment variable to the path
+//! of its file.
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use lillinput::actions::{Action, LogAction};
+use lillinput::controllers::DefaultController;
+use lillinput::events::{ActionEvent, DefaultProcessor, SwipeCorpus};
+use strum::IntoEnumIterator;
+
+/// Return the corpus to be replayed.
+fn corpus() -> SwipeCorpus {
+    match env::var("LILLINPUT_CORPUS") {
+        Ok(path) => fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("unable to read corpus {path}: {e}"))
+            .parse()
+            .unwrap_or_else(|e| panic!("unable to parse corpus {path}: {e}")),
+        Err(_) => SwipeCorpus::synthetic(10_000, 20),
+    }
+}
+
+/// Return a controller with a no-op action for each event.
+fn controller() -> DefaultController {
+    let actions: HashMap<ActionEvent, Vec<Box<dyn Action>>> = ActionEvent::iter()
+        .map(|action_event| {
+            let action: Box<dyn Action> = Box::new(LogAction::new(String::new(), action_event));
+            (action_event, vec![action])
+        })
+        .collect();
+
+    DefaultController::new(Box::new(DefaultProcessor::default()), actions)
+}
+
+/// Benchmark replaying the corpus through the processor and the controller.
+fn bench_replay(c: &mut Criterion) {
+    let corpus = corpus();
+    let mut processor = DefaultProcessor::default();
+
+    let mut group = c.benchmark_group("replay");
+    group.throughput(Throughput::Elements(corpus.events.len() as u64));
+    group.bench_function("processor", |b| {
+        b.iter(|| black_box(corpus.replay(&mut processor)));
+    });
+    group.bench_function("controller", |b| {
+        b.iter_batched_ref(
+            controller,
+            |controller| black_box(controller.replay(&corpus)),
+            BatchSize::LargeInput,
+        );
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_replay);
+criterion_main!(benches);
@@ -0,0 +1,82 @@
+//! Action for placing text on the Wayland clipboard.
+
+use std::fmt;
+
+use crate::actions::errors::ActionError;
+use crate::actions::{render_template, Action, ActionType};
+use crate::events::{ActionEvent, GestureContext};
+use wl_clipboard_rs::copy::{MimeType, Options, Source};
+
+/// Action that places text on the Wayland clipboard.
+///
+/// The text is offered via the `data-control` protocol of the compositor,
+/// without spawning external processes such as `wl-copy`. It can contain the
+/// following variables, which are replaced with information about the
+/// gesture when the action is executed:
+/// * `{event}`: the [`ActionEvent`] that triggered the action.
+#[derive(Debug)]
+pub struct ClipboardAction {
+    /// Template of the text to be placed on the clipboard.
+    template: String,
+    /// Event that triggers this action.
+    action_event: ActionEvent,
+}
+
+impl ClipboardAction {
+    /// Create a new [`ClipboardAction`].
+    ///
+    /// # Arguments
+    ///
+    /// * `template` - template of the text to be placed on the clipboard.
+    /// * `action_event` - event that triggers this action.
+    #[must_use]
+    pub fn new(template: String, action_event: ActionEvent) -> Self {
+        ClipboardAction {
+            template,
+            action_event,
+        }
+    }
+
+    /// Return the text placed on the clipboard by the action.
+    #[must_use]
+    pub fn text(&self) -> String {
+        render_template(&self.template, self.action_event)
+    }
+}
+
+impl Action for ClipboardAction {
+    fn execute_command(&mut self, _context: &GestureContext) -> Result<(), ActionError> {
+        // The selection is served from the background until another client
+        // replaces it.
+        Options::new()
+            .copy(
+                Source::Bytes(self.text().into_bytes().into()),
+                MimeType::Text,
+            )
+            .map_err(|e| ActionError::ExecutionError {
+                type_: ActionType::Clipboard.to_string(),
+                message: e.to_string(),
+            })
+    }
+
+    fn fmt_command(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:<{}>", ActionType::Clipboard, self.template)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ClipboardAction;
+    use crate::events::ActionEvent;
+
+    #[test]
+    /// Test the rendering of the text.
+    fn test_clipboard_text() {
+        let action = ClipboardAction::new(
+            String::from("Performed {event}"),
+            ActionEvent::FourFingerSwipeDown,
+        );
+
+        assert_eq!(action.text(), "Performed four-finger-swipe-down");
+    }
+}
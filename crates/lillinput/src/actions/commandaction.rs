@@ -1,17 +1,99 @@
 //! Action for executing commands.
 
+use std::collections::HashMap;
 use std::fmt;
-use std::process::Command;
+use std::path::PathBuf;
+use std::process::{Child, Command, Output, Stdio};
 
 use crate::actions::errors::ActionError;
 use crate::actions::{Action, ActionType};
+use crate::events::GestureContext;
+use log::debug;
 use shlex::split;
 
+/// Maximum number of bytes of each output stream of a command included in
+/// the logs.
+pub const MAX_LOGGED_OUTPUT: usize = 1024;
+
 /// Action that executes shell commands.
+///
+/// The context of the gesture is exposed to the command via environment
+/// variables (see [`context_env_vars`]), allowing a single script to handle
+/// several gestures. The output of the command is captured and logged at debug
+/// level, and included in the error if the command fails.
 #[derive(Debug)]
 pub struct CommandAction {
     /// Command to be executed in this action.
     command: String,
+    /// Options for executing the command.
+    options: CommandOptions,
+}
+
+/// Options for executing the command of a [`CommandAction`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CommandOptions {
+    /// Working directory of the command, instead of the current one.
+    pub working_directory: Option<PathBuf>,
+    /// Additional environment variables of the command.
+    pub env: HashMap<String, String>,
+    /// Whether to execute the command via `sh -c`, allowing shell syntax
+    /// (such as pipes or redirections), instead of splitting it into
+    /// arguments.
+    pub shell: bool,
+    /// Whether to discard the output of the command, instead of capturing
+    /// and logging it.
+    pub discard_output: bool,
+}
+
+/// Return the environment variables describing the context of a gesture.
+///
+/// The variables are:
+/// * `LILLINPUT_EVENT`: the event that triggered the action.
+/// * `LILLINPUT_FINGERS`: the number of fingers used for the gesture, if
+///   known.
+/// * `LILLINPUT_DX` and `LILLINPUT_DY`: the accumulated displacement of the
+///   gesture.
+/// * `LILLINPUT_DEVICE`: the name of the device that performed the gesture,
+///   if known.
+///
+/// # Arguments
+///
+/// * `context` - the context of the gesture.
+#[must_use]
+pub fn context_env_vars(context: &GestureContext) -> Vec<(&'static str, String)> {
+    let mut env_vars = vec![
+        ("LILLINPUT_EVENT", context.action_event.to_string()),
+        ("LILLINPUT_DX", context.dx.to_string()),
+        ("LILLINPUT_DY", context.dy.to_string()),
+    ];
+    if let Some(finger_count) = context.finger_count {
+        env_vars.push(("LILLINPUT_FINGERS", finger_count.to_string()));
+    }
+    if let Some(device) = &context.device {
+        env_vars.push(("LILLINPUT_DEVICE", device.name.clone()));
+    }
+
+    env_vars
+}
+
+/// Return an output stream of a command as text, truncated to
+/// [`MAX_LOGGED_OUTPUT`] bytes.
+///
+/// # Arguments
+///
+/// * `output` - the contents of the output stream.
+fn format_output(output: &[u8]) -> String {
+    let text = String::from_utf8_lossy(output);
+    let text = text.trim_end();
+    if text.len() <= MAX_LOGGED_OUTPUT {
+        return text.to_string();
+    }
+
+    let mut end = MAX_LOGGED_OUTPUT;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}... ({} bytes truncated)", &text[..end], text.len() - end)
 }
 
 impl CommandAction {
@@ -22,27 +104,135 @@ impl CommandAction {
     /// * `command` - shell command to be executed in this action.
     #[must_use]
     pub fn new(command: String) -> CommandAction {
-        CommandAction { command }
+        CommandAction::with_options(command, CommandOptions::default())
+    }
+
+    /// Create a new [`CommandAction`], with options for executing the
+    /// command.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - shell command to be executed in this action.
+    /// * `options` - options for executing the command.
+    #[must_use]
+    pub fn with_options(command: String, options: CommandOptions) -> CommandAction {
+        CommandAction { command, options }
+    }
+
+    /// Build the [`Command`] to be executed in this action.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - the context of the gesture that triggered the action.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command string could not be parsed.
+    fn build_command(&self, context: &GestureContext) -> Result<Command, ActionError> {
+        let mut command = if self.options.shell {
+            let mut command = Command::new("sh");
+            command.arg("-c").arg(&self.command);
+            command
+        } else {
+            let split_commands = split(&self.command).filter(|x| !x.is_empty()).ok_or(
+                ActionError::ExecutionError {
+                    type_: "command".into(),
+                    message: format!("Unable to parse command: {}", self.command),
+                },
+            )?;
+            let mut command = Command::new(&split_commands[0]);
+            command.args(&split_commands[1..]);
+            command
+        };
+        if let Some(working_directory) = &self.options.working_directory {
+            command.current_dir(working_directory);
+        }
+        // The variables specified by the user take precedence over the
+        // context of the gesture.
+        command.envs(context_env_vars(context));
+        command.envs(&self.options.env);
+
+        if self.options.discard_output {
+            command.stdout(Stdio::null()).stderr(Stdio::null());
+        } else {
+            command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        }
+
+        Ok(command)
+    }
+
+    /// Check the output of the finished command, logging it.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - the output of the command.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command did not exit successfully, including its
+    /// standard error.
+    fn check_output(&self, output: &Output) -> Result<(), ActionError> {
+        for (name, stream) in [("stdout", &output.stdout), ("stderr", &output.stderr)] {
+            if !stream.is_empty() {
+                debug!(
+                    "Output of command {} ({name}): {}",
+                    self.command,
+                    format_output(stream)
+                );
+            }
+        }
+        if output.status.success() {
+            return Ok(());
+        }
+
+        let stderr = format_output(&output.stderr);
+        Err(ActionError::ExecutionError {
+            type_: "command".into(),
+            message: if stderr.is_empty() {
+                output.status.to_string()
+            } else {
+                format!("{}: {stderr}", output.status)
+            },
+        })
     }
 }
 
 impl Action for CommandAction {
-    fn execute_command(&mut self) -> Result<(), ActionError> {
+    fn execute_command(&mut self, context: &GestureContext) -> Result<(), ActionError> {
         // Perform the command, if specified.
-        let split_commands = split(&self.command).ok_or(ActionError::ExecutionError {
-            type_: "command".into(),
-            message: format!("Unable to parse command: {}", self.command),
-        })?;
-        Command::new(&split_commands[0])
-            .args(&split_commands[1..])
+        let output = self
+            .build_command(context)?
+            .stdin(Stdio::null())
             .output()
-            .map(|_| ())
+            .map_err(|e| ActionError::ExecutionError {
+                type_: "command".into(),
+                message: e.to_string(),
+            })?;
+        self.check_output(&output)
+    }
+
+    fn start_command(&mut self, context: &GestureContext) -> Result<Option<Child>, ActionError> {
+        // Spawn the command, collecting its output in `finish_command()`.
+        self.build_command(context)?
+            .stdin(Stdio::null())
+            .spawn()
+            .map(Some)
             .map_err(|e| ActionError::ExecutionError {
                 type_: "command".into(),
                 message: e.to_string(),
             })
     }
 
+    fn finish_command(&mut self, child: Child) -> Result<(), ActionError> {
+        let output = child
+            .wait_with_output()
+            .map_err(|e| ActionError::ExecutionError {
+                type_: "command".into(),
+                message: e.to_string(),
+            })?;
+        self.check_output(&output)
+    }
+
     fn fmt_command(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}:<{}>", ActionType::Command, self.command)
     }
@@ -50,12 +240,17 @@ impl Action for CommandAction {
 
 #[cfg(test)]
 mod test {
-    use std::path::Path;
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
 
-    use super::CommandAction;
-    use crate::actions::Action;
-    use crate::controllers::{Controller, DefaultController};
-    use crate::events::ActionEvent;
+    use super::{format_output, CommandAction, CommandOptions, MAX_LOGGED_OUTPUT};
+    use crate::actions::{
+        Action, ActionError, DelayedAction, InternalAction, MacroRecorder, RecordMacroAction,
+    };
+    use crate::controllers::{Controller, ControllerError, DefaultController, ExecutionMode};
+    use crate::events::{ActionEvent, GestureContext, GestureDevice};
     use serial_test::serial;
 
     #[test]
@@ -84,4 +279,299 @@ mod test {
         assert!(Path::new(expected_file).exists());
         std::fs::remove_file(expected_file).ok();
     }
+
+    #[test]
+    #[serial]
+    /// Test exposing the context of the gesture to the commands.
+    fn test_command_context_env() {
+        // File where the environment will be written.
+        let expected_file = "/tmp/swipe-env";
+        std::fs::remove_file(expected_file).ok();
+
+        // Create the controller, with the context of a gesture.
+        let mut controller = DefaultController::default();
+        controller.actions.insert(
+            ActionEvent::ThreeFingerSwipeRight,
+            vec![Box::new(CommandAction::new(format!(
+                "sh -c 'env > {expected_file}'"
+            )))],
+        );
+        controller.gesture_context = Some(GestureContext {
+            action_event: ActionEvent::ThreeFingerSwipeRight,
+            finger_count: Some(3),
+            dx: 12.5,
+            dy: -1.0,
+            device: Some(GestureDevice {
+                name: String::from("Test Touchpad"),
+                sysname: String::from("event5"),
+            }),
+        });
+
+        // Trigger a swipe.
+        controller
+            .process_action_event(ActionEvent::ThreeFingerSwipeRight)
+            .unwrap();
+
+        // Assert.
+        let env = std::fs::read_to_string(expected_file).unwrap();
+        let env: Vec<&str> = env.lines().collect();
+        for expected in [
+            "LILLINPUT_EVENT=three-finger-swipe-right",
+            "LILLINPUT_FINGERS=3",
+            "LILLINPUT_DX=12.5",
+            "LILLINPUT_DY=-1",
+            "LILLINPUT_DEVICE=Test Touchpad",
+        ] {
+            assert!(env.contains(&expected), "{expected} not found");
+        }
+        std::fs::remove_file(expected_file).ok();
+    }
+
+    #[test]
+    #[serial]
+    /// Test executing a command with a working directory, environment
+    /// variables and via the shell.
+    fn test_command_options() {
+        // File that will be written, relative to the working directory.
+        let expected_file = "/tmp/swipe-options";
+        std::fs::remove_file(expected_file).ok();
+
+        let options = CommandOptions {
+            working_directory: Some(PathBuf::from("/tmp")),
+            env: HashMap::from([(String::from("GREETING"), String::from("hello"))]),
+            shell: true,
+            discard_output: false,
+        };
+        let mut action = CommandAction::with_options(
+            String::from("echo \"$GREETING $LILLINPUT_EVENT\" > swipe-options"),
+            options,
+        );
+        action
+            .execute_command(&GestureContext::new(ActionEvent::ThreeFingerSwipeUp))
+            .unwrap();
+
+        // Assert.
+        assert_eq!(
+            std::fs::read_to_string(expected_file).unwrap(),
+            "hello three-finger-swipe-up\n"
+        );
+        std::fs::remove_file(expected_file).ok();
+    }
+
+    #[test]
+    /// Test reporting the output of the commands that fail.
+    fn test_command_output() {
+        let context = GestureContext::new(ActionEvent::ThreeFingerSwipeUp);
+        let mut action = CommandAction::new(String::from("sh -c 'echo out; echo err >&2'"));
+        assert!(action.execute_command(&context).is_ok());
+
+        let mut action = CommandAction::new(String::from("sh -c 'echo out; echo err >&2; exit 3'"));
+        assert_eq!(
+            action.execute_command(&context),
+            Err(ActionError::ExecutionError {
+                type_: String::from("command"),
+                message: String::from("exit status: 3: err"),
+            })
+        );
+        let child = action.start_command(&context).unwrap().unwrap();
+        assert!(action.finish_command(child).is_err());
+
+        // Assert that the output is not captured if discarded.
+        let options = CommandOptions {
+            discard_output: true,
+            ..CommandOptions::default()
+        };
+        let mut action =
+            CommandAction::with_options(String::from("sh -c 'echo err >&2; exit 3'"), options);
+        assert_eq!(
+            action.execute_command(&context),
+            Err(ActionError::ExecutionError {
+                type_: String::from("command"),
+                message: String::from("exit status: 3"),
+            })
+        );
+
+        // Assert that long outputs are truncated.
+        let output = "é".repeat(MAX_LOGGED_OUTPUT);
+        let formatted = format_output(output.as_bytes());
+        assert!(formatted.starts_with(&"é".repeat(MAX_LOGGED_OUTPUT / 2)));
+        assert!(formatted.ends_with(&format!("... ({MAX_LOGGED_OUTPUT} bytes truncated)")));
+        assert_eq!(format_output(b"foo\n"), "foo");
+    }
+
+    #[test]
+    #[serial]
+    /// Test the triggering of commands for a swipe action in parallel mode.
+    fn test_command_parallel_actions() {
+        // Files that will be touched.
+        let expected_files = ["/tmp/swipe-left-1", "/tmp/swipe-left-2"];
+        for expected_file in expected_files {
+            std::fs::remove_file(expected_file).ok();
+        }
+
+        // Create the controller.
+        let actions_list: Vec<Box<dyn Action>> = expected_files
+            .iter()
+            .map(|x| Box::new(CommandAction::new(format!("touch {x}"))) as Box<dyn Action>)
+            .collect();
+        let mut controller = DefaultController::default();
+        controller
+            .actions
+            .insert(ActionEvent::ThreeFingerSwipeLeft, actions_list);
+        controller
+            .execution_modes
+            .insert(ActionEvent::ThreeFingerSwipeLeft, ExecutionMode::Parallel);
+
+        // Trigger a swipe.
+        controller
+            .process_action_event(ActionEvent::ThreeFingerSwipeLeft)
+            .ok();
+
+        // Assert.
+        for expected_file in expected_files {
+            assert!(Path::new(expected_file).exists());
+            std::fs::remove_file(expected_file).ok();
+        }
+    }
+
+    #[test]
+    #[serial]
+    /// Test the triggering of commands for a swipe action in chain mode.
+    fn test_command_chain_actions() {
+        // Files that will be touched, the second one after a failed step.
+        let expected_files = ["/tmp/swipe-chain-1", "/tmp/swipe-chain-2"];
+        for expected_file in expected_files {
+            std::fs::remove_file(expected_file).ok();
+        }
+
+        // Create the controller.
+        let mut controller = DefaultController::default();
+        controller.actions.insert(
+            ActionEvent::ThreeFingerSwipeDown,
+            vec![
+                Box::new(CommandAction::new(format!("touch {}", expected_files[0]))),
+                Box::new(DelayedAction::new(
+                    Duration::from_millis(50),
+                    Box::new(CommandAction::new(String::from("false"))),
+                )),
+                Box::new(CommandAction::new(format!("touch {}", expected_files[1]))),
+            ],
+        );
+        controller
+            .execution_modes
+            .insert(ActionEvent::ThreeFingerSwipeDown, ExecutionMode::Chain);
+
+        // Trigger a swipe.
+        let start = Instant::now();
+        controller
+            .process_action_event(ActionEvent::ThreeFingerSwipeDown)
+            .ok();
+
+        // Assert that the steps after the failed one are skipped.
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert!(Path::new(expected_files[0]).exists());
+        assert!(!Path::new(expected_files[1]).exists());
+        std::fs::remove_file(expected_files[0]).ok();
+    }
+
+    #[test]
+    #[serial]
+    /// Test the recording and replaying of a macro with commands.
+    fn test_command_macro_actions() {
+        // File that will be touched.
+        let expected_file = "/tmp/swipe-macro";
+        std::fs::remove_file(expected_file).ok();
+
+        // Create the controller, with a gesture for recording a macro bound
+        // to "four-finger swipe down".
+        let macro_recorder = Arc::new(Mutex::new(MacroRecorder::default()));
+        let mut controller = DefaultController::default();
+        controller.actions.insert(
+            ActionEvent::ThreeFingerSwipeUp,
+            vec![Box::new(
+                RecordMacroAction::new("four-finger-swipe-down", Arc::clone(&macro_recorder))
+                    .unwrap(),
+            )],
+        );
+        controller.actions.insert(
+            ActionEvent::ThreeFingerSwipeLeft,
+            vec![Box::new(CommandAction::new(format!(
+                "touch {expected_file}"
+            )))],
+        );
+        controller.macro_recorder = Some(Arc::clone(&macro_recorder));
+
+        // Record the macro.
+        for action_event in [
+            ActionEvent::ThreeFingerSwipeUp,
+            ActionEvent::ThreeFingerSwipeLeft,
+            ActionEvent::ThreeFingerSwipeUp,
+        ] {
+            controller.process_action_event(action_event).ok();
+        }
+        assert_eq!(
+            macro_recorder
+                .lock()
+                .unwrap()
+                .macros
+                .get(&ActionEvent::FourFingerSwipeDown),
+            Some(&vec![ActionEvent::ThreeFingerSwipeLeft])
+        );
+
+        // Trigger the macro.
+        std::fs::remove_file(expected_file).ok();
+        controller
+            .process_action_event(ActionEvent::FourFingerSwipeDown)
+            .ok();
+
+        // Assert.
+        assert!(Path::new(expected_file).exists());
+        std::fs::remove_file(expected_file).ok();
+    }
+
+    #[test]
+    #[serial]
+    /// Test pausing and resuming the execution of commands via a gesture.
+    fn test_command_paused_actions() {
+        // File that will be touched.
+        let expected_file = "/tmp/swipe-paused";
+        std::fs::remove_file(expected_file).ok();
+
+        // Create the controller, with a gesture for pausing.
+        let mut controller = DefaultController::default();
+        controller.actions.insert(
+            ActionEvent::FourFingerSwipeUp,
+            vec![Box::new(
+                InternalAction::new("toggle-pause", Arc::clone(&controller.state)).unwrap(),
+            )],
+        );
+        controller.actions.insert(
+            ActionEvent::ThreeFingerSwipeLeft,
+            vec![Box::new(CommandAction::new(format!(
+                "touch {expected_file}"
+            )))],
+        );
+
+        // Assert that the command is not executed while paused.
+        controller
+            .process_action_event(ActionEvent::FourFingerSwipeUp)
+            .unwrap();
+        assert!(controller.is_paused());
+        assert!(matches!(
+            controller.process_action_event(ActionEvent::ThreeFingerSwipeLeft),
+            Err(ControllerError::Paused)
+        ));
+        assert!(!Path::new(expected_file).exists());
+
+        // Assert that the command is executed after resuming.
+        controller
+            .process_action_event(ActionEvent::FourFingerSwipeUp)
+            .unwrap();
+        assert!(!controller.is_paused());
+        controller
+            .process_action_event(ActionEvent::ThreeFingerSwipeLeft)
+            .ok();
+        assert!(Path::new(expected_file).exists());
+        std::fs::remove_file(expected_file).ok();
+    }
 }
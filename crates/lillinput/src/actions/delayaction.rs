@@ -0,0 +1,97 @@
+//! Action for waiting before executing another action.
+
+use std::fmt;
+use std::process::Child;
+use std::thread;
+use std::time::Duration;
+
+use crate::actions::errors::ActionError;
+use crate::actions::Action;
+use crate::events::GestureContext;
+
+/// Action that waits for a delay before executing another action.
+///
+/// Allows declaring chains of steps (for example, focusing a window, waiting
+/// for it to be focused and sending a key) without relying on `sleep`
+/// commands. Along with [`ExecutionMode::Chain`], the remaining steps are
+/// skipped if one of them fails.
+///
+/// [`ExecutionMode::Chain`]: crate::controllers::ExecutionMode::Chain
+#[derive(Debug)]
+pub struct DelayedAction {
+    /// Time to wait before executing the action.
+    delay: Duration,
+    /// Action to be executed after the delay.
+    action: Box<dyn Action>,
+}
+
+impl DelayedAction {
+    /// Create a new [`DelayedAction`].
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - time to wait before executing the action.
+    /// * `action` - action to be executed after the delay.
+    #[must_use]
+    pub fn new(delay: Duration, action: Box<dyn Action>) -> Self {
+        DelayedAction { delay, action }
+    }
+}
+
+impl Action for DelayedAction {
+    fn execute_command(&mut self, context: &GestureContext) -> Result<(), ActionError> {
+        thread::sleep(self.delay);
+        self.action.execute_command(context)
+    }
+
+    fn start_command(&mut self, context: &GestureContext) -> Result<Option<Child>, ActionError> {
+        thread::sleep(self.delay);
+        self.action.start_command(context)
+    }
+
+    fn finish_command(&mut self, child: Child) -> Result<(), ActionError> {
+        self.action.finish_command(child)
+    }
+
+    fn runs_while_paused(&self) -> bool {
+        self.action.runs_while_paused()
+    }
+
+    fn is_available(&self) -> bool {
+        self.action.is_available()
+    }
+
+    fn validate(&self) -> Result<(), ActionError> {
+        self.action.validate()
+    }
+
+    fn fmt_command(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (after {}ms)", self.action, self.delay.as_millis())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use super::DelayedAction;
+    use crate::actions::{Action, LogAction};
+    use crate::events::{ActionEvent, GestureContext};
+
+    #[test]
+    /// Test waiting for the delay before executing the action.
+    fn test_delayed_action() {
+        let action_event = ActionEvent::ThreeFingerSwipeUp;
+        let mut action: Box<dyn Action> = Box::new(DelayedAction::new(
+            Duration::from_millis(50),
+            Box::new(LogAction::new(String::from("foo"), action_event)),
+        ));
+        assert_eq!(action.to_string(), "log:<foo> (after 50ms)");
+
+        let start = Instant::now();
+        action
+            .execute_command(&GestureContext::new(action_event))
+            .unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+}
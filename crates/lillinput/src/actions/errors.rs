@@ -2,12 +2,18 @@
 //!
 //! [`actions`]: crate::actions
 
+#[cfg(feature = "plugins")]
+use std::path::PathBuf;
+
+use strum::IntoStaticStr;
 use thiserror::Error;
 
 /// Errors raised during execution of an [`Action`].
 ///
 /// [`Action`]: crate::actions::Action
-#[derive(Error, Debug, PartialEq, Eq)]
+#[derive(Error, Debug, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "kebab_case")]
+#[non_exhaustive]
 pub enum ActionError {
     /// Command execution resulted in error.
     #[error("{type_}: command execution resulted in error: {message}")]
@@ -17,4 +23,68 @@ pub enum ActionError {
         /// Command error message.
         message: String,
     },
+
+    /// Command could not be parsed into a valid action.
+    #[error("{type_}: invalid command: {message}")]
+    InvalidCommand {
+        /// Action type.
+        type_: String,
+        /// Parsing error message.
+        message: String,
+    },
+
+    /// Action type is not known.
+    #[error("unknown action type: {0}")]
+    UnknownType(String),
+
+    /// Action type is known, but not compiled in.
+    #[error("{type_}: action type is disabled (requires the {feature} feature)")]
+    DisabledType {
+        /// Action type.
+        type_: String,
+        /// Cargo feature that enables the action type.
+        feature: String,
+    },
+}
+
+/// Errors raised while loading a plugin.
+#[cfg(feature = "plugins")]
+#[derive(Error, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PluginError {
+    /// The library could not be loaded, or does not declare a plugin.
+    #[error("{}: unable to load plugin: {message}", path.display())]
+    Load {
+        /// Path of the library.
+        path: PathBuf,
+        /// Loading error message.
+        message: String,
+    },
+
+    /// The plugin is not compatible with this version of `lillinput`.
+    #[error("{}: incompatible plugin: {message}", path.display())]
+    Incompatible {
+        /// Path of the library.
+        path: PathBuf,
+        /// Incompatibility message.
+        message: String,
+    },
+
+    /// A plugin with the same name is already registered.
+    #[error("{}: duplicate plugin: {name}", path.display())]
+    Duplicate {
+        /// Path of the library.
+        path: PathBuf,
+        /// Name of the plugin.
+        name: String,
+    },
+}
+
+impl ActionError {
+    /// Return the stable code of the error, as the kebab-case name of the
+    /// variant.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        self.into()
+    }
 }
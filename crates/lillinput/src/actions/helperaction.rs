@@ -0,0 +1,345 @@
+//! Action for sending the gestures to a long-running helper process.
+//!
+//! The helper process receives a request per gesture on its standard input,
+//! as a line with a JSON object:
+//!
+//! ```json
+//! {"event": "three-finger-swipe-up", "fingers": 3, "dx": 0.0, "dy": -42.5, "device": "Touchpad", "command": "next"}
+//! ```
+//!
+//! and acknowledges each request with a line on its standard output: `ok` if
+//! the request succeeded, or the reason of the failure otherwise.
+
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::io::AsRawFd;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::ptr;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use crate::actions::errors::ActionError;
+use crate::actions::{Action, ActionType};
+use crate::events::GestureContext;
+use log::info;
+use serde_json::json;
+use shlex::split;
+
+/// Default time to wait for the helper process to acknowledge a request.
+pub const DEFAULT_HELPER_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Acknowledgement of a successful request.
+const ACK_OK: &str = "ok";
+
+/// Shared helper process.
+pub type SharedHelperProcess = Arc<Mutex<HelperProcess>>;
+
+/// Running helper process, along with its standard input and output.
+struct RunningHelper {
+    /// Handle of the process.
+    child: Child,
+    /// Standard input of the process, for writing the requests.
+    stdin: ChildStdin,
+    /// Standard output of the process, for reading the acknowledgements.
+    stdout: BufReader<ChildStdout>,
+}
+
+/// Long-running helper process receiving the gestures.
+///
+/// The process is spawned lazily on the first request, and spawned again if
+/// it exited or failed to acknowledge a request. It is killed when the
+/// [`HelperProcess`] is dropped.
+pub struct HelperProcess {
+    /// Command of the helper process.
+    command: String,
+    /// Time to wait for the helper process to acknowledge a request.
+    timeout: Duration,
+    /// Helper process, if running.
+    running: Option<RunningHelper>,
+}
+
+impl fmt::Debug for HelperProcess {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HelperProcess")
+            .field("command", &self.command)
+            .field("running", &self.running.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for HelperProcess {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl HelperProcess {
+    /// Create a new [`HelperProcess`].
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - command of the helper process.
+    /// * `timeout` - time to wait for the helper process to acknowledge a
+    ///   request.
+    #[must_use]
+    pub fn new(command: String, timeout: Duration) -> Self {
+        HelperProcess {
+            command,
+            timeout,
+            running: None,
+        }
+    }
+
+    /// Spawn the helper process.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command could not be parsed or spawned.
+    fn spawn(&self) -> Result<RunningHelper, String> {
+        let arguments = split(&self.command)
+            .filter(|x| !x.is_empty())
+            .ok_or(format!("unable to parse command: {}", self.command))?;
+        let mut child = Command::new(&arguments[0])
+            .args(&arguments[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| e.to_string())?;
+        info!("Started helper process: {}", self.command);
+
+        match (child.stdin.take(), child.stdout.take()) {
+            (Some(stdin), Some(stdout)) => Ok(RunningHelper {
+                child,
+                stdin,
+                stdout: BufReader::new(stdout),
+            }),
+            _ => Err(String::from("unable to access the standard streams")),
+        }
+    }
+
+    /// Stop the helper process, if running.
+    fn stop(&mut self) {
+        if let Some(mut running) = self.running.take() {
+            running.child.kill().ok();
+            running.child.wait().ok();
+        }
+    }
+
+    /// Send a request to the helper process, waiting for it to be
+    /// acknowledged.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - the request, as a single line of JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the request could not be sent, if it was not
+    /// acknowledged in time, or if the helper process reported a failure.
+    pub fn send(&mut self, request: &str) -> Result<(), String> {
+        let running = match self.running.take() {
+            Some(running) => running,
+            None => self.spawn()?,
+        };
+        let running = self.running.insert(running);
+
+        let timeout = self.timeout;
+        let result = writeln!(running.stdin, "{request}")
+            .and_then(|()| running.stdin.flush())
+            .and_then(|()| read_line(&mut running.stdout, timeout));
+        match result {
+            Ok(line) if line.trim() == ACK_OK => Ok(()),
+            // The helper process reported a failure, but is still running.
+            Ok(line) => Err(line.trim().to_string()),
+            Err(e) => {
+                // Restart the helper process on the next request.
+                self.stop();
+                Err(format!("helper process failed: {e}"))
+            }
+        }
+    }
+}
+
+/// Read a line from the output of a process, waiting up to a timeout.
+///
+/// # Arguments
+///
+/// * `reader` - the output of the process.
+/// * `timeout` - maximum time to wait for the output to be readable.
+///
+/// # Errors
+///
+/// Returns `Err` if the output could not be read, if it was closed, or if
+/// the timeout expired.
+fn read_line(reader: &mut BufReader<ChildStdout>, timeout: Duration) -> io::Result<String> {
+    if reader.buffer().is_empty() {
+        let mut pollfd = libc::pollfd {
+            fd: reader.get_ref().as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let timeout = i32::try_from(timeout.as_millis()).unwrap_or(i32::MAX);
+        // SAFETY: the descriptor is valid during the call.
+        match unsafe { libc::poll(ptr::addr_of_mut!(pollfd), 1, timeout) } {
+            ready if ready < 0 => return Err(io::Error::last_os_error()),
+            0 => {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "no acknowledgement received",
+                ))
+            }
+            _ => {}
+        }
+    }
+
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "the process exited",
+        ));
+    }
+    Ok(line)
+}
+
+/// Action that sends the gestures to a long-running helper process.
+///
+/// Allows integrations with a high startup cost (such as scripts in
+/// interpreted languages) to handle the gestures without spawning a process
+/// for each of them.
+#[derive(Debug)]
+pub struct HelperAction {
+    /// Command passed to the helper process in the requests.
+    command: String,
+    /// Shared helper process.
+    process: SharedHelperProcess,
+}
+
+impl HelperAction {
+    /// Create a new [`HelperAction`].
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - command passed to the helper process in the requests.
+    /// * `process` - shared helper process.
+    #[must_use]
+    pub fn new(command: &str, process: SharedHelperProcess) -> Self {
+        HelperAction {
+            command: command.trim().to_string(),
+            process,
+        }
+    }
+
+    /// Return the request sent to the helper process for a gesture.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - the context of the gesture.
+    #[must_use]
+    pub fn request(&self, context: &GestureContext) -> String {
+        json!({
+            "event": context.action_event.to_string(),
+            "fingers": context.finger_count,
+            "dx": context.dx,
+            "dy": context.dy,
+            "device": context.device.as_ref().map(|x| x.name.clone()),
+            "command": self.command,
+        })
+        .to_string()
+    }
+}
+
+impl Action for HelperAction {
+    fn execute_command(&mut self, context: &GestureContext) -> Result<(), ActionError> {
+        let request = self.request(context);
+        self.process
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .send(&request)
+            .map_err(|message| ActionError::ExecutionError {
+                type_: ActionType::Helper.to_string(),
+                message,
+            })
+    }
+
+    fn fmt_command(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:<{}>", ActionType::Helper, self.command)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use super::{HelperAction, HelperProcess, DEFAULT_HELPER_TIMEOUT};
+    use crate::actions::{Action, ActionError};
+    use crate::events::{ActionEvent, GestureContext};
+    use serde_json::{json, Value};
+
+    #[test]
+    /// Test sending the gestures to a helper process.
+    fn test_helper_action() {
+        // Helper echoing the requests back, and failing on "fail".
+        let process = Arc::new(Mutex::new(HelperProcess::new(
+            String::from(
+                r#"sh -c 'while read line; do case "$line" in *\"fail\"*) echo nope;; *) echo ok;; esac; done'"#,
+            ),
+            DEFAULT_HELPER_TIMEOUT,
+        )));
+        let context = GestureContext {
+            finger_count: Some(3),
+            dy: -10.5,
+            ..GestureContext::new(ActionEvent::ThreeFingerSwipeUp)
+        };
+
+        let mut action = HelperAction::new(" next ", Arc::clone(&process));
+        assert_eq!((&action as &dyn Action).to_string(), "helper:<next>");
+        assert_eq!(
+            serde_json::from_str::<Value>(&action.request(&context)).unwrap(),
+            json!({
+                "event": "three-finger-swipe-up",
+                "fingers": 3,
+                "dx": 0.0,
+                "dy": -10.5,
+                "device": null,
+                "command": "next",
+            })
+        );
+        action.execute_command(&context).unwrap();
+        action.execute_command(&context).unwrap();
+
+        // Assert that the failures reported by the helper are returned.
+        let mut action = HelperAction::new("fail", Arc::clone(&process));
+        assert_eq!(
+            action.execute_command(&context),
+            Err(ActionError::ExecutionError {
+                type_: String::from("helper"),
+                message: String::from("nope"),
+            })
+        );
+        assert!(process.lock().unwrap().running.is_some());
+    }
+
+    #[test]
+    /// Test restarting the helper process after a failure.
+    fn test_helper_process_restart() {
+        // Helper acknowledging a single request before exiting.
+        let mut process = HelperProcess::new(
+            String::from("sh -c 'read line; echo ok'"),
+            DEFAULT_HELPER_TIMEOUT,
+        );
+        process.send("{}").unwrap();
+        assert!(process.send("{}").is_err());
+        assert!(process.running.is_none());
+        process.send("{}").unwrap();
+
+        // Helper not acknowledging the requests.
+        let mut process = HelperProcess::new(
+            String::from("sh -c 'while read line; do :; done'"),
+            Duration::from_millis(50),
+        );
+        assert!(process.send("{}").is_err());
+        assert!(process.running.is_none());
+    }
+}
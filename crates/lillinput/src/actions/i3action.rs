@@ -1,15 +1,20 @@
 //! Action for interacting with `i3`.
 
-use std::cell::RefCell;
 use std::fmt;
-use std::rc::Rc;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
 
 use crate::actions::errors::ActionError;
 use crate::actions::{Action, ActionType};
-use i3ipc::I3Connection;
+use crate::events::GestureContext;
+use i3ipc::event::inner::ShutdownChange;
+use i3ipc::event::Event;
+use i3ipc::{I3Connection, I3EventListener, Subscription};
+use log::{info, warn};
 
 /// Shared optional `i3` connection.
-pub type SharedConnection = Rc<RefCell<Option<I3Connection>>>;
+pub type SharedConnection = Arc<Mutex<Option<I3Connection>>>;
 
 /// Action that executes `i3` commands.
 #[derive(Debug)]
@@ -28,7 +33,7 @@ impl I3Action {
     /// * `command` - `i3` command to be executed in this action.
     /// * `connection` - `i3` RPC connection.
     #[must_use]
-    pub fn new(command: String, connection: Rc<RefCell<Option<I3Connection>>>) -> Self {
+    pub fn new(command: String, connection: Arc<Mutex<Option<I3Connection>>>) -> Self {
         I3Action {
             connection,
             command,
@@ -36,11 +41,249 @@ impl I3Action {
     }
 }
 
+/// Run an `i3` command, checking its outcomes.
+///
+/// # Arguments
+///
+/// * `connection` - `i3` RPC connection.
+/// * `command` - `i3` command to be executed.
+///
+/// # Errors
+///
+/// Returns `Err` if the command could not be sent or if any of its outcomes
+/// was not successful.
+pub(crate) fn run_i3_command(
+    connection: &mut I3Connection,
+    command: &str,
+) -> Result<(), ActionError> {
+    match connection.run_command(command) {
+        Err(e) => Err(ActionError::ExecutionError {
+            type_: "i3".into(),
+            message: e.to_string(),
+        }),
+        Ok(command_reply) => {
+            if command_reply.outcomes.iter().any(|x| !x.success) {
+                Err(ActionError::ExecutionError {
+                    type_: "i3".into(),
+                    message: "unsuccessful outcome(s)".into(),
+                })
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Maximum number of attempts for reconnecting to `i3` after a restart.
+const RECONNECT_ATTEMPTS: u32 = 50;
+
+/// Delay between the attempts for reconnecting to `i3` after a restart.
+const RECONNECT_DELAY: Duration = Duration::from_millis(100);
+
+/// Create an `i3` event listener subscribed to the shutdown events.
+///
+/// # Errors
+///
+/// Returns `Err` if the listener could not be connected or subscribed.
+fn subscribe_shutdown() -> Result<I3EventListener, ActionError> {
+    let to_error = |message: String| ActionError::ExecutionError {
+        type_: "i3".into(),
+        message,
+    };
+
+    let mut listener = I3EventListener::connect().map_err(|e| to_error(e.to_string()))?;
+    listener
+        .subscribe(&[Subscription::Shutdown])
+        .map_err(|e| to_error(e.to_string()))?;
+
+    Ok(listener)
+}
+
+/// Reconnect to `i3` after a restart, retrying until it is available.
+///
+/// # Returns
+///
+/// The new connection and shutdown listener, or `None` if `i3` was not
+/// available after all the attempts.
+fn reconnect() -> Option<(I3Connection, I3EventListener)> {
+    for _ in 0..RECONNECT_ATTEMPTS {
+        thread::sleep(RECONNECT_DELAY);
+        if let (Ok(connection), Ok(listener)) = (I3Connection::connect(), subscribe_shutdown()) {
+            return Some((connection, listener));
+        }
+    }
+
+    None
+}
+
+/// Watch the shutdown events of `i3`, keeping a shared connection usable
+/// across restarts.
+///
+/// The events are received in a separate thread. When `i3` restarts (or the
+/// events stop being received), the connection is unset until `i3` is
+/// available again, and then replaced by a new connection, so the first
+/// actions after the restart do not fail on the stale one. When `i3` exits,
+/// the connection is unset, and the actions using it are reported as
+/// unavailable.
+///
+/// # Arguments
+///
+/// * `connection` - `i3` RPC connection.
+///
+/// # Errors
+///
+/// Returns `Err` if the shutdown events could not be subscribed to.
+pub fn watch_i3_shutdown(connection: SharedConnection) -> Result<(), ActionError> {
+    let mut listener = subscribe_shutdown()?;
+
+    thread::spawn(move || loop {
+        let change = listener.listen().find_map(|event| match event {
+            Ok(Event::ShutdownEvent(info)) => Some(Some(info.change)),
+            Ok(_) => None,
+            Err(_) => Some(None),
+        });
+        *connection.lock().unwrap_or_else(PoisonError::into_inner) = None;
+
+        if matches!(change, Some(Some(ShutdownChange::Exit))) {
+            info!("i3: exited, disabling the i3 actions");
+            return;
+        }
+        info!("i3: restarting, reconnecting");
+        let Some((new_connection, new_listener)) = reconnect() else {
+            warn!("i3: could not reconnect, disabling the i3 actions");
+            return;
+        };
+        *connection.lock().unwrap_or_else(PoisonError::into_inner) = Some(new_connection);
+        listener = new_listener;
+        info!("i3: connection reopened");
+    });
+
+    Ok(())
+}
+
+/// Commands accepted by `i3`, used for validating the commands of the
+/// actions.
+const I3_COMMANDS: &[&str] = &[
+    "append_layout",
+    "bar",
+    "border",
+    "debuglog",
+    "exec",
+    "exit",
+    "floating",
+    "focus",
+    "fullscreen",
+    "gaps",
+    "kill",
+    "layout",
+    "mark",
+    "mode",
+    "move",
+    "nop",
+    "open",
+    "reload",
+    "rename",
+    "resize",
+    "restart",
+    "scratchpad",
+    "shmlog",
+    "split",
+    "sticky",
+    "swap",
+    "title_format",
+    "title_window_icon",
+    "unmark",
+    "workspace",
+];
+
+/// Check an `i3` command for syntax errors, without executing it.
+///
+/// The check is heuristic, as `i3` does not provide a way of parsing a
+/// command without executing it: the quotes and the criteria are checked for
+/// being balanced, and each of the chained commands is checked for starting
+/// with a known command.
+///
+/// # Arguments
+///
+/// * `command` - `i3` command to be checked.
+///
+/// # Errors
+///
+/// Returns `Err` with a description of the problem if the command is not
+/// valid.
+pub fn check_i3_command(command: &str) -> Result<(), String> {
+    let mut subcommands = vec![String::new()];
+    let mut in_quotes = false;
+    let mut in_criteria = false;
+    let mut chars = command.chars();
+
+    // Split the chained commands, skipping the separators within quotes and
+    // leaving out the criteria.
+    while let Some(c) = chars.next() {
+        let was_in_criteria = in_criteria;
+        match c {
+            '\\' if in_quotes => {
+                chars.next();
+                continue;
+            }
+            '"' => in_quotes = !in_quotes,
+            '[' if !in_quotes => {
+                if in_criteria {
+                    return Err(String::from("nested criteria"));
+                }
+                in_criteria = true;
+            }
+            ']' if !in_quotes => {
+                if !in_criteria {
+                    return Err(String::from("unbalanced criteria brackets"));
+                }
+                in_criteria = false;
+            }
+            ';' | ',' if !in_quotes && !in_criteria => {
+                subcommands.push(String::new());
+                continue;
+            }
+            _ => (),
+        }
+        if !was_in_criteria && !in_criteria {
+            if let Some(subcommand) = subcommands.last_mut() {
+                subcommand.push(c);
+            }
+        }
+    }
+    if in_quotes {
+        return Err(String::from("unterminated quotes"));
+    }
+    if in_criteria {
+        return Err(String::from("unbalanced criteria brackets"));
+    }
+
+    // Check the name of each of the commands.
+    let mut found = false;
+    for subcommand in &subcommands {
+        let Some(name) = subcommand.split_whitespace().next() else {
+            continue;
+        };
+        if !I3_COMMANDS.contains(&name) {
+            return Err(format!("unknown command {name}"));
+        }
+        found = true;
+    }
+    if found {
+        Ok(())
+    } else {
+        Err(String::from("empty command"))
+    }
+}
+
 impl Action for I3Action {
-    fn execute_command(&mut self) -> Result<(), ActionError> {
+    fn execute_command(&mut self, _context: &GestureContext) -> Result<(), ActionError> {
         // Perform the command, if specified.
-        let connection_rc = Rc::clone(&self.connection);
-        let connection_option = &mut *connection_rc.borrow_mut();
+        let mut connection_guard = self
+            .connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let connection_option = &mut *connection_guard;
 
         // Check if the i3 connection is valid.
         let Some(connection) = connection_option else {
@@ -50,22 +293,28 @@ impl Action for I3Action {
             });
         };
 
-        match connection.run_command(&self.command) {
-            Err(e) => Err(ActionError::ExecutionError {
+        run_i3_command(connection, &self.command)
+    }
+
+    fn is_available(&self) -> bool {
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_some()
+    }
+
+    fn validate(&self) -> Result<(), ActionError> {
+        if !self.is_available() {
+            return Err(ActionError::ExecutionError {
                 type_: "i3".into(),
-                message: e.to_string(),
-            }),
-            Ok(command_reply) => {
-                if command_reply.outcomes.iter().any(|x| !x.success) {
-                    Err(ActionError::ExecutionError {
-                        type_: "i3".into(),
-                        message: "unsuccessful outcome(s)".into(),
-                    })
-                } else {
-                    Ok(())
-                }
-            }
+                message: "i3 connection is not set".into(),
+            });
         }
+
+        check_i3_command(&self.command).map_err(|message| ActionError::InvalidCommand {
+            type_: "i3".into(),
+            message,
+        })
     }
 
     fn fmt_command(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -75,14 +324,12 @@ impl Action for I3Action {
 
 #[cfg(test)]
 mod test {
-    use std::cell::RefCell;
-    use std::rc::Rc;
     use std::sync::{Arc, Mutex};
 
-    use super::I3Action;
+    use super::{check_i3_command, I3Action};
     use crate::actions::{Action, ActionError};
     use crate::controllers::{Controller, DefaultController};
-    use crate::events::ActionEvent;
+    use crate::events::{ActionEvent, GestureContext};
     use crate::test_utils::init_listener;
 
     use i3ipc::I3Connection;
@@ -119,7 +366,7 @@ mod test {
 
         // Create the controller.
         let mut controller = DefaultController::default();
-        let connection = Rc::new(RefCell::new(Some(I3Connection::connect().unwrap())));
+        let connection = Arc::new(Mutex::new(Some(I3Connection::connect().unwrap())));
         for (event, command) in [
             (ActionEvent::ThreeFingerSwipeLeft, "swipe left 3"),
             (ActionEvent::ThreeFingerSwipeLeftUp, "swipe left up 3"),
@@ -142,7 +389,7 @@ mod test {
                 event,
                 vec![Box::new(I3Action::new(
                     String::from(command),
-                    Rc::clone(&connection),
+                    Arc::clone(&connection),
                 ))],
             );
         }
@@ -161,15 +408,38 @@ mod test {
         }
     }
 
+    #[test]
+    /// Test the syntax checks of the commands.
+    fn test_check_i3_command() {
+        for command in [
+            "workspace next",
+            "[class=\"Firefox\"] focus; move left",
+            "[title=\"a]; b\"] kill, nop \"quoted \\\" ]\"",
+            "mark --toggle foo;",
+        ] {
+            assert_eq!(check_i3_command(command), Ok(()), "{command}");
+        }
+
+        for (command, message) in [
+            ("", "empty command"),
+            ("workspace next; invalid", "unknown command invalid"),
+            ("[class=\"Firefox\" focus", "unbalanced criteria brackets"),
+            ("rename workspace to \"foo", "unterminated quotes"),
+        ] {
+            assert_eq!(check_i3_command(command), Err(String::from(message)));
+        }
+    }
+
     #[test]
     #[serial]
     ///Test graceful handling of unavailable i3 connection.
     fn test_i3_not_available() {
         // Create the action.
-        let mut action = I3Action::new(String::from("swipe right 3"), Rc::new(RefCell::new(None)));
+        let mut action = I3Action::new(String::from("swipe right 3"), Arc::new(Mutex::new(None)));
 
         // Trigger a swipe.
-        let result = action.execute_command();
+        let result =
+            action.execute_command(&GestureContext::new(ActionEvent::ThreeFingerSwipeRight));
 
         // Assert the command is not executed.
         assert_eq!(
@@ -0,0 +1,310 @@
+//! Action for builtin `i3` commands.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::PoisonError;
+
+use crate::actions::errors::ActionError;
+use crate::actions::i3action::run_i3_command;
+use crate::actions::i3state::I3State;
+use crate::actions::{Action, ActionType, SharedConnection};
+use crate::events::GestureContext;
+use i3ipc::I3Connection;
+use strum::{Display, EnumString, EnumVariantNames, VariantNames};
+
+/// Possible choices for builtin `i3` commands.
+#[derive(Clone, Copy, Debug, Display, EnumString, EnumVariantNames, Eq, PartialEq)]
+#[strum(serialize_all = "kebab_case")]
+pub enum I3Builtin {
+    /// Toggle a mark on the focused window.
+    MarkToggle,
+    /// Focus the window with a mark.
+    GotoMark,
+    /// Swap the focused window with the window with a mark.
+    SwapWithMark,
+    /// Focus (`focus`) or move the focused window to (`move`) the next
+    /// output.
+    OutputNext,
+    /// Focus (`focus`) or move the focused window to (`move`) the previous
+    /// output.
+    OutputPrev,
+}
+
+/// Possible arguments for the output builtin commands.
+const OUTPUT_ARGUMENTS: [&str; 2] = ["focus", "move"];
+
+/// Action that executes builtin `i3` commands.
+///
+/// Builtin commands are higher-level commands that perform one or more
+/// requests over the `i3` IPC interface, in the form `{builtin} {argument}`.
+#[derive(Debug)]
+pub struct I3BuiltinAction {
+    /// `i3` RPC connection.
+    connection: SharedConnection,
+    /// Builtin command to be executed in this action.
+    builtin: I3Builtin,
+    /// Argument for the builtin command.
+    argument: String,
+}
+
+impl I3BuiltinAction {
+    /// Create a new [`I3BuiltinAction`].
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - builtin command to be executed in this action.
+    /// * `connection` - `i3` RPC connection.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command is not a valid builtin command.
+    pub fn new(command: &str, connection: SharedConnection) -> Result<Self, ActionError> {
+        let (builtin, argument) = command.split_once(' ').unwrap_or((command, ""));
+        let builtin = I3Builtin::from_str(builtin).map_err(|_| ActionError::InvalidCommand {
+            type_: ActionType::I3Builtin.to_string(),
+            message: format!(
+                "unknown builtin {builtin} (possible values: {:?})",
+                I3Builtin::VARIANTS
+            ),
+        })?;
+        let argument = argument.trim();
+        if argument.is_empty() {
+            return Err(ActionError::InvalidCommand {
+                type_: ActionType::I3Builtin.to_string(),
+                message: format!("builtin {builtin} requires an argument"),
+            });
+        }
+        if matches!(builtin, I3Builtin::OutputNext | I3Builtin::OutputPrev)
+            && !OUTPUT_ARGUMENTS.contains(&argument)
+        {
+            return Err(ActionError::InvalidCommand {
+                type_: ActionType::I3Builtin.to_string(),
+                message: format!(
+                    "unknown argument {argument} for builtin {builtin} (possible values: {OUTPUT_ARGUMENTS:?})"
+                ),
+            });
+        }
+
+        Ok(I3BuiltinAction {
+            connection,
+            builtin,
+            argument: argument.to_string(),
+        })
+    }
+
+    /// Check if a mark exists in `i3`.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection` - `i3` RPC connection.
+    /// * `mark` - the mark to check.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the marks could not be retrieved or if the mark does
+    /// not exist.
+    fn check_mark_exists(connection: &mut I3Connection, mark: &str) -> Result<(), ActionError> {
+        let marks = connection
+            .get_marks()
+            .map_err(|e| ActionError::ExecutionError {
+                type_: ActionType::I3Builtin.to_string(),
+                message: e.to_string(),
+            })?;
+
+        if marks.marks.iter().any(|x| x == mark) {
+            Ok(())
+        } else {
+            Err(ActionError::ExecutionError {
+                type_: ActionType::I3Builtin.to_string(),
+                message: format!("mark {mark} does not exist"),
+            })
+        }
+    }
+
+    /// Find the output adjacent to the focused one in `i3`.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection` - `i3` RPC connection.
+    /// * `backwards` - whether to find the previous output instead of the
+    ///   next one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the outputs could not be retrieved or if there is no
+    /// other active output.
+    fn find_adjacent_output(
+        connection: &mut I3Connection,
+        backwards: bool,
+    ) -> Result<String, ActionError> {
+        let state = I3State::query(connection)?;
+
+        state
+            .adjacent_output(backwards)
+            .map(|x| x.name.clone())
+            .ok_or_else(|| ActionError::ExecutionError {
+                type_: ActionType::I3Builtin.to_string(),
+                message: "no other active output".into(),
+            })
+    }
+}
+
+/// Quote a string for its usage as an argument in an `i3` command.
+///
+/// # Arguments
+///
+/// * `value` - the string to be quoted.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl Action for I3BuiltinAction {
+    fn execute_command(&mut self, _context: &GestureContext) -> Result<(), ActionError> {
+        let mut connection_guard = self
+            .connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let connection_option = &mut *connection_guard;
+
+        // Check if the i3 connection is valid.
+        let Some(connection) = connection_option else {
+            return Err(ActionError::ExecutionError {
+                type_: ActionType::I3Builtin.to_string(),
+                message: "i3 connection is not set".into(),
+            });
+        };
+
+        match self.builtin {
+            I3Builtin::MarkToggle => run_i3_command(
+                connection,
+                &format!("mark --toggle {}", quote(&self.argument)),
+            ),
+            I3Builtin::GotoMark => {
+                Self::check_mark_exists(connection, &self.argument)?;
+                run_i3_command(
+                    connection,
+                    &format!(
+                        "[con_mark={}] focus",
+                        quote(&format!("^{}$", self.argument))
+                    ),
+                )
+            }
+            I3Builtin::SwapWithMark => {
+                Self::check_mark_exists(connection, &self.argument)?;
+                run_i3_command(
+                    connection,
+                    &format!("swap container with mark {}", quote(&self.argument)),
+                )
+            }
+            I3Builtin::OutputNext | I3Builtin::OutputPrev => {
+                let output = quote(&Self::find_adjacent_output(
+                    connection,
+                    self.builtin == I3Builtin::OutputPrev,
+                )?);
+                // The focus follows the moved window.
+                let command = if self.argument == "move" {
+                    format!("move container to output {output}, focus output {output}")
+                } else {
+                    format!("focus output {output}")
+                };
+                run_i3_command(connection, &command)
+            }
+        }
+    }
+
+    fn is_available(&self) -> bool {
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_some()
+    }
+
+    fn validate(&self) -> Result<(), ActionError> {
+        if self.is_available() {
+            Ok(())
+        } else {
+            Err(ActionError::ExecutionError {
+                type_: ActionType::I3Builtin.to_string(),
+                message: "i3 connection is not set".into(),
+            })
+        }
+    }
+
+    fn fmt_command(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:<{} {}>",
+            ActionType::I3Builtin,
+            self.builtin,
+            self.argument
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use super::I3BuiltinAction;
+    use crate::actions::{Action, ActionError};
+    use crate::events::{ActionEvent, GestureContext};
+    use crate::test_utils::init_listener;
+
+    use i3ipc::I3Connection;
+    use serial_test::serial;
+
+    #[test]
+    /// Test the parsing of builtin commands.
+    fn test_i3_builtin_parse() {
+        let connection = Arc::new(Mutex::new(None));
+
+        assert!(I3BuiltinAction::new("goto-mark foo", Arc::clone(&connection)).is_ok());
+        assert!(matches!(
+            I3BuiltinAction::new("invalid foo", Arc::clone(&connection)),
+            Err(ActionError::InvalidCommand { .. })
+        ));
+        assert!(matches!(
+            I3BuiltinAction::new("goto-mark", Arc::clone(&connection)),
+            Err(ActionError::InvalidCommand { .. })
+        ));
+        assert!(I3BuiltinAction::new("output-next move", Arc::clone(&connection)).is_ok());
+        assert!(matches!(
+            I3BuiltinAction::new("output-prev foo", connection),
+            Err(ActionError::InvalidCommand { .. })
+        ));
+    }
+
+    #[test]
+    #[serial]
+    /// Test the existence checks of the mark builtin commands.
+    fn test_i3_builtin_mark_exists() {
+        // Create the listener and the shared storage for the commands.
+        let message_log = Arc::new(Mutex::new(vec![]));
+        let socket_file = init_listener(Arc::clone(&message_log));
+
+        // Create the actions.
+        let connection = Arc::new(Mutex::new(Some(I3Connection::connect().unwrap())));
+        let mut existing_action =
+            I3BuiltinAction::new("goto-mark foo", Arc::clone(&connection)).unwrap();
+        let mut missing_action = I3BuiltinAction::new("goto-mark bar", connection).unwrap();
+
+        // Trigger the actions.
+        let context = GestureContext::new(ActionEvent::ThreeFingerSwipeRight);
+        assert!(existing_action.execute_command(&context).is_ok());
+        assert!(matches!(
+            missing_action.execute_command(&context),
+            Err(ActionError::ExecutionError { .. })
+        ));
+        std::fs::remove_file(socket_file.path().file_name().unwrap()).ok();
+
+        // Assert that only the command for the existing mark is sent.
+        let messages = message_log.lock().unwrap();
+        assert_eq!(
+            messages
+                .iter()
+                .filter(|x| !x.is_empty())
+                .collect::<Vec<&String>>(),
+            vec![r#"[con_mark="^foo$"] focus"#]
+        );
+    }
+}
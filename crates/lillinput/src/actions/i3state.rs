@@ -0,0 +1,311 @@
+//! Cached state of `i3`, kept up to date from its events.
+
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+
+use crate::actions::errors::ActionError;
+use crate::throttle::warn_throttled;
+use i3ipc::event::Event;
+use i3ipc::reply::{Outputs, Workspaces};
+use i3ipc::{I3Connection, I3EventListener, Subscription};
+use log::debug;
+
+/// Workspace in the cached state of `i3`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorkspaceState {
+    /// Number of the workspace (`-1` if it has no number).
+    pub num: i32,
+    /// Name of the workspace.
+    pub name: String,
+    /// Name of the output the workspace is on.
+    pub output: String,
+    /// Whether the workspace is visible on its output.
+    pub visible: bool,
+    /// Whether the workspace is focused.
+    pub focused: bool,
+    /// Whether the workspace contains urgent windows.
+    pub urgent: bool,
+}
+
+/// Output in the cached state of `i3`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutputState {
+    /// Name of the output.
+    pub name: String,
+    /// Whether the output is active.
+    pub active: bool,
+    /// Whether the output is the primary one.
+    pub primary: bool,
+    /// Name of the workspace visible on the output, if any.
+    pub current_workspace: Option<String>,
+    /// Position and size of the output, as `(x, y, width, height)`.
+    pub rect: (i32, i32, i32, i32),
+}
+
+/// Snapshot of the workspaces and outputs of `i3`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct I3State {
+    /// Workspaces, in the order reported by `i3`.
+    pub workspaces: Vec<WorkspaceState>,
+    /// Outputs, in the order reported by `i3`.
+    pub outputs: Vec<OutputState>,
+}
+
+impl I3State {
+    /// Return a new [`I3State`] from the replies of `i3`.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspaces` - the reply to a workspaces request.
+    /// * `outputs` - the reply to an outputs request.
+    #[must_use]
+    pub fn from_replies(workspaces: Workspaces, outputs: Outputs) -> Self {
+        I3State {
+            workspaces: workspaces
+                .workspaces
+                .into_iter()
+                .map(|x| WorkspaceState {
+                    num: x.num,
+                    name: x.name,
+                    output: x.output,
+                    visible: x.visible,
+                    focused: x.focused,
+                    urgent: x.urgent,
+                })
+                .collect(),
+            outputs: outputs
+                .outputs
+                .into_iter()
+                .map(|x| OutputState {
+                    name: x.name,
+                    active: x.active,
+                    primary: x.primary,
+                    current_workspace: x.current_workspace,
+                    rect: x.rect,
+                })
+                .collect(),
+        }
+    }
+
+    /// Retrieve the current state from `i3`.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection` - `i3` RPC connection.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the workspaces or outputs could not be retrieved.
+    pub fn query(connection: &mut I3Connection) -> Result<Self, ActionError> {
+        let to_error = |message: String| ActionError::ExecutionError {
+            type_: "i3".into(),
+            message,
+        };
+
+        let workspaces = connection
+            .get_workspaces()
+            .map_err(|e| to_error(e.to_string()))?;
+        let outputs = connection
+            .get_outputs()
+            .map_err(|e| to_error(e.to_string()))?;
+
+        Ok(Self::from_replies(workspaces, outputs))
+    }
+
+    /// Return the focused workspace, if any.
+    #[must_use]
+    pub fn focused_workspace(&self) -> Option<&WorkspaceState> {
+        self.workspaces.iter().find(|x| x.focused)
+    }
+
+    /// Return the active outputs.
+    pub fn active_outputs(&self) -> impl Iterator<Item = &OutputState> {
+        self.outputs.iter().filter(|x| x.active)
+    }
+
+    /// Return the active output next to the focused one.
+    ///
+    /// The active outputs are ordered by their position (from left to right,
+    /// and from top to bottom), wrapping around at the ends.
+    ///
+    /// # Arguments
+    ///
+    /// * `backwards` - whether to return the previous output instead of the
+    ///   next one.
+    ///
+    /// # Returns
+    ///
+    /// The adjacent output, or `None` if there is no focused workspace or no
+    /// other active output.
+    #[must_use]
+    pub fn adjacent_output(&self, backwards: bool) -> Option<&OutputState> {
+        let focused_output = &self.focused_workspace()?.output;
+        let mut outputs: Vec<&OutputState> = self.active_outputs().collect();
+        if outputs.len() < 2 {
+            return None;
+        }
+        outputs.sort_by_key(|x| (x.rect.0, x.rect.1));
+
+        let index = outputs.iter().position(|x| &x.name == focused_output)?;
+        let offset = if backwards { outputs.len() - 1 } else { 1 };
+        Some(outputs[(index + offset) % outputs.len()])
+    }
+}
+
+/// Cache of the state of `i3`, shared between threads.
+///
+/// The cache uses its own connections to `i3`, subscribing to the
+/// `workspace` and `output` events and refreshing the state when they are
+/// received. Consulting the state does not require a request to `i3`.
+#[derive(Clone, Debug, Default)]
+pub struct I3StateCache {
+    /// Latest state of `i3`.
+    state: Arc<Mutex<I3State>>,
+}
+
+impl I3StateCache {
+    /// Create a new [`I3StateCache`], keeping it up to date in a separate
+    /// thread.
+    ///
+    /// If the events stop being received (for example, due to `i3`
+    /// restarting), the cache keeps the latest state.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the connections to `i3` could not be established or
+    /// the initial state could not be retrieved.
+    pub fn start() -> Result<Self, ActionError> {
+        let to_error = |message: String| ActionError::ExecutionError {
+            type_: "i3".into(),
+            message,
+        };
+
+        let mut connection = I3Connection::connect().map_err(|e| to_error(e.to_string()))?;
+        let mut listener = I3EventListener::connect().map_err(|e| to_error(e.to_string()))?;
+        listener
+            .subscribe(&[Subscription::Workspace, Subscription::Output])
+            .map_err(|e| to_error(e.to_string()))?;
+        let cache = I3StateCache {
+            state: Arc::new(Mutex::new(I3State::query(&mut connection)?)),
+        };
+
+        let state = Arc::clone(&cache.state);
+        thread::spawn(move || {
+            for event in listener.listen() {
+                match event {
+                    Ok(Event::WorkspaceEvent(_) | Event::OutputEvent(_)) => {
+                        match I3State::query(&mut connection) {
+                            Ok(new_state) => {
+                                *state.lock().unwrap_or_else(PoisonError::into_inner) = new_state;
+                            }
+                            Err(e) => {
+                                warn_throttled!("i3: could not refresh the cached state: {e}");
+                            }
+                        }
+                    }
+                    Ok(_) => (),
+                    Err(e) => {
+                        debug!("i3: stopped receiving events for the cached state: {e}");
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok(cache)
+    }
+
+    /// Return a snapshot of the latest state of `i3`.
+    #[must_use]
+    pub fn state(&self) -> I3State {
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{I3State, OutputState, WorkspaceState};
+
+    use i3ipc::reply::{Output, Outputs, Workspace, Workspaces};
+
+    #[test]
+    /// Test building the state from the replies of `i3`.
+    fn test_state_from_replies() {
+        let workspace = |num: i32, output: &str, focused: bool| Workspace {
+            num,
+            name: num.to_string(),
+            visible: true,
+            focused,
+            urgent: false,
+            rect: (0, 0, 0, 0),
+            output: output.to_string(),
+        };
+        let output = |name: &str, active: bool| Output {
+            name: name.to_string(),
+            active,
+            primary: false,
+            current_workspace: None,
+            rect: (0, 0, 0, 0),
+        };
+
+        let state = I3State::from_replies(
+            Workspaces {
+                workspaces: vec![workspace(1, "eDP-1", false), workspace(2, "HDMI-1", true)],
+            },
+            Outputs {
+                outputs: vec![output("eDP-1", true), output("xroot-0", false)],
+            },
+        );
+
+        assert_eq!(state.focused_workspace().unwrap().num, 2);
+        assert_eq!(state.focused_workspace().unwrap().output, "HDMI-1");
+        assert_eq!(
+            state.active_outputs().collect::<Vec<&OutputState>>(),
+            vec![&state.outputs[0]]
+        );
+        assert_eq!(I3State::default().focused_workspace(), None);
+    }
+
+    #[test]
+    /// Test finding the outputs adjacent to the focused one.
+    fn test_state_adjacent_output() {
+        let workspace = |output: &str, focused: bool| WorkspaceState {
+            num: 1,
+            name: String::from("1"),
+            output: output.to_string(),
+            visible: true,
+            focused,
+            urgent: false,
+        };
+        let output = |name: &str, active: bool, x: i32| OutputState {
+            name: name.to_string(),
+            active,
+            primary: false,
+            current_workspace: None,
+            rect: (x, 0, 1920, 1080),
+        };
+
+        let mut state = I3State {
+            workspaces: vec![workspace("DP-1", true)],
+            outputs: vec![
+                output("HDMI-1", true, 3840),
+                output("eDP-1", true, 0),
+                output("xroot-0", false, 1920),
+                output("DP-1", true, 1920),
+            ],
+        };
+        assert_eq!(state.adjacent_output(false).unwrap().name, "HDMI-1");
+        assert_eq!(state.adjacent_output(true).unwrap().name, "eDP-1");
+
+        // Wrap around at the ends.
+        state.workspaces = vec![workspace("HDMI-1", true)];
+        assert_eq!(state.adjacent_output(false).unwrap().name, "eDP-1");
+
+        // No other active output.
+        state.outputs.retain(|x| x.name == "HDMI-1");
+        assert_eq!(state.adjacent_output(false), None);
+    }
+}
@@ -0,0 +1,218 @@
+//! Action for acting on `lillinput` itself.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::PoisonError;
+
+use crate::actions::errors::ActionError;
+use crate::actions::{Action, ActionType};
+use crate::controllers::SharedControllerState;
+use crate::events::GestureContext;
+use log::info;
+use strum::{Display, EnumString, EnumVariantNames, VariantNames};
+
+/// Possible choices for internal commands.
+#[derive(Clone, Debug, Display, EnumString, EnumVariantNames, Eq, PartialEq)]
+#[strum(serialize_all = "kebab_case")]
+pub enum InternalCommand {
+    /// Pause the execution of actions.
+    Pause,
+    /// Resume the execution of actions.
+    Resume,
+    /// Toggle the execution of actions, pausing or resuming the controller.
+    TogglePause,
+    /// Request a reload of the configuration, stopping the main loop of the
+    /// controller so it can be recreated.
+    ReloadConfig,
+    /// Select a profile, taking precedence over the schedules.
+    SetProfile(String),
+}
+
+/// Action that executes internal commands, acting on the controller.
+///
+/// Internal actions are executed even if the controller is paused, allowing
+/// a gesture to lock and unlock the rest of the gestures.
+#[derive(Debug)]
+pub struct InternalAction {
+    /// Internal command to be executed in this action.
+    command: InternalCommand,
+    /// Shared controller state.
+    state: SharedControllerState,
+}
+
+impl InternalAction {
+    /// Create a new [`InternalAction`].
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - internal command to be executed in this action.
+    /// * `state` - shared controller state.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command is not a valid internal command, or if
+    /// its argument is missing or not expected.
+    pub fn new(command: &str, state: SharedControllerState) -> Result<Self, ActionError> {
+        let invalid_command = |message| ActionError::InvalidCommand {
+            type_: ActionType::Internal.to_string(),
+            message,
+        };
+        let (name, argument) = command
+            .trim()
+            .split_once(char::is_whitespace)
+            .map_or((command.trim(), ""), |(name, argument)| {
+                (name, argument.trim())
+            });
+
+        let mut parsed = InternalCommand::from_str(name).map_err(|_| {
+            invalid_command(format!(
+                "unknown command {command} (possible values: {:?})",
+                InternalCommand::VARIANTS
+            ))
+        })?;
+        match (&mut parsed, argument.is_empty()) {
+            (InternalCommand::SetProfile(profile), false) => *profile = argument.to_string(),
+            (InternalCommand::SetProfile(_), true) => {
+                return Err(invalid_command(format!(
+                    "{name} requires the name of a profile"
+                )));
+            }
+            (_, false) => {
+                return Err(invalid_command(format!("{name} does not accept arguments")));
+            }
+            (_, true) => (),
+        }
+
+        Ok(InternalAction {
+            command: parsed,
+            state,
+        })
+    }
+}
+
+impl Action for InternalAction {
+    fn execute_command(&mut self, _context: &GestureContext) -> Result<(), ActionError> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        match &self.command {
+            InternalCommand::Pause => {
+                state.paused = true;
+                info!("Pausing the execution of actions");
+            }
+            InternalCommand::Resume => {
+                state.paused = false;
+                info!("Resuming the execution of actions");
+            }
+            InternalCommand::TogglePause => {
+                state.paused = !state.paused;
+                info!(
+                    "{} the execution of actions",
+                    if state.paused { "Pausing" } else { "Resuming" }
+                );
+            }
+            InternalCommand::ReloadConfig => {
+                state.reload_requested = true;
+                info!("Requesting a reload of the configuration");
+            }
+            InternalCommand::SetProfile(profile) => {
+                state.profile_override = Some(profile.clone());
+                info!("Selecting the profile: {profile}");
+            }
+        }
+
+        Ok(())
+    }
+
+    fn runs_while_paused(&self) -> bool {
+        true
+    }
+
+    fn fmt_command(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.command {
+            InternalCommand::SetProfile(profile) => {
+                write!(f, "{}:<{} {profile}>", ActionType::Internal, self.command)
+            }
+            _ => write!(f, "{}:<{}>", ActionType::Internal, self.command),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    use super::InternalAction;
+    use crate::actions::{Action, ActionError};
+    use crate::controllers::{ActionMap, ControllerState};
+    use crate::events::{ActionEvent, GestureContext};
+
+    #[test]
+    /// Test pausing, resuming and toggling the pause state.
+    fn test_internal_pause() {
+        let state = Arc::new(Mutex::new(ControllerState::default()));
+        let mut pause = InternalAction::new("pause", Arc::clone(&state)).unwrap();
+        let mut resume = InternalAction::new("resume", Arc::clone(&state)).unwrap();
+        let mut toggle = InternalAction::new("toggle-pause", Arc::clone(&state)).unwrap();
+        let context = GestureContext::new(ActionEvent::FourFingerSwipeUp);
+
+        pause.execute_command(&context).unwrap();
+        pause.execute_command(&context).unwrap();
+        assert!(state.lock().unwrap().paused);
+        resume.execute_command(&context).unwrap();
+        assert!(!state.lock().unwrap().paused);
+        toggle.execute_command(&context).unwrap();
+        assert!(state.lock().unwrap().paused);
+        toggle.execute_command(&context).unwrap();
+        assert!(!state.lock().unwrap().paused);
+
+        assert!(matches!(
+            InternalAction::new("invalid", state),
+            Err(ActionError::InvalidCommand { .. })
+        ));
+    }
+
+    #[test]
+    /// Test requesting a reload and selecting a profile.
+    fn test_internal_reload_and_profile() {
+        let state = Arc::new(Mutex::new(ControllerState::default()));
+        let mut reload = InternalAction::new("reload-config", Arc::clone(&state)).unwrap();
+        let mut set_profile: Box<dyn Action> =
+            Box::new(InternalAction::new(" set-profile  gaming ", Arc::clone(&state)).unwrap());
+        assert_eq!(set_profile.to_string(), "internal:<set-profile gaming>");
+        let context = GestureContext::new(ActionEvent::FourFingerSwipeUp);
+
+        reload.execute_command(&context).unwrap();
+        set_profile.execute_command(&context).unwrap();
+        let locked_state = state.lock().unwrap();
+        assert!(locked_state.reload_requested);
+        assert_eq!(locked_state.profile_override.as_deref(), Some("gaming"));
+        drop(locked_state);
+
+        assert!(InternalAction::new("set-profile", Arc::clone(&state)).is_err());
+        assert!(InternalAction::new("pause now", state).is_err());
+    }
+
+    #[test]
+    /// Test executing the actions from a different thread.
+    fn test_internal_pause_from_thread() {
+        let state = Arc::new(Mutex::new(ControllerState::default()));
+        let mut actions = ActionMap::new();
+        actions.insert(
+            ActionEvent::FourFingerSwipeUp,
+            vec![Box::new(
+                InternalAction::new("pause", Arc::clone(&state)).unwrap(),
+            )],
+        );
+
+        thread::spawn(move || {
+            let context = GestureContext::new(ActionEvent::FourFingerSwipeUp);
+            for action in actions.values_mut().flatten() {
+                action.execute_command(&context).unwrap();
+            }
+        })
+        .join()
+        .unwrap();
+
+        assert!(state.lock().unwrap().paused);
+    }
+}
@@ -0,0 +1,133 @@
+//! Parsing of key names into X keysyms, shared by the key injection actions.
+
+/// Names of the keysyms accepted in key combinations, besides the single
+/// characters and the function keys.
+const KEYSYM_NAMES: &[(&str, u32)] = &[
+    ("ctrl", 0xffe3),
+    ("Control_L", 0xffe3),
+    ("Control_R", 0xffe4),
+    ("shift", 0xffe1),
+    ("Shift_L", 0xffe1),
+    ("Shift_R", 0xffe2),
+    ("alt", 0xffe9),
+    ("Alt_L", 0xffe9),
+    ("Alt_R", 0xffea),
+    ("super", 0xffeb),
+    ("Super_L", 0xffeb),
+    ("Super_R", 0xffec),
+    ("space", 0x0020),
+    ("plus", 0x002b),
+    ("Return", 0xff0d),
+    ("Tab", 0xff09),
+    ("Escape", 0xff1b),
+    ("BackSpace", 0xff08),
+    ("Delete", 0xffff),
+    ("Home", 0xff50),
+    ("Left", 0xff51),
+    ("Up", 0xff52),
+    ("Right", 0xff53),
+    ("Down", 0xff54),
+    ("Prior", 0xff55),
+    ("Page_Up", 0xff55),
+    ("Next", 0xff56),
+    ("Page_Down", 0xff56),
+    ("End", 0xff57),
+    ("XF86Back", 0x1008_ff26),
+    ("XF86Forward", 0x1008_ff27),
+    ("XF86AudioLowerVolume", 0x1008_ff11),
+    ("XF86AudioMute", 0x1008_ff12),
+    ("XF86AudioRaiseVolume", 0x1008_ff13),
+    ("XF86AudioPlay", 0x1008_ff14),
+    ("XF86AudioPrev", 0x1008_ff16),
+    ("XF86AudioNext", 0x1008_ff17),
+];
+
+/// Return the keysym of a key name.
+///
+/// The name can be one of the names in [`KEYSYM_NAMES`], a function key
+/// (`F1` to `F24`), a single printable character, or a hexadecimal keysym
+/// (such as `0xff51`).
+///
+/// # Arguments
+///
+/// * `name` - name of the key.
+///
+/// # Returns
+///
+/// The keysym, or `None` if the name is not known.
+#[must_use]
+pub fn keysym_from_name(name: &str) -> Option<u32> {
+    if let Some((_, keysym)) = KEYSYM_NAMES.iter().find(|(x, _)| *x == name) {
+        return Some(*keysym);
+    }
+    if let Some(hex) = name.strip_prefix("0x") {
+        return u32::from_str_radix(hex, 16).ok();
+    }
+    if let Some(number) = name.strip_prefix('F').and_then(|x| x.parse::<u32>().ok()) {
+        return (1..=24).contains(&number).then_some(0xffbe + number - 1);
+    }
+
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        // Printable Latin-1 characters share the value of their keysym.
+        (Some(c), None) if (' '..='~').contains(&c) => Some(u32::from(c)),
+        _ => None,
+    }
+}
+
+/// Parse a list of key combinations, such as `ctrl+Tab`.
+///
+/// # Arguments
+///
+/// * `combinations` - the key combinations, with the names of the keys
+///   separated by `+`.
+///
+/// # Errors
+///
+/// Returns `Err` with the reason if a key is not known, or if there are no
+/// combinations.
+pub fn parse_key_combinations<'a>(
+    combinations: impl Iterator<Item = &'a str>,
+) -> Result<Vec<Vec<u32>>, String> {
+    let combinations = combinations
+        .map(|combination| {
+            combination
+                .split('+')
+                .map(|name| keysym_from_name(name).ok_or(format!("unknown key {name}")))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if combinations.is_empty() {
+        return Err(String::from("no keys specified"));
+    }
+
+    Ok(combinations)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{keysym_from_name, parse_key_combinations};
+
+    #[test]
+    /// Test parsing the key names into keysyms.
+    fn test_keysym_from_name() {
+        assert_eq!(keysym_from_name("ctrl"), Some(0xffe3));
+        assert_eq!(keysym_from_name("Left"), Some(0xff51));
+        assert_eq!(keysym_from_name("F5"), Some(0xffc2));
+        assert_eq!(keysym_from_name("t"), Some(0x74));
+        assert_eq!(keysym_from_name("0x1008ff26"), Some(0x1008_ff26));
+        assert_eq!(keysym_from_name("F25"), None);
+        assert_eq!(keysym_from_name("foo"), None);
+    }
+
+    #[test]
+    /// Test parsing the key combinations.
+    fn test_parse_key_combinations() {
+        assert_eq!(
+            parse_key_combinations(["super+Left", "Return"].into_iter()),
+            Ok(vec![vec![0xffeb, 0xff51], vec![0xff0d]])
+        );
+        assert!(parse_key_combinations(["ctrl+"].into_iter()).is_err());
+        assert!(parse_key_combinations([].into_iter()).is_err());
+    }
+}
@@ -0,0 +1,86 @@
+//! Action for logging the gestures, without side effects.
+
+use std::fmt;
+
+use crate::actions::errors::ActionError;
+use crate::actions::{Action, ActionType};
+use crate::events::{ActionEvent, GestureContext};
+use log::info;
+
+/// Action that emits a log entry with information about the gesture.
+///
+/// Intended for verifying the bindings and thresholds before configuring the
+/// actual actions: each entry is a set of `key=value` fields, including the
+/// event that triggered the action and an optional message.
+#[derive(Debug)]
+pub struct LogAction {
+    /// Message to be included in the log entry.
+    message: String,
+    /// Event that triggers this action.
+    action_event: ActionEvent,
+}
+
+impl LogAction {
+    /// Create a new [`LogAction`].
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - message to be included in the log entry (can be empty).
+    /// * `action_event` - the event that triggers the action.
+    #[must_use]
+    pub fn new(message: String, action_event: ActionEvent) -> Self {
+        LogAction {
+            message,
+            action_event,
+        }
+    }
+
+    /// Return the log entry emitted by the action.
+    #[must_use]
+    pub fn entry(&self) -> String {
+        format!(
+            "gesture event={} long={} message={:?}",
+            self.action_event,
+            self.action_event.is_long(),
+            self.message
+        )
+    }
+}
+
+impl Action for LogAction {
+    fn execute_command(&mut self, _context: &GestureContext) -> Result<(), ActionError> {
+        info!("{}", self.entry());
+
+        Ok(())
+    }
+
+    fn fmt_command(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:<{}>", ActionType::Log, self.message)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LogAction;
+    use crate::actions::Action;
+    use crate::events::{ActionEvent, GestureContext};
+
+    #[test]
+    /// Test the entries emitted by the action.
+    fn test_log_entry() {
+        let action = LogAction::new(
+            String::from("next workspace"),
+            ActionEvent::ThreeFingerSwipeRightLong,
+        );
+
+        assert_eq!(
+            action.entry(),
+            r#"gesture event=three-finger-swipe-right-long long=true message="next workspace""#
+        );
+        let mut action: Box<dyn Action> = Box::new(action);
+        assert_eq!(action.to_string(), "log:<next workspace>");
+        assert!(action
+            .execute_command(&GestureContext::new(ActionEvent::ThreeFingerSwipeRightLong))
+            .is_ok());
+    }
+}
@@ -0,0 +1,263 @@
+//! Action for recording macros.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::actions::errors::ActionError;
+use crate::actions::{Action, ActionType};
+use crate::events::{ActionEvent, GestureContext};
+use log::info;
+
+/// Shared macro recorder.
+pub type SharedMacroRecorder = Arc<Mutex<MacroRecorder>>;
+
+/// Recorder of sequences of [`ActionEvent`]s, bound to other events.
+///
+/// While recording, the events whose actions are executed are captured.
+/// Once the recording is stopped, the captured events are bound to the
+/// target event, replaying them when the target event is triggered.
+#[derive(Debug, Default)]
+pub struct MacroRecorder {
+    /// Target event of the current recording, if recording.
+    recording: Option<ActionEvent>,
+    /// Events captured during the current recording.
+    captured_events: Vec<ActionEvent>,
+    /// Recorded macros, as a map between target events and their events.
+    pub macros: HashMap<ActionEvent, Vec<ActionEvent>>,
+    /// Path to the file for persisting the macros.
+    pub path: Option<PathBuf>,
+}
+
+impl MacroRecorder {
+    /// Create a new [`MacroRecorder`], loading the macros from a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - optional path to the file for persisting the macros.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the file exists but could not be read.
+    pub fn new(path: Option<PathBuf>) -> Result<Self, io::Error> {
+        let mut recorder = MacroRecorder {
+            path,
+            ..Default::default()
+        };
+
+        if let Some(path) = &recorder.path {
+            match fs::read_to_string(path) {
+                Ok(contents) => recorder.macros = parse_macros(&contents),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(recorder)
+    }
+
+    /// Return `true` if a macro is being recorded.
+    #[must_use]
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Capture an event into the current recording.
+    ///
+    /// # Arguments
+    ///
+    /// * `action_event` - the event to be captured.
+    pub fn capture(&mut self, action_event: ActionEvent) {
+        if self.is_recording() {
+            self.captured_events.push(action_event);
+        }
+    }
+
+    /// Start or stop recording a macro for a target event.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - the event the macro will be bound to.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the macros could not be persisted.
+    pub fn toggle(&mut self, target: ActionEvent) -> Result<(), io::Error> {
+        let Some(recording) = self.recording.take() else {
+            info!("Recording macro for {target} ...");
+            self.recording = Some(target);
+            self.captured_events.clear();
+            return Ok(());
+        };
+
+        let captured_events = std::mem::take(&mut self.captured_events);
+        info!(
+            "Recorded macro for {recording} with {} events",
+            captured_events.len()
+        );
+        if captured_events.is_empty() {
+            self.macros.remove(&recording);
+        } else {
+            self.macros.insert(recording, captured_events);
+        }
+
+        match &self.path {
+            Some(path) => fs::write(path, format_macros(&self.macros)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parse the contents of a macros file.
+///
+/// Each line contains a macro, in the form `{target}: {event} {event} ...`.
+/// Malformed lines are ignored.
+///
+/// # Arguments
+///
+/// * `contents` - the contents of the file.
+fn parse_macros(contents: &str) -> HashMap<ActionEvent, Vec<ActionEvent>> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (target, events) = line.split_once(':')?;
+            let events = events
+                .split_whitespace()
+                .map(ActionEvent::from_str)
+                .collect::<Result<Vec<ActionEvent>, _>>()
+                .ok()?;
+
+            Some((ActionEvent::from_str(target.trim()).ok()?, events))
+        })
+        .collect()
+}
+
+/// Format macros as the contents of a macros file.
+///
+/// # Arguments
+///
+/// * `macros` - the macros to be formatted.
+fn format_macros(macros: &HashMap<ActionEvent, Vec<ActionEvent>>) -> String {
+    macros
+        .iter()
+        .map(|(target, events)| {
+            let events: Vec<String> = events.iter().map(ToString::to_string).collect();
+            format!("{target}: {}\n", events.join(" "))
+        })
+        .collect()
+}
+
+/// Action that starts or stops recording a macro.
+#[derive(Debug)]
+pub struct RecordMacroAction {
+    /// Event the recorded macro will be bound to.
+    target: ActionEvent,
+    /// Shared macro recorder.
+    recorder: SharedMacroRecorder,
+}
+
+impl RecordMacroAction {
+    /// Create a new [`RecordMacroAction`].
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - event the recorded macro will be bound to.
+    /// * `recorder` - shared macro recorder.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command is not a valid event.
+    pub fn new(command: &str, recorder: SharedMacroRecorder) -> Result<Self, ActionError> {
+        let target =
+            ActionEvent::from_str(command.trim()).map_err(|_| ActionError::InvalidCommand {
+                type_: ActionType::RecordMacro.to_string(),
+                message: format!("unknown event {command}"),
+            })?;
+
+        Ok(RecordMacroAction { target, recorder })
+    }
+}
+
+impl Action for RecordMacroAction {
+    fn execute_command(&mut self, _context: &GestureContext) -> Result<(), ActionError> {
+        self.recorder
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .toggle(self.target)
+            .map_err(|e| ActionError::ExecutionError {
+                type_: ActionType::RecordMacro.to_string(),
+                message: format!("unable to persist macros: {e}"),
+            })
+    }
+
+    fn fmt_command(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:<{}>", ActionType::RecordMacro, self.target)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use super::{format_macros, parse_macros, MacroRecorder, RecordMacroAction};
+    use crate::actions::Action;
+    use crate::events::{ActionEvent, GestureContext};
+
+    use tempfile::Builder;
+
+    #[test]
+    /// Test recording and persisting a macro.
+    fn test_record_macro() {
+        let file = Builder::new().tempfile().unwrap();
+        let recorder = Arc::new(Mutex::new(
+            MacroRecorder::new(Some(file.path().to_path_buf())).unwrap(),
+        ));
+        let mut action =
+            RecordMacroAction::new("four-finger-swipe-down", Arc::clone(&recorder)).unwrap();
+
+        // Record two events.
+        let context = GestureContext::new(ActionEvent::ThreeFingerSwipeDown);
+        action.execute_command(&context).unwrap();
+        assert!(recorder.lock().unwrap().is_recording());
+        recorder
+            .lock()
+            .unwrap()
+            .capture(ActionEvent::ThreeFingerSwipeLeft);
+        recorder
+            .lock()
+            .unwrap()
+            .capture(ActionEvent::ThreeFingerSwipeUp);
+        action.execute_command(&context).unwrap();
+        assert!(!recorder.lock().unwrap().is_recording());
+
+        // Assert that the macro is persisted and can be loaded.
+        let loaded_recorder = MacroRecorder::new(Some(file.path().to_path_buf())).unwrap();
+        assert_eq!(loaded_recorder.macros, recorder.lock().unwrap().macros);
+        assert_eq!(
+            loaded_recorder
+                .macros
+                .get(&ActionEvent::FourFingerSwipeDown),
+            Some(&vec![
+                ActionEvent::ThreeFingerSwipeLeft,
+                ActionEvent::ThreeFingerSwipeUp
+            ])
+        );
+    }
+
+    #[test]
+    /// Test parsing malformed macros files.
+    fn test_parse_macros() {
+        let macros = parse_macros(
+            "four-finger-swipe-down: three-finger-swipe-up\n\
+             invalid: three-finger-swipe-up\n\
+             four-finger-swipe-up: invalid\n",
+        );
+
+        assert_eq!(macros.len(), 1);
+        assert_eq!(parse_macros(&format_macros(&macros)), macros);
+    }
+}
@@ -1,34 +1,313 @@
 //! Components for representing actions.
 
+#[cfg(feature = "clipboard")]
+pub mod clipboardaction;
+#[cfg(feature = "command")]
 pub mod commandaction;
+pub mod delayaction;
 pub mod errors;
+#[cfg(feature = "helper")]
+pub mod helperaction;
+#[cfg(feature = "i3")]
 pub mod i3action;
+#[cfg(feature = "i3")]
+pub mod i3builtinaction;
+#[cfg(feature = "i3")]
+pub mod i3state;
+pub mod internalaction;
+#[cfg(any(feature = "xdo", feature = "wtype"))]
+pub mod keysyms;
+pub mod logaction;
+pub mod macroaction;
+#[cfg(feature = "obs")]
+pub mod obsaction;
+#[cfg(feature = "plugins")]
+pub mod pluginaction;
+#[cfg(feature = "uinput")]
+pub mod pointeraction;
+#[cfg(feature = "rest")]
+pub mod restaction;
+#[cfg(feature = "river")]
+pub mod riveraction;
+#[cfg(feature = "tmux")]
+pub mod tmuxaction;
+#[cfg(feature = "uinput")]
+pub mod uinputaction;
+#[cfg(feature = "volume")]
+pub mod volumeaction;
+#[cfg(feature = "wtype")]
+pub mod wtypeaction;
+#[cfg(feature = "xdo")]
+pub mod xdoaction;
 
-pub use crate::actions::commandaction::CommandAction;
+#[cfg(feature = "clipboard")]
+pub use crate::actions::clipboardaction::ClipboardAction;
+#[cfg(feature = "command")]
+pub use crate::actions::commandaction::{context_env_vars, CommandAction, CommandOptions};
+pub use crate::actions::delayaction::DelayedAction;
 pub use crate::actions::errors::ActionError;
-pub use crate::actions::i3action::{I3Action, SharedConnection};
+#[cfg(feature = "plugins")]
+pub use crate::actions::errors::PluginError;
+#[cfg(feature = "helper")]
+pub use crate::actions::helperaction::{
+    HelperAction, HelperProcess, SharedHelperProcess, DEFAULT_HELPER_TIMEOUT,
+};
+#[cfg(feature = "i3")]
+pub use crate::actions::i3action::{
+    check_i3_command, watch_i3_shutdown, I3Action, SharedConnection,
+};
+#[cfg(feature = "i3")]
+pub use crate::actions::i3builtinaction::{I3Builtin, I3BuiltinAction};
+#[cfg(feature = "i3")]
+pub use crate::actions::i3state::{I3State, I3StateCache, OutputState, WorkspaceState};
+pub use crate::actions::internalaction::{InternalAction, InternalCommand};
+pub use crate::actions::logaction::LogAction;
+pub use crate::actions::macroaction::{MacroRecorder, RecordMacroAction, SharedMacroRecorder};
+#[cfg(feature = "obs")]
+pub use crate::actions::obsaction::{ObsAction, ObsConnection, ObsRequest, SharedObsConnection};
+#[cfg(feature = "plugins")]
+pub use crate::actions::pluginaction::{
+    PluginAction, PluginDeclaration, PluginFactory, PluginRegistry, LILLINPUT_VERSION,
+    PLUGIN_API_VERSION, PLUGIN_SYMBOL,
+};
+#[cfg(feature = "uinput")]
+pub use crate::actions::pointeraction::{
+    PointerAction, PointerCommand, SharedVirtualPointer, VirtualPointer,
+};
+#[cfg(feature = "rest")]
+pub use crate::actions::restaction::{RestAction, RestRequest};
+#[cfg(feature = "river")]
+pub use crate::actions::riveraction::{RiverAction, RiverConnection, SharedRiverConnection};
+#[cfg(feature = "tmux")]
+pub use crate::actions::tmuxaction::TmuxAction;
+#[cfg(feature = "uinput")]
+pub use crate::actions::uinputaction::{SharedVirtualTouchpad, UinputAction, VirtualTouchpad};
+#[cfg(feature = "volume")]
+pub use crate::actions::volumeaction::{
+    SharedVolumeConnection, VolumeAction, VolumeCommand, VolumeConnection,
+};
+#[cfg(feature = "wtype")]
+pub use crate::actions::wtypeaction::{
+    SharedVirtualKeyboard, VirtualKeyboard, WtypeAction, WtypeCommand,
+};
+#[cfg(feature = "xdo")]
+pub use crate::actions::xdoaction::{SharedXdoConnection, XdoAction, XdoCommand, XdoConnection};
 
 use std::fmt;
-use strum::{Display, EnumString, EnumVariantNames};
+use std::process::Child;
+use std::str::FromStr;
+
+#[cfg(any(feature = "clipboard", feature = "rest"))]
+use crate::events::ActionEvent;
+use crate::events::GestureContext;
+
+use strum::{Display, EnumIter, EnumString, EnumVariantNames};
+
+/// Registry of all the action types, along with the cargo feature that
+/// enables each of them, if optional.
+///
+/// The registry includes the action types that are not compiled in, allowing
+/// them to be reported as disabled rather than as unknown.
+pub const ACTION_TYPES: &[(&str, Option<&str>)] = &[
+    ("i3", Some("i3")),
+    ("command", Some("command")),
+    ("i3!", Some("i3")),
+    ("tmux", Some("tmux")),
+    ("record-macro", None),
+    ("internal", None),
+    ("obs", Some("obs")),
+    ("rest", Some("rest")),
+    ("log", None),
+    ("clipboard", Some("clipboard")),
+    ("uinput", Some("uinput")),
+    ("pointer", Some("uinput")),
+    ("xdo", Some("xdo")),
+    ("wtype", Some("wtype")),
+    ("volume", Some("volume")),
+    ("plugin", Some("plugins")),
+    ("helper", Some("helper")),
+    ("river", Some("river")),
+];
 
 /// Possible choices for action types.
-#[derive(Display, EnumString, EnumVariantNames)]
+#[derive(Display, EnumIter, EnumString, EnumVariantNames)]
 #[strum(serialize_all = "kebab_case")]
 pub enum ActionType {
     /// Action for interacting with `i3`.
+    #[cfg(feature = "i3")]
     I3,
     /// Action for executing commands.
+    #[cfg(feature = "command")]
     Command,
+    /// Action for executing builtin `i3` commands.
+    #[cfg(feature = "i3")]
+    #[strum(serialize = "i3!")]
+    I3Builtin,
+    /// Action for interacting with `tmux`.
+    #[cfg(feature = "tmux")]
+    Tmux,
+    /// Action for recording macros.
+    RecordMacro,
+    /// Action for acting on `lillinput` itself.
+    Internal,
+    /// Action for interacting with `OBS` via `obs-websocket`.
+    #[cfg(feature = "obs")]
+    Obs,
+    /// Action for performing `REST` requests.
+    #[cfg(feature = "rest")]
+    Rest,
+    /// Action for logging the gestures, without side effects.
+    Log,
+    /// Action for placing text on the Wayland clipboard.
+    #[cfg(feature = "clipboard")]
+    Clipboard,
+    /// Action for emitting gestures on a virtual touchpad.
+    #[cfg(feature = "uinput")]
+    Uinput,
+    /// Action for emitting pointer events on a virtual pointer.
+    #[cfg(feature = "uinput")]
+    Pointer,
+    /// Action for injecting key and button presses on X11 via `XTest`.
+    #[cfg(feature = "xdo")]
+    Xdo,
+    /// Action for injecting key presses on Wayland via the `virtual-keyboard`
+    /// protocol.
+    #[cfg(feature = "wtype")]
+    Wtype,
+    /// Action for adjusting the audio volume via `PulseAudio` or `PipeWire`.
+    #[cfg(feature = "volume")]
+    Volume,
+    /// Action provided by a dynamically loaded plugin.
+    #[cfg(feature = "plugins")]
+    Plugin,
+    /// Action for sending the gestures to a long-running helper process.
+    #[cfg(feature = "helper")]
+    Helper,
+    /// Action for running `River` commands via the `river-control` protocol.
+    #[cfg(feature = "river")]
+    River,
+}
+
+impl ActionType {
+    /// Parse an action type, taking into account the compiled-in features.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - name of the action type.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the action type is unknown, or if the feature that
+    /// enables it is not compiled in.
+    pub fn parse(name: &str) -> Result<ActionType, ActionError> {
+        ActionType::from_str(name).map_err(|_| {
+            match ACTION_TYPES.iter().find(|(type_, _)| *type_ == name) {
+                Some((_, Some(feature))) => ActionError::DisabledType {
+                    type_: name.to_string(),
+                    feature: (*feature).to_string(),
+                },
+                _ => ActionError::UnknownType(name.to_string()),
+            }
+        })
+    }
+
+    /// Return the cargo feature that enables the action type, if optional.
+    #[must_use]
+    pub fn feature(&self) -> Option<&'static str> {
+        let name = self.to_string();
+        ACTION_TYPES
+            .iter()
+            .find(|(type_, _)| *type_ == name)
+            .and_then(|(_, feature)| *feature)
+    }
+}
+
+/// Render a template, replacing the gesture variables.
+///
+/// The template can contain the following variables:
+/// * `{event}`: the [`ActionEvent`] that triggered the action.
+///
+/// # Arguments
+///
+/// * `template` - the template to be rendered.
+/// * `action_event` - the event that triggered the action.
+#[cfg(any(feature = "clipboard", feature = "rest"))]
+pub(crate) fn render_template(template: &str, action_event: ActionEvent) -> String {
+    template.replace("{event}", &action_event.to_string())
 }
 
 /// Handler for a single action triggered by an event.
-pub trait Action: std::fmt::Debug {
+///
+/// Actions are required to be [`Send`], allowing the [`ActionMap`] to be moved
+/// to a different thread.
+///
+/// [`ActionMap`]: crate::controllers::ActionMap
+pub trait Action: std::fmt::Debug + Send {
     /// Execute the command for this action.
     ///
+    /// # Arguments
+    ///
+    /// * `context` - the context of the gesture that triggered the action.
+    ///
     /// # Errors
     ///
     /// Returns `Err` if the execution of the command was not successful.
-    fn execute_command(&mut self) -> Result<(), ActionError>;
+    fn execute_command(&mut self, context: &GestureContext) -> Result<(), ActionError>;
+    /// Start the command for this action, without waiting for it to finish.
+    ///
+    /// Actions that spawn external processes can return the [`Child`]
+    /// handle, allowing several actions to run concurrently. By default, the
+    /// command is executed synchronously via [`Action::execute_command`].
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - the context of the gesture that triggered the action.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command could not be started.
+    fn start_command(&mut self, context: &GestureContext) -> Result<Option<Child>, ActionError> {
+        self.execute_command(context).map(|()| None)
+    }
+    /// Wait for a process started by [`Action::start_command`] to finish.
+    ///
+    /// # Arguments
+    ///
+    /// * `child` - the process started by the action.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the process could not be waited for.
+    fn finish_command(&mut self, mut child: Child) -> Result<(), ActionError> {
+        child
+            .wait()
+            .map(|_| ())
+            .map_err(|e| ActionError::ExecutionError {
+                type_: String::from("process"),
+                message: e.to_string(),
+            })
+    }
+    /// Return `true` if the action is executed while the controller is paused.
+    fn runs_while_paused(&self) -> bool {
+        false
+    }
+    /// Return `true` if the backend used by the action is available.
+    ///
+    /// Actions whose backend requires a connection (such as `i3`) can report
+    /// it as unavailable, allowing the problem to be shown before executing
+    /// them.
+    fn is_available(&self) -> bool {
+        true
+    }
+    /// Validate the action, without executing its command.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the action is known to fail when executed, for
+    /// example due to an invalid command.
+    fn validate(&self) -> Result<(), ActionError> {
+        Ok(())
+    }
     /// Format the contents of the action as a [`String`].
     ///
     /// # Errors
@@ -43,3 +322,66 @@ impl fmt::Display for dyn Action {
         self.fmt_command(f)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{ActionType, ACTION_TYPES};
+    use crate::actions::ActionError;
+
+    use strum::IntoEnumIterator;
+
+    #[test]
+    /// Test that all the compiled-in action types are in the registry.
+    fn test_action_types_registry() {
+        for action_type in ActionType::iter() {
+            let name = action_type.to_string();
+            assert!(ACTION_TYPES.iter().any(|(type_, _)| *type_ == name));
+        }
+    }
+
+    #[test]
+    /// Test parsing action types according to the compiled-in features.
+    fn test_action_type_parse() {
+        for (name, feature) in ACTION_TYPES {
+            let enabled = feature.is_none_or(|feature| match feature {
+                "i3" => cfg!(feature = "i3"),
+                "command" => cfg!(feature = "command"),
+                "tmux" => cfg!(feature = "tmux"),
+                "obs" => cfg!(feature = "obs"),
+                "rest" => cfg!(feature = "rest"),
+                "clipboard" => cfg!(feature = "clipboard"),
+                "uinput" => cfg!(feature = "uinput"),
+                "xdo" => cfg!(feature = "xdo"),
+                "wtype" => cfg!(feature = "wtype"),
+                "volume" => cfg!(feature = "volume"),
+                "plugins" => cfg!(feature = "plugins"),
+                "helper" => cfg!(feature = "helper"),
+                "river" => cfg!(feature = "river"),
+                _ => unreachable!(),
+            });
+
+            match ActionType::parse(name) {
+                Ok(action_type) => {
+                    assert!(enabled);
+                    assert_eq!(action_type.to_string(), *name);
+                    assert_eq!(action_type.feature(), *feature);
+                }
+                Err(e) => {
+                    assert!(!enabled);
+                    assert_eq!(
+                        e,
+                        ActionError::DisabledType {
+                            type_: name.to_string(),
+                            feature: feature.unwrap().to_string(),
+                        }
+                    );
+                }
+            }
+        }
+
+        assert_eq!(
+            ActionType::parse("invalid").err(),
+            Some(ActionError::UnknownType("invalid".to_string()))
+        );
+    }
+}
@@ -0,0 +1,409 @@
+//! Action for interacting with `OBS` via `obs-websocket`.
+
+use std::fmt;
+use std::net::TcpStream;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::actions::errors::ActionError;
+use crate::actions::{Action, ActionType};
+use crate::events::GestureContext;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use log::{info, warn};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use strum::{Display, EnumString, EnumVariantNames, VariantNames};
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{connect, Message, WebSocket};
+
+/// Version of the `obs-websocket` RPC protocol.
+const RPC_VERSION: u64 = 1;
+
+/// Shared `obs-websocket` connection.
+pub type SharedObsConnection = Arc<Mutex<ObsConnection>>;
+
+/// Errors raised while performing a request to `obs-websocket`.
+enum RequestError {
+    /// The connection to the server is no longer valid.
+    Connection(String),
+    /// The server replied with an unsuccessful status.
+    Request(String),
+}
+
+impl From<RequestError> for ActionError {
+    fn from(error: RequestError) -> Self {
+        let (RequestError::Connection(message) | RequestError::Request(message)) = error;
+        ActionError::ExecutionError {
+            type_: ActionType::Obs.to_string(),
+            message,
+        }
+    }
+}
+
+/// Connection to an `obs-websocket` server.
+///
+/// The connection is established lazily on the first request, and
+/// re-established if it was closed by the server (for example, if `OBS` was
+/// restarted).
+pub struct ObsConnection {
+    /// URL of the `obs-websocket` server.
+    url: String,
+    /// Optional password for authenticating with the server.
+    password: Option<String>,
+    /// Websocket connected and identified with the server, if any.
+    socket: Option<WebSocket<MaybeTlsStream<TcpStream>>>,
+    /// Identifier of the last request sent to the server.
+    request_id: u64,
+}
+
+impl fmt::Debug for ObsConnection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Avoid including the password in the output.
+        f.debug_struct("ObsConnection")
+            .field("url", &self.url)
+            .field("connected", &self.socket.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ObsConnection {
+    /// Create a new [`ObsConnection`].
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - URL of the `obs-websocket` server.
+    /// * `password` - optional password for authenticating with the server.
+    #[must_use]
+    pub fn new(url: String, password: Option<String>) -> Self {
+        ObsConnection {
+            url,
+            password,
+            socket: None,
+            request_id: 0,
+        }
+    }
+
+    /// Read the next `obs-websocket` message from the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `socket` - websocket connected with the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the message could not be read or parsed.
+    fn read_message(
+        socket: &mut WebSocket<MaybeTlsStream<TcpStream>>,
+    ) -> Result<Value, RequestError> {
+        loop {
+            match socket.read() {
+                Ok(Message::Text(text)) => {
+                    return serde_json::from_str(&text)
+                        .map_err(|e| RequestError::Request(format!("invalid message: {e}")))
+                }
+                Ok(Message::Close(frame)) => {
+                    return Err(RequestError::Connection(format!(
+                        "connection closed by the server ({})",
+                        frame.map_or(String::from("no reason"), |x| x.reason.to_string())
+                    )))
+                }
+                Ok(_) => {}
+                Err(e) => return Err(RequestError::Connection(e.to_string())),
+            }
+        }
+    }
+
+    /// Send an `obs-websocket` message to the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `socket` - websocket connected with the server.
+    /// * `message` - the message to be sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the message could not be sent.
+    fn send_message(
+        socket: &mut WebSocket<MaybeTlsStream<TcpStream>>,
+        message: &Value,
+    ) -> Result<(), RequestError> {
+        socket
+            .send(Message::Text(message.to_string()))
+            .map_err(|e| RequestError::Connection(e.to_string()))
+    }
+
+    /// Connect to the server, performing the `obs-websocket` handshake.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the connection or the authentication failed.
+    fn connect(&mut self) -> Result<(), RequestError> {
+        let (mut socket, _) =
+            connect(self.url.as_str()).map_err(|e| RequestError::Connection(e.to_string()))?;
+
+        // Receive the `Hello` message, and reply with the `Identify` message.
+        let hello = Self::read_message(&mut socket)?;
+        let mut identify = json!({"rpcVersion": RPC_VERSION, "eventSubscriptions": 0});
+        if let Some(authentication) = hello["d"].get("authentication") {
+            let Some(password) = &self.password else {
+                return Err(RequestError::Connection(String::from(
+                    "the server requires a password",
+                )));
+            };
+            identify["authentication"] = Value::from(authentication_string(
+                password,
+                authentication["salt"].as_str().unwrap_or_default(),
+                authentication["challenge"].as_str().unwrap_or_default(),
+            ));
+        }
+        Self::send_message(&mut socket, &json!({"op": 1, "d": identify}))?;
+
+        // Receive the `Identified` message.
+        let identified = Self::read_message(&mut socket)?;
+        if identified["op"] != 2 {
+            return Err(RequestError::Connection(String::from(
+                "unexpected reply during identification",
+            )));
+        }
+
+        info!("obs: connection opened with {}", self.url);
+        self.socket = Some(socket);
+
+        Ok(())
+    }
+
+    /// Send a request to the server using the current connection, waiting
+    /// for its response.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_type` - `obs-websocket` request type.
+    /// * `request_data` - data for the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the request could not be performed or was not
+    /// successful.
+    fn send_request(
+        &mut self,
+        request_type: &str,
+        request_data: &Value,
+    ) -> Result<(), RequestError> {
+        let Some(socket) = self.socket.as_mut() else {
+            return Err(RequestError::Connection(String::from("not connected")));
+        };
+
+        self.request_id += 1;
+        let request_id = self.request_id.to_string();
+        Self::send_message(
+            socket,
+            &json!({
+                "op": 6,
+                "d": {
+                    "requestType": request_type,
+                    "requestId": request_id,
+                    "requestData": request_data,
+                }
+            }),
+        )?;
+
+        // Wait for the `RequestResponse` message for the request.
+        loop {
+            let message = Self::read_message(socket)?;
+            if message["op"] == 7 && message["d"]["requestId"] == request_id.as_str() {
+                let status = &message["d"]["requestStatus"];
+                return if status["result"].as_bool().unwrap_or(false) {
+                    Ok(())
+                } else {
+                    Err(RequestError::Request(format!(
+                        "request {request_type} failed: {}",
+                        status["comment"].as_str().unwrap_or("unknown error")
+                    )))
+                };
+            }
+        }
+    }
+
+    /// Perform a request, connecting to the server if needed.
+    ///
+    /// If the existing connection is no longer valid, a new connection is
+    /// established and the request is retried once.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_type` - `obs-websocket` request type.
+    /// * `request_data` - data for the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the request could not be performed or was not
+    /// successful.
+    pub fn request(&mut self, request_type: &str, request_data: &Value) -> Result<(), ActionError> {
+        let reconnected = self.socket.is_none();
+        if reconnected {
+            self.connect()?;
+        }
+
+        match self.send_request(request_type, request_data) {
+            Err(RequestError::Connection(e)) if !reconnected => {
+                warn!("obs: connection lost ({e}), reconnecting");
+                self.socket = None;
+                self.connect()?;
+                self.send_request(request_type, request_data)
+                    .map_err(Into::into)
+            }
+            Err(RequestError::Connection(e)) => {
+                self.socket = None;
+                Err(RequestError::Connection(e).into())
+            }
+            result => result.map_err(Into::into),
+        }
+    }
+}
+
+/// Compute the authentication string for the `obs-websocket` handshake.
+///
+/// # Arguments
+///
+/// * `password` - password for the server.
+/// * `salt` - salt provided by the server.
+/// * `challenge` - challenge provided by the server.
+fn authentication_string(password: &str, salt: &str, challenge: &str) -> String {
+    let secret = STANDARD.encode(Sha256::digest(format!("{password}{salt}")));
+    STANDARD.encode(Sha256::digest(format!("{secret}{challenge}")))
+}
+
+/// Possible choices for `OBS` requests.
+#[derive(Clone, Copy, Debug, Display, EnumString, EnumVariantNames, Eq, PartialEq)]
+#[strum(serialize_all = "kebab_case")]
+pub enum ObsRequest {
+    /// Switch the current program scene.
+    SetScene,
+    /// Toggle the recording.
+    ToggleRecord,
+    /// Toggle the streaming.
+    ToggleStream,
+}
+
+/// Action that performs requests to `OBS` via `obs-websocket`.
+#[derive(Debug)]
+pub struct ObsAction {
+    /// `obs-websocket` connection.
+    connection: SharedObsConnection,
+    /// Request to be performed in this action.
+    request: ObsRequest,
+    /// Argument for the request.
+    argument: String,
+}
+
+impl ObsAction {
+    /// Create a new [`ObsAction`].
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - request to be performed in this action, in the form
+    ///   `{request} {argument}`.
+    /// * `connection` - `obs-websocket` connection.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command is not a valid request.
+    pub fn new(command: &str, connection: SharedObsConnection) -> Result<Self, ActionError> {
+        let (request, argument) = command.split_once(' ').unwrap_or((command, ""));
+        let request = ObsRequest::from_str(request).map_err(|_| ActionError::InvalidCommand {
+            type_: ActionType::Obs.to_string(),
+            message: format!(
+                "unknown request {request} (possible values: {:?})",
+                ObsRequest::VARIANTS
+            ),
+        })?;
+        let argument = argument.trim();
+        if request == ObsRequest::SetScene && argument.is_empty() {
+            return Err(ActionError::InvalidCommand {
+                type_: ActionType::Obs.to_string(),
+                message: format!("request {request} requires a scene name"),
+            });
+        }
+
+        Ok(ObsAction {
+            connection,
+            request,
+            argument: argument.to_string(),
+        })
+    }
+}
+
+impl Action for ObsAction {
+    fn execute_command(&mut self, _context: &GestureContext) -> Result<(), ActionError> {
+        let (request_type, request_data) = match self.request {
+            ObsRequest::SetScene => (
+                "SetCurrentProgramScene",
+                json!({"sceneName": self.argument}),
+            ),
+            ObsRequest::ToggleRecord => ("ToggleRecord", json!({})),
+            ObsRequest::ToggleStream => ("ToggleStream", json!({})),
+        };
+
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .request(request_type, &request_data)
+    }
+
+    fn fmt_command(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.argument.is_empty() {
+            write!(f, "{}:<{}>", ActionType::Obs, self.request)
+        } else {
+            write!(
+                f,
+                "{}:<{} {}>",
+                ActionType::Obs,
+                self.request,
+                self.argument
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use super::{authentication_string, ObsAction, ObsConnection};
+    use crate::actions::ActionError;
+
+    #[test]
+    /// Test the computation of the authentication string.
+    fn test_obs_authentication_string() {
+        // Values from the `obs-websocket` protocol documentation.
+        assert_eq!(
+            authentication_string(
+                "supersecretpassword",
+                "lM1GncleQOaCu9lT1yeUZhFYnqhsLLP1G5lAGo3ixaI=",
+                "+IxH4CnCiqpX1rM9scsNynZzbOe4KhDeYcTNS3PDaeY="
+            ),
+            "1Ct943GAT+6YQUUX47Ia/ncufilbe6+oD6lY+5kaCu4="
+        );
+    }
+
+    #[test]
+    /// Test the parsing of `OBS` requests.
+    fn test_obs_parse() {
+        let connection = Arc::new(Mutex::new(ObsConnection::new(
+            String::from("ws://localhost:4455"),
+            None,
+        )));
+
+        assert!(ObsAction::new("set-scene Main", Arc::clone(&connection)).is_ok());
+        assert!(ObsAction::new("toggle-record", Arc::clone(&connection)).is_ok());
+        assert!(matches!(
+            ObsAction::new("set-scene", Arc::clone(&connection)),
+            Err(ActionError::InvalidCommand { .. })
+        ));
+        assert!(matches!(
+            ObsAction::new("invalid", connection),
+            Err(ActionError::InvalidCommand { .. })
+        ));
+    }
+}
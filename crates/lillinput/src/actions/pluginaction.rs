@@ -0,0 +1,417 @@
+//! Action for executing the actions provided by dynamically loaded plugins.
+//!
+//! A plugin is a dynamic library (`crate-type = ["cdylib"]`) that declares a
+//! factory of [`Action`]s via [`export_plugin!`]. As the declaration relies on
+//! the Rust ABI, the plugin must be built against the same version of
+//! `lillinput`, and with the same compiler, as the application loading it:
+//!
+//! ```ignore
+//! use lillinput::actions::{Action, ActionError};
+//!
+//! fn create(command: &str) -> Result<Box<dyn Action>, ActionError> {
+//!     Ok(Box::new(MyAction::new(command)))
+//! }
+//!
+//! lillinput::export_plugin!("my-plugin", create);
+//! ```
+//!
+//! The actions of the plugin are then specified as
+//! `plugin:{plugin name} {command}`.
+//!
+//! [`export_plugin!`]: crate::export_plugin
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::sync::Arc;
+
+use crate::actions::errors::{ActionError, PluginError};
+use crate::actions::{Action, ActionType};
+use crate::events::GestureContext;
+use libloading::Library;
+
+/// Version of the plugin API, increased on incompatible changes.
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+/// Version of `lillinput` the plugins are built against.
+pub const LILLINPUT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Name of the symbol of the [`PluginDeclaration`] exported by the plugins.
+pub const PLUGIN_SYMBOL: &str = "LILLINPUT_PLUGIN";
+
+/// Factory of the actions of a plugin, receiving the command of the action.
+pub type PluginFactory = fn(command: &str) -> Result<Box<dyn Action>, ActionError>;
+
+/// Declaration of a plugin, exported via [`export_plugin!`].
+///
+/// [`export_plugin!`]: crate::export_plugin
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct PluginDeclaration {
+    /// Version of the plugin API the plugin is built against.
+    pub api_version: u32,
+    /// Version of `lillinput` the plugin is built against.
+    pub lillinput_version: &'static str,
+    /// Name of the plugin, used for referencing it in the actions.
+    pub name: &'static str,
+    /// Factory of the actions of the plugin.
+    pub create: PluginFactory,
+}
+
+/// Export the [`PluginDeclaration`] of a plugin.
+///
+/// # Arguments
+///
+/// * `name` - name of the plugin.
+/// * `create` - factory of the actions of the plugin (a [`PluginFactory`]).
+///
+/// [`PluginDeclaration`]: crate::actions::PluginDeclaration
+/// [`PluginFactory`]: crate::actions::PluginFactory
+#[macro_export]
+macro_rules! export_plugin {
+    ($name:expr, $create:expr) => {
+        #[no_mangle]
+        pub static LILLINPUT_PLUGIN: $crate::actions::PluginDeclaration =
+            $crate::actions::PluginDeclaration {
+                api_version: $crate::actions::PLUGIN_API_VERSION,
+                lillinput_version: $crate::actions::LILLINPUT_VERSION,
+                name: $name,
+                create: $create,
+            };
+    };
+}
+
+/// Plugin registered in a [`PluginRegistry`].
+struct Plugin {
+    /// Declaration of the plugin.
+    declaration: PluginDeclaration,
+    /// Library of the plugin, if loaded dynamically.
+    library: Option<Arc<Library>>,
+}
+
+/// Registry of the available plugins, by name.
+#[derive(Default)]
+pub struct PluginRegistry {
+    /// Registered plugins.
+    plugins: HashMap<String, Plugin>,
+}
+
+impl fmt::Debug for PluginRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PluginRegistry")
+            .field("plugins", &self.names())
+            .finish()
+    }
+}
+
+impl PluginRegistry {
+    /// Return the names of the registered plugins, sorted.
+    #[must_use]
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.plugins.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Register a plugin declared by the application itself, without
+    /// loading a library.
+    ///
+    /// # Arguments
+    ///
+    /// * `declaration` - declaration of the plugin.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a plugin with the same name is already registered.
+    pub fn register(&mut self, declaration: PluginDeclaration) -> Result<(), PluginError> {
+        self.insert(declaration, None, PathBuf::new())
+    }
+
+    /// Load a plugin from a dynamic library, returning its name.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - path of the library.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the library could not be loaded, if it does not
+    /// declare a compatible plugin, or if a plugin with the same name is
+    /// already registered.
+    ///
+    /// # Safety
+    ///
+    /// Loading a library executes its initialization routines, and the
+    /// plugin is assumed to be built against the same version of `lillinput`
+    /// with the same compiler: see [`Library::new`].
+    pub unsafe fn load(&mut self, path: &Path) -> Result<String, PluginError> {
+        let load_error = |e: libloading::Error| PluginError::Load {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        };
+        let library = Library::new(path).map_err(load_error)?;
+        let declaration = *library
+            .get::<*const PluginDeclaration>(PLUGIN_SYMBOL.as_bytes())
+            .map_err(load_error)?;
+
+        // Check the versions before reading the rest of the declaration, as
+        // its layout might differ.
+        let api_version = (*declaration).api_version;
+        if api_version != PLUGIN_API_VERSION {
+            return Err(PluginError::Incompatible {
+                path: path.to_path_buf(),
+                message: format!("API version {api_version}, expected {PLUGIN_API_VERSION}"),
+            });
+        }
+        let declaration = *declaration;
+        if declaration.lillinput_version != LILLINPUT_VERSION {
+            return Err(PluginError::Incompatible {
+                path: path.to_path_buf(),
+                message: format!(
+                    "built against lillinput {}, expected {LILLINPUT_VERSION}",
+                    declaration.lillinput_version
+                ),
+            });
+        }
+
+        self.insert(declaration, Some(Arc::new(library)), path.to_path_buf())?;
+        Ok(declaration.name.to_string())
+    }
+
+    /// Load the plugins from the dynamic libraries (`.so` files) in a
+    /// directory, in alphabetical order.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - path of the directory.
+    ///
+    /// # Returns
+    ///
+    /// The result of loading each library: the name of the plugin, or the
+    /// reason it could not be loaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the directory could not be read.
+    ///
+    /// # Safety
+    ///
+    /// See [`PluginRegistry::load`].
+    pub unsafe fn load_directory(
+        &mut self,
+        directory: &Path,
+    ) -> Result<Vec<Result<String, PluginError>>, PluginError> {
+        let mut paths = fs::read_dir(directory)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| entry.map(|x| x.path()))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(|e| PluginError::Load {
+                path: directory.to_path_buf(),
+                message: e.to_string(),
+            })?;
+        paths.retain(|path| path.extension().is_some_and(|x| x == "so"));
+        paths.sort();
+
+        Ok(paths.iter().map(|path| self.load(path)).collect())
+    }
+
+    /// Insert a plugin in the registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `declaration` - declaration of the plugin.
+    /// * `library` - library of the plugin, if loaded dynamically.
+    /// * `path` - path of the library, for reporting errors.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a plugin with the same name is already registered.
+    fn insert(
+        &mut self,
+        declaration: PluginDeclaration,
+        library: Option<Arc<Library>>,
+        path: PathBuf,
+    ) -> Result<(), PluginError> {
+        if self.plugins.contains_key(declaration.name) {
+            return Err(PluginError::Duplicate {
+                path,
+                name: declaration.name.to_string(),
+            });
+        }
+
+        self.plugins.insert(
+            declaration.name.to_string(),
+            Plugin {
+                declaration,
+                library,
+            },
+        );
+        Ok(())
+    }
+}
+
+/// Action that executes an action provided by a plugin.
+///
+/// Allows third parties to provide their own action types, without
+/// modifying `lillinput`.
+#[derive(Debug)]
+pub struct PluginAction {
+    /// Action created by the plugin.
+    ///
+    /// Declared before the library, as it needs to be dropped before the
+    /// library is unloaded.
+    action: Box<dyn Action>,
+    /// Name of the plugin.
+    name: String,
+    /// Command of the action, as passed to the plugin.
+    command: String,
+    /// Library of the plugin, kept loaded while the action exists.
+    _library: Option<Arc<Library>>,
+}
+
+impl PluginAction {
+    /// Create a new [`PluginAction`].
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - name of the plugin, followed by the command passed to
+    ///   the plugin.
+    /// * `registry` - registry of the available plugins.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the plugin is not registered, or if the plugin could
+    /// not create the action.
+    pub fn new(command: &str, registry: &PluginRegistry) -> Result<Self, ActionError> {
+        let command = command.trim();
+        let (name, command) = command
+            .split_once(char::is_whitespace)
+            .unwrap_or((command, ""));
+        let plugin = registry
+            .plugins
+            .get(name)
+            .ok_or_else(|| ActionError::InvalidCommand {
+                type_: ActionType::Plugin.to_string(),
+                message: format!(
+                    "unknown plugin {name} (loaded plugins: {:?})",
+                    registry.names()
+                ),
+            })?;
+        let command = command.trim();
+        let action = (plugin.declaration.create)(command)?;
+
+        Ok(PluginAction {
+            action,
+            name: name.to_string(),
+            command: command.to_string(),
+            _library: plugin.library.clone(),
+        })
+    }
+}
+
+impl Action for PluginAction {
+    fn execute_command(&mut self, context: &GestureContext) -> Result<(), ActionError> {
+        self.action.execute_command(context)
+    }
+
+    fn start_command(&mut self, context: &GestureContext) -> Result<Option<Child>, ActionError> {
+        self.action.start_command(context)
+    }
+
+    fn finish_command(&mut self, child: Child) -> Result<(), ActionError> {
+        self.action.finish_command(child)
+    }
+
+    fn runs_while_paused(&self) -> bool {
+        self.action.runs_while_paused()
+    }
+
+    fn is_available(&self) -> bool {
+        self.action.is_available()
+    }
+
+    fn validate(&self) -> Result<(), ActionError> {
+        self.action.validate()
+    }
+
+    fn fmt_command(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:<{} {}>", ActionType::Plugin, self.name, self.command)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Write;
+
+    use super::{PluginAction, PluginDeclaration, PluginRegistry};
+    use crate::actions::errors::PluginError;
+    use crate::actions::{Action, ActionError, LogAction};
+    use crate::events::ActionEvent;
+    use tempfile::Builder;
+
+    /// Factory of the actions of the test plugin.
+    #[allow(clippy::unnecessary_wraps)]
+    fn create(command: &str) -> Result<Box<dyn Action>, ActionError> {
+        Ok(Box::new(LogAction::new(
+            command.to_string(),
+            ActionEvent::ThreeFingerSwipeUp,
+        )))
+    }
+
+    #[test]
+    /// Test creating the actions of a registered plugin.
+    fn test_plugin_action() {
+        let declaration = PluginDeclaration {
+            api_version: super::PLUGIN_API_VERSION,
+            lillinput_version: super::LILLINPUT_VERSION,
+            name: "test",
+            create,
+        };
+        let mut registry = PluginRegistry::default();
+        registry.register(declaration).unwrap();
+        assert!(matches!(
+            registry.register(declaration),
+            Err(PluginError::Duplicate { .. })
+        ));
+        assert_eq!(registry.names(), vec!["test"]);
+
+        let action: Box<dyn Action> =
+            Box::new(PluginAction::new(" test foo  bar ", &registry).unwrap());
+        assert_eq!(action.to_string(), "plugin:<test foo  bar>");
+        assert!(matches!(
+            PluginAction::new("unknown foo", &registry),
+            Err(ActionError::InvalidCommand { .. })
+        ));
+    }
+
+    #[test]
+    /// Test reporting the libraries that could not be loaded.
+    fn test_plugin_load_directory() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut file = Builder::new()
+            .suffix(".so")
+            .tempfile_in(directory.path())
+            .unwrap();
+        writeln!(file, "not a library").unwrap();
+        Builder::new()
+            .suffix(".txt")
+            .tempfile_in(directory.path())
+            .unwrap();
+
+        let mut registry = PluginRegistry::default();
+        // SAFETY: the only library in the directory is not valid, and can't
+        // be loaded.
+        let results = unsafe { registry.load_directory(directory.path()) }.unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0], Err(PluginError::Load { .. })));
+        assert!(registry.names().is_empty());
+
+        // SAFETY: the directory does not exist.
+        let result = unsafe { registry.load_directory(&directory.path().join("foo")) };
+        assert!(matches!(result, Err(PluginError::Load { .. })));
+    }
+}
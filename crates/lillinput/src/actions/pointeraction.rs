@@ -0,0 +1,254 @@
+//! Action for emitting pointer events (scrolling and buttons) via `uinput`.
+
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::actions::errors::ActionError;
+use crate::actions::{Action, ActionType};
+use crate::events::GestureContext;
+use evdev::uinput::{VirtualDevice, VirtualDeviceBuilder};
+use evdev::{AttributeSet, EventType, InputEvent, Key, RelativeAxisType};
+use strum::{Display, EnumIter, EnumString, EnumVariantNames, VariantNames};
+
+/// Name of the virtual pointer used for emitting pointer events.
+pub const VIRTUAL_POINTER_NAME: &str = "lillinput virtual pointer";
+
+/// Shared virtual pointer.
+pub type SharedVirtualPointer = Arc<Mutex<VirtualPointer>>;
+
+/// Possible choices for pointer commands.
+#[derive(Clone, Copy, Debug, Display, EnumIter, EnumString, EnumVariantNames, Eq, PartialEq)]
+#[strum(serialize_all = "kebab_case")]
+pub enum PointerCommand {
+    /// Scroll up by one step of the wheel.
+    ScrollUp,
+    /// Scroll down by one step of the wheel.
+    ScrollDown,
+    /// Scroll left by one step of the horizontal wheel.
+    ScrollLeft,
+    /// Scroll right by one step of the horizontal wheel.
+    ScrollRight,
+    /// Click the left button.
+    LeftClick,
+    /// Click the middle button.
+    MiddleClick,
+    /// Click the right button.
+    RightClick,
+    /// Click the back (side) button, usually navigating back.
+    Back,
+    /// Click the forward (extra) button, usually navigating forward.
+    Forward,
+}
+
+impl PointerCommand {
+    /// Return the frames of events emitted for the command.
+    ///
+    /// The scrolls are emitted in a single frame, and the clicks in two
+    /// frames (pressing and releasing the button).
+    #[must_use]
+    pub fn frames(self) -> Vec<Vec<InputEvent>> {
+        let wheel = |axis: RelativeAxisType, value: i32| {
+            vec![vec![InputEvent::new(EventType::RELATIVE, axis.0, value)]]
+        };
+        let click = |key: Key| {
+            vec![
+                vec![InputEvent::new(EventType::KEY, key.code(), 1)],
+                vec![InputEvent::new(EventType::KEY, key.code(), 0)],
+            ]
+        };
+
+        match self {
+            PointerCommand::ScrollUp => wheel(RelativeAxisType::REL_WHEEL, 1),
+            PointerCommand::ScrollDown => wheel(RelativeAxisType::REL_WHEEL, -1),
+            PointerCommand::ScrollLeft => wheel(RelativeAxisType::REL_HWHEEL, -1),
+            PointerCommand::ScrollRight => wheel(RelativeAxisType::REL_HWHEEL, 1),
+            PointerCommand::LeftClick => click(Key::BTN_LEFT),
+            PointerCommand::MiddleClick => click(Key::BTN_MIDDLE),
+            PointerCommand::RightClick => click(Key::BTN_RIGHT),
+            PointerCommand::Back => click(Key::BTN_SIDE),
+            PointerCommand::Forward => click(Key::BTN_EXTRA),
+        }
+    }
+}
+
+/// Virtual pointer for emitting scrolls and button clicks.
+///
+/// The `uinput` device is created lazily on the first command, and is named
+/// [`VIRTUAL_POINTER_NAME`].
+#[derive(Default)]
+pub struct VirtualPointer {
+    /// `uinput` device, if created.
+    device: Option<VirtualDevice>,
+}
+
+impl fmt::Debug for VirtualPointer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("VirtualPointer")
+            .field("created", &self.device.is_some())
+            .finish()
+    }
+}
+
+impl VirtualPointer {
+    /// Create the `uinput` device of the pointer.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the device could not be created (for example, if
+    /// `/dev/uinput` is not writable).
+    fn create_device() -> io::Result<VirtualDevice> {
+        let mut keys = AttributeSet::<Key>::new();
+        for key in [
+            Key::BTN_LEFT,
+            Key::BTN_RIGHT,
+            Key::BTN_MIDDLE,
+            Key::BTN_SIDE,
+            Key::BTN_EXTRA,
+        ] {
+            keys.insert(key);
+        }
+        // The motion axes are required for the device to be recognized as a
+        // pointer, even if no motion is emitted.
+        let mut axes = AttributeSet::<RelativeAxisType>::new();
+        for axis in [
+            RelativeAxisType::REL_X,
+            RelativeAxisType::REL_Y,
+            RelativeAxisType::REL_WHEEL,
+            RelativeAxisType::REL_HWHEEL,
+        ] {
+            axes.insert(axis);
+        }
+
+        VirtualDeviceBuilder::new()?
+            .name(VIRTUAL_POINTER_NAME)
+            .with_keys(&keys)?
+            .with_relative_axes(&axes)?
+            .build()
+    }
+
+    /// Emit the events of a pointer command.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - the pointer command.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the events could not be emitted.
+    pub fn emit(&mut self, command: PointerCommand) -> io::Result<()> {
+        let device = match self.device.take() {
+            Some(device) => device,
+            None => Self::create_device()?,
+        };
+        let device = self.device.insert(device);
+
+        let result = command
+            .frames()
+            .iter()
+            .try_for_each(|frame| device.emit(frame));
+        if result.is_err() {
+            // Recreate the device on the next command.
+            self.device = None;
+        }
+        result
+    }
+}
+
+/// Action that emits pointer events on a virtual pointer.
+///
+/// Allows gestures to scroll or to click the buttons of a mouse (for
+/// example, for navigating back and forward in a browser) on environments
+/// without `XTest`, such as Wayland compositors.
+#[derive(Debug)]
+pub struct PointerAction {
+    /// Pointer command to be executed in this action.
+    command: PointerCommand,
+    /// Shared virtual pointer.
+    pointer: SharedVirtualPointer,
+}
+
+impl PointerAction {
+    /// Create a new [`PointerAction`].
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - pointer command to be executed in this action.
+    /// * `pointer` - shared virtual pointer.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command is not a valid pointer command.
+    pub fn new(command: &str, pointer: SharedVirtualPointer) -> Result<Self, ActionError> {
+        let command =
+            PointerCommand::from_str(command.trim()).map_err(|_| ActionError::InvalidCommand {
+                type_: ActionType::Pointer.to_string(),
+                message: format!(
+                    "unknown command {command} (possible values: {:?})",
+                    PointerCommand::VARIANTS
+                ),
+            })?;
+
+        Ok(PointerAction { command, pointer })
+    }
+}
+
+impl Action for PointerAction {
+    fn execute_command(&mut self, _context: &GestureContext) -> Result<(), ActionError> {
+        self.pointer
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .emit(self.command)
+            .map_err(|e| ActionError::ExecutionError {
+                type_: ActionType::Pointer.to_string(),
+                message: e.to_string(),
+            })
+    }
+
+    fn fmt_command(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:<{}>", ActionType::Pointer, self.command)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use super::{PointerAction, PointerCommand, VirtualPointer};
+    use crate::actions::ActionError;
+    use evdev::{EventType, Key, RelativeAxisType};
+    use strum::IntoEnumIterator;
+
+    #[test]
+    /// Test the events emitted for each command.
+    fn test_pointer_frames() {
+        let frames = PointerCommand::ScrollDown.frames();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0][0].event_type(), EventType::RELATIVE);
+        assert_eq!(frames[0][0].code(), RelativeAxisType::REL_WHEEL.0);
+        assert_eq!(frames[0][0].value(), -1);
+
+        let frames = PointerCommand::Back.frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0][0].code(), Key::BTN_SIDE.code());
+        assert_eq!(frames[0][0].value(), 1);
+        assert_eq!(frames[1][0].value(), 0);
+
+        for command in PointerCommand::iter() {
+            assert!(!command.frames().is_empty());
+        }
+    }
+
+    #[test]
+    /// Test parsing the command of the action.
+    fn test_pointer_action_parse() {
+        let pointer = Arc::new(Mutex::new(VirtualPointer::default()));
+        assert!(PointerAction::new("middle-click", Arc::clone(&pointer)).is_ok());
+        assert!(PointerAction::new(" scroll-up ", Arc::clone(&pointer)).is_ok());
+        assert!(matches!(
+            PointerAction::new("foo", pointer),
+            Err(ActionError::InvalidCommand { .. })
+        ));
+    }
+}
@@ -0,0 +1,146 @@
+//! Action for performing `REST` requests.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::actions::errors::ActionError;
+use crate::actions::{render_template, Action, ActionType};
+use crate::events::{ActionEvent, GestureContext};
+use ureq::{Agent, AgentBuilder, Error as UreqError};
+
+/// Timeout for performing a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Definition of a `REST` request.
+///
+/// The `url` and `body` can contain the following variables, which are
+/// replaced with information about the gesture when the request is performed:
+/// * `{event}`: the [`ActionEvent`] that triggered the action.
+#[derive(Clone)]
+pub struct RestRequest {
+    /// HTTP method of the request.
+    pub method: String,
+    /// URL of the request.
+    pub url: String,
+    /// Headers of the request.
+    pub headers: Vec<(String, String)>,
+    /// Optional body template of the request.
+    pub body: Option<String>,
+}
+
+impl fmt::Debug for RestRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Avoid including the header values, as they might contain tokens.
+        f.debug_struct("RestRequest")
+            .field("method", &self.method)
+            .field("url", &self.url)
+            .field(
+                "headers",
+                &self.headers.iter().map(|(k, _)| k).collect::<Vec<_>>(),
+            )
+            .field("body", &self.body)
+            .finish()
+    }
+}
+
+/// Action that performs `REST` requests.
+pub struct RestAction {
+    /// Name of the request.
+    name: String,
+    /// Request to be performed in this action.
+    request: RestRequest,
+    /// Event that triggers this action.
+    action_event: ActionEvent,
+    /// HTTP agent for performing the request.
+    agent: Agent,
+}
+
+impl fmt::Debug for RestAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RestAction")
+            .field("name", &self.name)
+            .field("request", &self.request)
+            .field("action_event", &self.action_event)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RestAction {
+    /// Create a new [`RestAction`].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - name of the request.
+    /// * `request` - request to be performed in this action.
+    /// * `action_event` - event that triggers this action.
+    #[must_use]
+    pub fn new(name: String, request: RestRequest, action_event: ActionEvent) -> Self {
+        RestAction {
+            name,
+            request,
+            action_event,
+            agent: AgentBuilder::new().timeout(REQUEST_TIMEOUT).build(),
+        }
+    }
+}
+
+impl Action for RestAction {
+    fn execute_command(&mut self, _context: &GestureContext) -> Result<(), ActionError> {
+        let mut request = self.agent.request(
+            &self.request.method,
+            &render_template(&self.request.url, self.action_event),
+        );
+        for (header, value) in &self.request.headers {
+            request = request.set(header, value);
+        }
+
+        let result = match &self.request.body {
+            Some(body) => request.send_string(&render_template(body, self.action_event)),
+            None => request.call(),
+        };
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(UreqError::Status(code, response)) => Err(ActionError::ExecutionError {
+                type_: ActionType::Rest.to_string(),
+                message: format!(
+                    "request failed with status {code} ({})",
+                    response.status_text()
+                ),
+            }),
+            Err(e) => Err(ActionError::ExecutionError {
+                type_: ActionType::Rest.to_string(),
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    fn fmt_command(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:<{} ({} {})>",
+            ActionType::Rest,
+            self.name,
+            self.request.method,
+            self.request.url
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::actions::render_template;
+    use crate::events::ActionEvent;
+
+    #[test]
+    /// Test the rendering of templates.
+    fn test_rest_render_template() {
+        assert_eq!(
+            render_template(
+                r#"{"gesture": "{event}"}"#,
+                ActionEvent::ThreeFingerSwipeRight
+            ),
+            r#"{"gesture": "three-finger-swipe-right"}"#
+        );
+    }
+}
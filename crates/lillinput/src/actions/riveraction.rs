@@ -0,0 +1,291 @@
+//! Action for running `River` commands via the `river-control` protocol.
+
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::actions::errors::ActionError;
+use crate::actions::{Action, ActionType};
+use crate::events::GestureContext;
+use log::debug;
+use shlex::split;
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::wl_registry::WlRegistry;
+use wayland_client::protocol::wl_seat::WlSeat;
+use wayland_client::{delegate_noop, Connection, Dispatch, EventQueue, QueueHandle};
+
+use self::protocol::zriver_command_callback_v1::{self, ZriverCommandCallbackV1};
+use self::protocol::zriver_control_v1::ZriverControlV1;
+
+/// Bindings of the `river-control` protocol, generated from its definition.
+#[allow(
+    missing_docs,
+    clippy::missing_docs_in_private_items,
+    clippy::doc_markdown,
+    clippy::pedantic
+)]
+mod protocol {
+    use wayland_client;
+    use wayland_client::protocol::*;
+
+    /// Interfaces of the protocol.
+    pub mod __interfaces {
+        use wayland_client::backend as wayland_backend;
+        use wayland_client::protocol::__interfaces::*;
+        wayland_scanner::generate_interfaces!("protocols/river-control-unstable-v1.xml");
+    }
+    use self::__interfaces::*;
+
+    wayland_scanner::generate_client_code!("protocols/river-control-unstable-v1.xml");
+}
+
+/// Shared connection to `River`.
+pub type SharedRiverConnection = Arc<Mutex<RiverConnection>>;
+
+/// State of the event queue of the connection.
+#[derive(Default)]
+struct RiverState {
+    /// Result of the last command, once reported by the compositor.
+    result: Option<Result<String, String>>,
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for RiverState {
+    fn event(
+        _: &mut Self,
+        _: &WlRegistry,
+        _: <WlRegistry as wayland_client::Proxy>::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZriverCommandCallbackV1, ()> for RiverState {
+    fn event(
+        state: &mut Self,
+        _: &ZriverCommandCallbackV1,
+        event: zriver_command_callback_v1::Event,
+        (): &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        state.result = Some(match event {
+            zriver_command_callback_v1::Event::Success { output } => Ok(output),
+            zriver_command_callback_v1::Event::Failure { failure_message } => Err(failure_message),
+        });
+    }
+}
+
+delegate_noop!(RiverState: ignore WlSeat);
+delegate_noop!(RiverState: ZriverControlV1);
+
+/// Objects of an established connection to `River`.
+struct RiverControl {
+    /// Event queue of the connection.
+    queue: EventQueue<RiverState>,
+    /// Seat the commands are run for.
+    seat: WlSeat,
+    /// `river-control` global.
+    control: ZriverControlV1,
+}
+
+impl RiverControl {
+    /// Connect to the compositor (using the `WAYLAND_DISPLAY` environment
+    /// variable), binding the `river-control` global.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the connection could not be established, or if the
+    /// compositor does not support the protocol.
+    fn connect() -> Result<Self, String> {
+        let connection = Connection::connect_to_env().map_err(|e| e.to_string())?;
+        let (globals, queue) =
+            registry_queue_init::<RiverState>(&connection).map_err(|e| e.to_string())?;
+        let handle = queue.handle();
+        let seat: WlSeat = globals
+            .bind(&handle, 1..=1, ())
+            .map_err(|e| format!("no seat available: {e}"))?;
+        let control: ZriverControlV1 = globals
+            .bind(&handle, 1..=1, ())
+            .map_err(|e| format!("the river-control protocol is not supported: {e}"))?;
+
+        Ok(RiverControl {
+            queue,
+            seat,
+            control,
+        })
+    }
+
+    /// Run a command, waiting for its result.
+    ///
+    /// # Arguments
+    ///
+    /// * `arguments` - the command and its arguments.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command could not be sent, or if the compositor
+    /// reported a failure.
+    fn run(&mut self, arguments: &[String]) -> Result<Result<String, String>, String> {
+        for argument in arguments {
+            self.control.add_argument(argument.clone());
+        }
+        let handle = self.queue.handle();
+        self.control.run_command(&self.seat, &handle, ());
+
+        let mut state = RiverState::default();
+        while state.result.is_none() {
+            self.queue
+                .blocking_dispatch(&mut state)
+                .map_err(|e| e.to_string())?;
+        }
+        state.result.ok_or_else(String::new)
+    }
+}
+
+/// Connection to `River` for running commands.
+///
+/// The connection is established lazily on the first command, and
+/// re-established if it failed.
+#[derive(Default)]
+pub struct RiverConnection {
+    /// Objects of the connection, if connected.
+    control: Option<RiverControl>,
+}
+
+impl fmt::Debug for RiverConnection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RiverConnection")
+            .field("connected", &self.control.is_some())
+            .finish()
+    }
+}
+
+impl RiverConnection {
+    /// Run a command, returning its output.
+    ///
+    /// # Arguments
+    ///
+    /// * `arguments` - the command and its arguments.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command could not be sent, or if the compositor
+    /// reported a failure.
+    pub fn run(&mut self, arguments: &[String]) -> Result<String, String> {
+        let control = match self.control.take() {
+            Some(control) => control,
+            None => RiverControl::connect()?,
+        };
+        let control = self.control.insert(control);
+
+        match control.run(arguments) {
+            Ok(result) => result,
+            Err(e) => {
+                // Reconnect on the next command.
+                self.control = None;
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Action that runs `River` commands via the `river-control` protocol.
+///
+/// The commands are the ones accepted by `riverctl` (for example,
+/// `set-focused-tags 2` or `focus-view next`), without spawning a process for
+/// each gesture.
+#[derive(Debug)]
+pub struct RiverAction {
+    /// Original command, as specified by the user.
+    command: String,
+    /// Command split into its arguments.
+    arguments: Vec<String>,
+    /// Shared connection to `River`.
+    connection: SharedRiverConnection,
+}
+
+impl RiverAction {
+    /// Create a new [`RiverAction`].
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - command to be run in this action.
+    /// * `connection` - shared connection to `River`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command could not be parsed.
+    pub fn new(command: &str, connection: SharedRiverConnection) -> Result<Self, ActionError> {
+        let arguments =
+            split(command)
+                .filter(|x| !x.is_empty())
+                .ok_or(ActionError::InvalidCommand {
+                    type_: ActionType::River.to_string(),
+                    message: format!("unable to parse command: {command}"),
+                })?;
+
+        Ok(RiverAction {
+            command: command.trim().to_string(),
+            arguments,
+            connection,
+        })
+    }
+}
+
+impl Action for RiverAction {
+    fn execute_command(&mut self, _context: &GestureContext) -> Result<(), ActionError> {
+        let output = self
+            .connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .run(&self.arguments)
+            .map_err(|message| ActionError::ExecutionError {
+                type_: ActionType::River.to_string(),
+                message,
+            })?;
+        if !output.is_empty() {
+            debug!("Output of river command {}: {output}", self.command);
+        }
+
+        Ok(())
+    }
+
+    fn fmt_command(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:<{}>", ActionType::River, self.command)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use super::{RiverAction, RiverConnection};
+    use crate::actions::{Action, ActionError};
+
+    #[test]
+    /// Test parsing the command of the action.
+    fn test_river_action_parse() {
+        let connection = Arc::new(Mutex::new(RiverConnection::default()));
+        let action = RiverAction::new(
+            " map normal Super J 'focus-view next' ",
+            Arc::clone(&connection),
+        )
+        .unwrap();
+        assert_eq!(
+            action.arguments,
+            vec!["map", "normal", "Super", "J", "focus-view next"]
+        );
+        assert_eq!(
+            (&action as &dyn Action).to_string(),
+            "river:<map normal Super J 'focus-view next'>"
+        );
+
+        for command in ["", "  ", "focus-view 'next"] {
+            assert!(matches!(
+                RiverAction::new(command, Arc::clone(&connection)),
+                Err(ActionError::InvalidCommand { .. })
+            ));
+        }
+    }
+}
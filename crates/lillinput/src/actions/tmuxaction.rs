@@ -0,0 +1,113 @@
+//! Action for interacting with `tmux`.
+
+use std::fmt;
+use std::process::Command;
+
+use crate::actions::errors::ActionError;
+use crate::actions::{Action, ActionType};
+use crate::events::GestureContext;
+use shlex::split;
+
+/// Action that sends commands to a `tmux` server.
+///
+/// The command is sent through the `tmux` client, which connects to the
+/// server control socket: either the default socket for the current user, or
+/// an explicit socket path.
+#[derive(Debug)]
+pub struct TmuxAction {
+    /// `tmux` command to be executed in this action.
+    command: String,
+    /// Path to the `tmux` server socket.
+    socket: Option<String>,
+}
+
+impl TmuxAction {
+    /// Create a new [`TmuxAction`].
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - `tmux` command to be executed in this action.
+    /// * `socket` - optional path to the `tmux` server socket.
+    #[must_use]
+    pub fn new(command: String, socket: Option<String>) -> TmuxAction {
+        TmuxAction { command, socket }
+    }
+
+    /// Build the [`Command`] to be executed in this action.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command string could not be parsed.
+    fn build_command(&self) -> Result<Command, ActionError> {
+        let split_commands = split(&self.command).ok_or(ActionError::ExecutionError {
+            type_: ActionType::Tmux.to_string(),
+            message: format!("Unable to parse command: {}", self.command),
+        })?;
+        let mut command = Command::new("tmux");
+        if let Some(socket) = &self.socket {
+            command.arg("-S").arg(socket);
+        }
+        command.args(split_commands);
+
+        Ok(command)
+    }
+}
+
+impl Action for TmuxAction {
+    fn execute_command(&mut self, _context: &GestureContext) -> Result<(), ActionError> {
+        let output = self
+            .build_command()?
+            .output()
+            .map_err(|e| ActionError::ExecutionError {
+                type_: ActionType::Tmux.to_string(),
+                message: e.to_string(),
+            })?;
+
+        // Check the exit status, as `tmux` reports its errors via stderr.
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(ActionError::ExecutionError {
+                type_: ActionType::Tmux.to_string(),
+                message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            })
+        }
+    }
+
+    fn fmt_command(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:<{}>", ActionType::Tmux, self.command)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TmuxAction;
+
+    #[test]
+    /// Test the building of `tmux` commands.
+    fn test_tmux_build_command() {
+        let action = TmuxAction::new(
+            "send-keys -t main 'echo foo' Enter".into(),
+            Some("/tmp/tmux-socket".into()),
+        );
+        let command = action.build_command().unwrap();
+
+        assert_eq!(command.get_program(), "tmux");
+        assert_eq!(
+            command.get_args().collect::<Vec<_>>(),
+            vec![
+                "-S",
+                "/tmp/tmux-socket",
+                "send-keys",
+                "-t",
+                "main",
+                "echo foo",
+                "Enter"
+            ]
+        );
+
+        // Assert that malformed commands are rejected.
+        let action = TmuxAction::new("send-keys 'foo".into(), None);
+        assert!(action.build_command().is_err());
+    }
+}
@@ -0,0 +1,345 @@
+//! Action for re-emitting gestures on a virtual touchpad via `uinput`.
+
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use crate::actions::errors::ActionError;
+use crate::actions::{Action, ActionType};
+use crate::events::{ActionEvent, GestureContext, VIRTUAL_TOUCHPAD_NAME};
+use evdev::uinput::{VirtualDevice, VirtualDeviceBuilder};
+use evdev::{
+    AbsInfo, AbsoluteAxisType, AttributeSet, EventType, InputEvent, Key, PropType, UinputAbsSetup,
+};
+use strum::IntoEnumIterator;
+
+/// Resolution of the virtual touchpad, in units per millimeter.
+const RESOLUTION: i32 = 40;
+/// Size of the virtual touchpad, in millimeters.
+const SIZE_MM: (i32, i32) = (200, 150);
+/// Distance between the fingers of an emitted swipe, in millimeters.
+const FINGER_SPACING_MM: f64 = 10.0;
+/// Length of an emitted swipe, in millimeters.
+const SWIPE_MM: f64 = 30.0;
+/// Length of an emitted long swipe, in millimeters.
+const LONG_SWIPE_MM: f64 = 60.0;
+/// Number of movement frames of an emitted swipe.
+const SWIPE_FRAMES: i32 = 10;
+/// Interval between the frames of an emitted swipe.
+const FRAME_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Shared virtual touchpad.
+pub type SharedVirtualTouchpad = Arc<Mutex<VirtualTouchpad>>;
+
+/// Virtual touchpad for emitting swipe gestures.
+///
+/// The `uinput` device is created lazily on the first swipe, and is named
+/// [`VIRTUAL_TOUCHPAD_NAME`], so the gestures emitted are ignored by the
+/// [`DefaultProcessor`] while being handled by the rest of the system (such
+/// as the compositor).
+///
+/// [`DefaultProcessor`]: crate::events::DefaultProcessor
+#[derive(Default)]
+pub struct VirtualTouchpad {
+    /// `uinput` device, if created.
+    device: Option<VirtualDevice>,
+    /// Tracking id of the latest touch.
+    tracking_id: i32,
+}
+
+impl fmt::Debug for VirtualTouchpad {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("VirtualTouchpad")
+            .field("created", &self.device.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl VirtualTouchpad {
+    /// Create the `uinput` device of the touchpad.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the device could not be created (for example, if
+    /// `/dev/uinput` is not writable).
+    fn create_device() -> io::Result<VirtualDevice> {
+        let mut keys = AttributeSet::<Key>::new();
+        for key in [
+            Key::BTN_LEFT,
+            Key::BTN_TOUCH,
+            Key::BTN_TOOL_FINGER,
+            Key::BTN_TOOL_DOUBLETAP,
+            Key::BTN_TOOL_TRIPLETAP,
+            Key::BTN_TOOL_QUADTAP,
+        ] {
+            keys.insert(key);
+        }
+        let mut properties = AttributeSet::<PropType>::new();
+        properties.insert(PropType::POINTER);
+        properties.insert(PropType::BUTTONPAD);
+
+        let axis = |axis, maximum| {
+            UinputAbsSetup::new(axis, AbsInfo::new(0, 0, maximum, 0, 0, RESOLUTION))
+        };
+        let (width, height) = (SIZE_MM.0 * RESOLUTION, SIZE_MM.1 * RESOLUTION);
+
+        VirtualDeviceBuilder::new()?
+            .name(VIRTUAL_TOUCHPAD_NAME)
+            .with_keys(&keys)?
+            .with_properties(&properties)?
+            .with_absolute_axis(&axis(AbsoluteAxisType::ABS_X, width))?
+            .with_absolute_axis(&axis(AbsoluteAxisType::ABS_Y, height))?
+            .with_absolute_axis(&UinputAbsSetup::new(
+                AbsoluteAxisType::ABS_MT_SLOT,
+                AbsInfo::new(0, 0, 4, 0, 0, 0),
+            ))?
+            .with_absolute_axis(&UinputAbsSetup::new(
+                AbsoluteAxisType::ABS_MT_TRACKING_ID,
+                AbsInfo::new(0, 0, i32::from(u16::MAX), 0, 0, 0),
+            ))?
+            .with_absolute_axis(&axis(AbsoluteAxisType::ABS_MT_POSITION_X, width))?
+            .with_absolute_axis(&axis(AbsoluteAxisType::ABS_MT_POSITION_Y, height))?
+            .build()
+    }
+
+    /// Emit a swipe gesture, starting at the center of the touchpad.
+    ///
+    /// # Arguments
+    ///
+    /// * `finger_count` - number of fingers of the swipe (`2` to `4`).
+    /// * `dx` - displacement in the `x` axis, in millimeters.
+    /// * `dy` - displacement in the `y` axis, in millimeters.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the finger count is not supported, or if the events
+    /// could not be emitted.
+    pub fn swipe(&mut self, finger_count: i32, dx: f64, dy: f64) -> io::Result<()> {
+        let tool = match finger_count {
+            2 => Key::BTN_TOOL_DOUBLETAP,
+            3 => Key::BTN_TOOL_TRIPLETAP,
+            4 => Key::BTN_TOOL_QUADTAP,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unsupported finger count ({finger_count})"),
+                ))
+            }
+        };
+
+        let device = match self.device.take() {
+            Some(device) => device,
+            None => Self::create_device()?,
+        };
+        let first_id = self.tracking_id;
+        self.tracking_id = (self.tracking_id + finger_count) % i32::from(u16::MAX);
+        let device = self.device.insert(device);
+
+        let result = emit_swipe(device, tool, first_id, finger_count, dx, dy);
+        if result.is_err() {
+            // Recreate the device on the next swipe.
+            self.device = None;
+        }
+        result
+    }
+}
+
+/// Emit the events of a swipe gesture on a `uinput` device.
+///
+/// # Arguments
+///
+/// * `device` - the `uinput` device.
+/// * `tool` - the key of the tool for the number of fingers.
+/// * `first_id` - the tracking id of the first finger.
+/// * `finger_count` - number of fingers of the swipe.
+/// * `dx` - displacement in the `x` axis, in millimeters.
+/// * `dy` - displacement in the `y` axis, in millimeters.
+///
+/// # Errors
+///
+/// Returns `Err` if the events could not be emitted.
+fn emit_swipe(
+    device: &mut VirtualDevice,
+    tool: Key,
+    first_id: i32,
+    finger_count: i32,
+    dx: f64,
+    dy: f64,
+) -> io::Result<()> {
+    let abs =
+        |axis: AbsoluteAxisType, value: i32| InputEvent::new(EventType::ABSOLUTE, axis.0, value);
+    let key = |key: Key, value: i32| InputEvent::new(EventType::KEY, key.code(), value);
+
+    // Return the events for placing the fingers at a fraction of the swipe.
+    #[allow(clippy::cast_possible_truncation)]
+    let frame = |progress: f64, touch_down: bool| {
+        let mut events = vec![];
+        for finger in 0..finger_count {
+            let offset =
+                (f64::from(finger) - f64::from(finger_count - 1) / 2.0) * FINGER_SPACING_MM;
+            let x = (f64::from(SIZE_MM.0) / 2.0 + offset + dx * progress) * f64::from(RESOLUTION);
+            let y = (f64::from(SIZE_MM.1) / 2.0 + dy * progress) * f64::from(RESOLUTION);
+
+            events.push(abs(AbsoluteAxisType::ABS_MT_SLOT, finger));
+            if touch_down {
+                events.push(abs(AbsoluteAxisType::ABS_MT_TRACKING_ID, first_id + finger));
+            }
+            events.push(abs(AbsoluteAxisType::ABS_MT_POSITION_X, x as i32));
+            events.push(abs(AbsoluteAxisType::ABS_MT_POSITION_Y, y as i32));
+            if finger == 0 {
+                events.push(abs(AbsoluteAxisType::ABS_X, x as i32));
+                events.push(abs(AbsoluteAxisType::ABS_Y, y as i32));
+            }
+        }
+        if touch_down {
+            events.push(key(Key::BTN_TOUCH, 1));
+            events.push(key(tool, 1));
+        }
+        events
+    };
+
+    device.emit(&frame(0.0, true))?;
+    for step in 1..=SWIPE_FRAMES {
+        thread::sleep(FRAME_INTERVAL);
+        device.emit(&frame(f64::from(step) / f64::from(SWIPE_FRAMES), false))?;
+    }
+
+    thread::sleep(FRAME_INTERVAL);
+    let mut events: Vec<InputEvent> = (0..finger_count)
+        .flat_map(|finger| {
+            [
+                abs(AbsoluteAxisType::ABS_MT_SLOT, finger),
+                abs(AbsoluteAxisType::ABS_MT_TRACKING_ID, -1),
+            ]
+        })
+        .collect();
+    events.push(key(Key::BTN_TOUCH, 0));
+    events.push(key(tool, 0));
+    device.emit(&events)
+}
+
+/// Return the number of fingers and the displacement (in millimeters) of the
+/// swipe corresponding to an [`ActionEvent`].
+///
+/// # Arguments
+///
+/// * `action_event` - the event.
+fn swipe_geometry(action_event: ActionEvent) -> (i32, f64, f64) {
+    // The regular events are ordered by number of fingers, and then by
+    // direction, starting from the left and clock-wise.
+    let index = ActionEvent::iter()
+        .position(|x| x == action_event.short())
+        .unwrap_or_default();
+    let finger_count = if index < 8 { 3 } else { 4 };
+    #[allow(clippy::cast_precision_loss)]
+    let angle = ((index % 8) as f64 * 45.0).to_radians();
+    let length = if action_event.is_long() {
+        LONG_SWIPE_MM
+    } else {
+        SWIPE_MM
+    };
+
+    (finger_count, -length * angle.cos(), -length * angle.sin())
+}
+
+/// Action that emits a swipe gesture on a virtual touchpad.
+///
+/// Allows passing gestures through to the rest of the system (such as the
+/// compositor), or remapping them to different gestures.
+#[derive(Debug)]
+pub struct UinputAction {
+    /// Event whose gesture is emitted.
+    target: ActionEvent,
+    /// Shared virtual touchpad.
+    touchpad: SharedVirtualTouchpad,
+}
+
+impl UinputAction {
+    /// Create a new [`UinputAction`].
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - the event whose gesture is emitted (for example,
+    ///   `four-finger-swipe-up`).
+    /// * `touchpad` - shared virtual touchpad.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command is not a known touchpad swipe event.
+    pub fn new(command: &str, touchpad: SharedVirtualTouchpad) -> Result<Self, ActionError> {
+        let target =
+            ActionEvent::from_str(command.trim()).map_err(|_| ActionError::InvalidCommand {
+                type_: ActionType::Uinput.to_string(),
+                message: format!("unknown event: {command}"),
+            })?;
+        if !target.is_swipe() {
+            return Err(ActionError::InvalidCommand {
+                type_: ActionType::Uinput.to_string(),
+                message: format!("only swipes can be emitted: {command}"),
+            });
+        }
+
+        Ok(UinputAction { target, touchpad })
+    }
+}
+
+impl Action for UinputAction {
+    fn execute_command(&mut self, _context: &GestureContext) -> Result<(), ActionError> {
+        let (finger_count, dx, dy) = swipe_geometry(self.target);
+        self.touchpad
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .swipe(finger_count, dx, dy)
+            .map_err(|e| ActionError::ExecutionError {
+                type_: ActionType::Uinput.to_string(),
+                message: e.to_string(),
+            })
+    }
+
+    fn fmt_command(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:<{}>", ActionType::Uinput, self.target)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use super::{swipe_geometry, UinputAction, VirtualTouchpad};
+    use crate::actions::ActionError;
+    use crate::events::ActionEvent;
+
+    #[test]
+    /// Test the geometry of the swipes emitted for each event.
+    fn test_swipe_geometry() {
+        let (finger_count, dx, dy) = swipe_geometry(ActionEvent::ThreeFingerSwipeLeft);
+        assert_eq!(finger_count, 3);
+        assert!(dx < 0.0 && dy.abs() < 1e-9);
+
+        let (finger_count, dx, dy) = swipe_geometry(ActionEvent::FourFingerSwipeUp);
+        assert_eq!(finger_count, 4);
+        assert!(dx.abs() < 1e-9 && dy < 0.0);
+
+        let (_, dx, dy) = swipe_geometry(ActionEvent::FourFingerSwipeRightDownLong);
+        assert!(dx > 0.0 && dy > 0.0);
+        assert!((dx.hypot(dy) - 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    /// Test parsing the event of the action.
+    fn test_uinput_action_parse() {
+        let touchpad = Arc::new(Mutex::new(VirtualTouchpad::default()));
+        assert!(UinputAction::new("four-finger-swipe-up", Arc::clone(&touchpad)).is_ok());
+        assert!(matches!(
+            UinputAction::new("two-finger-rotate-clockwise", Arc::clone(&touchpad)),
+            Err(ActionError::InvalidCommand { .. })
+        ));
+        assert!(matches!(
+            UinputAction::new("foo", touchpad),
+            Err(ActionError::InvalidCommand { .. })
+        ));
+    }
+}
@@ -0,0 +1,670 @@
+//! Action for adjusting the audio volume via the `PulseAudio` native protocol.
+//!
+//! The protocol is spoken directly over the socket of the server, which is
+//! also provided by `PipeWire` (via `pipewire-pulse`), keeping a single
+//! connection open between actions.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use crate::actions::errors::ActionError;
+use crate::actions::{Action, ActionType};
+use crate::events::GestureContext;
+
+/// Version of the protocol announced to the server.
+const PROTOCOL_VERSION: u32 = 32;
+/// Size of the authentication cookie.
+const COOKIE_SIZE: usize = 256;
+/// Volume corresponding to 100%.
+const VOLUME_NORM: u32 = 0x10000;
+/// Name of the sink whose volume is adjusted.
+const DEFAULT_SINK: &str = "@DEFAULT_SINK@";
+/// Index for referring to objects by name.
+const INVALID_INDEX: u32 = u32::MAX;
+/// Channel of the control packets.
+const CONTROL_CHANNEL: u32 = u32::MAX;
+/// Maximum time to wait for the replies of the server.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(1);
+/// Default step for raising and lowering the volume, in percent.
+const DEFAULT_STEP: u32 = 5;
+
+/// Commands of the protocol.
+mod commands {
+    /// Reply to a request that failed.
+    pub const ERROR: u32 = 0;
+    /// Reply to a request that succeeded.
+    pub const REPLY: u32 = 2;
+    /// Authenticate with the server.
+    pub const AUTH: u32 = 8;
+    /// Set the properties of the client.
+    pub const SET_CLIENT_NAME: u32 = 9;
+    /// Retrieve the information of a sink.
+    pub const GET_SINK_INFO: u32 = 21;
+    /// Set the volume of a sink.
+    pub const SET_SINK_VOLUME: u32 = 36;
+    /// Set the mute state of a sink.
+    pub const SET_SINK_MUTE: u32 = 39;
+}
+
+/// Tags of the values of the protocol.
+mod tags {
+    /// Null-terminated string.
+    pub const STRING: u8 = b't';
+    /// Null string.
+    pub const STRING_NULL: u8 = b'N';
+    /// Unsigned 32-bit integer.
+    pub const U32: u8 = b'L';
+    /// Sample format, channels and rate.
+    pub const SAMPLE_SPEC: u8 = b'a';
+    /// Sized array of bytes.
+    pub const ARBITRARY: u8 = b'x';
+    /// True boolean.
+    pub const BOOLEAN_TRUE: u8 = b'1';
+    /// False boolean.
+    pub const BOOLEAN_FALSE: u8 = b'0';
+    /// Positions of the channels.
+    pub const CHANNEL_MAP: u8 = b'm';
+    /// Volumes of the channels.
+    pub const CVOLUME: u8 = b'v';
+    /// List of properties.
+    pub const PROPLIST: u8 = b'P';
+}
+
+/// Shared connection to the audio server.
+pub type SharedVolumeConnection = Arc<Mutex<VolumeConnection>>;
+
+/// Command for adjusting the volume of the default sink.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VolumeCommand {
+    /// Raise the volume by a step, in percent, up to 100%.
+    Raise(u32),
+    /// Lower the volume by a step, in percent.
+    Lower(u32),
+    /// Toggle muting the sink.
+    Mute,
+}
+
+impl VolumeCommand {
+    /// Parse a command, either `raise [step]`, `lower [step]` or `mute`. The
+    /// step is a percentage, 5 by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - the command to be parsed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` with the reason if the command is not valid.
+    pub fn parse(command: &str) -> Result<Self, String> {
+        let mut words = command.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let step = match words.next().map(str::parse::<u32>) {
+            None => DEFAULT_STEP,
+            Some(Ok(step)) if (1..=100).contains(&step) && name != "mute" => step,
+            Some(_) => return Err(format!("invalid argument for {name}")),
+        };
+        if words.next().is_some() {
+            return Err(format!("too many arguments for {name}"));
+        }
+
+        match name {
+            "raise" => Ok(VolumeCommand::Raise(step)),
+            "lower" => Ok(VolumeCommand::Lower(step)),
+            "mute" => Ok(VolumeCommand::Mute),
+            _ => Err(format!(
+                "unknown command {command} (expected \"raise [step]\", \"lower [step]\" or \"mute\")"
+            )),
+        }
+    }
+
+    /// Return the volume of a channel after applying the command.
+    ///
+    /// # Arguments
+    ///
+    /// * `volume` - the current volume of the channel.
+    #[must_use]
+    pub fn apply(self, volume: u32) -> u32 {
+        let delta = |step: u32| VOLUME_NORM / 100 * step;
+        match self {
+            VolumeCommand::Raise(step) => volume.saturating_add(delta(step)).min(VOLUME_NORM),
+            VolumeCommand::Lower(step) => volume.saturating_sub(delta(step)),
+            VolumeCommand::Mute => volume,
+        }
+    }
+}
+
+/// Writer of the values of a packet.
+#[derive(Default)]
+struct TagWriter(Vec<u8>);
+
+impl TagWriter {
+    /// Append an unsigned 32-bit integer.
+    fn u32(mut self, value: u32) -> Self {
+        self.0.push(tags::U32);
+        self.0.extend(value.to_be_bytes());
+        self
+    }
+
+    /// Append a string.
+    fn string(mut self, value: &str) -> Self {
+        self.0.push(tags::STRING);
+        self.0.extend(value.as_bytes());
+        self.0.push(0);
+        self
+    }
+
+    /// Append an array of bytes.
+    fn arbitrary(mut self, value: &[u8]) -> Self {
+        self.0.push(tags::ARBITRARY);
+        self.0
+            .extend(u32::try_from(value.len()).unwrap_or_default().to_be_bytes());
+        self.0.extend(value);
+        self
+    }
+
+    /// Append a boolean.
+    fn boolean(mut self, value: bool) -> Self {
+        self.0.push(if value {
+            tags::BOOLEAN_TRUE
+        } else {
+            tags::BOOLEAN_FALSE
+        });
+        self
+    }
+
+    /// Append the volumes of the channels.
+    fn cvolume(mut self, volumes: &[u32]) -> Self {
+        self.0.push(tags::CVOLUME);
+        self.0.push(u8::try_from(volumes.len()).unwrap_or_default());
+        for volume in volumes {
+            self.0.extend(volume.to_be_bytes());
+        }
+        self
+    }
+
+    /// Append a list of properties.
+    fn proplist(mut self, properties: &[(&str, &str)]) -> Self {
+        self.0.push(tags::PROPLIST);
+        for (key, value) in properties {
+            // Values are stored with their terminating null byte.
+            let mut value = value.as_bytes().to_vec();
+            value.push(0);
+            self = self
+                .string(key)
+                .u32(u32::try_from(value.len()).unwrap_or_default())
+                .arbitrary(&value);
+        }
+        self.0.push(tags::STRING_NULL);
+        self
+    }
+}
+
+/// Reader of the values of a packet.
+struct TagReader<'a>(&'a [u8]);
+
+impl<'a> TagReader<'a> {
+    /// Consume a number of bytes.
+    fn take(&mut self, count: usize) -> io::Result<&'a [u8]> {
+        if self.0.len() < count {
+            return Err(invalid_data("truncated packet"));
+        }
+        let (taken, rest) = self.0.split_at(count);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    /// Consume a tag, checking that it is the expected one.
+    fn expect_tag(&mut self, expected: u8) -> io::Result<()> {
+        match self.take(1)?[0] {
+            x if x == expected => Ok(()),
+            x => Err(invalid_data(&format!(
+                "unexpected tag {}, expected {}",
+                char::from(x),
+                char::from(expected)
+            ))),
+        }
+    }
+
+    /// Consume an unsigned 32-bit integer, without tag.
+    fn raw_u32(&mut self) -> io::Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Consume an unsigned 32-bit integer.
+    fn u32(&mut self) -> io::Result<u32> {
+        self.expect_tag(tags::U32)?;
+        self.raw_u32()
+    }
+
+    /// Consume a string, which can be null.
+    fn string(&mut self) -> io::Result<Option<String>> {
+        match self.take(1)?[0] {
+            tags::STRING_NULL => Ok(None),
+            tags::STRING => {
+                let length = self
+                    .0
+                    .iter()
+                    .position(|x| *x == 0)
+                    .ok_or_else(|| invalid_data("unterminated string"))?;
+                let bytes = self.take(length + 1)?;
+                Ok(Some(String::from_utf8_lossy(&bytes[..length]).to_string()))
+            }
+            _ => Err(invalid_data("unexpected tag, expected a string")),
+        }
+    }
+
+    /// Consume a boolean.
+    fn boolean(&mut self) -> io::Result<bool> {
+        match self.take(1)?[0] {
+            tags::BOOLEAN_TRUE => Ok(true),
+            tags::BOOLEAN_FALSE => Ok(false),
+            _ => Err(invalid_data("unexpected tag, expected a boolean")),
+        }
+    }
+
+    /// Consume a sample specification.
+    fn skip_sample_spec(&mut self) -> io::Result<()> {
+        self.expect_tag(tags::SAMPLE_SPEC)?;
+        self.take(6).map(|_| ())
+    }
+
+    /// Consume a channel map.
+    fn skip_channel_map(&mut self) -> io::Result<()> {
+        self.expect_tag(tags::CHANNEL_MAP)?;
+        let channels = self.take(1)?[0];
+        self.take(usize::from(channels)).map(|_| ())
+    }
+
+    /// Consume the volumes of the channels.
+    fn cvolume(&mut self) -> io::Result<Vec<u32>> {
+        self.expect_tag(tags::CVOLUME)?;
+        let channels = self.take(1)?[0];
+        (0..channels).map(|_| self.raw_u32()).collect()
+    }
+}
+
+/// Return an error for malformed data received from the server.
+///
+/// # Arguments
+///
+/// * `message` - description of the error.
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Return the path of the socket of the server, from the `PULSE_SERVER` or
+/// the `XDG_RUNTIME_DIR` environment variables.
+#[must_use]
+pub fn default_socket_path() -> Option<PathBuf> {
+    if let Some(server) = env::var_os("PULSE_SERVER") {
+        let server = server.to_string_lossy();
+        return server.strip_prefix("unix:").map(PathBuf::from);
+    }
+    env::var_os("XDG_RUNTIME_DIR").map(|x| PathBuf::from(x).join("pulse/native"))
+}
+
+/// Return the authentication cookie, from the `PULSE_COOKIE` environment
+/// variable or the default location, or a blank cookie if not available
+/// (which is accepted by `PipeWire`).
+fn read_cookie() -> Vec<u8> {
+    env::var_os("PULSE_COOKIE")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|x| PathBuf::from(x).join(".config/pulse/cookie")))
+        .and_then(|path| fs::read(path).ok())
+        .filter(|cookie| cookie.len() == COOKIE_SIZE)
+        .unwrap_or_else(|| vec![0; COOKIE_SIZE])
+}
+
+/// Connection to a `PulseAudio` (or `PipeWire`) server for adjusting the
+/// volume of the default sink.
+///
+/// The connection is established lazily on the first command, and
+/// re-established if it failed.
+pub struct VolumeConnection {
+    /// Path of the socket of the server.
+    socket_path: Option<PathBuf>,
+    /// Socket connected and authenticated with the server, if any.
+    stream: Option<UnixStream>,
+    /// Tag of the last request sent to the server.
+    tag: u32,
+}
+
+impl fmt::Debug for VolumeConnection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("VolumeConnection")
+            .field("socket_path", &self.socket_path)
+            .field("connected", &self.stream.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for VolumeConnection {
+    fn default() -> Self {
+        VolumeConnection::new(default_socket_path())
+    }
+}
+
+impl VolumeConnection {
+    /// Create a new [`VolumeConnection`].
+    ///
+    /// # Arguments
+    ///
+    /// * `socket_path` - path of the socket of the server.
+    #[must_use]
+    pub fn new(socket_path: Option<PathBuf>) -> Self {
+        VolumeConnection {
+            socket_path,
+            stream: None,
+            tag: 0,
+        }
+    }
+
+    /// Send a request to the server, returning the values of its reply.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - the socket connected to the server.
+    /// * `command` - the command of the request.
+    /// * `arguments` - the values of the arguments of the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the request failed or the server replied with an
+    /// error.
+    fn request(
+        &mut self,
+        stream: &mut UnixStream,
+        command: u32,
+        arguments: TagWriter,
+    ) -> io::Result<Vec<u8>> {
+        self.tag = self.tag.wrapping_add(1);
+        let mut payload = TagWriter::default().u32(command).u32(self.tag).0;
+        payload.extend(arguments.0);
+
+        let mut packet = Vec::with_capacity(20 + payload.len());
+        let length = u32::try_from(payload.len()).map_err(|_| invalid_data("packet too large"))?;
+        for value in [length, CONTROL_CHANNEL, 0, 0, 0] {
+            packet.extend(value.to_be_bytes());
+        }
+        packet.extend(payload);
+        stream.write_all(&packet)?;
+
+        // Skip the packets that are not the reply to the request.
+        loop {
+            let mut descriptor = [0; 20];
+            stream.read_exact(&mut descriptor)?;
+            let mut reader = TagReader(&descriptor);
+            let length = reader.raw_u32()?;
+            let channel = reader.raw_u32()?;
+            let mut payload = vec![0; usize::try_from(length).unwrap_or_default()];
+            stream.read_exact(&mut payload)?;
+            if channel != CONTROL_CHANNEL {
+                continue;
+            }
+
+            let mut reader = TagReader(&payload);
+            let reply_command = reader.u32()?;
+            if reader.u32()? != self.tag {
+                continue;
+            }
+            return match reply_command {
+                commands::REPLY => Ok(reader.0.to_vec()),
+                commands::ERROR => Err(io::Error::other(format!(
+                    "server error {}",
+                    reader.u32().unwrap_or_default()
+                ))),
+                _ => Err(invalid_data("unexpected reply")),
+            };
+        }
+    }
+
+    /// Connect and authenticate with the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the connection could not be established.
+    fn connect(&mut self) -> io::Result<UnixStream> {
+        let socket_path = self.socket_path.as_ref().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "no audio server socket found")
+        })?;
+        let mut stream = UnixStream::connect(socket_path)?;
+        stream.set_read_timeout(Some(REPLY_TIMEOUT))?;
+
+        let auth = TagWriter::default()
+            .u32(PROTOCOL_VERSION)
+            .arbitrary(&read_cookie());
+        self.request(&mut stream, commands::AUTH, auth)?;
+        let client_name =
+            TagWriter::default().proplist(&[("application.name", env!("CARGO_PKG_NAME"))]);
+        self.request(&mut stream, commands::SET_CLIENT_NAME, client_name)?;
+
+        Ok(stream)
+    }
+
+    /// Apply a command to the default sink, with the connection to the
+    /// server.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - the socket connected to the server.
+    /// * `command` - the command.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command could not be applied.
+    fn apply(&mut self, stream: &mut UnixStream, command: VolumeCommand) -> io::Result<()> {
+        // Retrieve the volume and mute state of the sink.
+        let sink = TagWriter::default().u32(INVALID_INDEX).string(DEFAULT_SINK);
+        let reply = self.request(stream, commands::GET_SINK_INFO, sink)?;
+        let mut reader = TagReader(&reply);
+        reader.u32()?;
+        reader.string()?;
+        reader.string()?;
+        reader.skip_sample_spec()?;
+        reader.skip_channel_map()?;
+        reader.u32()?;
+        let volumes = reader.cvolume()?;
+        let muted = reader.boolean()?;
+
+        let sink = TagWriter::default().u32(INVALID_INDEX).string(DEFAULT_SINK);
+        if command == VolumeCommand::Mute {
+            self.request(stream, commands::SET_SINK_MUTE, sink.boolean(!muted))?;
+        } else {
+            let volumes: Vec<u32> = volumes.iter().map(|x| command.apply(*x)).collect();
+            self.request(stream, commands::SET_SINK_VOLUME, sink.cvolume(&volumes))?;
+        }
+
+        Ok(())
+    }
+
+    /// Apply a command to the default sink.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - the command.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command could not be applied.
+    pub fn execute(&mut self, command: VolumeCommand) -> io::Result<()> {
+        let mut stream = match self.stream.take() {
+            Some(stream) => stream,
+            None => self.connect()?,
+        };
+
+        let result = self.apply(&mut stream, command);
+        // Reconnect on the next command if the command failed.
+        if result.is_ok() {
+            self.stream = Some(stream);
+        }
+        result
+    }
+}
+
+/// Action that adjusts the volume of the default audio sink.
+///
+/// The requests are sent over a connection kept open between actions,
+/// allowing continuous gestures to adjust the volume smoothly without
+/// spawning processes such as `pactl`.
+#[derive(Debug)]
+pub struct VolumeAction {
+    /// Original command, as specified by the user.
+    command: String,
+    /// Parsed command to be applied in this action.
+    parsed: VolumeCommand,
+    /// Shared connection to the audio server.
+    connection: SharedVolumeConnection,
+}
+
+impl VolumeAction {
+    /// Create a new [`VolumeAction`].
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - command to be applied in this action.
+    /// * `connection` - shared connection to the audio server.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command is not valid.
+    pub fn new(command: &str, connection: SharedVolumeConnection) -> Result<Self, ActionError> {
+        let parsed =
+            VolumeCommand::parse(command).map_err(|message| ActionError::InvalidCommand {
+                type_: ActionType::Volume.to_string(),
+                message,
+            })?;
+
+        Ok(VolumeAction {
+            command: command.trim().to_string(),
+            parsed,
+            connection,
+        })
+    }
+}
+
+impl Action for VolumeAction {
+    fn execute_command(&mut self, _context: &GestureContext) -> Result<(), ActionError> {
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .execute(self.parsed)
+            .map_err(|e| ActionError::ExecutionError {
+                type_: ActionType::Volume.to_string(),
+                message: e.to_string(),
+            })
+    }
+
+    fn fmt_command(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:<{}>", ActionType::Volume, self.command)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Read, Write};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::thread;
+
+    use super::{
+        commands, TagReader, TagWriter, VolumeCommand, VolumeConnection, CONTROL_CHANNEL,
+        VOLUME_NORM,
+    };
+
+    /// Read a request from a client, returning its command, tag and values.
+    fn read_request(stream: &mut UnixStream) -> (u32, u32, Vec<u8>) {
+        let mut descriptor = [0; 20];
+        stream.read_exact(&mut descriptor).unwrap();
+        let length = TagReader(&descriptor).raw_u32().unwrap();
+        let mut payload = vec![0; usize::try_from(length).unwrap()];
+        stream.read_exact(&mut payload).unwrap();
+
+        let mut reader = TagReader(&payload);
+        let command = reader.u32().unwrap();
+        let tag = reader.u32().unwrap();
+        (command, tag, reader.0.to_vec())
+    }
+
+    /// Send a reply to a client.
+    fn write_reply(stream: &mut UnixStream, tag: u32, values: TagWriter) {
+        let mut payload = TagWriter::default().u32(commands::REPLY).u32(tag).0;
+        payload.extend(values.0);
+        let mut packet = vec![];
+        for value in [
+            u32::try_from(payload.len()).unwrap(),
+            CONTROL_CHANNEL,
+            0,
+            0,
+            0,
+        ] {
+            packet.extend(value.to_be_bytes());
+        }
+        packet.extend(payload);
+        stream.write_all(&packet).unwrap();
+    }
+
+    #[test]
+    /// Test parsing the commands and applying them to a volume.
+    fn test_volume_command() {
+        assert_eq!(VolumeCommand::parse("raise"), Ok(VolumeCommand::Raise(5)));
+        assert_eq!(VolumeCommand::parse("lower 2"), Ok(VolumeCommand::Lower(2)));
+        assert_eq!(VolumeCommand::parse(" mute "), Ok(VolumeCommand::Mute));
+        assert!(VolumeCommand::parse("raise 0").is_err());
+        assert!(VolumeCommand::parse("raise 5 5").is_err());
+        assert!(VolumeCommand::parse("mute 5").is_err());
+        assert!(VolumeCommand::parse("louder").is_err());
+
+        assert_eq!(VolumeCommand::Raise(5).apply(VOLUME_NORM), VOLUME_NORM);
+        assert_eq!(VolumeCommand::Raise(10).apply(0), VOLUME_NORM / 100 * 10);
+        assert_eq!(VolumeCommand::Lower(10).apply(VOLUME_NORM / 100 * 5), 0);
+    }
+
+    #[test]
+    /// Test the requests sent to the server for raising the volume.
+    fn test_volume_connection() {
+        let directory = tempfile::tempdir().unwrap();
+        let socket_path = directory.path().join("native");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let (command, tag, _) = read_request(&mut stream);
+            assert_eq!(command, commands::AUTH);
+            write_reply(&mut stream, tag, TagWriter::default().u32(32));
+            let (command, tag, _) = read_request(&mut stream);
+            assert_eq!(command, commands::SET_CLIENT_NAME);
+            write_reply(&mut stream, tag, TagWriter::default().u32(1));
+
+            // Reply with a stereo sink at 50% volume.
+            let (command, tag, _) = read_request(&mut stream);
+            assert_eq!(command, commands::GET_SINK_INFO);
+            let mut sink = TagWriter::default().u32(0).string("sink").string("Sink");
+            sink.0.extend([b'a', 3, 2, 0, 0, 0xac, 0x44, b'm', 2, 1, 2]);
+            let sink = sink
+                .u32(0)
+                .cvolume(&[VOLUME_NORM / 2, VOLUME_NORM / 2])
+                .boolean(false);
+            write_reply(&mut stream, tag, sink);
+
+            let (command, tag, values) = read_request(&mut stream);
+            assert_eq!(command, commands::SET_SINK_VOLUME);
+            let mut reader = TagReader(&values);
+            reader.u32().unwrap();
+            reader.string().unwrap();
+            let volumes = reader.cvolume().unwrap();
+            write_reply(&mut stream, tag, TagWriter::default());
+            volumes
+        });
+
+        let mut connection = VolumeConnection::new(Some(socket_path));
+        connection.execute(VolumeCommand::Raise(10)).unwrap();
+        let expected = VOLUME_NORM / 2 + VOLUME_NORM / 100 * 10;
+        assert_eq!(server.join().unwrap(), vec![expected, expected]);
+    }
+}
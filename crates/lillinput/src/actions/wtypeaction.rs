@@ -0,0 +1,448 @@
+//! Action for injecting key presses on Wayland via the `virtual-keyboard`
+//! protocol.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Write};
+use std::os::unix::io::{AsFd, FromRawFd};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+
+use crate::actions::errors::ActionError;
+use crate::actions::keysyms::parse_key_combinations;
+use crate::actions::{Action, ActionType};
+use crate::events::GestureContext;
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::wl_registry::WlRegistry;
+use wayland_client::protocol::wl_seat::WlSeat;
+use wayland_client::{delegate_noop, Connection, Dispatch, EventQueue, QueueHandle};
+use wayland_protocols_misc::zwp_virtual_keyboard_v1::client::zwp_virtual_keyboard_manager_v1::ZwpVirtualKeyboardManagerV1;
+use wayland_protocols_misc::zwp_virtual_keyboard_v1::client::zwp_virtual_keyboard_v1::ZwpVirtualKeyboardV1;
+
+/// Format of the keymaps uploaded to the compositor (`xkb_v1`).
+const KEYMAP_FORMAT_XKB_V1: u32 = 1;
+
+/// Masks of the modifiers set for the modifier keysyms in key combinations.
+const MODIFIER_MASKS: &[(u32, u32)] = &[
+    // Shift.
+    (0xffe1, 1),
+    (0xffe2, 1),
+    // Control.
+    (0xffe3, 1 << 2),
+    (0xffe4, 1 << 2),
+    // Mod1 (alt).
+    (0xffe9, 1 << 3),
+    (0xffea, 1 << 3),
+    // Mod4 (super).
+    (0xffeb, 1 << 6),
+    (0xffec, 1 << 6),
+];
+
+/// Shared virtual keyboard.
+pub type SharedVirtualKeyboard = Arc<Mutex<VirtualKeyboard>>;
+
+/// Command to be injected via the virtual keyboard.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WtypeCommand {
+    /// Press and release key combinations, in order. Each combination is a
+    /// list of keysyms, with the modifiers held while pressing the rest.
+    Key(Vec<Vec<u32>>),
+    /// Type a text, character by character.
+    Type(String),
+}
+
+impl WtypeCommand {
+    /// Parse a command, either `key <combination>...` (for example,
+    /// `key ctrl+Tab`) or `type <text>` (for example, `type hello`).
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - the command to be parsed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` with the reason if the command is not valid.
+    pub fn parse(command: &str) -> Result<Self, String> {
+        let command = command.trim();
+        let (name, arguments) = command
+            .split_once(char::is_whitespace)
+            .unwrap_or((command, ""));
+        match name {
+            "key" => parse_key_combinations(arguments.split_whitespace()).map(WtypeCommand::Key),
+            "type" if !arguments.trim_start().is_empty() => {
+                Ok(WtypeCommand::Type(arguments.trim_start().to_string()))
+            }
+            "type" => Err(String::from("no text specified")),
+            _ => Err(format!(
+                "unknown command {command} (expected \"key <keys>\" or \"type <text>\")"
+            )),
+        }
+    }
+
+    /// Return the keystrokes of the command, as the modifiers mask to be set
+    /// and the keysyms to be pressed while the modifiers are set.
+    #[must_use]
+    pub fn strokes(&self) -> Vec<(u32, Vec<u32>)> {
+        match self {
+            WtypeCommand::Key(combinations) => combinations
+                .iter()
+                .map(|combination| {
+                    let mask_of = |keysym: &u32| {
+                        MODIFIER_MASKS
+                            .iter()
+                            .find(|(x, _)| x == keysym)
+                            .map(|(_, mask)| *mask)
+                    };
+                    let keys: Vec<u32> = combination
+                        .iter()
+                        .filter(|keysym| mask_of(keysym).is_none())
+                        .copied()
+                        .collect();
+                    if keys.is_empty() {
+                        // Press the modifiers themselves (for example, for
+                        // opening a launcher with `super`).
+                        (0, combination.clone())
+                    } else {
+                        (
+                            combination.iter().filter_map(mask_of).fold(0, |a, b| a | b),
+                            keys,
+                        )
+                    }
+                })
+                .collect(),
+            WtypeCommand::Type(text) => text
+                .chars()
+                .map(|c| {
+                    let keysym = match c {
+                        '\n' => 0xff0d,
+                        '\t' => 0xff09,
+                        // Printable Latin-1 characters share the value of
+                        // their keysym, and the rest use the Unicode range.
+                        ' '..='~' => u32::from(c),
+                        _ => 0x0100_0000 + u32::from(c),
+                    };
+                    (0, vec![keysym])
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Return a `xkb` keymap assigning a keycode to each keysym.
+///
+/// The keysym at index `i` is assigned to the `evdev` key code `i + 1`.
+///
+/// # Arguments
+///
+/// * `keysyms` - the keysyms of the keymap.
+#[must_use]
+pub fn keymap(keysyms: &[u32]) -> String {
+    let keycodes: String = (1..=keysyms.len())
+        .map(|index| format!("<K{index}> = {};\n", index + 8))
+        .collect();
+    let symbols: String = keysyms
+        .iter()
+        .enumerate()
+        .map(|(index, keysym)| format!("key <K{}> {{[{keysym:#x}]}};\n", index + 1))
+        .collect();
+
+    format!(
+        "xkb_keymap {{\n\
+         xkb_keycodes \"(unnamed)\" {{\n\
+         minimum = 8;\n\
+         maximum = {};\n\
+         {keycodes}}};\n\
+         xkb_types \"(unnamed)\" {{ include \"complete\" }};\n\
+         xkb_compatibility \"(unnamed)\" {{ include \"complete\" }};\n\
+         xkb_symbols \"(unnamed)\" {{\n\
+         {symbols}}};\n\
+         }};\n",
+        keysyms.len() + 9
+    )
+}
+
+/// State of the Wayland event queue, without any handling of the events.
+struct KeyboardState;
+
+impl Dispatch<WlRegistry, GlobalListContents> for KeyboardState {
+    fn event(
+        _: &mut Self,
+        _: &WlRegistry,
+        _: <WlRegistry as wayland_client::Proxy>::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+delegate_noop!(KeyboardState: ignore WlSeat);
+delegate_noop!(KeyboardState: ZwpVirtualKeyboardManagerV1);
+delegate_noop!(KeyboardState: ZwpVirtualKeyboardV1);
+
+/// Virtual keyboard created on the compositor.
+struct WaylandKeyboard {
+    /// Connection to the compositor.
+    connection: Connection,
+    /// Event queue of the connection.
+    queue: EventQueue<KeyboardState>,
+    /// Virtual keyboard.
+    keyboard: ZwpVirtualKeyboardV1,
+    /// Creation time, as the base of the timestamps of the keys.
+    start: Instant,
+}
+
+impl WaylandKeyboard {
+    /// Connect to the compositor, creating the virtual keyboard on the first
+    /// seat.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the connection could not be established, or if the
+    /// compositor does not support the `virtual-keyboard` protocol.
+    fn connect() -> Result<Self, String> {
+        let connection = Connection::connect_to_env().map_err(|e| e.to_string())?;
+        let (globals, queue) =
+            registry_queue_init::<KeyboardState>(&connection).map_err(|e| e.to_string())?;
+        let handle = queue.handle();
+        let seat: WlSeat = globals
+            .bind(&handle, 1..=1, ())
+            .map_err(|e| format!("no seat available: {e}"))?;
+        let manager: ZwpVirtualKeyboardManagerV1 = globals
+            .bind(&handle, 1..=1, ())
+            .map_err(|e| format!("the virtual-keyboard protocol is not supported: {e}"))?;
+        let keyboard = manager.create_virtual_keyboard(&seat, &handle, ());
+
+        Ok(WaylandKeyboard {
+            connection,
+            queue,
+            keyboard,
+            start: Instant::now(),
+        })
+    }
+
+    /// Upload the keymap of the keysyms and press the keys of the strokes.
+    ///
+    /// # Arguments
+    ///
+    /// * `strokes` - the modifiers mask and the keysyms of each keystroke.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the requests could not be sent to the compositor.
+    fn emit(&mut self, strokes: &[(u32, Vec<u32>)]) -> Result<(), String> {
+        let mut keysyms: Vec<u32> = Vec::new();
+        for keysym in strokes.iter().flat_map(|(_, keys)| keys) {
+            if !keysyms.contains(keysym) {
+                keysyms.push(*keysym);
+            }
+        }
+        let keymap = keymap(&keysyms);
+        let file = keymap_file(&keymap).map_err(|e| e.to_string())?;
+        let size = u32::try_from(keymap.len() + 1).map_err(|e| e.to_string())?;
+        self.keyboard
+            .keymap(KEYMAP_FORMAT_XKB_V1, file.as_fd(), size);
+
+        let key = |keysym: &u32| {
+            keysyms
+                .iter()
+                .position(|x| x == keysym)
+                .and_then(|index| u32::try_from(index + 1).ok())
+                .unwrap_or_default()
+        };
+        for (mask, keys) in strokes {
+            let time =
+                u32::try_from(self.start.elapsed().as_millis() % (1 << 32)).unwrap_or_default();
+            self.keyboard.modifiers(*mask, 0, 0, 0);
+            for keysym in keys {
+                self.keyboard.key(time, key(keysym), 1);
+            }
+            for keysym in keys.iter().rev() {
+                self.keyboard.key(time, key(keysym), 0);
+            }
+            self.keyboard.modifiers(0, 0, 0, 0);
+        }
+
+        self.connection.flush().map_err(|e| e.to_string())?;
+        self.queue
+            .roundtrip(&mut KeyboardState)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Return an anonymous file with the contents of a keymap, for sharing it
+/// with the compositor.
+///
+/// # Arguments
+///
+/// * `keymap` - the keymap.
+///
+/// # Errors
+///
+/// Returns `Err` if the file could not be created.
+fn keymap_file(keymap: &str) -> io::Result<File> {
+    // SAFETY: the name is a valid C string.
+    let fd = unsafe { libc::memfd_create(c"lillinput-keymap".as_ptr(), libc::MFD_CLOEXEC) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: the descriptor is valid and not owned by anything else.
+    let mut file = unsafe { File::from_raw_fd(fd) };
+    file.write_all(keymap.as_bytes())?;
+    file.write_all(&[0])?;
+
+    Ok(file)
+}
+
+/// Virtual keyboard for injecting key presses on Wayland.
+///
+/// The keyboard is created lazily on the first command (using the
+/// `WAYLAND_DISPLAY` environment variable), and re-created if it failed.
+/// The keymap is generated for the keys of each command, avoiding depending
+/// on the keyboard layout of the user.
+#[derive(Default)]
+pub struct VirtualKeyboard {
+    /// Virtual keyboard, if created.
+    keyboard: Option<WaylandKeyboard>,
+}
+
+impl fmt::Debug for VirtualKeyboard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("VirtualKeyboard")
+            .field("created", &self.keyboard.is_some())
+            .finish()
+    }
+}
+
+impl VirtualKeyboard {
+    /// Inject the key presses of a command.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - the command.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the key presses could not be injected.
+    pub fn emit(&mut self, command: &WtypeCommand) -> Result<(), String> {
+        let keyboard = match self.keyboard.take() {
+            Some(keyboard) => keyboard,
+            None => WaylandKeyboard::connect()?,
+        };
+        let keyboard = self.keyboard.insert(keyboard);
+
+        let result = keyboard.emit(&command.strokes());
+        if result.is_err() {
+            // Recreate the keyboard on the next command.
+            self.keyboard = None;
+        }
+        result
+    }
+}
+
+/// Action that injects key presses on Wayland via the `virtual-keyboard`
+/// protocol.
+///
+/// Allows binding gestures to shortcuts on `wlroots`-based compositors,
+/// without requiring access to `/dev/uinput`.
+#[derive(Debug)]
+pub struct WtypeAction {
+    /// Original command, as specified by the user.
+    command: String,
+    /// Parsed command to be injected in this action.
+    parsed: WtypeCommand,
+    /// Shared virtual keyboard.
+    keyboard: SharedVirtualKeyboard,
+}
+
+impl WtypeAction {
+    /// Create a new [`WtypeAction`].
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - command to be injected in this action.
+    /// * `keyboard` - shared virtual keyboard.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command is not valid.
+    pub fn new(command: &str, keyboard: SharedVirtualKeyboard) -> Result<Self, ActionError> {
+        let parsed =
+            WtypeCommand::parse(command).map_err(|message| ActionError::InvalidCommand {
+                type_: ActionType::Wtype.to_string(),
+                message,
+            })?;
+
+        Ok(WtypeAction {
+            command: command.trim().to_string(),
+            parsed,
+            keyboard,
+        })
+    }
+}
+
+impl Action for WtypeAction {
+    fn execute_command(&mut self, _context: &GestureContext) -> Result<(), ActionError> {
+        self.keyboard
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .emit(&self.parsed)
+            .map_err(|message| ActionError::ExecutionError {
+                type_: ActionType::Wtype.to_string(),
+                message,
+            })
+    }
+
+    fn fmt_command(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:<{}>", ActionType::Wtype, self.command)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{keymap, WtypeCommand};
+
+    #[test]
+    /// Test parsing the commands.
+    fn test_wtype_command_parse() {
+        assert_eq!(
+            WtypeCommand::parse("key ctrl+Tab"),
+            Ok(WtypeCommand::Key(vec![vec![0xffe3, 0xff09]]))
+        );
+        assert_eq!(
+            WtypeCommand::parse("type  hello world"),
+            Ok(WtypeCommand::Type(String::from("hello world")))
+        );
+        assert!(WtypeCommand::parse("type").is_err());
+        assert!(WtypeCommand::parse("key foo").is_err());
+        assert!(WtypeCommand::parse("click 1").is_err());
+    }
+
+    #[test]
+    /// Test the keystrokes of the commands.
+    fn test_wtype_strokes() {
+        let command = WtypeCommand::parse("key ctrl+shift+t super").unwrap();
+        assert_eq!(
+            command.strokes(),
+            vec![(0b101, vec![0x74]), (0, vec![0xffeb])]
+        );
+
+        let command = WtypeCommand::parse("type a€").unwrap();
+        assert_eq!(
+            command.strokes(),
+            vec![(0, vec![0x61]), (0, vec![0x0100_20ac])]
+        );
+    }
+
+    #[test]
+    /// Test generating the keymap for the keysyms.
+    fn test_keymap() {
+        let keymap = keymap(&[0xff51, 0x61]);
+        assert!(keymap.contains("maximum = 11;"));
+        assert!(keymap.contains("<K1> = 9;"));
+        assert!(keymap.contains("<K2> = 10;"));
+        assert!(keymap.contains("key <K1> {[0xff51]};"));
+        assert!(keymap.contains("key <K2> {[0x61]};"));
+    }
+}
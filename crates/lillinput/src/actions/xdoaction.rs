@@ -0,0 +1,280 @@
+//! Action for injecting key and button presses on X11 via `XTest`.
+
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::actions::errors::ActionError;
+use crate::actions::keysyms::parse_key_combinations;
+use crate::actions::{Action, ActionType};
+use crate::events::GestureContext;
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::xproto::{
+    ConnectionExt as _, BUTTON_PRESS_EVENT, BUTTON_RELEASE_EVENT, KEY_PRESS_EVENT,
+    KEY_RELEASE_EVENT,
+};
+use x11rb::protocol::xtest::ConnectionExt as _;
+use x11rb::rust_connection::RustConnection;
+
+/// Shared connection to the X server.
+pub type SharedXdoConnection = Arc<Mutex<XdoConnection>>;
+
+/// Command to be injected via `XTest`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum XdoCommand {
+    /// Press and release key combinations, in order. Each combination is a
+    /// list of keysyms, pressed in order and released in reverse order.
+    Key(Vec<Vec<u32>>),
+    /// Press and release a pointer button.
+    Click(u8),
+}
+
+impl XdoCommand {
+    /// Parse a command, either `key <combination>...` (for example,
+    /// `key ctrl+Tab`) or `click <button>` (for example, `click 8`).
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - the command to be parsed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` with the reason if the command is not valid.
+    pub fn parse(command: &str) -> Result<Self, String> {
+        let mut words = command.split_whitespace();
+        match words.next() {
+            Some("key") => parse_key_combinations(words).map(XdoCommand::Key),
+            Some("click") => match (words.next().map(str::parse::<u8>), words.next()) {
+                (Some(Ok(button)), None) if button > 0 => Ok(XdoCommand::Click(button)),
+                _ => Err(String::from("expected a single button number")),
+            },
+            _ => Err(format!(
+                "unknown command {command} (expected \"key <keys>\" or \"click <button>\")"
+            )),
+        }
+    }
+}
+
+/// Connection to the X server for injecting events via `XTest`.
+///
+/// The connection is established lazily on the first command (using the
+/// `DISPLAY` environment variable), and re-established if it failed.
+#[derive(Default)]
+pub struct XdoConnection {
+    /// Connection and keyboard mapping, if connected.
+    connection: Option<(RustConnection, KeyboardMapping)>,
+}
+
+impl fmt::Debug for XdoConnection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("XdoConnection")
+            .field("connected", &self.connection.is_some())
+            .finish()
+    }
+}
+
+/// Keysyms of each keycode of the keyboard.
+struct KeyboardMapping {
+    /// First keycode of the mapping.
+    min_keycode: u8,
+    /// Number of keysyms for each keycode.
+    keysyms_per_keycode: u8,
+    /// Keysyms of all the keycodes, in order.
+    keysyms: Vec<u32>,
+}
+
+impl KeyboardMapping {
+    /// Return the keycode that produces a keysym, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `keysym` - the keysym.
+    fn keycode(&self, keysym: u32) -> Option<u8> {
+        self.keysyms
+            .chunks(usize::from(self.keysyms_per_keycode.max(1)))
+            .position(|keysyms| keysyms.contains(&keysym))
+            .and_then(|index| u8::try_from(index).ok())
+            .and_then(|index| self.min_keycode.checked_add(index))
+    }
+}
+
+impl XdoConnection {
+    /// Connect to the X server, fetching the keyboard mapping.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the connection could not be established.
+    fn connect() -> Result<(RustConnection, KeyboardMapping), String> {
+        let (connection, _) = x11rb::connect(None).map_err(|e| e.to_string())?;
+        if connection
+            .extension_information(x11rb::protocol::xtest::X11_EXTENSION_NAME)
+            .map_err(|e| e.to_string())?
+            .is_none()
+        {
+            return Err(String::from("the XTest extension is not available"));
+        }
+
+        let setup = connection.setup();
+        let (min_keycode, max_keycode) = (setup.min_keycode, setup.max_keycode);
+        let reply = connection
+            .get_keyboard_mapping(min_keycode, max_keycode - min_keycode + 1)
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| e.to_string())?;
+        let mapping = KeyboardMapping {
+            min_keycode,
+            keysyms_per_keycode: reply.keysyms_per_keycode,
+            keysyms: reply.keysyms,
+        };
+
+        Ok((connection, mapping))
+    }
+
+    /// Inject the events of a command.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - the command.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the events could not be injected.
+    pub fn execute(&mut self, command: &XdoCommand) -> Result<(), String> {
+        let (connection, mapping) = match self.connection.take() {
+            Some(connection) => connection,
+            None => Self::connect()?,
+        };
+        let (connection, mapping) = self.connection.insert((connection, mapping));
+
+        // Translate the keysyms before emitting any event, avoiding leaving
+        // keys pressed.
+        let mut events = Vec::new();
+        match command {
+            XdoCommand::Key(combinations) => {
+                for combination in combinations {
+                    let keycodes = combination
+                        .iter()
+                        .map(|keysym| {
+                            mapping
+                                .keycode(*keysym)
+                                .ok_or(format!("no keycode for keysym {keysym:#x}"))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    events.extend(keycodes.iter().map(|x| (KEY_PRESS_EVENT, *x)));
+                    events.extend(keycodes.iter().rev().map(|x| (KEY_RELEASE_EVENT, *x)));
+                }
+            }
+            XdoCommand::Click(button) => {
+                events.push((BUTTON_PRESS_EVENT, *button));
+                events.push((BUTTON_RELEASE_EVENT, *button));
+            }
+        }
+
+        let result = events
+            .into_iter()
+            .try_for_each(|(type_, detail)| {
+                connection
+                    .xtest_fake_input(type_, detail, x11rb::CURRENT_TIME, x11rb::NONE, 0, 0, 0)
+                    .map(|_| ())
+            })
+            .and_then(|()| connection.flush());
+        if let Err(e) = result {
+            // Reconnect on the next command.
+            self.connection = None;
+            return Err(e.to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// Action that injects key and button presses on X11 via `XTest`.
+///
+/// Allows binding gestures to shortcuts on X11 sessions other than `i3`,
+/// without spawning an `xdotool` process for each gesture.
+#[derive(Debug)]
+pub struct XdoAction {
+    /// Original command, as specified by the user.
+    command: String,
+    /// Parsed command to be injected in this action.
+    parsed: XdoCommand,
+    /// Shared connection to the X server.
+    connection: SharedXdoConnection,
+}
+
+impl XdoAction {
+    /// Create a new [`XdoAction`].
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - command to be injected in this action.
+    /// * `connection` - shared connection to the X server.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command is not valid.
+    pub fn new(command: &str, connection: SharedXdoConnection) -> Result<Self, ActionError> {
+        let parsed = XdoCommand::parse(command).map_err(|message| ActionError::InvalidCommand {
+            type_: ActionType::Xdo.to_string(),
+            message,
+        })?;
+
+        Ok(XdoAction {
+            command: command.trim().to_string(),
+            parsed,
+            connection,
+        })
+    }
+}
+
+impl Action for XdoAction {
+    fn execute_command(&mut self, _context: &GestureContext) -> Result<(), ActionError> {
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .execute(&self.parsed)
+            .map_err(|message| ActionError::ExecutionError {
+                type_: ActionType::Xdo.to_string(),
+                message,
+            })
+    }
+
+    fn fmt_command(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:<{}>", ActionType::Xdo, self.command)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{KeyboardMapping, XdoCommand};
+
+    #[test]
+    /// Test parsing the commands.
+    fn test_xdo_command_parse() {
+        assert_eq!(
+            XdoCommand::parse("key ctrl+Tab alt+Left"),
+            Ok(XdoCommand::Key(vec![
+                vec![0xffe3, 0xff09],
+                vec![0xffe9, 0xff51]
+            ]))
+        );
+        assert_eq!(XdoCommand::parse(" click 8 "), Ok(XdoCommand::Click(8)));
+        assert!(XdoCommand::parse("key").is_err());
+        assert!(XdoCommand::parse("key ctrl+foo").is_err());
+        assert!(XdoCommand::parse("click 0").is_err());
+        assert!(XdoCommand::parse("click 1 2").is_err());
+        assert!(XdoCommand::parse("type foo").is_err());
+    }
+
+    #[test]
+    /// Test translating keysyms into keycodes.
+    fn test_keyboard_mapping() {
+        let mapping = KeyboardMapping {
+            min_keycode: 8,
+            keysyms_per_keycode: 2,
+            keysyms: vec![0x61, 0x41, 0xff51, 0],
+        };
+        assert_eq!(mapping.keycode(0x41), Some(8));
+        assert_eq!(mapping.keycode(0xff51), Some(9));
+        assert_eq!(mapping.keycode(0xff52), None);
+    }
+}
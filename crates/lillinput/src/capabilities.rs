@@ -0,0 +1,73 @@
+//! Introspection of the features compiled into the library.
+
+use crate::actions::ActionType;
+
+use strum::IntoEnumIterator;
+
+/// Backend compiled into the library.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Backend {
+    /// Name of the backend.
+    pub name: String,
+    /// Cargo feature that enables the backend, if it is optional.
+    pub feature: Option<&'static str>,
+}
+
+/// Features compiled into the library.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Capabilities {
+    /// Version of the library, shared by all the backends.
+    pub version: &'static str,
+    /// Action backends, as the action types that can be used.
+    pub action_backends: Vec<Backend>,
+    /// Processor backends, as the ways of creating the `libinput` context.
+    pub processor_backends: Vec<Backend>,
+}
+
+/// Return the features compiled into the library.
+#[must_use]
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        version: env!("CARGO_PKG_VERSION"),
+        action_backends: ActionType::iter()
+            .map(|action_type| Backend {
+                name: action_type.to_string(),
+                feature: action_type.feature(),
+            })
+            .collect(),
+        processor_backends: ["udev", "path"]
+            .into_iter()
+            .map(|name| Backend {
+                name: name.to_string(),
+                feature: None,
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::capabilities;
+
+    #[test]
+    /// Test the backends reported as compiled in.
+    fn test_capabilities() {
+        let capabilities = capabilities();
+        let action_backends: Vec<&str> = capabilities
+            .action_backends
+            .iter()
+            .map(|x| x.name.as_str())
+            .collect();
+
+        assert_eq!(capabilities.version, env!("CARGO_PKG_VERSION"));
+        assert!(action_backends.contains(&"internal"));
+        assert_eq!(
+            action_backends.contains(&"command"),
+            cfg!(feature = "command")
+        );
+        assert_eq!(action_backends.contains(&"tmux"), cfg!(feature = "tmux"));
+        assert_eq!(action_backends.contains(&"i3!"), cfg!(feature = "i3"));
+        assert_eq!(action_backends.contains(&"obs"), cfg!(feature = "obs"));
+        assert_eq!(action_backends.contains(&"rest"), cfg!(feature = "rest"));
+    }
+}
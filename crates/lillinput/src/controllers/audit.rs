@@ -0,0 +1,105 @@
+//! Append-only log of the executed actions.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
+
+use crate::events::ActionEvent;
+use crate::throttle::warn_throttled;
+
+use chrono::{Local, SecondsFormat};
+
+/// Append-only log of the executed actions.
+///
+/// Each executed action is appended as a line containing the time of the
+/// execution, the event that triggered it, the action and its result,
+/// separated by tabs. The log is kept separate from the regular logging, as a
+/// history of the actions performed by the gestures.
+#[derive(Debug)]
+pub struct AuditLog {
+    /// File the entries are appended to.
+    file: Mutex<File>,
+}
+
+impl AuditLog {
+    /// Open an [`AuditLog`], creating its file if it does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - path to the file of the log.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the file could not be opened for appending.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(AuditLog {
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Append an executed action to the log.
+    ///
+    /// Errors while writing to the file are logged, without interrupting the
+    /// execution of the actions.
+    ///
+    /// # Arguments
+    ///
+    /// * `action_event` - the event that triggered the action.
+    /// * `action` - the action, in printable form.
+    /// * `result` - the result of the execution, with the error message in
+    ///   case of failure.
+    pub fn record(&self, action_event: ActionEvent, action: &str, result: Result<(), String>) {
+        let line = format!(
+            "{}\t{action_event}\t{action}\t{}\n",
+            Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+            result.map_or_else(|e| format!("error: {e}"), |()| String::from("ok"))
+        );
+
+        // Write the entry at once, so concurrent writers do not interleave.
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Err(e) = file.write_all(line.as_bytes()) {
+            warn_throttled!("Unable to write to the audit log: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::AuditLog;
+    use crate::events::ActionEvent;
+
+    use tempfile::Builder;
+
+    #[test]
+    /// Test appending entries to the log, preserving the existing ones.
+    fn test_audit_log_append() {
+        let file = Builder::new().tempfile().unwrap();
+        fs::write(file.path(), "existing\n").unwrap();
+
+        let audit_log = AuditLog::open(file.path()).unwrap();
+        audit_log.record(ActionEvent::ThreeFingerSwipeUp, "command:<foo>", Ok(()));
+        audit_log.record(
+            ActionEvent::FourFingerSwipeLeft,
+            "i3:<bar>",
+            Err(String::from("failed")),
+        );
+
+        let contents = fs::read_to_string(file.path()).unwrap();
+        let lines: Vec<Vec<&str>> = contents.lines().map(|x| x.split('\t').collect()).collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], vec!["existing"]);
+        assert_eq!(
+            lines[1][1..],
+            ["three-finger-swipe-up", "command:<foo>", "ok"]
+        );
+        assert_eq!(
+            lines[2][1..],
+            ["four-finger-swipe-left", "i3:<bar>", "error: failed"]
+        );
+    }
+}
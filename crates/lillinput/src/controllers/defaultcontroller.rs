@@ -1,16 +1,32 @@
 //! Default [`Controller`] for actions.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::iter;
+use std::os::unix::io::RawFd;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
 
-use crate::actions::Action;
+use crate::actions::{Action, ActionError, SharedMacroRecorder};
+use crate::controllers::audit::AuditLog;
 use crate::controllers::errors::ControllerError;
-use crate::controllers::Controller;
+use crate::controllers::latency::GestureLatency;
+use crate::controllers::schedule::{find_active_profile, Schedule};
+use crate::controllers::sequence::{find_completed_sequence, GestureSequence};
+use crate::controllers::{
+    ActionMap, Controller, ControllerState, EventFilter, ExecutionMode, SharedControllerState,
+    DEFAULT_PROFILE,
+};
 use crate::events::defaultprocessor::DefaultProcessor;
-use crate::events::{ActionEvent, Processor};
+use crate::events::{
+    monotonic_usec, ActionEvent, GestureContext, Modifier, Processor, ProcessorSettings,
+    SwipeCorpus,
+};
+use crate::throttle::{flush_warnings, warn_throttled};
 
+use chrono::Local;
 use itertools::Itertools;
-use log::{debug, info, warn};
-use strum::IntoEnumIterator;
+use log::{debug, info};
+use strum::{Display, IntoEnumIterator};
 
 /// Controller that maps between events and actions.
 pub struct DefaultController {
@@ -18,6 +34,109 @@ pub struct DefaultController {
     pub processor: Box<dyn Processor>,
     /// Map between events and actions.
     pub actions: HashMap<ActionEvent, Vec<Box<dyn Action>>>,
+    /// Map between events and actions, for each set of modifiers (keyboard
+    /// modifiers and pointer buttons).
+    ///
+    /// If the modifiers held during a gesture have actions for the event,
+    /// they are used instead of the ones in [`DefaultController::actions`].
+    pub modifier_actions: HashMap<BTreeSet<Modifier>, ActionMap>,
+    /// Map between events and actions, for each device (by name or system
+    /// name).
+    ///
+    /// If the device performing a gesture has actions for the event, they
+    /// are used instead of the ones in [`DefaultController::actions`].
+    pub device_actions: HashMap<String, ActionMap>,
+    /// Map between events and actions, for each seat.
+    ///
+    /// If the seat performing a gesture has actions for the event, they are
+    /// used instead of the ones in [`DefaultController::actions`].
+    pub seat_actions: HashMap<String, ActionMap>,
+    /// Map between events and actions, for each profile.
+    ///
+    /// If a profile is active and has actions for the event, they are used
+    /// instead of the ones in [`DefaultController::actions`].
+    pub profiles: HashMap<String, ActionMap>,
+    /// Schedules for activating the profiles, in order of precedence.
+    pub schedules: Vec<Schedule>,
+    /// Currently active profile.
+    pub active_profile: Option<String>,
+    /// Map between events and the mode for executing their actions.
+    ///
+    /// Events not present in the map use [`ExecutionMode::Sequential`].
+    pub execution_modes: HashMap<ActionEvent, ExecutionMode>,
+    /// Maximum number of consecutive identical events processed from a
+    /// single dispatch.
+    ///
+    /// Events received while actions are being executed are queued, and
+    /// processed in the next dispatch. If set, runs of repeated events in
+    /// excess of this number are collapsed, avoiding a burst of actions after
+    /// a laggy moment. The progress and repeated events emitted while a
+    /// swipe is in progress are not collapsed.
+    pub max_repeated_events: Option<usize>,
+    /// Filters applied to the events before processing them, in order.
+    ///
+    /// If any of the filters rejects an event, the event is discarded.
+    pub filters: Vec<Box<dyn EventFilter>>,
+    /// Shared macro recorder.
+    ///
+    /// If set, the events are captured while recording, and the recorded
+    /// macros take precedence over the actions of their events.
+    pub macro_recorder: Option<SharedMacroRecorder>,
+    /// Map between events and the actions for undoing them.
+    ///
+    /// If the opposite event of an event with undo actions is received
+    /// within [`DefaultController::undo_window`], the undo actions are
+    /// executed instead of the actions of the opposite event.
+    pub undo_actions: ActionMap,
+    /// Maximum time between an event and its opposite for undoing it.
+    pub undo_window: Duration,
+    /// Latest event processed, along with the time it was processed.
+    pub last_event: Option<(ActionEvent, Instant)>,
+    /// Sequences of events with their own actions.
+    ///
+    /// If an event completes a sequence, the actions of the sequence are
+    /// executed instead of the actions of the event.
+    pub sequences: Vec<GestureSequence>,
+    /// Latest events processed, along with the time they were processed, for
+    /// recognizing the sequences.
+    pub recent_events: Vec<(ActionEvent, Instant)>,
+    /// State shared with the actions that act on the controller.
+    pub state: SharedControllerState,
+    /// Interval between the periodic ticks delivered by the main loop.
+    ///
+    /// If not set, no ticks are delivered, and the main loop waits
+    /// indefinitely for events.
+    pub tick_interval: Option<Duration>,
+    /// Events without actions for which a hint has been emitted.
+    ///
+    /// The hint about binding an event is only emitted the first time the
+    /// event is received, avoiding repeating it during the session.
+    pub hinted_events: HashSet<ActionEvent>,
+    /// Append-only log of the executed actions.
+    ///
+    /// If set, every action executed is recorded in the log, along with the
+    /// event that triggered it and its result.
+    pub audit_log: Option<Arc<AuditLog>>,
+    /// Maximum number of events processed by the main loop.
+    ///
+    /// If set, the main loop returns once this number of events has been
+    /// processed, allowing bounded runs (for example, for scripting).
+    pub max_events: Option<usize>,
+    /// Maximum time the main loop runs for.
+    ///
+    /// If set, the main loop returns once the time has elapsed, even if no
+    /// events are received.
+    pub run_duration: Option<Duration>,
+    /// Context of the gesture being handled, as reported by the processor.
+    ///
+    /// The context is passed to the actions of its event. The actions of
+    /// other events (such as the ones replayed from a macro) receive a
+    /// context without information about the gesture.
+    pub gesture_context: Option<GestureContext>,
+    /// Accumulated displacement in the `x` axis of the current gesture.
+    pub dx: f64,
+    /// Accumulated displacement in the `y` axis of the current gesture.
+    pub dy: f64,
 }
 
 impl DefaultController {
@@ -32,39 +151,590 @@ impl DefaultController {
         processor: Box<dyn Processor>,
         actions: HashMap<ActionEvent, Vec<Box<dyn Action>>>,
     ) -> Self {
-        let controller = DefaultController { processor, actions };
-        controller._log_status_info();
+        DefaultController {
+            processor,
+            actions,
+            modifier_actions: HashMap::new(),
+            device_actions: HashMap::new(),
+            seat_actions: HashMap::new(),
+            profiles: HashMap::new(),
+            schedules: vec![],
+            active_profile: None,
+            execution_modes: HashMap::new(),
+            max_repeated_events: None,
+            filters: vec![],
+            macro_recorder: None,
+            undo_actions: HashMap::new(),
+            undo_window: Duration::from_millis(500),
+            last_event: None,
+            sequences: vec![],
+            recent_events: vec![],
+            state: Arc::new(Mutex::new(ControllerState::default())),
+            tick_interval: None,
+            hinted_events: HashSet::new(),
+            audit_log: None,
+            max_events: None,
+            run_duration: None,
+            gesture_context: None,
+            dx: 0.0,
+            dy: 0.0,
+        }
+    }
 
-        controller
+    /// Log the status of the controller, as a table of the events along
+    /// with their actions.
+    ///
+    /// # Arguments
+    ///
+    /// * `colored` - whether to highlight the status of the events using
+    ///   terminal colors.
+    pub fn log_status_info(&self, colored: bool) {
+        for line in self.status_table(colored).lines() {
+            info!("{line}");
+        }
     }
 
     /// Return the status of the controller in printable form.
-    fn _log_status_info(&self) {
-        // Print information.
-        for action_event in ActionEvent::iter() {
-            debug!(
-                " * {}: {}",
-                action_event,
-                self.actions
-                    .get(&action_event)
-                    .unwrap_or(&vec![])
-                    .iter()
-                    .format(", ")
+    ///
+    /// The status is a table with a row for each event, listing its actions
+    /// and whether their backends are available. The long variants of the
+    /// events are only listed if they have actions.
+    ///
+    /// # Arguments
+    ///
+    /// * `colored` - whether to highlight the status of the events using
+    ///   terminal colors.
+    #[must_use]
+    pub fn status_table(&self, colored: bool) -> String {
+        format_status_table(&self.actions, colored)
+    }
+
+    /// Validate the actions of the controller, without executing them.
+    ///
+    /// The actions for the modifiers, the devices, the profiles, the undo
+    /// actions and the sequences are validated along with the default ones. The actions of
+    /// the sequences are reported along with the last event of the sequence.
+    ///
+    /// # Returns
+    ///
+    /// The actions that failed validation, along with their events and the
+    /// validation errors.
+    #[must_use]
+    pub fn validate_actions(&self) -> Vec<(ActionEvent, String, ActionError)> {
+        iter::once(&self.actions)
+            .chain(self.modifier_actions.values())
+            .chain(self.device_actions.values())
+            .chain(self.seat_actions.values())
+            .chain(self.profiles.values())
+            .chain(iter::once(&self.undo_actions))
+            .flat_map(|action_map| {
+                action_map.iter().flat_map(|(action_event, actions)| {
+                    actions.iter().filter_map(|action| {
+                        action
+                            .validate()
+                            .err()
+                            .map(|e| (*action_event, action.to_string(), e))
+                    })
+                })
+            })
+            .chain(self.sequences.iter().flat_map(|sequence| {
+                let action_event = sequence.events.last().copied();
+                sequence.actions.iter().filter_map(move |action| {
+                    Some((action_event?, action.to_string(), action.validate().err()?))
+                })
+            }))
+            .collect()
+    }
+
+    /// Return an iterator over the events and their actions.
+    ///
+    /// The events are returned in the order of [`ActionEvent::iter`], and
+    /// only the ones with actions are included.
+    pub fn iter_actions(&self) -> impl Iterator<Item = (ActionEvent, &[Box<dyn Action>])> {
+        ActionEvent::iter().filter_map(|action_event| {
+            self.actions
+                .get(&action_event)
+                .map(|actions| (action_event, actions.as_slice()))
+        })
+    }
+
+    /// Return the parameters used by the processor for recognizing gestures.
+    #[must_use]
+    pub fn processor_settings(&self) -> ProcessorSettings {
+        self.processor.settings()
+    }
+
+    /// Replay a [`SwipeCorpus`] through the processor and the actions.
+    ///
+    /// The recognized events are handled as if they were received from the
+    /// backend, bypassing the event queue and the bounds of the run.
+    ///
+    /// # Arguments
+    ///
+    /// * `corpus` - the corpus of swipe events.
+    ///
+    /// # Returns
+    ///
+    /// The number of [`ActionEvent`]s recognized.
+    pub fn replay(&mut self, corpus: &SwipeCorpus) -> usize {
+        let action_events = corpus.replay(self.processor.as_mut());
+        let count = action_events.len();
+        for action_event in action_events {
+            self._handle_action_event(action_event);
+        }
+
+        count
+    }
+}
+
+/// Record the action being executed in the shared controller state.
+///
+/// # Arguments
+///
+/// * `state` - the shared controller state.
+/// * `action` - the action being executed in printable form, if any.
+fn set_current_action(state: &SharedControllerState, action: Option<String>) {
+    state
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .current_action = action;
+}
+
+/// Execute a list of [`Action`]s.
+///
+/// # Arguments
+///
+/// * `actions` - the actions to be executed.
+/// * `context` - the context of the gesture that triggered the actions.
+/// * `execution_mode` - the mode for executing the actions.
+/// * `state` - the shared controller state, for recording the action being
+///   executed.
+/// * `audit_log` - the log for recording the executed actions, if any.
+fn execute_actions(
+    actions: &mut [Box<dyn Action>],
+    context: &GestureContext,
+    execution_mode: ExecutionMode,
+    state: &SharedControllerState,
+    audit_log: Option<&AuditLog>,
+) {
+    let record = |action: &str, result: Result<(), String>| {
+        if let Some(audit_log) = audit_log {
+            audit_log.record(context.action_event, action, result);
+        }
+    };
+
+    match execution_mode {
+        ExecutionMode::Sequential | ExecutionMode::Chain => {
+            let count = actions.len();
+            for (index, action) in actions.iter_mut().enumerate() {
+                set_current_action(state, Some(action.to_string()));
+                match action.execute_command(context) {
+                    Ok(()) => record(&action.to_string(), Ok(())),
+                    Err(e) => {
+                        warn_throttled!("Error execution action {action}: {e}");
+                        record(&action.to_string(), Err(e.to_string()));
+
+                        // Abort the chain, skipping the remaining steps.
+                        if execution_mode == ExecutionMode::Chain && index + 1 < count {
+                            warn_throttled!(
+                                "Aborting the actions of {}, skipping {} actions",
+                                context.action_event,
+                                count - index - 1
+                            );
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        ExecutionMode::Parallel => {
+            // Start all the actions, and wait for the spawned processes
+            // once all of them have been started.
+            let mut children = vec![];
+            for (index, action) in actions.iter_mut().enumerate() {
+                set_current_action(state, Some(action.to_string()));
+                match action.start_command(context) {
+                    Ok(Some(child)) => children.push((index, child)),
+                    Ok(None) => record(&action.to_string(), Ok(())),
+                    Err(e) => {
+                        warn_throttled!("Error execution action {action}: {e}");
+                        record(&action.to_string(), Err(e.to_string()));
+                    }
+                }
+            }
+
+            for (index, child) in children {
+                let action = &mut actions[index];
+                match action.finish_command(child) {
+                    Ok(()) => record(&action.to_string(), Ok(())),
+                    Err(e) => {
+                        warn_throttled!("Error execution action {action}: {e}");
+                        record(&action.to_string(), Err(e.to_string()));
+                    }
+                }
+            }
+        }
+    }
+    set_current_action(state, None);
+}
+
+/// Status of the actions associated with an event.
+#[derive(Clone, Copy, Debug, Display, Eq, PartialEq)]
+#[strum(serialize_all = "kebab_case")]
+enum BindingStatus {
+    /// The event has actions, and all their backends are available.
+    Ready,
+    /// The event has actions, but some of their backends are not available.
+    Unavailable,
+    /// The event has no actions.
+    Unbound,
+}
+
+impl BindingStatus {
+    /// Return the terminal color code used for highlighting the status.
+    fn color_code(self) -> &'static str {
+        match self {
+            BindingStatus::Ready => "32",
+            BindingStatus::Unavailable => "31",
+            BindingStatus::Unbound => "2",
+        }
+    }
+}
+
+/// Format the events and their actions as a table.
+///
+/// # Arguments
+///
+/// * `actions` - map between events and actions.
+/// * `colored` - whether to highlight the status of the events using
+///   terminal colors.
+fn format_status_table(actions: &ActionMap, colored: bool) -> String {
+    let header = ("gesture", "actions", "status");
+    let rows: Vec<(String, String, BindingStatus)> = ActionEvent::iter()
+        .filter(|action_event| {
+            // List the long, fast, hold, rotation, pinch, edge swipe and
+            // switch events only if they are bound.
+            !(action_event.is_long()
+                || action_event.is_fast()
+                || action_event.is_hold()
+                || action_event.is_rotation()
+                || action_event.is_pinch()
+                || action_event.is_edge_swipe()
+                || action_event.is_switch())
+                || actions.contains_key(action_event)
+        })
+        .map(|action_event| {
+            let event_actions = actions.get(&action_event).map_or(&[][..], Vec::as_slice);
+            let status = if event_actions.is_empty() {
+                BindingStatus::Unbound
+            } else if event_actions.iter().all(|action| action.is_available()) {
+                BindingStatus::Ready
+            } else {
+                BindingStatus::Unavailable
+            };
+            let formatted_actions = if event_actions.is_empty() {
+                String::from("-")
+            } else {
+                event_actions.iter().format(", ").to_string()
+            };
+
+            (action_event.to_string(), formatted_actions, status)
+        })
+        .collect();
+
+    let gesture_width = rows
+        .iter()
+        .map(|(gesture, _, _)| gesture.len())
+        .fold(header.0.len(), usize::max);
+    let actions_width = rows
+        .iter()
+        .map(|(_, actions, _)| actions.chars().count())
+        .fold(header.1.len(), usize::max);
+
+    let mut table = vec![format!(
+        "{:gesture_width$}  {:actions_width$}  {}",
+        header.0, header.1, header.2
+    )];
+    for (gesture, actions, status) in rows {
+        let status = if colored {
+            format!("\x1b[{}m{status}\x1b[0m", status.color_code())
+        } else {
+            status.to_string()
+        };
+        table.push(format!(
+            "{gesture:gesture_width$}  {actions:actions_width$}  {status}"
+        ));
+    }
+
+    table.join("\n")
+}
+
+/// Collapse the runs of consecutive identical [`ActionEvent`]s, keeping at
+/// most `max_repeated` events of each run.
+///
+/// # Arguments
+///
+/// * `events` - list of events, in the order they were received.
+/// * `max_repeated` - maximum number of events of each run.
+/// * `exempt` - events that are never collapsed.
+fn collapse_repeated_events(
+    events: Vec<ActionEvent>,
+    max_repeated: usize,
+    exempt: &HashSet<ActionEvent>,
+) -> Vec<ActionEvent> {
+    let mut previous: Option<ActionEvent> = None;
+    let mut count: usize = 0;
+
+    events
+        .into_iter()
+        .filter(|event| {
+            if previous == Some(*event) {
+                count += 1;
+            } else {
+                previous = Some(*event);
+                count = 1;
+            }
+            count <= max_repeated || exempt.contains(event)
+        })
+        .collect()
+}
+
+impl DefaultController {
+    /// Update the active profile, according to the profile selected manually
+    /// or, if none, to the schedules.
+    fn _update_active_profile(&mut self) {
+        let profile_override = self
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .profile_override
+            .clone();
+        let active_profile = match profile_override {
+            Some(profile) => (profile != DEFAULT_PROFILE).then_some(profile),
+            None if self.schedules.is_empty() => return,
+            None => {
+                find_active_profile(&self.schedules, Local::now().naive_local()).map(String::from)
+            }
+        };
+        if active_profile != self.active_profile {
+            info!(
+                "Switching to profile: {}",
+                active_profile.as_deref().unwrap_or(DEFAULT_PROFILE)
             );
+            self.active_profile = active_profile;
         }
-        let three_finger_counts: String = ActionEvent::iter()
-            .take(8)
-            .map(|x| format!("{:?}/", self.actions.get(&x).unwrap_or(&vec![]).len()))
-            .collect();
-        let four_finger_counts: String = ActionEvent::iter()
-            .skip(8)
-            .map(|x| format!("{:?}/", self.actions.get(&x).unwrap_or(&vec![]).len()))
+    }
+
+    /// Return the actions associated with an [`ActionEvent`].
+    ///
+    /// The actions for the held modifiers, the device or the seat performing
+    /// the gesture, or the active profile are used if present, in that order.
+    ///
+    /// # Arguments
+    ///
+    /// * `action_event` - the [`ActionEvent`] to handle.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if no actions are registered for the event.
+    fn _find_actions(
+        &mut self,
+        action_event: ActionEvent,
+    ) -> Result<&mut Vec<Box<dyn Action>>, ControllerError> {
+        self._update_active_profile();
+        let modifiers = self.processor.gesture_modifiers();
+        let actions = if let Some(actions) = self
+            .modifier_actions
+            .get_mut(&modifiers)
+            .and_then(|x| x.get_mut(&action_event))
+        {
+            actions
+        } else if let Some(actions) = self
+            .processor
+            .gesture_device()
+            .and_then(|device| {
+                self.device_actions
+                    .iter_mut()
+                    .find(|(pattern, _)| device.matches(pattern))
+            })
+            .and_then(|(_, x)| x.get_mut(&action_event))
+        {
+            actions
+        } else if let Some(actions) = self
+            .processor
+            .gesture_seat()
+            .and_then(|seat| self.seat_actions.get_mut(&seat))
+            .and_then(|x| x.get_mut(&action_event))
+        {
+            actions
+        } else if let Some(actions) = self
+            .active_profile
+            .as_ref()
+            .and_then(|x| self.profiles.get_mut(x))
+            .and_then(|x| x.get_mut(&action_event))
+        {
+            actions
+        } else {
+            self.actions
+                .get_mut(&action_event)
+                .ok_or(ControllerError::NoActionsRegistered(action_event))?
+        };
+
+        Ok(actions)
+    }
+
+    /// Return the context of the gesture for an [`ActionEvent`].
+    ///
+    /// # Arguments
+    ///
+    /// * `action_event` - the [`ActionEvent`] to handle.
+    fn _gesture_context(&self, action_event: ActionEvent) -> GestureContext {
+        self.gesture_context
+            .clone()
+            .filter(|x| x.action_event == action_event)
+            .unwrap_or_else(|| GestureContext::new(action_event))
+    }
+
+    /// Record an [`ActionEvent`] in the latest events, returning the index of
+    /// the sequence it completes, if any.
+    ///
+    /// Only as many events as the longest sequence are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `action_event` - the [`ActionEvent`] processed.
+    fn _update_recent_events(&mut self, action_event: ActionEvent) -> Option<usize> {
+        let max_length = self.sequences.iter().map(|x| x.events.len()).max()?;
+
+        self.recent_events.push((action_event, Instant::now()));
+        let excess = self.recent_events.len().saturating_sub(max_length);
+        self.recent_events.drain(..excess);
+
+        find_completed_sequence(&self.sequences, &self.recent_events)
+    }
+
+    /// Process an [`ActionEvent`], logging the reason if it is discarded.
+    ///
+    /// The event is recorded in the shared controller state. The first time
+    /// an event without actions is received, a hint about binding it is
+    /// emitted instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `action_event` - the [`ActionEvent`] to handle.
+    fn _handle_action_event(&mut self, action_event: ActionEvent) {
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .last_event = Some(action_event);
+        let timing = self.processor.take_gesture_timing(action_event);
+        self.gesture_context = self.processor.take_gesture_context(action_event);
+        let result = self.process_action_event(action_event);
+        self.gesture_context = None;
+        match result {
+            Ok(()) => {
+                if let Some(timing) = timing {
+                    let latency = GestureLatency::new(&timing, monotonic_usec());
+                    debug!("Gesture {action_event} latency: {latency}");
+                    self.state
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .latency
+                        .record(latency);
+                }
+            }
+            Err(ControllerError::NoActionsRegistered(event))
+                if self.hinted_events.insert(event) =>
+            {
+                info!(
+                    "Gesture {event} has no actions configured: they can be bound via the \
+                     `--{event}` argument or the `{event}` entry of the `actions` section of \
+                     the configuration file"
+                );
+            }
+            Err(e) => debug!("Discarding event: {e}"),
+        }
+    }
+
+    /// Collapse the repeated [`ActionEvent`]s received from a dispatch, if
+    /// needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - the events received, in order.
+    fn _collapse_repeated_events(&self, events: Vec<ActionEvent>) -> Vec<ActionEvent> {
+        let Some(max_repeated_events) = self.max_repeated_events else {
+            return events;
+        };
+
+        // The progress and repeated events are emitted repeatedly on purpose.
+        let settings = self.processor.settings();
+        let exempt: HashSet<ActionEvent> = settings
+            .progress_steps
+            .into_keys()
+            .chain(settings.repeat_events)
             .collect();
-        info!(
-            "{}, {} actions enabled",
-            &three_finger_counts.as_str()[0..three_finger_counts.len() - 1],
-            &four_finger_counts.as_str()[0..four_finger_counts.len() - 1],
+
+        let received_count = events.len();
+        let events = collapse_repeated_events(events, max_repeated_events, &exempt);
+        if events.len() < received_count {
+            debug!(
+                "Discarding {} repeated events",
+                received_count - events.len()
+            );
+        }
+        events
+    }
+
+    /// Handle the [`ActionEvent`]s received from a dispatch.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - the events received, in order.
+    fn _handle_action_events(&mut self, events: Vec<ActionEvent>) {
+        for event in events {
+            self._handle_action_event(event);
+        }
+    }
+
+    /// Execute the actions associated with an [`ActionEvent`].
+    ///
+    /// # Arguments
+    ///
+    /// * `action_event` - the [`ActionEvent`] to handle.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if no actions are registered for the event, or if the
+    /// list of actions is empty.
+    fn _execute_actions(&mut self, action_event: ActionEvent) -> Result<(), ControllerError> {
+        let execution_mode = self
+            .execution_modes
+            .get(&action_event)
+            .copied()
+            .unwrap_or_default();
+        let state = Arc::clone(&self.state);
+        let audit_log = self.audit_log.clone();
+        let context = self._gesture_context(action_event);
+        let actions = self._find_actions(action_event)?;
+        if actions.is_empty() {
+            return Err(ControllerError::NoActionsRegistered(action_event));
+        }
+
+        debug!(
+            "Received end event: {action_event}, triggering {} actions ({execution_mode})",
+            actions.len()
+        );
+
+        execute_actions(
+            actions,
+            &context,
+            execution_mode,
+            &state,
+            audit_log.as_deref(),
         );
+
+        Ok(())
     }
 }
 
@@ -77,21 +747,116 @@ impl Default for DefaultController {
 
 impl Controller for DefaultController {
     fn process_action_event(&mut self, action_event: ActionEvent) -> Result<(), ControllerError> {
-        // Invoke actions.
-        let actions = self
-            .actions
-            .get_mut(&action_event)
-            .ok_or(ControllerError::NoActionsRegistered(action_event))?;
+        // Discard the event if rejected by any of the filters.
+        for filter in &mut self.filters {
+            filter.filter(action_event)?;
+        }
 
-        debug!(
-            "Received end event: {action_event}, triggering {} actions",
-            actions.len()
-        );
+        // Execute only the actions that run while paused, if paused.
+        if self.is_paused() {
+            let state = Arc::clone(&self.state);
+            let audit_log = self.audit_log.clone();
+            let context = self._gesture_context(action_event);
+            let mut executed = false;
+            for action in self
+                ._find_actions(action_event)?
+                .iter_mut()
+                .filter(|x| x.runs_while_paused())
+            {
+                set_current_action(&state, Some(action.to_string()));
+                let result = action.execute_command(&context).map_err(|e| {
+                    warn_throttled!("Error execution action {action}: {e}");
+                    e.to_string()
+                });
+                if let Some(audit_log) = &audit_log {
+                    audit_log.record(action_event, &action.to_string(), result);
+                }
+                executed = true;
+            }
+            set_current_action(&state, None);
+
+            return if executed {
+                Ok(())
+            } else {
+                Err(ControllerError::Paused)
+            };
+        }
 
-        for action in &mut *actions {
-            match action.execute_command() {
-                Ok(_) => (),
-                Err(e) => warn!("Error execution action {action}: {e}"),
+        // Execute the actions of the sequence completed by the event, if any.
+        let context = self._gesture_context(action_event);
+        if let Some(index) = self._update_recent_events(action_event) {
+            let sequence = &mut self.sequences[index];
+            debug!(
+                "Received end event: {action_event}, completing sequence {sequence} with {} actions",
+                sequence.actions.len()
+            );
+            execute_actions(
+                &mut sequence.actions,
+                &context,
+                ExecutionMode::Sequential,
+                &self.state,
+                self.audit_log.as_deref(),
+            );
+            self.recent_events.clear();
+            self.last_event = None;
+            return Ok(());
+        }
+
+        // Execute the undo actions if the event is the opposite of the latest
+        // one, within the undo window.
+        let last_event = self.last_event.replace((action_event, Instant::now()));
+        if let Some((last_event, last_time)) = last_event {
+            if last_event.opposite() == action_event && last_time.elapsed() < self.undo_window {
+                if let Some(undo_actions) = self.undo_actions.get_mut(&last_event) {
+                    debug!(
+                        "Received end event: {action_event}, undoing {last_event} with {} actions",
+                        undo_actions.len()
+                    );
+                    execute_actions(
+                        undo_actions,
+                        &context,
+                        ExecutionMode::Sequential,
+                        &self.state,
+                        self.audit_log.as_deref(),
+                    );
+                    self.last_event = None;
+                    return Ok(());
+                }
+            }
+        }
+
+        // Replay the recorded macro for the event, if any.
+        let recorded_macro = self.macro_recorder.as_ref().and_then(|x| {
+            x.lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .macros
+                .get(&action_event)
+                .cloned()
+        });
+        if let Some(events) = recorded_macro {
+            debug!("Received end event: {action_event}, replaying macro");
+            for event in events {
+                if let Err(e) = self._execute_actions(event) {
+                    debug!("Discarding macro event: {e}");
+                }
+            }
+            return Ok(());
+        }
+
+        // Capture the event if a macro is being recorded, skipping the events
+        // that start or stop the recording.
+        let was_recording = self.macro_recorder.as_ref().is_some_and(|x| {
+            x.lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .is_recording()
+        });
+        self._execute_actions(action_event)?;
+        if let Some(macro_recorder) = &self.macro_recorder {
+            let mut macro_recorder = macro_recorder
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            if was_recording && macro_recorder.is_recording() {
+                macro_recorder.capture(action_event);
             }
         }
 
@@ -99,21 +864,487 @@ impl Controller for DefaultController {
     }
 
     fn run(&mut self) -> Result<(), ControllerError> {
-        // Variables for tracking the cursor position changes.
-        let mut dx: f64 = 0.0;
-        let mut dy: f64 = 0.0;
+        let mut last_tick = Instant::now();
+        let started = Instant::now();
+        let mut processed_count: usize = 0;
 
         loop {
-            let events = self.processor.dispatch(&mut dx, &mut dy)?;
+            // Stop if the bounds of the run have been reached.
+            if self
+                .max_events
+                .is_some_and(|max_events| processed_count >= max_events)
+            {
+                info!("Processed {processed_count} events, stopping");
+                return Ok(());
+            }
+            if self
+                .run_duration
+                .is_some_and(|run_duration| started.elapsed() >= run_duration)
+            {
+                info!("Run duration elapsed, stopping");
+                return Ok(());
+            }
+            if self
+                .state
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .reload_requested
+            {
+                info!("Reload of the configuration requested, stopping");
+                return Ok(());
+            }
 
-            for event in events {
-                match self.process_action_event(event) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        debug!("Discarding event: {e}");
-                    }
-                }
+            // Wait for events up to the next tick or the end of the run, if
+            // needed.
+            let tick_timeout = self
+                .tick_interval
+                .map(|tick_interval| tick_interval.saturating_sub(last_tick.elapsed()));
+            let run_timeout = self
+                .run_duration
+                .map(|run_duration| run_duration.saturating_sub(started.elapsed()));
+            self.processor
+                .set_timeout(tick_timeout.into_iter().chain(run_timeout).min());
+            let events = self.processor.dispatch(&mut self.dx, &mut self.dy)?;
+            let mut events = self._collapse_repeated_events(events);
+
+            // Discard the events beyond the maximum number of events, if
+            // needed.
+            if let Some(max_events) = self.max_events {
+                events.truncate(max_events.saturating_sub(processed_count));
+            }
+            processed_count += events.len();
+
+            self._handle_action_events(events);
+
+            // Deliver a tick, if the interval has elapsed.
+            if self
+                .tick_interval
+                .is_some_and(|tick_interval| last_tick.elapsed() >= tick_interval)
+            {
+                self.tick();
+                last_tick = Instant::now();
             }
         }
     }
+
+    fn dispatch_ready(&mut self) -> Result<usize, ControllerError> {
+        self.processor.set_timeout(Some(Duration::ZERO));
+        let events = self.processor.dispatch(&mut self.dx, &mut self.dy)?;
+        let events = self._collapse_repeated_events(events);
+        let count = events.len();
+        self._handle_action_events(events);
+
+        Ok(count)
+    }
+
+    fn raw_fd(&self) -> Option<RawFd> {
+        self.processor.raw_fd()
+    }
+
+    fn tick(&mut self) {
+        self._update_active_profile();
+        flush_warnings();
+    }
+
+    fn pause(&mut self) {
+        info!("Pausing the execution of actions");
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .paused = true;
+    }
+
+    fn resume(&mut self) {
+        info!("Resuming the execution of actions");
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .paused = false;
+    }
+
+    fn is_paused(&self) -> bool {
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .paused
+    }
+
+    fn update_processor_settings(&mut self, settings: ProcessorSettings) {
+        info!("Updating the processor settings");
+        self.processor.update_settings(settings);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{HashMap, HashSet};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[cfg(feature = "command")]
+    use super::format_status_table;
+    use super::{collapse_repeated_events, DefaultController};
+    #[cfg(feature = "command")]
+    use crate::actions::{Action, CommandAction};
+    #[cfg(feature = "command")]
+    use crate::controllers::GestureSequence;
+    use crate::controllers::{Controller, ControllerState, Schedule};
+    use crate::events::ActionEvent;
+    #[cfg(feature = "command")]
+    use crate::events::{DefaultProcessor, GestureDevice};
+
+    use chrono::NaiveTime;
+    use serial_test::serial;
+
+    #[test]
+    /// Test collapsing repeated events.
+    fn test_collapse_repeated_events() {
+        let events = vec![
+            ActionEvent::ThreeFingerSwipeRight,
+            ActionEvent::ThreeFingerSwipeRight,
+            ActionEvent::ThreeFingerSwipeLeft,
+            ActionEvent::ThreeFingerSwipeRight,
+            ActionEvent::ThreeFingerSwipeLeft,
+        ];
+
+        // Keep a single event of each run.
+        assert_eq!(
+            collapse_repeated_events(events.clone(), 1, &HashSet::new()),
+            vec![
+                ActionEvent::ThreeFingerSwipeRight,
+                ActionEvent::ThreeFingerSwipeLeft,
+                ActionEvent::ThreeFingerSwipeRight,
+                ActionEvent::ThreeFingerSwipeLeft
+            ]
+        );
+
+        // Keep up to two events of each run.
+        let events = vec![
+            ActionEvent::ThreeFingerSwipeRight,
+            ActionEvent::ThreeFingerSwipeRight,
+            ActionEvent::ThreeFingerSwipeRight,
+            ActionEvent::ThreeFingerSwipeLeft,
+        ];
+        assert_eq!(
+            collapse_repeated_events(events.clone(), 2, &HashSet::new()),
+            vec![
+                ActionEvent::ThreeFingerSwipeRight,
+                ActionEvent::ThreeFingerSwipeRight,
+                ActionEvent::ThreeFingerSwipeLeft
+            ]
+        );
+
+        // Keep all the exempt events.
+        assert_eq!(
+            collapse_repeated_events(
+                events.clone(),
+                1,
+                &HashSet::from([ActionEvent::ThreeFingerSwipeRight])
+            ),
+            events
+        );
+    }
+
+    #[test]
+    /// Test that non-consecutive identical events are not collapsed.
+    fn test_collapse_non_consecutive_events() {
+        let events = vec![
+            ActionEvent::ThreeFingerSwipeLeft,
+            ActionEvent::ThreeFingerSwipeRight,
+            ActionEvent::ThreeFingerSwipeLeft,
+        ];
+
+        assert_eq!(
+            collapse_repeated_events(events.clone(), 1, &HashSet::new()),
+            events
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "command")]
+    /// Test formatting the events and their actions as a table.
+    fn test_format_status_table() {
+        let mut actions = HashMap::new();
+        let actions_list: Vec<Box<dyn Action>> = vec![
+            Box::new(CommandAction::new(String::from("echo foo"))),
+            Box::new(CommandAction::new(String::from("echo bar"))),
+        ];
+        actions.insert(ActionEvent::ThreeFingerSwipeUp, actions_list);
+
+        // Assert over the header and the rows of bound and unbound events.
+        let table = format_status_table(&actions, false);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 17);
+        assert_eq!(
+            lines[0],
+            "gesture                        actions                                 status"
+        );
+        assert_eq!(
+            lines[1],
+            "three-finger-swipe-left        -                                       unbound"
+        );
+        assert_eq!(
+            lines[3],
+            "three-finger-swipe-up          command:<echo foo>, command:<echo bar>  ready"
+        );
+
+        // Assert that only the status is highlighted when colored.
+        let table = format_status_table(&actions, true);
+        assert!(table
+            .lines()
+            .nth(3)
+            .unwrap()
+            .ends_with("\x1b[32mready\x1b[0m"));
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(feature = "command")]
+    /// Test the introspection of the actions and processor settings.
+    fn test_iter_actions() {
+        let mut controller = DefaultController::default();
+        for action_event in [
+            ActionEvent::FourFingerSwipeUp,
+            ActionEvent::ThreeFingerSwipeLeft,
+        ] {
+            let actions_list: Vec<Box<dyn Action>> =
+                vec![Box::new(CommandAction::new(format!("echo {action_event}")))];
+            controller.actions.insert(action_event, actions_list);
+        }
+
+        // Assert that the events are returned in order, along their actions.
+        let actions: Vec<(ActionEvent, String)> = controller
+            .iter_actions()
+            .map(|(action_event, actions)| (action_event, actions[0].to_string()))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                (
+                    ActionEvent::ThreeFingerSwipeLeft,
+                    String::from("command:<echo three-finger-swipe-left>")
+                ),
+                (
+                    ActionEvent::FourFingerSwipeUp,
+                    String::from("command:<echo four-finger-swipe-up>")
+                ),
+            ]
+        );
+
+        let processor_settings = controller.processor_settings();
+        assert!((processor_settings.threshold - 5.0).abs() < f64::EPSILON);
+        assert!(!processor_settings.invert_x);
+        assert_eq!(processor_settings.disable_while_typing, None);
+    }
+
+    #[test]
+    #[serial]
+    /// Test updating the processor settings while running.
+    fn test_update_processor_settings() {
+        let mut controller = DefaultController::default();
+        let mut processor_settings = controller.processor_settings();
+        processor_settings.threshold = 20.0;
+        processor_settings.invert_y = true;
+        processor_settings
+            .event_thresholds
+            .insert(ActionEvent::ThreeFingerSwipeUp, 10.0);
+        controller.update_processor_settings(processor_settings);
+
+        let processor_settings = controller.processor_settings();
+        assert!((processor_settings.threshold - 20.0).abs() < f64::EPSILON);
+        assert!(!processor_settings.invert_x);
+        assert!(processor_settings.invert_y);
+        assert_eq!(
+            processor_settings.event_thresholds,
+            HashMap::from([(ActionEvent::ThreeFingerSwipeUp, 10.0)])
+        );
+    }
+
+    #[test]
+    #[serial]
+    /// Test returning from the main loop once its bounds are reached.
+    fn test_run_bounds() {
+        let mut controller = DefaultController {
+            max_events: Some(0),
+            ..DefaultController::default()
+        };
+        assert!(controller.run().is_ok());
+
+        let mut controller = DefaultController {
+            run_duration: Some(Duration::ZERO),
+            ..DefaultController::default()
+        };
+        assert!(controller.run().is_ok());
+
+        let controller_state = ControllerState {
+            reload_requested: true,
+            ..ControllerState::default()
+        };
+        let mut controller = DefaultController {
+            state: Arc::new(Mutex::new(controller_state)),
+            ..DefaultController::default()
+        };
+        assert!(controller.run().is_ok());
+    }
+
+    #[test]
+    #[serial]
+    /// Test emitting the hint for events without actions only once.
+    fn test_unbound_event_hint() {
+        let mut controller = DefaultController::default();
+        controller
+            .actions
+            .insert(ActionEvent::FourFingerSwipeUp, vec![]);
+
+        for _ in 0..2 {
+            controller._handle_action_event(ActionEvent::ThreeFingerSwipeUp);
+            controller._handle_action_event(ActionEvent::FourFingerSwipeUp);
+        }
+        assert_eq!(
+            controller.hinted_events,
+            HashSet::from([
+                ActionEvent::ThreeFingerSwipeUp,
+                ActionEvent::FourFingerSwipeUp
+            ])
+        );
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(feature = "command")]
+    /// Test recording the latest activity in the controller state.
+    fn test_state_activity() {
+        let mut controller = DefaultController::default();
+        let actions_list: Vec<Box<dyn Action>> =
+            vec![Box::new(CommandAction::new(String::from("true")))];
+        controller
+            .actions
+            .insert(ActionEvent::ThreeFingerSwipeUp, actions_list);
+
+        controller._handle_action_event(ActionEvent::ThreeFingerSwipeUp);
+        let state = controller.state.lock().unwrap();
+        assert_eq!(state.last_event, Some(ActionEvent::ThreeFingerSwipeUp));
+        assert_eq!(state.current_action, None);
+    }
+
+    #[test]
+    #[serial]
+    /// Test switching the scheduled profiles on ticks.
+    fn test_tick_updates_profile() {
+        let mut controller = DefaultController {
+            schedules: vec![Schedule {
+                profile: String::from("always"),
+                start: NaiveTime::MIN,
+                end: NaiveTime::from_hms_nano_opt(23, 59, 59, 999_999_999).unwrap(),
+                days: vec![],
+            }],
+            ..Default::default()
+        };
+        assert_eq!(controller.active_profile, None);
+
+        controller.tick();
+        assert_eq!(controller.active_profile.as_deref(), Some("always"));
+
+        // Assert that the profile selected manually takes precedence.
+        controller.state.lock().unwrap().profile_override = Some(String::from("manual"));
+        controller.tick();
+        assert_eq!(controller.active_profile.as_deref(), Some("manual"));
+        controller.state.lock().unwrap().profile_override = Some(String::from("default"));
+        controller.tick();
+        assert_eq!(controller.active_profile, None);
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(feature = "command")]
+    /// Test executing the actions of the completed sequences.
+    fn test_sequence_actions() {
+        let actions_list: Vec<Box<dyn Action>> =
+            vec![Box::new(CommandAction::new(String::from("true")))];
+        let mut controller = DefaultController {
+            sequences: vec![GestureSequence {
+                events: vec![
+                    ActionEvent::ThreeFingerSwipeUp,
+                    ActionEvent::ThreeFingerSwipeDown,
+                ],
+                timeout: Duration::from_secs(60),
+                actions: actions_list,
+            }],
+            ..Default::default()
+        };
+
+        // Assert that the events of the sequence without actions are
+        // discarded until the sequence is completed.
+        assert!(controller
+            .process_action_event(ActionEvent::ThreeFingerSwipeDown)
+            .is_err());
+        assert!(controller
+            .process_action_event(ActionEvent::ThreeFingerSwipeUp)
+            .is_err());
+        assert!(controller
+            .process_action_event(ActionEvent::ThreeFingerSwipeDown)
+            .is_ok());
+        assert!(controller.recent_events.is_empty());
+
+        // Assert that the latest events are kept up to the sequence length.
+        for _ in 0..3 {
+            let _ = controller.process_action_event(ActionEvent::ThreeFingerSwipeUp);
+        }
+        assert_eq!(controller.recent_events.len(), 2);
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(feature = "command")]
+    /// Test using the actions of the device performing the gesture.
+    fn test_device_actions() {
+        let actions_list: Vec<Box<dyn Action>> =
+            vec![Box::new(CommandAction::new(String::from("true")))];
+        let processor = DefaultProcessor {
+            current_gesture_device: Some(GestureDevice {
+                name: String::from("External Touchpad"),
+                sysname: String::from("event5"),
+            }),
+            ..DefaultProcessor::default()
+        };
+        let mut controller = DefaultController {
+            device_actions: HashMap::from([(
+                String::from("external touchpad"),
+                HashMap::from([(ActionEvent::ThreeFingerSwipeUp, actions_list)]),
+            )]),
+            ..DefaultController::new(Box::new(processor), HashMap::new())
+        };
+
+        // Assert that only the events of the matching device have actions.
+        assert!(controller
+            .process_action_event(ActionEvent::ThreeFingerSwipeUp)
+            .is_ok());
+        assert!(controller
+            .process_action_event(ActionEvent::ThreeFingerSwipeDown)
+            .is_err());
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(feature = "command")]
+    /// Test using the actions of the seat performing the gesture.
+    fn test_seat_actions() {
+        let actions_list: Vec<Box<dyn Action>> =
+            vec![Box::new(CommandAction::new(String::from("true")))];
+        let mut controller = DefaultController {
+            seat_actions: HashMap::from([(
+                String::from("seat0"),
+                HashMap::from([(ActionEvent::ThreeFingerSwipeUp, actions_list)]),
+            )]),
+            ..DefaultController::new(Box::new(DefaultProcessor::default()), HashMap::new())
+        };
+
+        // Assert that only the events of the seat have actions.
+        assert!(controller
+            .process_action_event(ActionEvent::ThreeFingerSwipeUp)
+            .is_ok());
+        assert!(controller
+            .process_action_event(ActionEvent::ThreeFingerSwipeDown)
+            .is_err());
+    }
 }
@@ -4,17 +4,32 @@
 
 use crate::events::errors::{LibinputError, ProcessorError};
 use crate::events::ActionEvent;
+use strum::IntoStaticStr;
 use thiserror::Error;
 
 /// Errors raised during processing of events in the [`Controller`].
 ///
 /// [`Controller`]: crate::controllers::Controller
-#[derive(Error, Debug)]
+#[derive(Error, Debug, IntoStaticStr)]
+#[strum(serialize_all = "kebab_case")]
+#[non_exhaustive]
 pub enum ControllerError {
     /// No actions registered for event.
     #[error("no actions registered for event {0}")]
     NoActionsRegistered(ActionEvent),
 
+    /// Execution of actions is paused.
+    #[error("execution of actions is paused")]
+    Paused,
+
+    /// Event repeated within the rate limit interval.
+    #[error("event {0} repeated within the rate limit interval")]
+    RateLimited(ActionEvent),
+
+    /// Focused window is fullscreen.
+    #[error("focused window is fullscreen")]
+    FullscreenWindow,
+
     /// Error raised by the event processor.
     #[error("unknown error from the event processor")]
     ProcessorError(#[from] ProcessorError),
@@ -23,3 +38,12 @@ pub enum ControllerError {
     #[error("unknown error during libinput initialization")]
     LibinputError(#[from] LibinputError),
 }
+
+impl ControllerError {
+    /// Return the stable code of the error, as the kebab-case name of the
+    /// variant.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        self.into()
+    }
+}
@@ -0,0 +1,307 @@
+//! Filters applied to [`ActionEvent`]s before processing them.
+
+use std::collections::HashMap;
+use std::fmt;
+#[cfg(feature = "i3")]
+use std::sync::PoisonError;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "i3")]
+use crate::actions::SharedConnection;
+use crate::controllers::errors::ControllerError;
+use crate::events::ActionEvent;
+
+#[cfg(feature = "i3")]
+use i3ipc::reply::{Node, NodeType};
+#[cfg(feature = "i3")]
+use log::debug;
+
+/// Filter that decides whether an [`ActionEvent`] is processed.
+///
+/// Filters are applied in order by the controller before processing each
+/// event, allowing behaviors that apply to all the events to be composed.
+pub trait EventFilter: fmt::Debug + Send {
+    /// Check whether an [`ActionEvent`] should be processed.
+    ///
+    /// # Arguments
+    ///
+    /// * `action_event` - the [`ActionEvent`] to check.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the event should be discarded, describing the reason.
+    fn filter(&mut self, action_event: ActionEvent) -> Result<(), ControllerError>;
+}
+
+/// Filter that discards the events while the focused `i3` window is
+/// fullscreen.
+#[cfg(feature = "i3")]
+#[derive(Debug)]
+pub struct FullscreenFilter {
+    /// `i3` RPC connection.
+    connection: SharedConnection,
+}
+
+#[cfg(feature = "i3")]
+impl FullscreenFilter {
+    /// Create a new [`FullscreenFilter`].
+    ///
+    /// # Arguments
+    ///
+    /// * `connection` - `i3` RPC connection.
+    #[must_use]
+    pub fn new(connection: SharedConnection) -> Self {
+        FullscreenFilter { connection }
+    }
+
+    /// Return whether the focused window is fullscreen.
+    ///
+    /// If the `i3` tree could not be retrieved, the window is considered not
+    /// fullscreen.
+    fn is_fullscreen(&self) -> bool {
+        let connection_option = &mut *self
+            .connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let Some(connection) = connection_option else {
+            return false;
+        };
+
+        match connection.get_tree() {
+            Ok(tree) => is_focused_fullscreen(&tree, None).unwrap_or(false),
+            Err(e) => {
+                debug!("Unable to retrieve i3 tree: {e}");
+                false
+            }
+        }
+    }
+}
+
+#[cfg(feature = "i3")]
+impl EventFilter for FullscreenFilter {
+    fn filter(&mut self, _action_event: ActionEvent) -> Result<(), ControllerError> {
+        if self.is_fullscreen() {
+            return Err(ControllerError::FullscreenWindow);
+        }
+
+        Ok(())
+    }
+}
+
+/// Filter that discards the events repeated within a time interval.
+#[derive(Debug)]
+pub struct RateLimitFilter {
+    /// Minimum time between two occurrences of the same event.
+    min_interval: Duration,
+    /// Minimum time between two occurrences of specific events, overriding
+    /// the global interval.
+    event_intervals: HashMap<ActionEvent, Duration>,
+    /// Time of the latest accepted occurrence of each event.
+    last_times: HashMap<ActionEvent, Instant>,
+}
+
+impl RateLimitFilter {
+    /// Create a new [`RateLimitFilter`].
+    ///
+    /// # Arguments
+    ///
+    /// * `min_interval` - minimum time between two occurrences of the same
+    ///   event.
+    #[must_use]
+    pub fn new(min_interval: Duration) -> Self {
+        RateLimitFilter {
+            min_interval,
+            event_intervals: HashMap::new(),
+            last_times: HashMap::new(),
+        }
+    }
+
+    /// Set the minimum time between two occurrences of a specific event,
+    /// overriding the global interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `action_event` - the event.
+    /// * `min_interval` - minimum time between two occurrences of the event.
+    pub fn set_event_interval(&mut self, action_event: ActionEvent, min_interval: Duration) {
+        self.event_intervals.insert(action_event, min_interval);
+    }
+}
+
+impl EventFilter for RateLimitFilter {
+    fn filter(&mut self, action_event: ActionEvent) -> Result<(), ControllerError> {
+        let now = Instant::now();
+        let min_interval = self
+            .event_intervals
+            .get(&action_event)
+            .unwrap_or(&self.min_interval);
+        if let Some(last_time) = self.last_times.get(&action_event) {
+            if now.duration_since(*last_time) < *min_interval {
+                return Err(ControllerError::RateLimited(action_event));
+            }
+        }
+
+        self.last_times.insert(action_event, now);
+        Ok(())
+    }
+}
+
+/// Return whether the focused window in an `i3` tree is fullscreen.
+///
+/// As the `i3` tree does not include the fullscreen mode of the nodes, a
+/// window is considered fullscreen if it covers its whole output.
+///
+/// # Arguments
+///
+/// * `node` - the `i3` tree node to inspect.
+/// * `output_rect` - the rectangle of the output containing the node, if any.
+///
+/// # Returns
+///
+/// `None` if the focused window is not found in the node.
+#[cfg(feature = "i3")]
+fn is_focused_fullscreen(node: &Node, output_rect: Option<(i32, i32, i32, i32)>) -> Option<bool> {
+    if node.focused {
+        return Some(node.window.is_some() && Some(node.rect) == output_rect);
+    }
+
+    let output_rect = if node.nodetype == NodeType::Output {
+        Some(node.rect)
+    } else {
+        output_rect
+    };
+    node.nodes
+        .iter()
+        .chain(node.floating_nodes.iter())
+        .find_map(|x| is_focused_fullscreen(x, output_rect))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    #[cfg(feature = "i3")]
+    use super::is_focused_fullscreen;
+    use super::{EventFilter, RateLimitFilter};
+    use crate::controllers::ControllerError;
+    use crate::events::ActionEvent;
+
+    #[cfg(feature = "i3")]
+    use i3ipc::reply::{Node, NodeBorder, NodeLayout, NodeType};
+
+    /// Create a `i3` tree node for testing.
+    ///
+    /// # Arguments
+    ///
+    /// * `nodetype` - the type of the node.
+    /// * `rect` - the rectangle of the node.
+    /// * `focused` - whether the node is focused.
+    /// * `nodes` - the children of the node.
+    #[cfg(feature = "i3")]
+    fn create_node(
+        nodetype: NodeType,
+        rect: (i32, i32, i32, i32),
+        focused: bool,
+        nodes: Vec<Node>,
+    ) -> Node {
+        Node {
+            focus: vec![],
+            nodes,
+            floating_nodes: vec![],
+            id: 0,
+            name: None,
+            window: if nodetype == NodeType::Con {
+                Some(1)
+            } else {
+                None
+            },
+            nodetype,
+            border: NodeBorder::None,
+            current_border_width: 0,
+            layout: NodeLayout::SplitH,
+            percent: None,
+            rect,
+            window_rect: rect,
+            deco_rect: (0, 0, 0, 0),
+            geometry: rect,
+            window_properties: None,
+            urgent: false,
+            focused,
+        }
+    }
+
+    #[test]
+    /// Test the detection of fullscreen focused windows.
+    #[cfg(feature = "i3")]
+    fn test_is_focused_fullscreen() {
+        let output_rect = (0, 0, 1920, 1080);
+        let workspace_rect = (0, 20, 1920, 1060);
+
+        // Create a tree with a window covering the whole output.
+        let tree = create_node(
+            NodeType::Root,
+            output_rect,
+            false,
+            vec![create_node(
+                NodeType::Output,
+                output_rect,
+                false,
+                vec![create_node(
+                    NodeType::Workspace,
+                    workspace_rect,
+                    false,
+                    vec![create_node(NodeType::Con, output_rect, true, vec![])],
+                )],
+            )],
+        );
+        assert_eq!(is_focused_fullscreen(&tree, None), Some(true));
+
+        // Create a tree with a window covering the workspace.
+        let tree = create_node(
+            NodeType::Root,
+            output_rect,
+            false,
+            vec![create_node(
+                NodeType::Output,
+                output_rect,
+                false,
+                vec![create_node(
+                    NodeType::Workspace,
+                    workspace_rect,
+                    false,
+                    vec![create_node(NodeType::Con, workspace_rect, true, vec![])],
+                )],
+            )],
+        );
+        assert_eq!(is_focused_fullscreen(&tree, None), Some(false));
+
+        // Create a tree without a focused window.
+        let tree = create_node(NodeType::Root, output_rect, false, vec![]);
+        assert_eq!(is_focused_fullscreen(&tree, None), None);
+    }
+
+    #[test]
+    /// Test discarding events repeated within the interval.
+    fn test_rate_limit_filter() {
+        let mut filter = RateLimitFilter::new(Duration::from_secs(30));
+
+        assert!(filter.filter(ActionEvent::ThreeFingerSwipeLeft).is_ok());
+        assert!(filter.filter(ActionEvent::ThreeFingerSwipeRight).is_ok());
+        assert!(matches!(
+            filter.filter(ActionEvent::ThreeFingerSwipeLeft),
+            Err(ControllerError::RateLimited(
+                ActionEvent::ThreeFingerSwipeLeft
+            ))
+        ));
+
+        let mut filter = RateLimitFilter::new(Duration::ZERO);
+        assert!(filter.filter(ActionEvent::ThreeFingerSwipeLeft).is_ok());
+        assert!(filter.filter(ActionEvent::ThreeFingerSwipeLeft).is_ok());
+
+        // Assert that the interval of specific events overrides the global one.
+        filter.set_event_interval(ActionEvent::ThreeFingerSwipeUp, Duration::from_secs(30));
+        assert!(filter.filter(ActionEvent::ThreeFingerSwipeUp).is_ok());
+        assert!(filter.filter(ActionEvent::ThreeFingerSwipeUp).is_err());
+        assert!(filter.filter(ActionEvent::ThreeFingerSwipeLeft).is_ok());
+    }
+}
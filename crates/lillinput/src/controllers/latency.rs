@@ -0,0 +1,112 @@
+//! Tracking of the end-to-end latency of the gestures.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::events::GestureTiming;
+
+/// Latency of a single gesture, measured from the time `libinput` reported
+/// its end.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GestureLatency {
+    /// Time until the gesture was recognized by the processor.
+    pub recognition: Duration,
+    /// Time until the actions of the gesture completed.
+    pub completion: Duration,
+}
+
+impl GestureLatency {
+    /// Return the [`GestureLatency`] of a gesture whose actions completed.
+    ///
+    /// # Arguments
+    ///
+    /// * `timing` - the timing of the gesture, as recorded by the processor.
+    /// * `completed_usec` - the monotonic time (in microseconds) at which the
+    ///   actions completed.
+    #[must_use]
+    pub fn new(timing: &GestureTiming, completed_usec: u64) -> Self {
+        GestureLatency {
+            recognition: Duration::from_micros(
+                timing.recognized_usec.saturating_sub(timing.event_usec),
+            ),
+            completion: Duration::from_micros(completed_usec.saturating_sub(timing.event_usec)),
+        }
+    }
+}
+
+impl fmt::Display for GestureLatency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "recognized after {:.1}ms, actions completed after {:.1}ms",
+            self.recognition.as_secs_f64() * 1000.0,
+            self.completion.as_secs_f64() * 1000.0
+        )
+    }
+}
+
+/// Aggregated latency of the gestures processed by a controller.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct LatencyStats {
+    /// Number of gestures measured.
+    pub count: u32,
+    /// Sum of the completion latencies of the gestures.
+    pub total: Duration,
+    /// Maximum completion latency of the gestures.
+    pub max: Duration,
+    /// Latency of the latest gesture.
+    pub last: Option<GestureLatency>,
+}
+
+impl LatencyStats {
+    /// Record the latency of a gesture.
+    ///
+    /// # Arguments
+    ///
+    /// * `latency` - the latency of the gesture.
+    pub fn record(&mut self, latency: GestureLatency) {
+        self.count += 1;
+        self.total += latency.completion;
+        self.max = self.max.max(latency.completion);
+        self.last = Some(latency);
+    }
+
+    /// Return the mean completion latency of the gestures, if any.
+    #[must_use]
+    pub fn mean(&self) -> Option<Duration> {
+        (self.count > 0).then(|| self.total / self.count)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{GestureLatency, LatencyStats};
+    use crate::events::GestureTiming;
+
+    #[test]
+    /// Test measuring and aggregating the latency of gestures.
+    fn test_latency_stats() {
+        let timing = GestureTiming {
+            event_usec: 1_000,
+            recognized_usec: 1_500,
+        };
+        let latency = GestureLatency::new(&timing, 4_000);
+        assert_eq!(latency.recognition, Duration::from_micros(500));
+        assert_eq!(latency.completion, Duration::from_micros(3_000));
+        assert_eq!(
+            latency.to_string(),
+            "recognized after 0.5ms, actions completed after 3.0ms"
+        );
+
+        let mut stats = LatencyStats::default();
+        assert_eq!(stats.mean(), None);
+        stats.record(latency);
+        stats.record(GestureLatency::new(&timing, 2_000));
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.mean(), Some(Duration::from_micros(2_000)));
+        assert_eq!(stats.max, Duration::from_micros(3_000));
+        assert_eq!(stats.last.unwrap().completion, Duration::from_micros(1_000));
+    }
+}
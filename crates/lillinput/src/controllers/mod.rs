@@ -2,13 +2,82 @@
 //!
 //! [`Action`]: crate::actions::Action
 
+pub mod audit;
 pub mod defaultcontroller;
 pub mod errors;
+pub mod filters;
+pub mod latency;
+pub mod queue;
+pub mod schedule;
+pub mod sequence;
+pub mod supervisor;
 
+pub use crate::controllers::audit::AuditLog;
 pub use crate::controllers::defaultcontroller::DefaultController;
 pub use crate::controllers::errors::ControllerError;
+#[cfg(feature = "i3")]
+pub use crate::controllers::filters::FullscreenFilter;
+pub use crate::controllers::filters::{EventFilter, RateLimitFilter};
+pub use crate::controllers::latency::{GestureLatency, LatencyStats};
+pub use crate::controllers::queue::{EventQueue, OverflowPolicy};
+pub use crate::controllers::schedule::Schedule;
+pub use crate::controllers::sequence::GestureSequence;
+pub use crate::controllers::supervisor::{ControllerFactory, ControllerStats, Supervisor};
 
-use crate::events::ActionEvent;
+use std::collections::HashMap;
+use std::os::unix::io::RawFd;
+use std::sync::{Arc, Mutex};
+
+use crate::actions::Action;
+use crate::events::{ActionEvent, ProcessorSettings};
+
+use strum::{Display, EnumString, EnumVariantNames};
+
+/// Map between events and the [`Action`]s they trigger.
+pub type ActionMap = HashMap<ActionEvent, Vec<Box<dyn Action>>>;
+
+/// State of a [`Controller`], shared with the [`Action`]s that act on it.
+///
+/// Along with the state used by the actions, it keeps track of the latest
+/// activity of the controller, allowing it to be reported on failures.
+#[derive(Debug, Default)]
+pub struct ControllerState {
+    /// Whether the execution of actions is paused.
+    pub paused: bool,
+    /// Latest event received for processing.
+    pub last_event: Option<ActionEvent>,
+    /// Action being executed, in printable form.
+    pub current_action: Option<String>,
+    /// Latency of the gestures whose actions were executed.
+    pub latency: LatencyStats,
+    /// Profile selected manually, taking precedence over the schedules.
+    ///
+    /// [`DEFAULT_PROFILE`] selects the actions outside of any profile.
+    pub profile_override: Option<String>,
+    /// Whether a reload of the configuration was requested, stopping the
+    /// main loop of the controller so it can be recreated.
+    pub reload_requested: bool,
+}
+
+/// Name of the profile selecting the actions outside of any profile.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Shared controller state.
+pub type SharedControllerState = Arc<Mutex<ControllerState>>;
+
+/// Possible choices for executing the actions associated with an event.
+#[derive(Copy, Clone, Debug, Default, Display, EnumString, EnumVariantNames, Eq, PartialEq)]
+#[strum(serialize_all = "kebab_case")]
+pub enum ExecutionMode {
+    /// Execute the actions one after the other, in order.
+    #[default]
+    Sequential,
+    /// Start all the actions at once, waiting for them to finish afterwards.
+    Parallel,
+    /// Execute the actions one after the other, in order, skipping the
+    /// remaining ones if an action fails.
+    Chain,
+}
 
 /// Controller that connects events and actions.
 pub trait Controller {
@@ -33,4 +102,57 @@ pub trait Controller {
     /// Returns `Err` if the main loop encountered an error while polling or
     /// dispatching events.
     fn run(&mut self) -> Result<(), ControllerError>;
+
+    /// Dispatch the pending events and invoke their [`Action`]s, without
+    /// waiting for new ones.
+    ///
+    /// Intended for driving the controller from an external loop, calling it
+    /// when the file descriptor returned by [`Controller::raw_fd`] is ready
+    /// for reading.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if an error was encountered while dispatching events.
+    ///
+    /// # Returns
+    ///
+    /// The number of [`ActionEvent`]s received.
+    ///
+    /// [`Action`]: crate::actions::Action
+    fn dispatch_ready(&mut self) -> Result<usize, ControllerError>;
+
+    /// Return the file descriptor that becomes readable when events are
+    /// pending, if the controller can be driven from an external loop.
+    fn raw_fd(&self) -> Option<RawFd> {
+        None
+    }
+
+    /// Perform the periodic tasks of the controller.
+    ///
+    /// Ticks are delivered periodically by the main loop if a tick interval
+    /// is configured, even if no events are received.
+    fn tick(&mut self);
+
+    /// Pause the execution of actions.
+    ///
+    /// While paused, the `libinput` events are still processed in order to
+    /// avoid a backlog, but only the actions that run while paused (such as
+    /// the ones for resuming) are executed.
+    fn pause(&mut self);
+
+    /// Resume the execution of actions.
+    fn resume(&mut self);
+
+    /// Return `true` if the execution of actions is paused.
+    fn is_paused(&self) -> bool;
+
+    /// Update the parameters used by the processor for recognizing gestures.
+    ///
+    /// Allows tuning the recognition while running (for example, on
+    /// configuration reloads), without recreating the processor.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - the new parameters.
+    fn update_processor_settings(&mut self, settings: ProcessorSettings);
 }
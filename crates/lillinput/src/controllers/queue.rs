@@ -0,0 +1,189 @@
+//! Bounded queue of [`ActionEvent`]s pending execution.
+
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex, PoisonError};
+
+use crate::events::ActionEvent;
+
+use strum::{Display, EnumString, EnumVariantNames};
+
+/// Possible choices for handling events received while the queue is full.
+#[derive(Copy, Clone, Debug, Default, Display, EnumString, EnumVariantNames, Eq, PartialEq)]
+#[strum(serialize_all = "kebab_case")]
+pub enum OverflowPolicy {
+    /// Discard the oldest event in the queue, keeping the most recent ones.
+    #[default]
+    DropOldest,
+    /// Discard the received event, keeping the ones already in the queue.
+    DropNewest,
+    /// Wait until there is room in the queue.
+    Block,
+}
+
+/// Bounded queue of [`ActionEvent`]s pending execution.
+///
+/// The queue can be shared between the thread processing the events and the
+/// thread executing their actions, limiting the events pending execution if
+/// the latter stalls.
+#[derive(Debug)]
+pub struct EventQueue {
+    /// Maximum number of events in the queue.
+    capacity: usize,
+    /// Policy for handling events received while the queue is full.
+    policy: OverflowPolicy,
+    /// Events in the queue, in order of arrival.
+    events: Mutex<VecDeque<ActionEvent>>,
+    /// Condition notified when an event is removed from the queue.
+    not_full: Condvar,
+}
+
+impl EventQueue {
+    /// Create a new [`EventQueue`].
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - maximum number of events in the queue (at least `1`).
+    /// * `policy` - policy for handling events received while the queue is
+    ///   full.
+    #[must_use]
+    pub fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        EventQueue {
+            capacity: capacity.max(1),
+            policy,
+            events: Mutex::new(VecDeque::new()),
+            not_full: Condvar::new(),
+        }
+    }
+
+    /// Return the policy for handling events received while the queue is
+    /// full.
+    #[must_use]
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    /// Return the number of events in the queue.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.events
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Return `true` if the queue contains no events.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return `true` if the queue is at its capacity.
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.len() >= self.capacity
+    }
+
+    /// Add an event to the queue, applying the overflow policy if full.
+    ///
+    /// With [`OverflowPolicy::Block`], the call waits until an event is
+    /// removed from the queue by a different thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `action_event` - the event to be added.
+    ///
+    /// # Returns
+    ///
+    /// The event discarded due to the queue being full, if any.
+    pub fn push(&self, action_event: ActionEvent) -> Option<ActionEvent> {
+        let mut events = self.events.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut dropped = None;
+
+        if events.len() >= self.capacity {
+            match self.policy {
+                OverflowPolicy::DropOldest => dropped = events.pop_front(),
+                OverflowPolicy::DropNewest => return Some(action_event),
+                OverflowPolicy::Block => {
+                    events = self
+                        .not_full
+                        .wait_while(events, |events| events.len() >= self.capacity)
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+            }
+        }
+
+        events.push_back(action_event);
+        dropped
+    }
+
+    /// Remove the oldest event from the queue, if any.
+    pub fn pop(&self) -> Option<ActionEvent> {
+        let action_event = self
+            .events
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front();
+        if action_event.is_some() {
+            self.not_full.notify_one();
+        }
+
+        action_event
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use std::thread;
+
+    use super::{EventQueue, OverflowPolicy};
+    use crate::events::ActionEvent;
+
+    /// Events used for filling the queues, in order.
+    const EVENTS: [ActionEvent; 3] = [
+        ActionEvent::ThreeFingerSwipeLeft,
+        ActionEvent::ThreeFingerSwipeUp,
+        ActionEvent::ThreeFingerSwipeRight,
+    ];
+
+    #[test]
+    /// Test discarding the oldest events when full.
+    fn test_queue_drop_oldest() {
+        let queue = EventQueue::new(2, OverflowPolicy::DropOldest);
+        let dropped: Vec<Option<ActionEvent>> = EVENTS.iter().map(|x| queue.push(*x)).collect();
+
+        assert_eq!(dropped, vec![None, None, Some(EVENTS[0])]);
+        assert_eq!(queue.pop(), Some(EVENTS[1]));
+        assert_eq!(queue.pop(), Some(EVENTS[2]));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    /// Test discarding the received events when full.
+    fn test_queue_drop_newest() {
+        let queue = EventQueue::new(2, OverflowPolicy::DropNewest);
+        let dropped: Vec<Option<ActionEvent>> = EVENTS.iter().map(|x| queue.push(*x)).collect();
+
+        assert_eq!(dropped, vec![None, None, Some(EVENTS[2])]);
+        assert_eq!(queue.pop(), Some(EVENTS[0]));
+        assert_eq!(queue.pop(), Some(EVENTS[1]));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    /// Test waiting for room in the queue when full.
+    fn test_queue_block() {
+        let queue = Arc::new(EventQueue::new(1, OverflowPolicy::Block));
+        assert_eq!(queue.push(EVENTS[0]), None);
+        assert!(queue.is_full());
+
+        // Push from a different thread, which waits until an event is popped.
+        let producer = {
+            let queue = Arc::clone(&queue);
+            thread::spawn(move || queue.push(EVENTS[1]))
+        };
+        assert_eq!(queue.pop(), Some(EVENTS[0]));
+        assert_eq!(producer.join().unwrap(), None);
+        assert_eq!(queue.pop(), Some(EVENTS[1]));
+    }
+}
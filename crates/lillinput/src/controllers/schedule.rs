@@ -0,0 +1,133 @@
+//! Time-based schedules for activating action profiles.
+
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Weekday};
+
+/// Schedule that activates an action profile during a time window.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Schedule {
+    /// Name of the profile activated by the schedule.
+    pub profile: String,
+    /// Start of the time window (inclusive).
+    pub start: NaiveTime,
+    /// End of the time window (exclusive).
+    ///
+    /// If `end` is earlier than `start`, the window spans midnight.
+    pub end: NaiveTime,
+    /// Days of the week in which the schedule is active.
+    ///
+    /// If empty, the schedule is active every day.
+    pub days: Vec<Weekday>,
+}
+
+impl Schedule {
+    /// Return `true` if the schedule is active at a given date and time.
+    ///
+    /// For windows that span midnight, the day of the week is checked
+    /// against the day the window started.
+    ///
+    /// # Arguments
+    ///
+    /// * `datetime` - the date and time to check.
+    #[must_use]
+    pub fn is_active(&self, datetime: NaiveDateTime) -> bool {
+        let time = datetime.time();
+        let weekday = datetime.weekday();
+
+        let (in_window, window_weekday) = if self.start <= self.end {
+            (self.start <= time && time < self.end, weekday)
+        } else if time >= self.start {
+            (true, weekday)
+        } else {
+            (time < self.end, weekday.pred())
+        };
+
+        in_window && (self.days.is_empty() || self.days.contains(&window_weekday))
+    }
+}
+
+/// Return the profile of the first active schedule, if any.
+///
+/// # Arguments
+///
+/// * `schedules` - the list of schedules, in order of precedence.
+/// * `datetime` - the date and time to check.
+#[must_use]
+pub fn find_active_profile(schedules: &[Schedule], datetime: NaiveDateTime) -> Option<&str> {
+    schedules
+        .iter()
+        .find(|x| x.is_active(datetime))
+        .map(|x| x.profile.as_str())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{find_active_profile, Schedule};
+
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+
+    /// Return a date and time on Monday 2023-01-02.
+    ///
+    /// # Arguments
+    ///
+    /// * `hour` - the hour.
+    /// * `minute` - the minute.
+    fn monday_at(hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, 1, 2)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    /// Test the activation of schedules.
+    fn test_schedule_is_active() {
+        let work = Schedule {
+            profile: String::from("work"),
+            start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+            days: vec![Weekday::Mon, Weekday::Tue],
+        };
+        assert!(work.is_active(monday_at(9, 0)));
+        assert!(!work.is_active(monday_at(17, 0)));
+        assert!(!work.is_active(monday_at(10, 0) + chrono::Duration::days(2)));
+
+        // Assert that windows spanning midnight use the starting day.
+        let night = Schedule {
+            profile: String::from("night"),
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+            days: vec![Weekday::Sun],
+        };
+        assert!(night.is_active(monday_at(1, 0)));
+        assert!(!night.is_active(monday_at(23, 0)));
+    }
+
+    #[test]
+    /// Test finding the active profile from a list of schedules.
+    fn test_find_active_profile() {
+        let schedules = vec![
+            Schedule {
+                profile: String::from("lunch"),
+                start: NaiveTime::from_hms_opt(13, 0, 0).unwrap(),
+                end: NaiveTime::from_hms_opt(14, 0, 0).unwrap(),
+                days: vec![],
+            },
+            Schedule {
+                profile: String::from("work"),
+                start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                end: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+                days: vec![],
+            },
+        ];
+
+        assert_eq!(
+            find_active_profile(&schedules, monday_at(13, 30)),
+            Some("lunch")
+        );
+        assert_eq!(
+            find_active_profile(&schedules, monday_at(10, 0)),
+            Some("work")
+        );
+        assert_eq!(find_active_profile(&schedules, monday_at(20, 0)), None);
+    }
+}
@@ -0,0 +1,149 @@
+//! Recognition of sequences of gestures performed in quick succession.
+
+use std::cmp::Reverse;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::actions::Action;
+use crate::events::ActionEvent;
+
+/// Sequence of [`ActionEvent`]s with its own actions.
+///
+/// The sequence is recognized when its events are received in order, with
+/// each of them following the previous one within the timeout.
+#[derive(Debug)]
+pub struct GestureSequence {
+    /// Events of the sequence, in order.
+    pub events: Vec<ActionEvent>,
+    /// Maximum time between two consecutive events of the sequence.
+    pub timeout: Duration,
+    /// Actions executed when the sequence is recognized.
+    pub actions: Vec<Box<dyn Action>>,
+}
+
+impl GestureSequence {
+    /// Return `true` if the latest events received complete the sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `history` - the latest events received, along with the time they
+    ///   were received, in order.
+    #[must_use]
+    pub fn matches(&self, history: &[(ActionEvent, Instant)]) -> bool {
+        if self.events.is_empty() || history.len() < self.events.len() {
+            return false;
+        }
+
+        let recent = &history[history.len() - self.events.len()..];
+        recent
+            .iter()
+            .zip(&self.events)
+            .all(|((received, _), expected)| received == expected)
+            && recent
+                .windows(2)
+                .all(|pair| pair[1].1.duration_since(pair[0].1) <= self.timeout)
+    }
+}
+
+impl fmt::Display for GestureSequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let events: Vec<String> = self.events.iter().map(ToString::to_string).collect();
+        write!(f, "{}", events.join(" > "))
+    }
+}
+
+/// Return the index of the sequence completed by the latest events received.
+///
+/// If several sequences are completed, the longest one is returned, with
+/// ties resolved in favor of the earliest one.
+///
+/// # Arguments
+///
+/// * `sequences` - the list of sequences.
+/// * `history` - the latest events received, along with the time they were
+///   received, in order.
+#[must_use]
+pub fn find_completed_sequence(
+    sequences: &[GestureSequence],
+    history: &[(ActionEvent, Instant)],
+) -> Option<usize> {
+    sequences
+        .iter()
+        .enumerate()
+        .filter(|(_, sequence)| sequence.matches(history))
+        .max_by_key(|(index, sequence)| (sequence.events.len(), Reverse(*index)))
+        .map(|(index, _)| index)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use super::{find_completed_sequence, GestureSequence};
+    use crate::events::ActionEvent;
+
+    /// Return a [`GestureSequence`] without actions.
+    fn sequence(events: &[ActionEvent], timeout_ms: u64) -> GestureSequence {
+        GestureSequence {
+            events: events.to_vec(),
+            timeout: Duration::from_millis(timeout_ms),
+            actions: vec![],
+        }
+    }
+
+    #[test]
+    /// Test recognizing sequences from the latest events received.
+    fn test_find_completed_sequence() {
+        let sequences = [
+            sequence(
+                &[
+                    ActionEvent::ThreeFingerSwipeUp,
+                    ActionEvent::ThreeFingerSwipeDown,
+                ],
+                500,
+            ),
+            sequence(
+                &[
+                    ActionEvent::ThreeFingerSwipeLeft,
+                    ActionEvent::ThreeFingerSwipeUp,
+                    ActionEvent::ThreeFingerSwipeDown,
+                ],
+                500,
+            ),
+        ];
+        assert_eq!(
+            sequences[0].to_string(),
+            "three-finger-swipe-up > three-finger-swipe-down"
+        );
+
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        // Complete the first sequence, within the timeout.
+        let history = [
+            (ActionEvent::ThreeFingerSwipeRight, at(0)),
+            (ActionEvent::ThreeFingerSwipeUp, at(100)),
+            (ActionEvent::ThreeFingerSwipeDown, at(400)),
+        ];
+        assert_eq!(find_completed_sequence(&sequences, &history), Some(0));
+
+        // Prefer the longest sequence completed.
+        let history = [
+            (ActionEvent::ThreeFingerSwipeLeft, at(0)),
+            (ActionEvent::ThreeFingerSwipeUp, at(100)),
+            (ActionEvent::ThreeFingerSwipeDown, at(400)),
+        ];
+        assert_eq!(find_completed_sequence(&sequences, &history), Some(1));
+
+        // Discard the sequences with events too far apart.
+        let history = [
+            (ActionEvent::ThreeFingerSwipeUp, at(0)),
+            (ActionEvent::ThreeFingerSwipeDown, at(600)),
+        ];
+        assert_eq!(find_completed_sequence(&sequences, &history), None);
+
+        // Discard the incomplete sequences.
+        let history = [(ActionEvent::ThreeFingerSwipeDown, at(0))];
+        assert_eq!(find_completed_sequence(&sequences, &history), None);
+    }
+}
@@ -0,0 +1,449 @@
+//! Supervisor for driving several [`Controller`]s from a single loop.
+
+use std::time::{Duration, Instant};
+
+use crate::controllers::errors::ControllerError;
+use crate::controllers::Controller;
+use crate::events::errors::LibinputError;
+
+use filedescriptor::{poll, pollfd, POLLIN};
+use log::{info, warn};
+
+/// Function that creates a [`Controller`], used for (re)starting it.
+pub type ControllerFactory = Box<dyn FnMut() -> Result<Box<dyn Controller>, ControllerError>>;
+
+/// Statistics of a supervised [`Controller`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ControllerStats {
+    /// Number of events received.
+    pub events: usize,
+    /// Number of failures, including the failed restarts.
+    pub failures: usize,
+    /// Number of successful restarts.
+    pub restarts: usize,
+}
+
+impl ControllerStats {
+    /// Add the statistics of another controller.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - the statistics to add.
+    pub fn merge(&mut self, other: &ControllerStats) {
+        self.events += other.events;
+        self.failures += other.failures;
+        self.restarts += other.restarts;
+    }
+}
+
+/// [`Controller`] owned by a [`Supervisor`].
+struct SupervisedController {
+    /// Name of the controller, for identifying it (for example, the seat).
+    name: String,
+    /// Function for (re)starting the controller.
+    factory: ControllerFactory,
+    /// Running controller, or `None` if it failed.
+    controller: Option<Box<dyn Controller>>,
+    /// Statistics of the controller.
+    stats: ControllerStats,
+    /// Delay before the next restart, if the controller fails.
+    backoff: Duration,
+    /// Time of the next restart, if the controller failed.
+    restart_at: Option<Instant>,
+}
+
+/// Supervisor that drives several [`Controller`]s (for example, one per seat
+/// or backend) from a single loop.
+///
+/// The file descriptors of the controllers are multiplexed, dispatching the
+/// events of each controller when its descriptor is ready. The controllers
+/// that fail are restarted using their factory, waiting an exponentially
+/// increasing delay between attempts.
+///
+/// ```no_run
+/// # use std::collections::HashMap;
+/// # use lillinput::controllers::{Controller, DefaultController, Supervisor};
+/// # use lillinput::events::DefaultProcessor;
+/// let mut supervisor = Supervisor::default();
+/// for seat in ["seat0", "seat1"] {
+///     supervisor.add(
+///         seat,
+///         Box::new(move || {
+///             let processor = DefaultProcessor::new(5.0, seat, false, false)?;
+///             let controller = DefaultController::new(Box::new(processor), HashMap::new());
+///             Ok(Box::new(controller) as Box<dyn Controller>)
+///         }),
+///     )?;
+/// }
+/// supervisor.run()?;
+/// # Ok::<(), lillinput::controllers::ControllerError>(())
+/// ```
+pub struct Supervisor {
+    /// Delay before the first restart of a failed controller.
+    pub initial_backoff: Duration,
+    /// Maximum delay between the restarts of a failed controller.
+    pub max_backoff: Duration,
+    /// Interval between the periodic ticks delivered to the controllers.
+    ///
+    /// If not set, no ticks are delivered.
+    pub tick_interval: Option<Duration>,
+    /// Supervised controllers, in the order they were added.
+    controllers: Vec<SupervisedController>,
+    /// Time of the latest tick.
+    last_tick: Instant,
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Supervisor {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            tick_interval: None,
+            controllers: vec![],
+            last_tick: Instant::now(),
+        }
+    }
+}
+
+impl Supervisor {
+    /// Add a controller to the supervisor, starting it.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the controller (for example, the seat).
+    /// * `factory` - the function for (re)starting the controller.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the controller could not be started.
+    pub fn add(
+        &mut self,
+        name: &str,
+        mut factory: ControllerFactory,
+    ) -> Result<(), ControllerError> {
+        let controller = factory()?;
+        if controller.raw_fd().is_none() {
+            warn!("Controller {name} cannot be polled, and will not receive events");
+        }
+
+        self.controllers.push(SupervisedController {
+            name: name.to_string(),
+            factory,
+            controller: Some(controller),
+            stats: ControllerStats::default(),
+            backoff: self.initial_backoff,
+            restart_at: None,
+        });
+
+        Ok(())
+    }
+
+    /// Return the statistics of each controller, along with its name.
+    pub fn stats(&self) -> impl Iterator<Item = (&str, ControllerStats)> {
+        self.controllers
+            .iter()
+            .map(|supervised| (supervised.name.as_str(), supervised.stats))
+    }
+
+    /// Return the statistics aggregated across the controllers.
+    #[must_use]
+    pub fn total_stats(&self) -> ControllerStats {
+        self.controllers
+            .iter()
+            .fold(ControllerStats::default(), |mut total, supervised| {
+                total.merge(&supervised.stats);
+                total
+            })
+    }
+
+    /// Run the main loop, driving the controllers.
+    ///
+    /// The loop returns immediately if no controllers have been added.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the main loop encountered an error while polling the
+    /// file descriptors. The errors of the controllers are handled by
+    /// restarting them.
+    pub fn run(&mut self) -> Result<(), ControllerError> {
+        if self.controllers.is_empty() {
+            return Ok(());
+        }
+
+        loop {
+            self.run_once()?;
+        }
+    }
+
+    /// Run a single iteration of the main loop.
+    ///
+    /// The due restarts are performed, and the loop waits for any of the
+    /// controllers to be ready (up to the next restart or tick), dispatching
+    /// their events.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if an error was encountered while polling the file
+    /// descriptors.
+    pub fn run_once(&mut self) -> Result<(), ControllerError> {
+        self._restart_due_controllers();
+
+        // Wait for any of the controllers to be ready.
+        let (indices, mut poll_array): (Vec<usize>, Vec<pollfd>) = self
+            .controllers
+            .iter()
+            .enumerate()
+            .filter_map(|(index, supervised)| {
+                let fd = supervised.controller.as_ref()?.raw_fd()?;
+                Some((
+                    index,
+                    pollfd {
+                        fd,
+                        events: POLLIN,
+                        revents: 0,
+                    },
+                ))
+            })
+            .unzip();
+        poll(&mut poll_array, self._next_timeout()).map_err(LibinputError::from)?;
+
+        for (index, entry) in indices.into_iter().zip(poll_array) {
+            if entry.revents != 0 {
+                self._dispatch(index);
+            }
+        }
+
+        // Deliver a tick, if the interval has elapsed.
+        if self
+            .tick_interval
+            .is_some_and(|tick_interval| self.last_tick.elapsed() >= tick_interval)
+        {
+            for controller in self
+                .controllers
+                .iter_mut()
+                .filter_map(|supervised| supervised.controller.as_mut())
+            {
+                controller.tick();
+            }
+            self.last_tick = Instant::now();
+        }
+
+        Ok(())
+    }
+
+    /// Return the maximum time to wait for the controllers, until the next
+    /// restart or tick.
+    fn _next_timeout(&self) -> Option<Duration> {
+        let now = Instant::now();
+        let tick_timeout = self
+            .tick_interval
+            .map(|tick_interval| tick_interval.saturating_sub(self.last_tick.elapsed()));
+
+        self.controllers
+            .iter()
+            .filter_map(|supervised| supervised.restart_at)
+            .map(|restart_at| restart_at.saturating_duration_since(now))
+            .chain(tick_timeout)
+            .min()
+    }
+
+    /// Dispatch the events of a controller, marking it as failed on errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - the index of the controller.
+    fn _dispatch(&mut self, index: usize) {
+        let initial_backoff = self.initial_backoff;
+        let supervised = &mut self.controllers[index];
+        let Some(controller) = supervised.controller.as_mut() else {
+            return;
+        };
+
+        match controller.dispatch_ready() {
+            Ok(count) => {
+                supervised.stats.events += count;
+                supervised.backoff = initial_backoff;
+            }
+            Err(e) => {
+                warn!("Controller {} failed: {e}", supervised.name);
+                supervised.controller = None;
+                self._schedule_restart(index);
+            }
+        }
+    }
+
+    /// Restart the failed controllers whose delay has elapsed.
+    fn _restart_due_controllers(&mut self) {
+        let now = Instant::now();
+        for index in 0..self.controllers.len() {
+            let supervised = &mut self.controllers[index];
+            match supervised.restart_at {
+                Some(restart_at) if restart_at <= now => (),
+                _ => continue,
+            }
+
+            supervised.restart_at = None;
+            match (supervised.factory)() {
+                Ok(controller) => {
+                    info!("Controller {} restarted", supervised.name);
+                    supervised.controller = Some(controller);
+                    supervised.stats.restarts += 1;
+                }
+                Err(e) => {
+                    warn!("Unable to restart controller {}: {e}", supervised.name);
+                    self._schedule_restart(index);
+                }
+            }
+        }
+    }
+
+    /// Record the failure of a controller, scheduling its restart.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - the index of the controller.
+    fn _schedule_restart(&mut self, index: usize) {
+        let max_backoff = self.max_backoff;
+        let supervised = &mut self.controllers[index];
+
+        supervised.stats.failures += 1;
+        supervised.restart_at = Some(Instant::now() + supervised.backoff);
+        info!(
+            "Restarting controller {} in {:?}",
+            supervised.name, supervised.backoff
+        );
+        supervised.backoff = (supervised.backoff * 2).min(max_backoff);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+    use std::io::{Read, Write};
+    use std::os::unix::io::{AsRawFd, RawFd};
+    use std::rc::Rc;
+    use std::time::{Duration, Instant};
+
+    use super::{ControllerStats, Supervisor};
+    use crate::controllers::{Controller, ControllerError};
+    use crate::events::{ActionEvent, ProcessorSettings};
+
+    use filedescriptor::Pipe;
+
+    /// Controller that receives an event when its pipe is readable.
+    struct PipeController {
+        /// Pipe with a pending byte.
+        pipe: Pipe,
+        /// Whether the dispatch of events fails.
+        fail: bool,
+    }
+
+    impl PipeController {
+        fn new(fail: bool) -> Self {
+            let mut pipe = Pipe::new().unwrap();
+            pipe.write.write_all(b"x").unwrap();
+            PipeController { pipe, fail }
+        }
+    }
+
+    impl Controller for PipeController {
+        fn process_action_event(&mut self, _: ActionEvent) -> Result<(), ControllerError> {
+            Ok(())
+        }
+
+        fn run(&mut self) -> Result<(), ControllerError> {
+            Ok(())
+        }
+
+        fn dispatch_ready(&mut self) -> Result<usize, ControllerError> {
+            self.pipe.read.read_exact(&mut [0]).unwrap();
+            if self.fail {
+                return Err(ControllerError::Paused);
+            }
+            Ok(1)
+        }
+
+        fn raw_fd(&self) -> Option<RawFd> {
+            Some(self.pipe.read.as_raw_fd())
+        }
+
+        fn tick(&mut self) {}
+
+        fn pause(&mut self) {}
+
+        fn resume(&mut self) {}
+
+        fn is_paused(&self) -> bool {
+            false
+        }
+
+        fn update_processor_settings(&mut self, _: ProcessorSettings) {}
+    }
+
+    #[test]
+    /// Test restarting a failed controller, and the statistics.
+    fn test_supervisor_restart() {
+        let mut supervisor = Supervisor {
+            initial_backoff: Duration::from_millis(10),
+            ..Supervisor::default()
+        };
+
+        // The controller fails the first time it dispatches.
+        let created = Rc::new(Cell::new(0));
+        let factory_created = Rc::clone(&created);
+        supervisor
+            .add(
+                "failing",
+                Box::new(move || {
+                    factory_created.set(factory_created.get() + 1);
+                    let fail = factory_created.get() == 1;
+                    Ok(Box::new(PipeController::new(fail)) as Box<dyn Controller>)
+                }),
+            )
+            .unwrap();
+        supervisor
+            .add(
+                "working",
+                Box::new(|| Ok(Box::new(PipeController::new(false)) as Box<dyn Controller>)),
+            )
+            .unwrap();
+
+        supervisor.run_once().unwrap();
+        assert_eq!(
+            supervisor.stats().collect::<Vec<_>>(),
+            vec![
+                (
+                    "failing",
+                    ControllerStats {
+                        events: 0,
+                        failures: 1,
+                        restarts: 0
+                    }
+                ),
+                (
+                    "working",
+                    ControllerStats {
+                        events: 1,
+                        failures: 0,
+                        restarts: 0
+                    }
+                )
+            ]
+        );
+
+        // Wait for the backoff, and restart the controller.
+        let started = Instant::now();
+        while supervisor.total_stats().restarts == 0 && started.elapsed() < Duration::from_secs(1) {
+            supervisor.run_once().unwrap();
+        }
+        assert_eq!(created.get(), 2);
+        assert_eq!(
+            supervisor.total_stats(),
+            ControllerStats {
+                events: 2,
+                failures: 1,
+                restarts: 1
+            }
+        );
+    }
+}
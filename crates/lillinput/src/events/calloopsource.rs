@@ -0,0 +1,146 @@
+//! [`calloop`] event source for integrating a [`DefaultProcessor`] into an
+//! existing event loop.
+
+use std::os::unix::io::{AsRawFd, RawFd};
+
+use crate::events::errors::LibinputError;
+use crate::events::{ActionEvent, DefaultProcessor};
+
+use calloop::generic::{FdWrapper, Generic};
+use calloop::{EventSource, Interest, Mode, Poll, PostAction, Readiness, Token, TokenFactory};
+
+/// Event source that dispatches the `libinput` events of a
+/// [`DefaultProcessor`] when its file descriptor is ready.
+///
+/// The callback is invoked once for each [`ActionEvent`] produced, along
+/// with a mutable reference to the processor.
+pub struct ProcessorSource {
+    /// Source for the `libinput` file descriptor.
+    ///
+    /// Declared before the processor, so it is unregistered before the
+    /// `libinput` context closes the descriptor.
+    source: Generic<FdWrapper<RawFd>, LibinputError>,
+    /// Processor for the `libinput` events.
+    processor: DefaultProcessor,
+    /// Accumulated displacement in the `x` axis of the current gesture.
+    dx: f64,
+    /// Accumulated displacement in the `y` axis of the current gesture.
+    dy: f64,
+}
+
+impl ProcessorSource {
+    /// Create a new [`ProcessorSource`].
+    ///
+    /// # Arguments
+    ///
+    /// * `processor` - the processor whose events will be dispatched.
+    #[must_use]
+    pub fn new(processor: DefaultProcessor) -> Self {
+        // SAFETY: the descriptor is owned by the `libinput` context of the
+        // processor, which is dropped after the source.
+        let fd = unsafe { FdWrapper::new(processor.as_raw_fd()) };
+
+        ProcessorSource {
+            source: Generic::new_with_error(fd, Interest::READ, Mode::Level),
+            processor,
+            dx: 0.0,
+            dy: 0.0,
+        }
+    }
+
+    /// Return a reference to the processor.
+    #[must_use]
+    pub fn processor(&self) -> &DefaultProcessor {
+        &self.processor
+    }
+
+    /// Return a mutable reference to the processor.
+    pub fn processor_mut(&mut self) -> &mut DefaultProcessor {
+        &mut self.processor
+    }
+
+    /// Consume the source, returning the processor.
+    #[must_use]
+    pub fn into_inner(self) -> DefaultProcessor {
+        self.processor
+    }
+}
+
+impl EventSource for ProcessorSource {
+    type Event = ActionEvent;
+    type Metadata = DefaultProcessor;
+    type Ret = ();
+    type Error = LibinputError;
+
+    fn process_events<F>(
+        &mut self,
+        readiness: Readiness,
+        token: Token,
+        mut callback: F,
+    ) -> Result<PostAction, Self::Error>
+    where
+        F: FnMut(Self::Event, &mut Self::Metadata) -> Self::Ret,
+    {
+        let processor = &mut self.processor;
+        let (dx, dy) = (&mut self.dx, &mut self.dy);
+
+        self.source.process_events(readiness, token, |_, _| {
+            for action_event in processor.handle_ready(dx, dy)? {
+                callback(action_event, processor);
+            }
+            Ok(PostAction::Continue)
+        })
+    }
+
+    fn register(
+        &mut self,
+        poll: &mut Poll,
+        token_factory: &mut TokenFactory,
+    ) -> calloop::Result<()> {
+        self.source.register(poll, token_factory)
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &mut Poll,
+        token_factory: &mut TokenFactory,
+    ) -> calloop::Result<()> {
+        self.source.reregister(poll, token_factory)
+    }
+
+    fn unregister(&mut self, poll: &mut Poll) -> calloop::Result<()> {
+        self.source.unregister(poll)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::ProcessorSource;
+    use crate::events::DefaultProcessor;
+
+    use calloop::EventLoop;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    /// Test inserting the source into an event loop and dispatching it.
+    fn test_processor_source_dispatch() {
+        let mut event_loop: EventLoop<Vec<String>> = EventLoop::try_new().unwrap();
+        let source = ProcessorSource::new(DefaultProcessor::default());
+        event_loop
+            .handle()
+            .insert_source(source, |action_event, _, received| {
+                received.push(action_event.to_string());
+            })
+            .unwrap();
+
+        // Dispatch without waiting: no gestures are performed.
+        let mut received = vec![];
+        event_loop
+            .dispatch(Some(Duration::ZERO), &mut received)
+            .unwrap();
+        assert!(received.is_empty());
+    }
+}
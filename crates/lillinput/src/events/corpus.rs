@@ -0,0 +1,213 @@
+//! Corpora of recorded swipe events, for replaying them through a
+//! [`Processor`].
+//!
+//! A corpus is serialized as text, with one [`SwipeEvent`] per line. Empty
+//! lines and lines starting with `#` are ignored:
+//!
+//! ```text
+//! # time_usec kind finger_count [dx dy | cancelled]
+//! 1000 begin 3
+//! 2000 update 3 -4.5 0.25
+//! 3000 end 3 false
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::events::errors::ProcessorError;
+use crate::events::{ActionEvent, Processor, SwipeBegin, SwipeEnd, SwipeEvent, SwipeUpdate};
+
+/// Sequence of swipe events, as recorded from a backend or generated.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SwipeCorpus {
+    /// Events of the corpus, in order.
+    pub events: Vec<SwipeEvent>,
+}
+
+impl SwipeCorpus {
+    /// Return a synthetic [`SwipeCorpus`].
+    ///
+    /// The gestures alternate between three and four fingers, and rotate
+    /// through the eight directions, with a displacement well beyond the
+    /// default threshold. The corpus is deterministic, allowing its replays
+    /// to be compared.
+    ///
+    /// # Arguments
+    ///
+    /// * `gesture_count` - number of gestures in the corpus.
+    /// * `updates_per_gesture` - number of update events of each gesture.
+    #[must_use]
+    pub fn synthetic(gesture_count: usize, updates_per_gesture: usize) -> Self {
+        let mut events = Vec::with_capacity(gesture_count * (updates_per_gesture + 2));
+        let mut time_usec = 0;
+        let mut next_time = || {
+            time_usec += 1000;
+            time_usec
+        };
+
+        for gesture in 0..gesture_count {
+            let finger_count = if gesture % 2 == 0 { 3 } else { 4 };
+            #[allow(clippy::cast_precision_loss)]
+            let angle = ((gesture % 8) as f64 * 45.0).to_radians();
+            let (dx, dy) = (-2.0 * angle.cos(), -2.0 * angle.sin());
+
+            events.push(SwipeEvent::Begin(SwipeBegin {
+                time_usec: next_time(),
+                finger_count,
+            }));
+            for _ in 0..updates_per_gesture {
+                events.push(SwipeEvent::Update(SwipeUpdate {
+                    time_usec: next_time(),
+                    finger_count,
+                    dx,
+                    dy,
+                }));
+            }
+            events.push(SwipeEvent::End(SwipeEnd {
+                time_usec: next_time(),
+                finger_count,
+                cancelled: false,
+            }));
+        }
+
+        SwipeCorpus { events }
+    }
+
+    /// Replay the events of the corpus through a [`Processor`].
+    ///
+    /// The events discarded by the processor (for example, due to being
+    /// below the threshold) are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `processor` - the processor that recognizes the gestures.
+    ///
+    /// # Returns
+    ///
+    /// The [`ActionEvent`]s recognized by the processor, in order.
+    pub fn replay(&self, processor: &mut dyn Processor) -> Vec<ActionEvent> {
+        let mut dx: f64 = 0.0;
+        let mut dy: f64 = 0.0;
+
+        self.events
+            .iter()
+            .filter_map(|event| {
+                processor
+                    .process_event(*event, &mut dx, &mut dy)
+                    .ok()
+                    .flatten()
+            })
+            .collect()
+    }
+}
+
+impl FromStr for SwipeCorpus {
+    type Err = ProcessorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let events = s
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|(index, line)| {
+                parse_event(line).ok_or_else(|| ProcessorError::InvalidCorpus {
+                    line: index + 1,
+                    content: line.to_string(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(SwipeCorpus { events })
+    }
+}
+
+impl fmt::Display for SwipeCorpus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for event in &self.events {
+            match event {
+                SwipeEvent::Begin(x) => writeln!(f, "{} begin {}", x.time_usec, x.finger_count)?,
+                SwipeEvent::Update(x) => writeln!(
+                    f,
+                    "{} update {} {} {}",
+                    x.time_usec, x.finger_count, x.dx, x.dy
+                )?,
+                SwipeEvent::End(x) => {
+                    writeln!(f, "{} end {} {}", x.time_usec, x.finger_count, x.cancelled)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse a line of a serialized corpus into a [`SwipeEvent`].
+///
+/// # Arguments
+///
+/// * `line` - the line, with its fields separated by whitespace.
+///
+/// # Returns
+///
+/// The event, or `None` if the line is not valid.
+fn parse_event(line: &str) -> Option<SwipeEvent> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let time_usec = fields.first()?.parse().ok()?;
+    let finger_count = fields.get(2)?.parse().ok()?;
+
+    let event = match (fields[1], &fields[3..]) {
+        ("begin", []) => SwipeEvent::Begin(SwipeBegin {
+            time_usec,
+            finger_count,
+        }),
+        ("update", [dx, dy]) => SwipeEvent::Update(SwipeUpdate {
+            time_usec,
+            finger_count,
+            dx: dx.parse().ok()?,
+            dy: dy.parse().ok()?,
+        }),
+        ("end", [cancelled]) => SwipeEvent::End(SwipeEnd {
+            time_usec,
+            finger_count,
+            cancelled: cancelled.parse().ok()?,
+        }),
+        _ => return None,
+    };
+
+    Some(event)
+}
+
+#[cfg(test)]
+mod test {
+    use super::SwipeCorpus;
+    use crate::events::errors::ProcessorError;
+    use crate::events::{SwipeBegin, SwipeEvent};
+
+    #[test]
+    /// Test serializing and parsing corpora.
+    fn test_corpus_round_trip() {
+        let corpus = SwipeCorpus::synthetic(16, 4);
+        assert_eq!(corpus.events.len(), 16 * 6);
+        assert_eq!(
+            corpus.events[0],
+            SwipeEvent::Begin(SwipeBegin {
+                time_usec: 1000,
+                finger_count: 3
+            })
+        );
+        assert_eq!(corpus.to_string().parse::<SwipeCorpus>().unwrap(), corpus);
+
+        // Ignore comments and empty lines.
+        let parsed: SwipeCorpus =
+            "# comment\n\n1000 begin 3\n2000 update 3 -1.5 2\n3000 end 3 true\n"
+                .parse()
+                .unwrap();
+        assert_eq!(parsed.events.len(), 3);
+
+        // Report the invalid lines.
+        assert!(matches!(
+            "1000 begin 3\n2000 update 3 -1.5\n".parse::<SwipeCorpus>(),
+            Err(ProcessorError::InvalidCorpus { line: 2, .. })
+        ));
+        assert!("1000 pinch 3".parse::<SwipeCorpus>().is_err());
+    }
+}
@@ -1,19 +1,40 @@
 //! Default [`Processor`] for events.
 
 use crate::events::errors::{LibinputError, ProcessorError};
-use crate::events::libinput::Interface;
-use crate::events::{ActionEvent, FingerCount, Processor};
+use crate::events::libinput::{Interface, SharedOpenErrors};
+use crate::events::{
+    monotonic_usec, rotation_event, switch_event, ActionEvent, DeviceClass, DeviceFilter,
+    EdgeSwipeThresholds, EdgeSwipeTracker, GestureContext, GestureDevice, GestureTiming, Modifier,
+    PinchThresholds, Processor, ProcessorSettings, RawGesture, SharedThresholdLearner, SwipeEnd,
+    SwipeEvent, SwipeSample, ThresholdMode, VIRTUAL_TOUCHPAD_NAME,
+};
 
-use std::f64::consts::PI;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::mem;
 use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::PoisonError;
+use std::time::Duration;
 
 use filedescriptor::{poll, pollfd, POLLIN};
 use input::event::gesture::{
-    GestureEvent, GestureEventCoordinates, GestureEventTrait, GestureSwipeEvent,
+    GestureEndEvent, GestureEvent, GestureEventTrait, GesturePinchEvent, GesturePinchEventTrait,
+    GestureSwipeEvent,
 };
-use input::event::Event;
-use input::Libinput;
-use log::{debug, info};
+use input::event::keyboard::{KeyState, KeyboardEvent, KeyboardEventTrait};
+use input::event::pointer::{ButtonState, PointerEvent};
+use input::event::switch::SwitchEvent;
+use input::event::touch::{TouchEvent, TouchEventPosition, TouchEventSlot, TouchEventTrait};
+use input::event::{DeviceEvent, Event, EventTrait};
+use input::{Device, DeviceCapability, Libinput};
+use libc::{EACCES, EPERM};
+use log::{debug, info, warn};
+use strum::IntoEnumIterator;
+
+/// Maximum displacement of a swipe update for the fingers to be considered
+/// still, when recognizing the hold variant of the swipes.
+const HOLD_TOLERANCE: f64 = 0.5;
 
 /// Default [`Processor`] for events.
 pub struct DefaultProcessor {
@@ -29,6 +50,105 @@ pub struct DefaultProcessor {
     /// Whether positive displacement on the `Y` axis should be interpreted as
     /// "up".
     pub invert_y: bool,
+    /// Key codes of the modifier keys and pointer buttons currently held.
+    pub held_modifier_keys: HashSet<u32>,
+    /// Modifiers held at the start of the latest gesture.
+    pub current_gesture_modifiers: BTreeSet<Modifier>,
+    /// Device that performed the latest gesture.
+    pub current_gesture_device: Option<GestureDevice>,
+    /// Time after a key press during which gestures are discarded.
+    pub disable_while_typing: Option<Duration>,
+    /// Time of the latest key press (in microseconds), excluding modifiers.
+    pub last_key_time: Option<u64>,
+    /// `libinput` seat id (`path` if the devices were added directly).
+    pub seat_id: String,
+    /// Errors raised while opening devices.
+    pub open_errors: SharedOpenErrors,
+    /// Maximum time to wait for events during a dispatch.
+    pub poll_timeout: Option<Duration>,
+    /// Margin (in degrees) beyond its sector that the angle of a gesture can
+    /// deviate before changing its initially detected direction.
+    pub direction_margin: Option<f64>,
+    /// Minimum threshold for displacement changes of specific events,
+    /// overriding the global threshold.
+    pub event_thresholds: HashMap<ActionEvent, f64>,
+    /// Minimum threshold for displacement changes of specific events in
+    /// order to produce their long variant.
+    pub long_thresholds: HashMap<ActionEvent, f64>,
+    /// Sample of the latest gesture when it first reached the threshold.
+    pub initial_sample: Option<SwipeSample>,
+    /// Mode for interpreting the thresholds.
+    pub threshold_mode: ThresholdMode,
+    /// Size (in millimeters) of the device performing the latest gesture,
+    /// if known.
+    pub device_size: Option<(f64, f64)>,
+    /// System names of the gesture-capable devices currently connected.
+    pub gesture_devices: HashSet<String>,
+    /// Classes of the devices whose gestures are processed.
+    pub device_classes: BTreeSet<DeviceClass>,
+    /// Lists of devices whose gestures are processed or ignored.
+    pub device_filter: DeviceFilter,
+    /// Timing of the latest gesture recognized as each event, pending to be
+    /// taken by the controller.
+    pub gesture_timings: HashMap<ActionEvent, GestureTiming>,
+    /// Context of the latest gesture recognized as each event, pending to be
+    /// taken by the controller.
+    pub gesture_contexts: HashMap<ActionEvent, GestureContext>,
+    /// Recorder of the magnitudes of the swipes, if learning the thresholds.
+    pub threshold_learner: Option<SharedThresholdLearner>,
+    /// Raw swipe gestures finished, pending to be taken, if recording them.
+    pub raw_gestures: Option<Vec<RawGesture>>,
+    /// Minimum rotation (in degrees) of a pinch gesture for recognizing it as
+    /// a rotation event.
+    pub rotation_threshold: f64,
+    /// Accumulated angle (in degrees) of the latest pinch gesture.
+    pub rotation_angle: f64,
+    /// Scale thresholds for recognizing the pinch gestures.
+    pub pinch_thresholds: PinchThresholds,
+    /// Thresholds for recognizing the edge swipes of touchscreens.
+    pub edge_swipe_thresholds: EdgeSwipeThresholds,
+    /// Tracker of the touch points of the touchscreens.
+    pub edge_swipe_tracker: EdgeSwipeTracker,
+    /// Displacement between the progress events emitted while specific
+    /// swipes are in progress.
+    pub progress_steps: HashMap<ActionEvent, f64>,
+    /// Events emitted again each time the displacement reaches another
+    /// multiple of their threshold, while the swipe is in progress.
+    pub repeat_events: HashSet<ActionEvent>,
+    /// Number of progress events emitted during the latest gesture.
+    pub progress_emitted: u32,
+    /// Minimum velocity (displacement per second) of specific events in
+    /// order to produce their fast variant.
+    pub fast_velocities: HashMap<ActionEvent, f64>,
+    /// Time (in microseconds) at which the latest swipe began.
+    pub swipe_begin_usec: u64,
+    /// Number of directions recognized for the swipes: `4` (left, up, right
+    /// and down) or `8` (including the diagonals).
+    pub directions: u8,
+    /// Width (in degrees) of the sector of each direction, starting from the
+    /// left and clock-wise, overriding the equal sectors of the `directions`.
+    pub sector_widths: Option<[f64; 8]>,
+    /// Angle (in degrees) around the boundaries between directions in which
+    /// the swipes are discarded as ambiguous.
+    pub direction_deadzone: Option<f64>,
+    /// Minimum threshold for displacement changes on the `X` axis,
+    /// overriding the global threshold.
+    pub threshold_x: Option<f64>,
+    /// Minimum threshold for displacement changes on the `Y` axis,
+    /// overriding the global threshold.
+    pub threshold_y: Option<f64>,
+    /// Maximum time between the start and the end of a swipe, beyond which
+    /// it is discarded.
+    pub max_duration: Option<Duration>,
+    /// Minimum time the fingers are held still at the end of specific
+    /// events in order to produce their hold variant.
+    pub hold_times: HashMap<ActionEvent, Duration>,
+    /// Time (in microseconds) at which the fingers last moved during the
+    /// latest swipe.
+    pub swipe_motion_usec: u64,
+    /// Settings used for recognizing the swipe in progress, cleared when a
+    /// swipe begins or the settings are updated.
+    pub cached_settings: Option<ProcessorSettings>,
 }
 
 impl DefaultProcessor {
@@ -53,12 +173,99 @@ impl DefaultProcessor {
         invert_y: bool,
     ) -> Result<Self, LibinputError> {
         // Create the libinput context.
-        let mut input = Libinput::new_with_udev(Interface {});
+        let interface = Interface::default();
+        let open_errors = Rc::clone(&interface.open_errors);
+        let mut input = Libinput::new_with_udev(interface);
         input
             .udev_assign_seat(seat_id)
-            .map_err(|_| LibinputError::SeatError)?;
+            .map_err(|()| LibinputError::UdevError(seat_id.to_string()))?;
 
         info!("Assigned seat {seat_id} to the libinput context.");
+
+        Ok(DefaultProcessor::from_input(
+            input,
+            threshold,
+            seat_id,
+            invert_x,
+            invert_y,
+            open_errors,
+        ))
+    }
+
+    /// Return a new [`DefaultProcessor`] for a list of device nodes.
+    ///
+    /// The devices are added directly to the `libinput` context, bypassing
+    /// `udev` and the seat assignment.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - Minimum threshold for displacement changes.
+    /// * `devices` - paths to the device nodes (for example,
+    ///   `/dev/input/event5`).
+    /// * `invert_x` - Whether positive displacement on the `X` axis should be
+    ///   interpreted as "left".
+    /// * `invert_y` - Whether positive displacement on the `Y` axis should be
+    ///   interpreted as "up".
+    ///
+    /// # Errors
+    ///
+    /// Return `Err` if any of the devices could not be added.
+    pub fn new_from_path(
+        threshold: f64,
+        devices: &[String],
+        invert_x: bool,
+        invert_y: bool,
+    ) -> Result<Self, LibinputError> {
+        // Create the libinput context.
+        let interface = Interface::default();
+        let open_errors = Rc::clone(&interface.open_errors);
+        let mut input = Libinput::new_from_path(interface);
+        for device in devices {
+            if input.path_add_device(device).is_none() {
+                let permission_denied = open_errors
+                    .borrow()
+                    .iter()
+                    .any(|(_, errno)| *errno == EACCES || *errno == EPERM);
+                return Err(if permission_denied {
+                    LibinputError::PermissionDenied(PathBuf::from(device))
+                } else {
+                    LibinputError::DeviceError(device.clone())
+                });
+            }
+            info!("Added device {device} to the libinput context.");
+        }
+
+        Ok(DefaultProcessor::from_input(
+            input,
+            threshold,
+            "path",
+            invert_x,
+            invert_y,
+            open_errors,
+        ))
+    }
+
+    /// Return a new [`DefaultProcessor`] for an initialized `libinput`
+    /// context.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - the `libinput` context.
+    /// * `threshold` - Minimum threshold for displacement changes.
+    /// * `seat_id` - `libinput` seat id.
+    /// * `invert_x` - Whether positive displacement on the `X` axis should be
+    ///   interpreted as "left".
+    /// * `invert_y` - Whether positive displacement on the `Y` axis should be
+    ///   interpreted as "up".
+    /// * `open_errors` - errors raised while opening devices.
+    fn from_input(
+        input: Libinput,
+        threshold: f64,
+        seat_id: &str,
+        invert_x: bool,
+        invert_y: bool,
+        open_errors: SharedOpenErrors,
+    ) -> Self {
         // Use a raw file descriptor for polling.
         let raw_fd: RawFd = input.as_raw_fd();
 
@@ -69,14 +276,573 @@ impl DefaultProcessor {
         }]
         .to_vec();
 
-        Ok(DefaultProcessor {
+        DefaultProcessor {
             threshold,
             input,
             poll_array,
             invert_x,
             invert_y,
+            held_modifier_keys: HashSet::new(),
+            current_gesture_modifiers: BTreeSet::new(),
+            current_gesture_device: None,
+            disable_while_typing: None,
+            last_key_time: None,
+            seat_id: seat_id.to_string(),
+            open_errors,
+            poll_timeout: None,
+            direction_margin: None,
+            event_thresholds: HashMap::new(),
+            long_thresholds: HashMap::new(),
+            initial_sample: None,
+            threshold_mode: ThresholdMode::default(),
+            device_size: None,
+            gesture_devices: HashSet::new(),
+            device_classes: DeviceClass::iter().collect(),
+            device_filter: DeviceFilter::default(),
+            threshold_learner: None,
+            raw_gestures: None,
+            gesture_timings: HashMap::new(),
+            gesture_contexts: HashMap::new(),
+            rotation_threshold: 30.0,
+            rotation_angle: 0.0,
+            pinch_thresholds: PinchThresholds::default(),
+            edge_swipe_thresholds: EdgeSwipeThresholds::default(),
+            edge_swipe_tracker: EdgeSwipeTracker::default(),
+            progress_steps: HashMap::new(),
+            repeat_events: HashSet::new(),
+            progress_emitted: 0,
+            fast_velocities: HashMap::new(),
+            swipe_begin_usec: 0,
+            directions: 8,
+            sector_widths: None,
+            direction_deadzone: None,
+            threshold_x: None,
+            threshold_y: None,
+            max_duration: None,
+            hold_times: HashMap::new(),
+            swipe_motion_usec: 0,
+            cached_settings: None,
+        }
+    }
+
+    /// Check that gesture-capable devices were found on the seat.
+    ///
+    /// The pending `libinput` events are dispatched and consumed, and the
+    /// number of gesture-capable devices added is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if no gesture-capable devices were found on the seat,
+    /// distinguishing the devices that could not be opened due to missing
+    /// permissions.
+    pub fn check_devices(&mut self) -> Result<usize, LibinputError> {
+        self.input.dispatch()?;
+        let events: Vec<Event> = (&mut self.input).collect();
+        for event in events {
+            if let Event::Device(device_event) = event {
+                self.update_devices(&device_event);
+            }
+        }
+        if !self.gesture_devices.is_empty() {
+            return Ok(self.gesture_devices.len());
+        }
+
+        let open_errors = self.open_errors.borrow();
+        match open_errors
+            .iter()
+            .find(|(_, errno)| *errno == EACCES || *errno == EPERM)
+        {
+            Some((path, _)) => Err(LibinputError::PermissionDenied(path.clone())),
+            None => Err(LibinputError::NoDevices(self.seat_id.clone())),
+        }
+    }
+
+    /// Set the minimum threshold for displacement changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - the new threshold.
+    pub fn set_threshold(&mut self, threshold: f64) {
+        self.threshold = threshold;
+        self.cached_settings = None;
+    }
+
+    /// Set whether the displacement on each axis is inverted.
+    ///
+    /// # Arguments
+    ///
+    /// * `invert_x` - Whether positive displacement on the `X` axis should be
+    ///   interpreted as "left".
+    /// * `invert_y` - Whether positive displacement on the `Y` axis should be
+    ///   interpreted as "up".
+    pub fn set_invert(&mut self, invert_x: bool, invert_y: bool) {
+        self.invert_x = invert_x;
+        self.invert_y = invert_y;
+        self.cached_settings = None;
+    }
+
+    /// Set the margin beyond its sector that the angle of a gesture can
+    /// deviate before changing its initially detected direction.
+    ///
+    /// # Arguments
+    ///
+    /// * `direction_margin` - the new margin (in degrees), or `None` for
+    ///   using the direction of the whole gesture.
+    pub fn set_direction_margin(&mut self, direction_margin: Option<f64>) {
+        self.direction_margin = direction_margin;
+        self.cached_settings = None;
+    }
+
+    /// Update the gesture-capable devices (touchpads and touchscreens)
+    /// currently connected, reporting the changes.
+    ///
+    /// As devices are connected and disconnected while processing events (for
+    /// example, Bluetooth touchpads), the processing of gestures starts when
+    /// the first gesture-capable device is connected.
+    ///
+    /// # Arguments
+    ///
+    /// * `device_event` - a device added or removed event.
+    fn update_devices(&mut self, device_event: &DeviceEvent) {
+        let device = device_event.device();
+        if !(device.has_capability(DeviceCapability::Gesture)
+            || device.has_capability(DeviceCapability::Touch))
+            || device.name() == VIRTUAL_TOUCHPAD_NAME
+        {
+            return;
+        }
+
+        let sysname = device.sysname().to_string();
+        let device_class = DeviceClass::from(&device);
+        match device_event {
+            DeviceEvent::Added(_) if !self.is_device_enabled(&device) => {
+                info!(
+                    "Ignoring gesture-capable device connected: {} ({device_class})",
+                    device.name()
+                );
+            }
+            DeviceEvent::Added(_) => {
+                info!(
+                    "Gesture-capable device connected: {} ({device_class})",
+                    device.name()
+                );
+                if self.gesture_devices.is_empty() {
+                    info!("Processing gestures from the connected devices");
+                }
+                self.gesture_devices.insert(sysname);
+            }
+            DeviceEvent::Removed(_) => {
+                if !self.gesture_devices.remove(&sysname) {
+                    return;
+                }
+                info!("Gesture-capable device disconnected: {}", device.name());
+                if self.gesture_devices.is_empty() {
+                    warn!("No gesture-capable devices connected, waiting for one ...");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Update the state of the modifier keys and pointer buttons.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - the key or button code, as reported by `libinput`.
+    /// * `pressed` - whether the key was pressed or released.
+    fn update_modifier_keys(&mut self, key: u32, pressed: bool) {
+        if Modifier::from_key(key).is_none() {
+            return;
+        }
+
+        if pressed {
+            self.held_modifier_keys.insert(key);
+        } else {
+            self.held_modifier_keys.remove(&key);
+        }
+    }
+
+    /// Update the time of the latest key press.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - the key code, as reported by `libinput`.
+    /// * `time` - the time of the key press, in microseconds.
+    fn update_last_key_time(&mut self, key: u32, time: u64) {
+        // Modifiers are not considered typing, as they can be used along
+        // with gestures.
+        if Modifier::from_key(key).is_none() {
+            self.last_key_time = Some(time);
+        }
+    }
+
+    /// Return `true` if the given time is within the typing window.
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - the time of the event, in microseconds.
+    fn is_typing(&self, time: u64) -> bool {
+        match (self.disable_while_typing, self.last_key_time) {
+            (Some(disable_while_typing), Some(last_key_time)) => {
+                Duration::from_micros(time.saturating_sub(last_key_time)) < disable_while_typing
+            }
+            _ => false,
+        }
+    }
+
+    /// Dispatch the pending `libinput` events, without waiting for new ones.
+    ///
+    /// Intended for integrating the processor into an external event loop,
+    /// calling it when the file descriptor returned by [`AsRawFd::as_raw_fd`]
+    /// is ready for reading.
+    ///
+    /// # Arguments
+    ///
+    /// * `dx` - the current position in the `x` axis.
+    /// * `dy` - the current position in the `y` axis.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if an error was encountered while dispatching events.
+    pub fn handle_ready(
+        &mut self,
+        dx: &mut f64,
+        dy: &mut f64,
+    ) -> Result<Vec<ActionEvent>, LibinputError> {
+        // Dispatch, bubbling up in case of an error.
+        self.input.dispatch()?;
+
+        let mut action_events = Vec::new();
+        let events: Vec<Event> = (&mut self.input).collect();
+
+        for event in events {
+            match event {
+                Event::Gesture(GestureEvent::Swipe(swipe_event)) => {
+                    if !self.is_device_enabled(&swipe_event.device()) {
+                        continue;
+                    }
+
+                    // Keep track of the device and its size, for relative
+                    // thresholds.
+                    if let GestureSwipeEvent::Begin(begin_event) = &swipe_event {
+                        self.device_size = begin_event.device().size();
+                        self.update_gesture_device(&begin_event.device());
+                    }
+
+                    let result = SwipeEvent::try_from(swipe_event)
+                        .and_then(|swipe_event| self.process_event(swipe_event, dx, dy));
+
+                    match result {
+                        Err(e) => {
+                            debug!("Discarding event: {}", e);
+                        }
+                        Ok(None) => {}
+                        Ok(Some(action_event)) => action_events.push(action_event),
+                    }
+                }
+                Event::Gesture(GestureEvent::Pinch(pinch_event)) => {
+                    if !self.is_device_enabled(&pinch_event.device()) {
+                        continue;
+                    }
+
+                    if let GesturePinchEvent::Begin(begin_event) = &pinch_event {
+                        self.update_gesture_device(&begin_event.device());
+                    }
+                    match self.process_pinch_event(&pinch_event) {
+                        Err(e) => {
+                            debug!("Discarding event: {}", e);
+                        }
+                        Ok(None) => {}
+                        Ok(Some(action_event)) => action_events.push(action_event),
+                    }
+                }
+                Event::Touch(touch_event) => {
+                    if !self.is_device_enabled(&touch_event.device()) {
+                        continue;
+                    }
+
+                    if matches!(touch_event, TouchEvent::Down(_))
+                        && self.edge_swipe_tracker.is_idle()
+                    {
+                        self.update_gesture_device(&touch_event.device());
+                    }
+                    if let Some(action_event) = self.process_touch_event(&touch_event) {
+                        action_events.push(action_event);
+                    }
+                }
+                Event::Device(device_event) => self.update_devices(&device_event),
+                Event::Keyboard(KeyboardEvent::Key(key_event)) => {
+                    let pressed = key_event.key_state() == KeyState::Pressed;
+                    self.update_modifier_keys(key_event.key(), pressed);
+                    if pressed {
+                        self.update_last_key_time(key_event.key(), key_event.time_usec());
+                    }
+                }
+                Event::Pointer(PointerEvent::Button(button_event)) => {
+                    self.update_modifier_keys(
+                        button_event.button(),
+                        button_event.button_state() == ButtonState::Pressed,
+                    );
+                }
+                // The switches are not gesture-capable devices, and as such
+                // their toggles are processed regardless of the devices
+                // selected.
+                Event::Switch(SwitchEvent::Toggle(toggle_event)) => {
+                    if let Some(action_event) =
+                        switch_event(toggle_event.switch(), toggle_event.switch_state())
+                    {
+                        debug!("Switch toggled: {action_event}");
+                        action_events.push(action_event);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(action_events)
+    }
+
+    /// Record the device performing the gesture being started.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - the `libinput` device of the gesture.
+    fn update_gesture_device(&mut self, device: &Device) {
+        self.current_gesture_device = Some(GestureDevice {
+            name: device.name().to_string(),
+            sysname: device.sysname().to_string(),
+        });
+    }
+
+    /// Process a pinch event, recognizing two-finger rotations and zooms.
+    ///
+    /// The angle of the pinch is accumulated along its updates, and compared
+    /// against the rotation threshold once the pinch ends. Pinches that are not
+    /// recognized as rotations are classified by their final scale.
+    ///
+    /// # Arguments
+    ///
+    /// * `pinch_event` - a `libinput` pinch event.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the pinch was discarded.
+    fn process_pinch_event(
+        &mut self,
+        pinch_event: &GesturePinchEvent,
+    ) -> Result<Option<ActionEvent>, ProcessorError> {
+        match pinch_event {
+            GesturePinchEvent::Begin(_) => {
+                self.rotation_angle = 0.0;
+                self.current_gesture_modifiers = self.held_modifiers();
+            }
+            GesturePinchEvent::Update(update_event) => {
+                self.rotation_angle += update_event.angle_delta();
+            }
+            GesturePinchEvent::End(end_event) => {
+                if end_event.cancelled() {
+                    return Err(ProcessorError::Cancelled);
+                }
+                let finger_count = end_event.finger_count();
+                if !(2..=4).contains(&finger_count) {
+                    return Err(ProcessorError::UnsupportedFingerCount(finger_count));
+                }
+                // Discard the gesture if a key was pressed recently.
+                if self.is_typing(end_event.time_usec()) {
+                    return Err(ProcessorError::WhileTyping);
+                }
+
+                let scale = end_event.scale();
+                let rotation = if finger_count == 2 {
+                    rotation_event(self.rotation_angle, self.rotation_threshold)
+                } else {
+                    None
+                };
+                let Some(action_event) = rotation.or_else(|| {
+                    self.pinch_thresholds
+                        .classify(scale)
+                        .and_then(|pinch_event| pinch_event.action_event(finger_count))
+                }) else {
+                    return Err(ProcessorError::PinchBelowThreshold {
+                        scale,
+                        angle: self.rotation_angle.abs(),
+                    });
+                };
+                self.record_gesture(
+                    action_event,
+                    end_event.time_usec(),
+                    finger_count,
+                    (0.0, 0.0),
+                );
+                return Ok(Some(action_event));
+            }
+            _ => {}
+        }
+
+        Ok(None)
+    }
+
+    /// Process a touchscreen event, recognizing edge swipes.
+    ///
+    /// The positions of the touch points are tracked as percentages of the
+    /// size of the touchscreen, and the swipe is recognized once its touch
+    /// point is lifted.
+    ///
+    /// # Arguments
+    ///
+    /// * `touch_event` - a `libinput` touch event.
+    fn process_touch_event(&mut self, touch_event: &TouchEvent) -> Option<ActionEvent> {
+        match touch_event {
+            TouchEvent::Down(down_event) => {
+                if self.edge_swipe_tracker.is_idle() {
+                    self.current_gesture_modifiers = self.held_modifiers();
+                }
+                self.edge_swipe_tracker.down(
+                    down_event.seat_slot(),
+                    (down_event.x_transformed(100), down_event.y_transformed(100)),
+                );
+            }
+            TouchEvent::Motion(motion_event) => {
+                self.edge_swipe_tracker.motion(
+                    motion_event.seat_slot(),
+                    (
+                        motion_event.x_transformed(100),
+                        motion_event.y_transformed(100),
+                    ),
+                );
+            }
+            TouchEvent::Up(up_event) => {
+                let action_event = self
+                    .edge_swipe_tracker
+                    .up(up_event.seat_slot(), &self.edge_swipe_thresholds)?;
+                // Discard the gesture if a key was pressed recently.
+                if self.is_typing(up_event.time_usec()) {
+                    debug!("Discarding event: {}", ProcessorError::WhileTyping);
+                    return None;
+                }
+
+                self.record_gesture(action_event, up_event.time_usec(), 1, (0.0, 0.0));
+                return Some(action_event);
+            }
+            TouchEvent::Cancel(_) => self.edge_swipe_tracker.cancel(),
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Return `true` if the gestures of a device are processed.
+    ///
+    /// The gestures emitted by `lillinput` itself, the ones of devices whose
+    /// class is not enabled, and the ones of devices filtered out are
+    /// ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - the device performing the gesture.
+    fn is_device_enabled(&self, device: &Device) -> bool {
+        device.name() != VIRTUAL_TOUCHPAD_NAME
+            && self.device_classes.contains(&DeviceClass::from(device))
+            && (self.device_filter.is_empty() || self.is_device_allowed(device))
+    }
+
+    /// Return `true` if a device is allowed by the device filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - the device performing the gesture.
+    fn is_device_allowed(&self, device: &Device) -> bool {
+        let gesture_device = GestureDevice {
+            name: device.name().to_string(),
+            sysname: device.sysname().to_string(),
+        };
+        self.device_filter.allows(&gesture_device, |key| {
+            // SAFETY: the `udev` device is only used for reading its
+            // properties, and belongs to the context of this processor.
+            let udev_device = unsafe { device.udev_device() }?;
+            udev_device
+                .property_value(key)
+                .map(|value| value.to_string_lossy().into_owned())
         })
     }
+
+    /// Record a finished swipe as a raw gesture, if recording them.
+    ///
+    /// # Arguments
+    ///
+    /// * `end_event` - the end event of the swipe.
+    /// * `dx` - the accumulated displacement in the `x` axis.
+    /// * `dy` - the accumulated displacement in the `y` axis.
+    fn record_raw_gesture(&mut self, end_event: &SwipeEnd, dx: f64, dy: f64) {
+        if self.raw_gestures.is_none() {
+            return;
+        }
+
+        let direction = SwipeSample::new(dx, dy, end_event.finger_count)
+            .direction_event(&self.settings(), None)
+            .ok();
+        if let Some(raw_gestures) = &mut self.raw_gestures {
+            raw_gestures.push(RawGesture {
+                finger_count: end_event.finger_count,
+                dx,
+                dy,
+                duration: Duration::from_micros(
+                    end_event.time_usec.saturating_sub(self.swipe_begin_usec),
+                ),
+                direction,
+                cancelled: end_event.cancelled,
+            });
+        }
+    }
+
+    /// Record the timing and the context of a recognized gesture, for the
+    /// controller to take them.
+    ///
+    /// # Arguments
+    ///
+    /// * `action_event` - the event the gesture was recognized as.
+    /// * `event_usec` - the time of the event that completed the gesture.
+    /// * `finger_count` - the number of fingers used for the gesture.
+    /// * `(dx, dy)` - the accumulated displacement of the gesture.
+    fn record_gesture(
+        &mut self,
+        action_event: ActionEvent,
+        event_usec: u64,
+        finger_count: i32,
+        (dx, dy): (f64, f64),
+    ) {
+        self.gesture_timings.insert(
+            action_event,
+            GestureTiming {
+                event_usec,
+                recognized_usec: monotonic_usec(),
+            },
+        );
+        self.gesture_contexts.insert(
+            action_event,
+            GestureContext {
+                action_event,
+                finger_count: Some(finger_count),
+                dx,
+                dy,
+                device: self.current_gesture_device.clone(),
+            },
+        );
+    }
+
+    /// Return the modifiers currently held.
+    fn held_modifiers(&self) -> BTreeSet<Modifier> {
+        self.held_modifier_keys
+            .iter()
+            .filter_map(|key| Modifier::from_key(*key))
+            .collect()
+    }
+}
+
+impl AsRawFd for DefaultProcessor {
+    fn as_raw_fd(&self) -> RawFd {
+        self.input.as_raw_fd()
+    }
 }
 
 impl Default for DefaultProcessor {
@@ -88,28 +854,134 @@ impl Default for DefaultProcessor {
 impl Processor for DefaultProcessor {
     fn process_event(
         &mut self,
-        event: GestureEvent,
+        event: SwipeEvent,
         dx: &mut f64,
         dy: &mut f64,
     ) -> Result<Option<ActionEvent>, ProcessorError> {
-        if let GestureEvent::Swipe(event) = event {
-            match event {
-                GestureSwipeEvent::Begin(_begin_event) => {
-                    (*dx) = 0.0;
-                    (*dy) = 0.0;
+        match event {
+            SwipeEvent::Begin(begin_event) => {
+                self.swipe_begin_usec = begin_event.time_usec;
+                self.swipe_motion_usec = begin_event.time_usec;
+                (*dx) = 0.0;
+                (*dy) = 0.0;
+                self.current_gesture_modifiers = self.held_modifiers();
+                self.initial_sample = None;
+                self.progress_emitted = 0;
+                self.cached_settings = None;
+            }
+            SwipeEvent::Update(update_event) => {
+                (*dx) += update_event.dx;
+                (*dy) += update_event.dy;
+                if update_event.dx.hypot(update_event.dy) > HOLD_TOLERANCE {
+                    self.swipe_motion_usec = update_event.time_usec;
                 }
-                GestureSwipeEvent::Update(update_event) => {
-                    (*dx) += update_event.dx();
-                    (*dy) += update_event.dy();
+
+                // Reuse the settings along the updates of the swipe, instead
+                // of cloning them for each update.
+                let settings = self
+                    .cached_settings
+                    .take()
+                    .unwrap_or_else(|| self.settings());
+
+                // Store the sample the first time the threshold is reached.
+                let sample = SwipeSample::new(*dx, *dy, update_event.finger_count);
+                if self.initial_sample.is_none()
+                    && sample.threshold_magnitude(&settings) >= sample.axis_threshold(&settings)
+                {
+                    self.initial_sample = Some(sample);
                 }
-                GestureSwipeEvent::End(ref _end_event) => {
-                    return match self._end_event_to_action_event(*dx, *dy, event.finger_count()) {
-                        Ok(event) => Ok(Some(event)),
-                        Err(e) => Err(e),
-                    };
+                let progress = sample.progress(&settings, self.initial_sample.as_ref());
+                self.cached_settings = Some(settings);
+
+                // Emit a progress event for each step completed, one per
+                // update at most.
+                match progress {
+                    Some((action_event, steps))
+                        if steps > self.progress_emitted
+                            && !self.is_typing(update_event.time_usec) =>
+                    {
+                        self.progress_emitted += 1;
+                        self.record_gesture(
+                            action_event,
+                            update_event.time_usec,
+                            update_event.finger_count,
+                            (*dx, *dy),
+                        );
+                        return Ok(Some(action_event));
+                    }
+                    _ => {}
+                }
+            }
+            SwipeEvent::End(end_event) => {
+                // Record the raw gesture, before discarding it.
+                self.record_raw_gesture(&end_event, *dx, *dy);
+
+                // Discard the gestures cancelled by `libinput` (for example,
+                // due to palm rejection).
+                if end_event.cancelled {
+                    self.initial_sample = None;
+                    return Err(ProcessorError::Cancelled);
+                }
+
+                // Discard the gesture if the fingers rested on the device for
+                // too long.
+                let duration = Duration::from_micros(
+                    end_event.time_usec.saturating_sub(self.swipe_begin_usec),
+                );
+                if let Some(max_duration) = self.max_duration.filter(|x| duration > *x) {
+                    self.initial_sample = None;
+                    return Err(ProcessorError::DurationExceeded {
+                        duration,
+                        max_duration,
+                    });
                 }
-                // GestureEvent::Swipe is non-exhaustive.
-                other => return Err(ProcessorError::UnsupportedSwipeEvent(other)),
+
+                // Discard the gesture if a key was pressed recently.
+                if self.is_typing(end_event.time_usec) {
+                    return Err(ProcessorError::WhileTyping);
+                }
+
+                // The gestures that emitted progress events are complete.
+                if self.progress_emitted > 0 {
+                    self.initial_sample = None;
+                    return Ok(None);
+                }
+
+                let action_event =
+                    self._end_event_to_action_event(*dx, *dy, end_event.finger_count)?;
+
+                // Produce the fast variant of the event if its velocity is
+                // reached.
+                let action_event = match self.fast_velocities.get(&action_event) {
+                    Some(fast_velocity)
+                        if SwipeSample::new(*dx, *dy, end_event.finger_count)
+                            .velocity(&self.settings(), duration)
+                            >= *fast_velocity =>
+                    {
+                        action_event.fast()
+                    }
+                    _ => action_event,
+                };
+
+                // Produce the hold variant of the event if the fingers were
+                // held still long enough before being lifted, taking
+                // precedence over the fast variant.
+                let held = Duration::from_micros(
+                    end_event.time_usec.saturating_sub(self.swipe_motion_usec),
+                );
+                let action_event = match self.hold_times.get(&action_event.short()) {
+                    Some(hold_time) if !action_event.is_long() && held >= *hold_time => {
+                        action_event.short().hold()
+                    }
+                    _ => action_event,
+                };
+                self.record_gesture(
+                    action_event,
+                    end_event.time_usec,
+                    end_event.finger_count,
+                    (*dx, *dy),
+                );
+                return Ok(Some(action_event));
             }
         }
 
@@ -118,113 +990,146 @@ impl Processor for DefaultProcessor {
 
     fn _end_event_to_action_event(
         &mut self,
-        mut dx: f64,
-        mut dy: f64,
+        dx: f64,
+        dy: f64,
         finger_count: i32,
     ) -> Result<ActionEvent, ProcessorError> {
-        /// Return the octant for the given displacement.
-        ///
-        /// # Arguments
-        ///
-        /// * `x` - the final position in the `x` axis.
-        /// * `y` - the final position in the `y` axis.
-        ///
-        /// # Returns
-        ///
-        /// The octant the displacement is closest to in the `X-Y` coordinates,
-        /// with `0` being the left direction and increasing clock-wise.
-        fn get_event_octant(dx: f64, dy: f64) -> i8 {
-            // Get the angle, scaled to `[0..1]`.
-            let mut angle = -dy.atan2(-dx);
-            if angle < 0.0 {
-                angle += 2.0 * PI;
-            };
-            angle /= 2.0 * PI;
-
-            // Get the octant, rounding the angle to the nearest possible of
-            // the `8` (determined by the number of `ActionEvents` directions.
-            #[allow(clippy::cast_possible_truncation)]
-            let mut octant = (angle * 8.0).round() as i8;
-            if octant == 8 {
-                // Wrap to the initial direction.
-                octant = 0;
+        let initial_sample = self.initial_sample.take();
+        let settings = self.settings();
+        let sample = SwipeSample::new(dx, dy, finger_count);
+        let result = sample.to_action_event_from(&settings, initial_sample.as_ref());
+
+        // Record the magnitude of the swipe, along with whether it reached
+        // the threshold.
+        if let Some(threshold_learner) = &self.threshold_learner {
+            if let Ok(direction_event) = sample.direction_event(&settings, initial_sample.as_ref())
+            {
+                threshold_learner
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .record(
+                        direction_event,
+                        sample.threshold_magnitude(&settings),
+                        result.is_ok(),
+                    );
             }
+        }
+        let action_event = result?;
 
-            octant
+        // Produce the long variant of the event if its threshold is reached.
+        match settings.long_thresholds.get(&action_event) {
+            Some(long_threshold) if sample.threshold_magnitude(&settings) >= *long_threshold => {
+                Ok(action_event.long())
+            }
+            _ => Ok(action_event),
         }
+    }
 
-        // Determine finger count.
-        let finger_count_as_enum = FingerCount::try_from(finger_count)?;
+    fn dispatch(&mut self, dx: &mut f64, dy: &mut f64) -> Result<Vec<ActionEvent>, LibinputError> {
+        // Block until the descriptor is ready, or the timeout expires.
+        poll(&mut self.poll_array, self.poll_timeout)?;
 
-        // Discard displacements below threshold.
-        if (dx.powi(2) + dy.powi(2)).sqrt() < self.threshold {
-            return Err(ProcessorError::DisplacementBelowThreshold(self.threshold));
-        };
+        self.handle_ready(dx, dy)
+    }
 
-        // Determine the `ActionEvent` for the event.
-        if self.invert_x {
-            dx = -dx;
-        }
-        if self.invert_y {
-            dy = -dy;
+    fn gesture_modifiers(&self) -> BTreeSet<Modifier> {
+        self.current_gesture_modifiers.clone()
+    }
+
+    fn gesture_device(&self) -> Option<GestureDevice> {
+        self.current_gesture_device.clone()
+    }
+
+    fn gesture_seat(&self) -> Option<String> {
+        Some(self.seat_id.clone())
+    }
+
+    fn settings(&self) -> ProcessorSettings {
+        ProcessorSettings {
+            threshold: self.threshold,
+            invert_x: self.invert_x,
+            invert_y: self.invert_y,
+            disable_while_typing: self.disable_while_typing,
+            direction_margin: self.direction_margin,
+            event_thresholds: self.event_thresholds.clone(),
+            long_thresholds: self.long_thresholds.clone(),
+            threshold_mode: self.threshold_mode,
+            device_size: self.device_size,
+            rotation_threshold: self.rotation_threshold,
+            pinch_thresholds: self.pinch_thresholds,
+            progress_steps: self.progress_steps.clone(),
+            repeat_events: self.repeat_events.clone(),
+            fast_velocities: self.fast_velocities.clone(),
+            directions: self.directions,
+            sector_widths: self.sector_widths,
+            direction_deadzone: self.direction_deadzone,
+            threshold_x: self.threshold_x,
+            threshold_y: self.threshold_y,
+            max_duration: self.max_duration,
+            hold_times: self.hold_times.clone(),
         }
-        Ok(match (get_event_octant(dx, dy), finger_count_as_enum) {
-            (0, FingerCount::ThreeFinger) => ActionEvent::ThreeFingerSwipeLeft,
-            (1, FingerCount::ThreeFinger) => ActionEvent::ThreeFingerSwipeLeftUp,
-            (2, FingerCount::ThreeFinger) => ActionEvent::ThreeFingerSwipeUp,
-            (3, FingerCount::ThreeFinger) => ActionEvent::ThreeFingerSwipeRightUp,
-            (4, FingerCount::ThreeFinger) => ActionEvent::ThreeFingerSwipeRight,
-            (5, FingerCount::ThreeFinger) => ActionEvent::ThreeFingerSwipeRightDown,
-            (6, FingerCount::ThreeFinger) => ActionEvent::ThreeFingerSwipeDown,
-            (7, FingerCount::ThreeFinger) => ActionEvent::ThreeFingerSwipeLeftDown,
-
-            (0, FingerCount::FourFinger) => ActionEvent::FourFingerSwipeLeft,
-            (1, FingerCount::FourFinger) => ActionEvent::FourFingerSwipeLeftUp,
-            (2, FingerCount::FourFinger) => ActionEvent::FourFingerSwipeUp,
-            (3, FingerCount::FourFinger) => ActionEvent::FourFingerSwipeRightUp,
-            (4, FingerCount::FourFinger) => ActionEvent::FourFingerSwipeRight,
-            (5, FingerCount::FourFinger) => ActionEvent::FourFingerSwipeRightDown,
-            (6, FingerCount::FourFinger) => ActionEvent::FourFingerSwipeDown,
-            (7, FingerCount::FourFinger) => ActionEvent::FourFingerSwipeLeftDown,
-            (_, _) => todo!(),
-        })
     }
 
-    fn dispatch(&mut self, dx: &mut f64, dy: &mut f64) -> Result<Vec<ActionEvent>, LibinputError> {
-        // Block until the descriptor is ready.
-        poll(&mut self.poll_array, None)?;
+    fn update_settings(&mut self, settings: ProcessorSettings) {
+        self.set_threshold(settings.threshold);
+        self.set_invert(settings.invert_x, settings.invert_y);
+        self.set_direction_margin(settings.direction_margin);
+        self.disable_while_typing = settings.disable_while_typing;
+        self.event_thresholds = settings.event_thresholds;
+        self.long_thresholds = settings.long_thresholds;
+        self.threshold_mode = settings.threshold_mode;
+        self.rotation_threshold = settings.rotation_threshold;
+        self.pinch_thresholds = settings.pinch_thresholds;
+        self.progress_steps = settings.progress_steps;
+        self.repeat_events = settings.repeat_events;
+        self.fast_velocities = settings.fast_velocities;
+        self.directions = settings.directions;
+        self.sector_widths = settings.sector_widths;
+        self.direction_deadzone = settings.direction_deadzone;
+        self.threshold_x = settings.threshold_x;
+        self.threshold_y = settings.threshold_y;
+        self.max_duration = settings.max_duration;
+        self.hold_times = settings.hold_times;
+        self.cached_settings = None;
+    }
 
-        // Dispatch, bubbling up in case of an error.
-        self.input.dispatch()?;
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.poll_timeout = timeout;
+    }
 
-        let mut action_events = Vec::new();
-        let events: Vec<Event> = (&mut self.input).collect();
+    fn raw_fd(&self) -> Option<RawFd> {
+        Some(self.as_raw_fd())
+    }
 
-        for event in events {
-            if let Event::Gesture(gesture_event) = event {
-                let result = self.process_event(gesture_event, dx, dy);
+    fn take_gesture_timing(&mut self, action_event: ActionEvent) -> Option<GestureTiming> {
+        self.gesture_timings.remove(&action_event)
+    }
 
-                match result {
-                    Err(e) => {
-                        debug!("Discarding event: {}", e);
-                    }
-                    Ok(None) => {}
-                    Ok(Some(action_event)) => action_events.push(action_event),
-                }
-            }
-        }
+    fn take_gesture_context(&mut self, action_event: ActionEvent) -> Option<GestureContext> {
+        self.gesture_contexts.remove(&action_event)
+    }
 
-        Ok(action_events)
+    fn take_raw_gestures(&mut self) -> Vec<RawGesture> {
+        self.raw_gestures
+            .as_mut()
+            .map(mem::take)
+            .unwrap_or_default()
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::DefaultProcessor;
-    use crate::events::{ActionEvent, Processor, ProcessorError};
+    use crate::events::{
+        ActionEvent, Modifier, Processor, ProcessorError, SwipeBegin, SwipeEnd, SwipeEvent,
+        SwipeUpdate,
+    };
     use crate::test_utils::init_listener;
 
+    use std::collections::{BTreeSet, HashMap};
+    use std::os::unix::io::AsRawFd;
     use std::sync::{Arc, Mutex};
+    use std::time::Duration;
 
     use serial_test::serial;
 
@@ -272,9 +1177,14 @@ mod test {
 
         // Trigger swipe below threshold.
         let action_event = processor._end_event_to_action_event(4.99, 0.0, 3);
-        #[allow(clippy::no_effect_underscore_binding)]
-        let _expected_err = ProcessorError::DisplacementBelowThreshold(5.0);
-        assert!(matches!(action_event, Err(_expected_err)));
+        assert!(matches!(
+            action_event,
+            Err(ProcessorError::DisplacementBelowThreshold {
+                magnitude,
+                finger_count: 3,
+                ..
+            }) if (magnitude - 4.99).abs() < f64::EPSILON
+        ));
 
         // Trigger swipe above threshold.
         let action_event = processor._end_event_to_action_event(5.0, 0.0, 3);
@@ -283,6 +1193,29 @@ mod test {
         std::fs::remove_file(socket_file.path().file_name().unwrap()).ok();
     }
 
+    #[test]
+    #[serial]
+    /// Test the handling of the long thresholds of events.
+    fn test_long_threshold() {
+        let mut processor = DefaultProcessor::default();
+        processor
+            .long_thresholds
+            .insert(ActionEvent::ThreeFingerSwipeRight, 50.0);
+
+        // Trigger swipes below and above the long threshold.
+        let action_event = processor._end_event_to_action_event(49.0, 0.0, 3);
+        assert_eq!(action_event.unwrap(), ActionEvent::ThreeFingerSwipeRight);
+        let action_event = processor._end_event_to_action_event(50.0, 0.0, 3);
+        assert_eq!(
+            action_event.unwrap(),
+            ActionEvent::ThreeFingerSwipeRightLong
+        );
+
+        // Events without long threshold are not affected.
+        let action_event = processor._end_event_to_action_event(-50.0, 0.0, 3);
+        assert_eq!(action_event.unwrap(), ActionEvent::ThreeFingerSwipeLeft);
+    }
+
     #[test]
     #[serial]
     /// Test the handling of different directions.
@@ -386,4 +1319,321 @@ mod test {
 
         std::fs::remove_file(socket_file.path().file_name().unwrap()).ok();
     }
+
+    #[test]
+    #[serial]
+    /// Test the tracking of the held keyboard modifiers and pointer buttons.
+    fn test_held_modifiers() {
+        // Create the listener and the shared storage for the commands.
+        let message_log = Arc::new(Mutex::new(vec![]));
+        let socket_file = init_listener(Arc::clone(&message_log));
+
+        // Initialize the processor.
+        let mut processor = DefaultProcessor::default();
+
+        // Press left shift, right shift, left meta and a non-modifier key.
+        processor.update_modifier_keys(42, true);
+        processor.update_modifier_keys(54, true);
+        processor.update_modifier_keys(125, true);
+        processor.update_modifier_keys(30, true);
+        assert_eq!(
+            processor.held_modifiers(),
+            BTreeSet::from([Modifier::Shift, Modifier::Super])
+        );
+
+        // Release left shift: shift is still held via right shift.
+        processor.update_modifier_keys(42, false);
+        assert_eq!(
+            processor.held_modifiers(),
+            BTreeSet::from([Modifier::Shift, Modifier::Super])
+        );
+
+        // Press the side button, which is combined with the keyboard
+        // modifiers.
+        processor.update_modifier_keys(0x113, true);
+        assert_eq!(
+            processor.held_modifiers(),
+            BTreeSet::from([Modifier::Shift, Modifier::Super, Modifier::SideButton])
+        );
+
+        // Release the remaining modifiers.
+        processor.update_modifier_keys(54, false);
+        processor.update_modifier_keys(125, false);
+        processor.update_modifier_keys(0x113, false);
+        assert!(processor.held_modifiers().is_empty());
+
+        std::fs::remove_file(socket_file.path().file_name().unwrap()).ok();
+    }
+
+    #[test]
+    #[serial]
+    /// Test discarding gestures while typing.
+    fn test_disable_while_typing() {
+        // Create the listener and the shared storage for the commands.
+        let message_log = Arc::new(Mutex::new(vec![]));
+        let socket_file = init_listener(Arc::clone(&message_log));
+
+        // Initialize the processor.
+        let mut processor = DefaultProcessor {
+            disable_while_typing: Some(Duration::from_millis(500)),
+            ..Default::default()
+        };
+
+        // No key has been pressed yet.
+        assert!(!processor.is_typing(1_000_000));
+
+        // Press a regular key, and check the window.
+        processor.update_last_key_time(30, 1_000_000);
+        assert!(processor.is_typing(1_499_999));
+        assert!(!processor.is_typing(1_500_000));
+
+        // Press a modifier key, which should not be considered typing.
+        processor.update_last_key_time(125, 2_000_000);
+        assert!(!processor.is_typing(2_000_000));
+
+        std::fs::remove_file(socket_file.path().file_name().unwrap()).ok();
+    }
+
+    #[test]
+    #[serial]
+    /// Test processing a sequence of swipe events.
+    fn test_process_swipe_events() {
+        // Create the listener and the shared storage for the commands.
+        let message_log = Arc::new(Mutex::new(vec![]));
+        let socket_file = init_listener(Arc::clone(&message_log));
+
+        // Initialize the processor.
+        let mut processor = DefaultProcessor::default();
+        let (mut dx, mut dy) = (0.0, 0.0);
+
+        // Feed a swipe to the right, split across several updates.
+        let mut events = vec![SwipeEvent::Begin(SwipeBegin {
+            time_usec: 0,
+            finger_count: 3,
+        })];
+        events.extend((1..=3).map(|i| {
+            SwipeEvent::Update(SwipeUpdate {
+                time_usec: i,
+                finger_count: 3,
+                dx: 2.0,
+                dy: 0.1,
+            })
+        }));
+        for event in events {
+            assert_eq!(
+                processor.process_event(event, &mut dx, &mut dy).unwrap(),
+                None
+            );
+        }
+        let end_event = SwipeEvent::End(SwipeEnd {
+            time_usec: 4,
+            finger_count: 3,
+            cancelled: false,
+        });
+        assert_eq!(
+            processor
+                .process_event(end_event, &mut dx, &mut dy)
+                .unwrap(),
+            Some(ActionEvent::ThreeFingerSwipeRight)
+        );
+
+        // Assert that the context of the gesture is recorded.
+        let context = processor
+            .take_gesture_context(ActionEvent::ThreeFingerSwipeRight)
+            .unwrap();
+        assert_eq!(context.finger_count, Some(3));
+        assert!((context.dx - 6.0).abs() < 1e-9);
+        assert!((context.dy - 0.3).abs() < 1e-9);
+        assert!(processor
+            .take_gesture_context(ActionEvent::ThreeFingerSwipeRight)
+            .is_none());
+
+        // Assert that a new swipe resets the displacement.
+        let begin_event = SwipeEvent::Begin(SwipeBegin {
+            time_usec: 5,
+            finger_count: 3,
+        });
+        processor
+            .process_event(begin_event, &mut dx, &mut dy)
+            .unwrap();
+        assert!(matches!(
+            processor.process_event(end_event, &mut dx, &mut dy),
+            Err(ProcessorError::DisplacementBelowThreshold { .. })
+        ));
+
+        // Assert that cancelled swipes are discarded.
+        for event in [
+            begin_event,
+            SwipeEvent::Update(SwipeUpdate {
+                time_usec: 6,
+                finger_count: 3,
+                dx: 10.0,
+                dy: 0.0,
+            }),
+        ] {
+            processor.process_event(event, &mut dx, &mut dy).unwrap();
+        }
+        let cancelled_event = SwipeEvent::End(SwipeEnd {
+            time_usec: 7,
+            finger_count: 3,
+            cancelled: true,
+        });
+        assert!(matches!(
+            processor.process_event(cancelled_event, &mut dx, &mut dy),
+            Err(ProcessorError::Cancelled)
+        ));
+
+        std::fs::remove_file(socket_file.path().file_name().unwrap()).ok();
+    }
+
+    #[test]
+    #[serial]
+    /// Test the handling of the fast velocities of events.
+    fn test_fast_velocity() {
+        let mut processor = DefaultProcessor::default();
+        processor
+            .fast_velocities
+            .insert(ActionEvent::ThreeFingerSwipeRight, 100.0);
+        let (mut dx, mut dy) = (0.0, 0.0);
+
+        // Trigger swipes of 20 units in 0.1 and 0.5 seconds.
+        for (duration_usec, expected) in [
+            (100_000, ActionEvent::ThreeFingerSwipeRightFast),
+            (500_000, ActionEvent::ThreeFingerSwipeRight),
+        ] {
+            let events = [
+                SwipeEvent::Begin(SwipeBegin {
+                    time_usec: 1_000_000,
+                    finger_count: 3,
+                }),
+                SwipeEvent::Update(SwipeUpdate {
+                    time_usec: 1_000_000 + duration_usec / 2,
+                    finger_count: 3,
+                    dx: 20.0,
+                    dy: 0.0,
+                }),
+                SwipeEvent::End(SwipeEnd {
+                    time_usec: 1_000_000 + duration_usec,
+                    finger_count: 3,
+                    cancelled: false,
+                }),
+            ];
+            let action_events: Vec<_> = events
+                .into_iter()
+                .filter_map(|event| processor.process_event(event, &mut dx, &mut dy).unwrap())
+                .collect();
+            assert_eq!(action_events, vec![expected]);
+        }
+    }
+
+    #[test]
+    #[serial]
+    /// Test discarding the swipes lasting longer than the maximum duration.
+    fn test_max_duration() {
+        let mut processor = DefaultProcessor {
+            max_duration: Some(Duration::from_secs(1)),
+            ..DefaultProcessor::default()
+        };
+        let (mut dx, mut dy) = (0.0, 0.0);
+
+        for (end_usec, expected) in [(900_000, true), (1_100_000, false)] {
+            let events = [
+                SwipeEvent::Begin(SwipeBegin {
+                    time_usec: 0,
+                    finger_count: 3,
+                }),
+                SwipeEvent::Update(SwipeUpdate {
+                    time_usec: 100_000,
+                    finger_count: 3,
+                    dx: 20.0,
+                    dy: 0.0,
+                }),
+            ];
+            for event in events {
+                processor.process_event(event, &mut dx, &mut dy).unwrap();
+            }
+            let result = processor.process_event(
+                SwipeEvent::End(SwipeEnd {
+                    time_usec: end_usec,
+                    finger_count: 3,
+                    cancelled: false,
+                }),
+                &mut dx,
+                &mut dy,
+            );
+            if expected {
+                assert_eq!(result.unwrap(), Some(ActionEvent::ThreeFingerSwipeRight));
+            } else {
+                assert!(matches!(
+                    result,
+                    Err(ProcessorError::DurationExceeded { .. })
+                ));
+            }
+        }
+    }
+
+    #[test]
+    #[serial]
+    /// Test producing the hold variant of the swipes held still at the end.
+    fn test_hold_time() {
+        let mut processor = DefaultProcessor {
+            hold_times: HashMap::from([(
+                ActionEvent::ThreeFingerSwipeUp,
+                Duration::from_millis(500),
+            )]),
+            ..DefaultProcessor::default()
+        };
+        let (mut dx, mut dy) = (0.0, 0.0);
+
+        for (finger_count, end_usec, expected) in [
+            (3, 350_000, ActionEvent::ThreeFingerSwipeUp),
+            (3, 700_000, ActionEvent::ThreeFingerSwipeUpHold),
+            (4, 700_000, ActionEvent::FourFingerSwipeUp),
+        ] {
+            // Swipe, and rest the fingers with slight jitter.
+            let events = [
+                SwipeEvent::Begin(SwipeBegin {
+                    time_usec: 0,
+                    finger_count,
+                }),
+                SwipeEvent::Update(SwipeUpdate {
+                    time_usec: 100_000,
+                    finger_count,
+                    dx: 0.0,
+                    dy: -20.0,
+                }),
+                SwipeEvent::Update(SwipeUpdate {
+                    time_usec: 300_000,
+                    finger_count,
+                    dx: 0.1,
+                    dy: 0.2,
+                }),
+                SwipeEvent::End(SwipeEnd {
+                    time_usec: end_usec,
+                    finger_count,
+                    cancelled: false,
+                }),
+            ];
+            let action_events: Vec<_> = events
+                .into_iter()
+                .filter_map(|event| processor.process_event(event, &mut dx, &mut dy).unwrap())
+                .collect();
+            assert_eq!(action_events, vec![expected]);
+        }
+    }
+
+    #[test]
+    #[serial]
+    /// Test handling the pending events from an external event loop.
+    fn test_handle_ready() {
+        let mut processor = DefaultProcessor::default();
+        let (mut dx, mut dy) = (0.0, 0.0);
+
+        // Assert that the exposed descriptor is the one used for polling.
+        assert_eq!(processor.as_raw_fd(), processor.poll_array[0].fd);
+
+        // Consume the device events, and assert no gestures are recognized.
+        processor.check_devices().ok();
+        assert_eq!(processor.handle_ready(&mut dx, &mut dy).unwrap(), vec![]);
+    }
 }
@@ -3,9 +3,11 @@
 //! [`events`]: crate::events
 
 use std::io::Error as IoError;
+use std::path::PathBuf;
+use std::time::Duration;
 
 use filedescriptor::Error as FileDescriptorError;
-use input::event::gesture::GestureSwipeEvent;
+use strum::IntoStaticStr;
 use thiserror::Error;
 
 /// Errors raised during `libinput` initialization.
@@ -14,11 +16,28 @@ use thiserror::Error;
 /// some of which wrap over:
 /// * [`filedescriptor::Error`] (during [`filedescriptor::poll`]).
 /// * [`std::io::Error`] (during [`input::Libinput::dispatch`]).
-#[derive(Error, Debug)]
+#[derive(Error, Debug, IntoStaticStr)]
+#[strum(serialize_all = "kebab_case")]
+#[non_exhaustive]
 pub enum LibinputError {
-    /// Error while assigning seat to the libinput context.
-    #[error("error while assigning seat to the libinput context")]
-    SeatError,
+    /// Error while assigning seat to the `udev` context.
+    #[error("unable to assign seat {0} to the udev context")]
+    UdevError(String),
+
+    /// No gesture-capable devices found on the seat.
+    #[error("no gesture-capable devices found on seat {0}")]
+    NoDevices(String),
+
+    /// Error while adding a device to the libinput context.
+    #[error("unable to add device {0} to the libinput context")]
+    DeviceError(String),
+
+    /// Permission denied while opening a device.
+    #[error(
+        "permission denied while opening device {} (is the user in the input group?)",
+        .0.display()
+    )]
+    PermissionDenied(PathBuf),
 
     /// Unknown error while dispatching libinput event.
     #[error("unknown error while dispatching libinput event")]
@@ -29,20 +48,99 @@ pub enum LibinputError {
     IOError(#[from] FileDescriptorError),
 }
 
+impl LibinputError {
+    /// Return the stable code of the error, as the kebab-case name of the
+    /// variant.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        self.into()
+    }
+}
+
 /// Errors raised during processing of events in the [`Processor`].
 ///
 /// [`Processor`]: crate::events::Processor
-#[derive(Error, Debug)]
+#[derive(Error, Debug, IntoStaticStr)]
+#[strum(serialize_all = "kebab_case")]
+#[non_exhaustive]
 pub enum ProcessorError {
     /// Unsupported finger count.
     #[error("unsupported finger count ({0})")]
     UnsupportedFingerCount(i32),
 
     /// Unsupported swipe event.
-    #[error("unsupported swipe event ({:?})", .0)]
-    UnsupportedSwipeEvent(GestureSwipeEvent),
+    #[error("unsupported swipe event ({0})")]
+    UnsupportedSwipeEvent(String),
 
     /// Event displacement is below threshold.
-    #[error("event displacement is below threshold ({0})")]
-    DisplacementBelowThreshold(f64),
+    #[error(
+        "event displacement is below threshold ({magnitude:.2} < {threshold}, \
+         dx: {dx:.2}, dy: {dy:.2}, {finger_count} fingers)"
+    )]
+    DisplacementBelowThreshold {
+        /// Displacement in the `x` axis.
+        dx: f64,
+        /// Displacement in the `y` axis.
+        dy: f64,
+        /// Magnitude of the displacement.
+        magnitude: f64,
+        /// Minimum threshold for displacement changes.
+        threshold: f64,
+        /// Number of fingers used for the gesture.
+        finger_count: i32,
+    },
+
+    /// Direction of a swipe is too close to the boundary between two
+    /// directions.
+    #[error("ambiguous direction ({angle:.2} degrees, {finger_count} fingers)")]
+    AmbiguousDirection {
+        /// Angle (in degrees) of the swipe.
+        angle: f64,
+        /// Number of fingers used for the gesture.
+        finger_count: i32,
+    },
+
+    /// Scale and rotation of a pinch gesture are below their thresholds.
+    #[error("pinch is below thresholds (scale {scale:.2}, rotation {angle:.2} degrees)")]
+    PinchBelowThreshold {
+        /// Scale of the gesture at its end.
+        scale: f64,
+        /// Accumulated angle (in degrees) of the gesture.
+        angle: f64,
+    },
+
+    /// Gesture was cancelled by `libinput`.
+    #[error("gesture was cancelled")]
+    Cancelled,
+
+    /// Gesture lasted longer than the maximum duration.
+    #[error("gesture lasted too long ({duration:?} > {max_duration:?})")]
+    DurationExceeded {
+        /// Time elapsed between the start and the end of the gesture.
+        duration: Duration,
+        /// Maximum duration of the gestures.
+        max_duration: Duration,
+    },
+
+    /// Event happened while typing.
+    #[error("event happened while typing")]
+    WhileTyping,
+
+    /// Invalid line in a serialized corpus of events.
+    #[error("invalid corpus event at line {line}: {content}")]
+    InvalidCorpus {
+        /// Number of the line, starting from `1`.
+        line: usize,
+        /// Content of the line.
+        content: String,
+    },
+}
+
+impl ProcessorError {
+    /// Return the stable code of the error, as the kebab-case name of the
+    /// variant.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        self.into()
+    }
 }
@@ -0,0 +1,193 @@
+//! Learning of the displacement thresholds from the gestures performed.
+//!
+//! The magnitudes of the swipes accepted and discarded due to being below the
+//! threshold are recorded per event, allowing to suggest a threshold for each
+//! finger count and direction that fits the way the user swipes.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use strum::IntoEnumIterator;
+
+use crate::events::{ActionEvent, ProcessorSettings};
+
+/// Minimum number of intended swipes of an event for suggesting a threshold.
+const MIN_SAMPLES: usize = 5;
+
+/// Shared threshold learner.
+pub type SharedThresholdLearner = Arc<Mutex<ThresholdLearner>>;
+
+/// Magnitudes of the swipes recorded for an event.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MagnitudeSamples {
+    /// Magnitudes of the swipes that reached the threshold.
+    pub accepted: Vec<f64>,
+    /// Magnitudes of the swipes that were below the threshold.
+    pub rejected: Vec<f64>,
+}
+
+/// Threshold suggested for an event.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ThresholdSuggestion {
+    /// Event the threshold applies to.
+    pub action_event: ActionEvent,
+    /// Threshold currently in use.
+    pub current: f64,
+    /// Suggested threshold.
+    pub suggested: f64,
+    /// Number of swipes that reached the threshold.
+    pub accepted: usize,
+    /// Number of swipes that were below the threshold.
+    pub rejected: usize,
+}
+
+impl fmt::Display for ThresholdSuggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: threshold {} -> {} ({} accepted, {} below threshold)",
+            self.action_event, self.current, self.suggested, self.accepted, self.rejected
+        )
+    }
+}
+
+/// Recorder of the magnitudes of the swipes, for suggesting thresholds.
+#[derive(Clone, Debug, Default)]
+pub struct ThresholdLearner {
+    /// Magnitudes recorded for each event (regular variants only).
+    pub samples: HashMap<ActionEvent, MagnitudeSamples>,
+}
+
+impl ThresholdLearner {
+    /// Record the magnitude of a swipe.
+    ///
+    /// # Arguments
+    ///
+    /// * `action_event` - the event matching the finger count and direction
+    ///   of the swipe.
+    /// * `magnitude` - the magnitude of the swipe, as compared against the
+    ///   threshold.
+    /// * `accepted` - whether the swipe reached the threshold.
+    pub fn record(&mut self, action_event: ActionEvent, magnitude: f64, accepted: bool) {
+        let samples = self.samples.entry(action_event.short()).or_default();
+        if accepted {
+            samples.accepted.push(magnitude);
+        } else {
+            samples.rejected.push(magnitude);
+        }
+    }
+
+    /// Return the thresholds suggested for the events with enough swipes.
+    ///
+    /// The swipes below half the current threshold are considered
+    /// accidental, and the rest intended. The suggested threshold is half the
+    /// 10th percentile of the intended swipes, without going below the 90th
+    /// percentile of the accidental ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - the parameters currently used for recognizing gestures.
+    #[must_use]
+    pub fn suggestions(&self, settings: &ProcessorSettings) -> Vec<ThresholdSuggestion> {
+        ActionEvent::iter()
+            .filter_map(|action_event| {
+                let samples = self.samples.get(&action_event)?;
+                let current = settings
+                    .event_thresholds
+                    .get(&action_event)
+                    .copied()
+                    .unwrap_or(settings.threshold);
+
+                let (mut intended, mut accidental): (Vec<f64>, Vec<f64>) = samples
+                    .accepted
+                    .iter()
+                    .chain(&samples.rejected)
+                    .partition(|magnitude| **magnitude >= current / 2.0);
+                if intended.len() < MIN_SAMPLES {
+                    return None;
+                }
+                intended.sort_by(f64::total_cmp);
+                accidental.sort_by(f64::total_cmp);
+
+                let mut suggested = intended[intended.len() / 10] / 2.0;
+                if !accidental.is_empty() {
+                    suggested = suggested.max(accidental[accidental.len() * 9 / 10]);
+                }
+
+                Some(ThresholdSuggestion {
+                    action_event,
+                    current,
+                    suggested: (suggested * 10.0).round() / 10.0,
+                    accepted: samples.accepted.len(),
+                    rejected: samples.rejected.len(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{HashMap, HashSet};
+
+    use super::ThresholdLearner;
+    use crate::events::{ActionEvent, PinchThresholds, ProcessorSettings, ThresholdMode};
+
+    #[test]
+    /// Test suggesting thresholds from the recorded swipes.
+    fn test_threshold_suggestions() {
+        let settings = ProcessorSettings {
+            threshold: 5.0,
+            invert_x: false,
+            invert_y: false,
+            disable_while_typing: None,
+            direction_margin: None,
+            event_thresholds: HashMap::new(),
+            long_thresholds: HashMap::new(),
+            threshold_mode: ThresholdMode::Absolute,
+            device_size: None,
+            rotation_threshold: 30.0,
+            pinch_thresholds: PinchThresholds::default(),
+            progress_steps: HashMap::new(),
+            repeat_events: HashSet::new(),
+            fast_velocities: HashMap::new(),
+            directions: 8,
+            sector_widths: None,
+            direction_deadzone: None,
+            threshold_x: None,
+            threshold_y: None,
+            max_duration: None,
+            hold_times: HashMap::new(),
+        };
+        let mut learner = ThresholdLearner::default();
+
+        // Record swipes comfortably above the threshold, near misses and
+        // accidental touches.
+        for magnitude in [12.0, 14.0, 16.0, 18.0, 20.0] {
+            learner.record(ActionEvent::ThreeFingerSwipeLeft, magnitude, true);
+        }
+        learner.record(ActionEvent::ThreeFingerSwipeLeftLong, 40.0, true);
+        learner.record(ActionEvent::ThreeFingerSwipeLeft, 4.0, false);
+        learner.record(ActionEvent::ThreeFingerSwipeLeft, 1.0, false);
+
+        // Not enough swipes for suggesting a threshold.
+        for _ in 0..4 {
+            learner.record(ActionEvent::FourFingerSwipeUp, 10.0, true);
+        }
+
+        let suggestions = learner.suggestions(&settings);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(
+            suggestions[0].action_event,
+            ActionEvent::ThreeFingerSwipeLeft
+        );
+        assert_eq!(suggestions[0].accepted, 6);
+        assert_eq!(suggestions[0].rejected, 2);
+        assert!((suggestions[0].suggested - 2.0).abs() < f64::EPSILON);
+        assert_eq!(
+            suggestions[0].to_string(),
+            "three-finger-swipe-left: threshold 5 -> 2 (6 accepted, 2 below threshold)"
+        );
+    }
+}
@@ -1,15 +1,103 @@
 //! Components for interacting with `libinput`.
 
-use std::fs::{File, OpenOptions};
+use std::cell::RefCell;
+use std::fs::{self, File, OpenOptions};
 use std::os::unix::fs::OpenOptionsExt;
 use std::os::unix::io::OwnedFd;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
-use input::LibinputInterface;
+use input::event::gesture::{
+    GestureEndEvent, GestureEventCoordinates, GestureEventTrait, GestureSwipeEvent,
+};
+use input::{Device, DeviceCapability, LibinputInterface, ScrollMethod};
 use libc::{O_RDONLY, O_RDWR, O_WRONLY};
 
+use crate::events::errors::ProcessorError;
+use crate::events::swipe::{SwipeBegin, SwipeEnd, SwipeEvent, SwipeUpdate};
+use crate::events::DeviceClass;
+
+/// Errors raised while opening devices, as pairs of paths and error codes.
+pub type SharedOpenErrors = Rc<RefCell<Vec<(PathBuf, i32)>>>;
+
+/// Directory where `systemd-logind` lists the available seats.
+const SEATS_DIR: &str = "/run/systemd/seats";
+
+/// Return the names of the available seats.
+///
+/// The seats are retrieved from `systemd-logind`, falling back to `seat0` if
+/// they could not be listed.
+#[must_use]
+pub fn available_seats() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(SEATS_DIR) else {
+        return vec![String::from("seat0")];
+    };
+
+    let mut seats: Vec<String> = entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .collect();
+    seats.sort();
+    seats
+}
+
+impl TryFrom<GestureSwipeEvent> for SwipeEvent {
+    type Error = ProcessorError;
+
+    fn try_from(event: GestureSwipeEvent) -> Result<Self, Self::Error> {
+        let time_usec = event.time_usec();
+        let finger_count = event.finger_count();
+        match event {
+            GestureSwipeEvent::Begin(_) => Ok(SwipeEvent::Begin(SwipeBegin {
+                time_usec,
+                finger_count,
+            })),
+            GestureSwipeEvent::Update(update_event) => Ok(SwipeEvent::Update(SwipeUpdate {
+                time_usec,
+                finger_count,
+                dx: update_event.dx(),
+                dy: update_event.dy(),
+            })),
+            GestureSwipeEvent::End(end_event) => Ok(SwipeEvent::End(SwipeEnd {
+                time_usec,
+                finger_count,
+                cancelled: end_event.cancelled(),
+            })),
+            // GestureSwipeEvent is non-exhaustive.
+            other => Err(ProcessorError::UnsupportedSwipeEvent(format!("{other:?}"))),
+        }
+    }
+}
+
+impl From<&Device> for DeviceClass {
+    fn from(device: &Device) -> Self {
+        if device.has_capability(DeviceCapability::Gesture) {
+            // `libinput` only provides disable-while-typing for the internal
+            // touchpads, as external ones are usually away from the keyboard.
+            if device.config_dwt_is_available() {
+                DeviceClass::InternalTouchpad
+            } else {
+                DeviceClass::ExternalTouchpad
+            }
+        } else if device.has_capability(DeviceCapability::Touch) {
+            DeviceClass::Touchscreen
+        } else if device.has_capability(DeviceCapability::Pointer)
+            && device.config_scroll_default_method() == Some(ScrollMethod::OnButtonDown)
+        {
+            // Pointing sticks are the only devices that scroll by default
+            // while holding a button.
+            DeviceClass::Trackpoint
+        } else {
+            DeviceClass::Other
+        }
+    }
+}
+
 /// Struct for `libinput` interface.
-pub struct Interface;
+#[derive(Default)]
+pub(crate) struct Interface {
+    /// Errors raised while opening devices.
+    pub open_errors: SharedOpenErrors,
+}
 
 impl LibinputInterface for Interface {
     #[allow(clippy::bad_bit_mask)]
@@ -20,7 +108,13 @@ impl LibinputInterface for Interface {
             .write((flags & O_WRONLY != 0) | (flags & O_RDWR != 0))
             .open(path)
             .map(std::convert::Into::into)
-            .map_err(|err| err.raw_os_error().unwrap())
+            .map_err(|err| {
+                let errno = err.raw_os_error().unwrap();
+                self.open_errors
+                    .borrow_mut()
+                    .push((path.to_path_buf(), errno));
+                errno
+            })
     }
 
     fn close_restricted(&mut self, fd: OwnedFd) {
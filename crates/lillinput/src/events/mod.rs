@@ -1,15 +1,47 @@
 //! Components for capturing and handling events.
 
+#[cfg(feature = "calloop")]
+pub mod calloopsource;
+pub mod corpus;
 pub mod defaultprocessor;
 pub mod errors;
+pub mod learning;
 pub mod libinput;
+pub mod multiseat;
+pub mod pinch;
+pub mod stream;
+pub mod swipe;
+pub mod switch;
+pub mod touch;
 
+#[cfg(feature = "calloop")]
+pub use crate::events::calloopsource::ProcessorSource;
+pub use crate::events::corpus::SwipeCorpus;
 pub use crate::events::defaultprocessor::DefaultProcessor;
 pub use crate::events::errors::{LibinputError, ProcessorError};
+pub use crate::events::learning::{SharedThresholdLearner, ThresholdLearner, ThresholdSuggestion};
+pub use crate::events::multiseat::MultiSeatProcessor;
+pub use crate::events::pinch::{rotation_event, PinchEvent, PinchThresholds};
+pub use crate::events::stream::{GestureStream, RawGesture};
+pub use crate::events::swipe::{
+    SwipeBegin, SwipeEnd, SwipeEvent, SwipeSample, SwipeUpdate, SWIPE_DIRECTIONS,
+};
+pub use crate::events::switch::switch_event;
+pub use crate::events::touch::{EdgeSwipeThresholds, EdgeSwipeTracker};
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::os::unix::io::RawFd;
+use std::ptr;
+use std::time::Duration;
 
-use input::event::GestureEvent;
 use strum::{Display, EnumIter, EnumString, EnumVariantNames};
 
+/// Name of the virtual touchpad used for emitting gestures.
+///
+/// The gestures performed on a device with this name are ignored, avoiding
+/// handling the gestures emitted by `lillinput` itself.
+pub const VIRTUAL_TOUCHPAD_NAME: &str = "lillinput virtual touchpad";
+
 /// High-level application events that can trigger an action.
 #[derive(
     Copy, Clone, Display, EnumIter, EnumString, EnumVariantNames, Eq, Hash, PartialEq, Debug,
@@ -48,6 +80,508 @@ pub enum ActionEvent {
     FourFingerSwipeDown,
     /// Four-finger swipe to left-down.
     FourFingerSwipeLeftDown,
+    /// Long three-finger swipe to left.
+    ThreeFingerSwipeLeftLong,
+    /// Long three-finger swipe to left-up.
+    ThreeFingerSwipeLeftUpLong,
+    /// Long three-finger swipe to up.
+    ThreeFingerSwipeUpLong,
+    /// Long three-finger swipe to right-up.
+    ThreeFingerSwipeRightUpLong,
+    /// Long three-finger swipe to right.
+    ThreeFingerSwipeRightLong,
+    /// Long three-finger swipe to right-down.
+    ThreeFingerSwipeRightDownLong,
+    /// Long three-finger swipe to down.
+    ThreeFingerSwipeDownLong,
+    /// Long three-finger swipe to left-down.
+    ThreeFingerSwipeLeftDownLong,
+    /// Long four-finger swipe to left.
+    FourFingerSwipeLeftLong,
+    /// Long four-finger swipe to left-up.
+    FourFingerSwipeLeftUpLong,
+    /// Long four-finger swipe to up.
+    FourFingerSwipeUpLong,
+    /// Long four-finger swipe to right-up.
+    FourFingerSwipeRightUpLong,
+    /// Long four-finger swipe to right.
+    FourFingerSwipeRightLong,
+    /// Long four-finger swipe to right-down.
+    FourFingerSwipeRightDownLong,
+    /// Long four-finger swipe to down.
+    FourFingerSwipeDownLong,
+    /// Long four-finger swipe to left-down.
+    FourFingerSwipeLeftDownLong,
+    /// Fast three-finger swipe to left.
+    ThreeFingerSwipeLeftFast,
+    /// Fast three-finger swipe to left-up.
+    ThreeFingerSwipeLeftUpFast,
+    /// Fast three-finger swipe to up.
+    ThreeFingerSwipeUpFast,
+    /// Fast three-finger swipe to right-up.
+    ThreeFingerSwipeRightUpFast,
+    /// Fast three-finger swipe to right.
+    ThreeFingerSwipeRightFast,
+    /// Fast three-finger swipe to right-down.
+    ThreeFingerSwipeRightDownFast,
+    /// Fast three-finger swipe to down.
+    ThreeFingerSwipeDownFast,
+    /// Fast three-finger swipe to left-down.
+    ThreeFingerSwipeLeftDownFast,
+    /// Fast four-finger swipe to left.
+    FourFingerSwipeLeftFast,
+    /// Fast four-finger swipe to left-up.
+    FourFingerSwipeLeftUpFast,
+    /// Fast four-finger swipe to up.
+    FourFingerSwipeUpFast,
+    /// Fast four-finger swipe to right-up.
+    FourFingerSwipeRightUpFast,
+    /// Fast four-finger swipe to right.
+    FourFingerSwipeRightFast,
+    /// Fast four-finger swipe to right-down.
+    FourFingerSwipeRightDownFast,
+    /// Fast four-finger swipe to down.
+    FourFingerSwipeDownFast,
+    /// Fast four-finger swipe to left-down.
+    FourFingerSwipeLeftDownFast,
+    /// Three-finger swipe to left, followed by a hold.
+    ThreeFingerSwipeLeftHold,
+    /// Three-finger swipe to left-up, followed by a hold.
+    ThreeFingerSwipeLeftUpHold,
+    /// Three-finger swipe to up, followed by a hold.
+    ThreeFingerSwipeUpHold,
+    /// Three-finger swipe to right-up, followed by a hold.
+    ThreeFingerSwipeRightUpHold,
+    /// Three-finger swipe to right, followed by a hold.
+    ThreeFingerSwipeRightHold,
+    /// Three-finger swipe to right-down, followed by a hold.
+    ThreeFingerSwipeRightDownHold,
+    /// Three-finger swipe to down, followed by a hold.
+    ThreeFingerSwipeDownHold,
+    /// Three-finger swipe to left-down, followed by a hold.
+    ThreeFingerSwipeLeftDownHold,
+    /// Four-finger swipe to left, followed by a hold.
+    FourFingerSwipeLeftHold,
+    /// Four-finger swipe to left-up, followed by a hold.
+    FourFingerSwipeLeftUpHold,
+    /// Four-finger swipe to up, followed by a hold.
+    FourFingerSwipeUpHold,
+    /// Four-finger swipe to right-up, followed by a hold.
+    FourFingerSwipeRightUpHold,
+    /// Four-finger swipe to right, followed by a hold.
+    FourFingerSwipeRightHold,
+    /// Four-finger swipe to right-down, followed by a hold.
+    FourFingerSwipeRightDownHold,
+    /// Four-finger swipe to down, followed by a hold.
+    FourFingerSwipeDownHold,
+    /// Four-finger swipe to left-down, followed by a hold.
+    FourFingerSwipeLeftDownHold,
+    /// Two-finger clockwise rotation.
+    TwoFingerRotateClockwise,
+    /// Two-finger counter-clockwise rotation.
+    TwoFingerRotateCounterClockwise,
+    /// Two-finger pinch in.
+    TwoFingerPinchIn,
+    /// Two-finger strong pinch in.
+    TwoFingerPinchInStrong,
+    /// Two-finger pinch out.
+    TwoFingerPinchOut,
+    /// Two-finger strong pinch out.
+    TwoFingerPinchOutStrong,
+    /// Three-finger pinch in.
+    ThreeFingerPinchIn,
+    /// Three-finger strong pinch in.
+    ThreeFingerPinchInStrong,
+    /// Three-finger pinch out.
+    ThreeFingerPinchOut,
+    /// Three-finger strong pinch out.
+    ThreeFingerPinchOutStrong,
+    /// Four-finger pinch in.
+    FourFingerPinchIn,
+    /// Four-finger strong pinch in.
+    FourFingerPinchInStrong,
+    /// Four-finger pinch out.
+    FourFingerPinchOut,
+    /// Four-finger strong pinch out.
+    FourFingerPinchOutStrong,
+    /// Touchscreen swipe starting at the left edge.
+    EdgeSwipeFromLeft,
+    /// Touchscreen swipe starting at the right edge.
+    EdgeSwipeFromRight,
+    /// Touchscreen swipe starting at the top edge.
+    EdgeSwipeFromTop,
+    /// Touchscreen swipe starting at the bottom edge.
+    EdgeSwipeFromBottom,
+    /// Device switched into tablet mode.
+    TabletModeOn,
+    /// Device switched out of tablet mode.
+    TabletModeOff,
+    /// Lid closed.
+    LidClosed,
+    /// Lid opened.
+    LidOpened,
+}
+
+impl ActionEvent {
+    /// Return the event with the same number of fingers and variant, and
+    /// opposite direction.
+    #[must_use]
+    pub fn opposite(self) -> Self {
+        match self {
+            ActionEvent::ThreeFingerSwipeLeft => ActionEvent::ThreeFingerSwipeRight,
+            ActionEvent::ThreeFingerSwipeLeftUp => ActionEvent::ThreeFingerSwipeRightDown,
+            ActionEvent::ThreeFingerSwipeUp => ActionEvent::ThreeFingerSwipeDown,
+            ActionEvent::ThreeFingerSwipeRightUp => ActionEvent::ThreeFingerSwipeLeftDown,
+            ActionEvent::ThreeFingerSwipeRight => ActionEvent::ThreeFingerSwipeLeft,
+            ActionEvent::ThreeFingerSwipeRightDown => ActionEvent::ThreeFingerSwipeLeftUp,
+            ActionEvent::ThreeFingerSwipeDown => ActionEvent::ThreeFingerSwipeUp,
+            ActionEvent::ThreeFingerSwipeLeftDown => ActionEvent::ThreeFingerSwipeRightUp,
+            ActionEvent::FourFingerSwipeLeft => ActionEvent::FourFingerSwipeRight,
+            ActionEvent::FourFingerSwipeLeftUp => ActionEvent::FourFingerSwipeRightDown,
+            ActionEvent::FourFingerSwipeUp => ActionEvent::FourFingerSwipeDown,
+            ActionEvent::FourFingerSwipeRightUp => ActionEvent::FourFingerSwipeLeftDown,
+            ActionEvent::FourFingerSwipeRight => ActionEvent::FourFingerSwipeLeft,
+            ActionEvent::FourFingerSwipeRightDown => ActionEvent::FourFingerSwipeLeftUp,
+            ActionEvent::FourFingerSwipeDown => ActionEvent::FourFingerSwipeUp,
+            ActionEvent::FourFingerSwipeLeftDown => ActionEvent::FourFingerSwipeRightUp,
+            ActionEvent::TwoFingerRotateClockwise => ActionEvent::TwoFingerRotateCounterClockwise,
+            ActionEvent::TwoFingerRotateCounterClockwise => ActionEvent::TwoFingerRotateClockwise,
+            ActionEvent::TwoFingerPinchIn => ActionEvent::TwoFingerPinchOut,
+            ActionEvent::TwoFingerPinchInStrong => ActionEvent::TwoFingerPinchOutStrong,
+            ActionEvent::TwoFingerPinchOut => ActionEvent::TwoFingerPinchIn,
+            ActionEvent::TwoFingerPinchOutStrong => ActionEvent::TwoFingerPinchInStrong,
+            ActionEvent::ThreeFingerPinchIn => ActionEvent::ThreeFingerPinchOut,
+            ActionEvent::ThreeFingerPinchInStrong => ActionEvent::ThreeFingerPinchOutStrong,
+            ActionEvent::ThreeFingerPinchOut => ActionEvent::ThreeFingerPinchIn,
+            ActionEvent::ThreeFingerPinchOutStrong => ActionEvent::ThreeFingerPinchInStrong,
+            ActionEvent::FourFingerPinchIn => ActionEvent::FourFingerPinchOut,
+            ActionEvent::FourFingerPinchInStrong => ActionEvent::FourFingerPinchOutStrong,
+            ActionEvent::FourFingerPinchOut => ActionEvent::FourFingerPinchIn,
+            ActionEvent::FourFingerPinchOutStrong => ActionEvent::FourFingerPinchInStrong,
+            ActionEvent::EdgeSwipeFromLeft => ActionEvent::EdgeSwipeFromRight,
+            ActionEvent::EdgeSwipeFromRight => ActionEvent::EdgeSwipeFromLeft,
+            ActionEvent::EdgeSwipeFromTop => ActionEvent::EdgeSwipeFromBottom,
+            ActionEvent::EdgeSwipeFromBottom => ActionEvent::EdgeSwipeFromTop,
+            ActionEvent::TabletModeOn => ActionEvent::TabletModeOff,
+            ActionEvent::TabletModeOff => ActionEvent::TabletModeOn,
+            ActionEvent::LidClosed => ActionEvent::LidOpened,
+            ActionEvent::LidOpened => ActionEvent::LidClosed,
+            _ if self.is_fast() => self.short().opposite().fast(),
+            _ if self.is_hold() => self.short().opposite().hold(),
+            _ => self.short().opposite().long(),
+        }
+    }
+
+    /// Return the long variant of the event.
+    #[must_use]
+    pub fn long(self) -> Self {
+        match self {
+            ActionEvent::ThreeFingerSwipeLeft => ActionEvent::ThreeFingerSwipeLeftLong,
+            ActionEvent::ThreeFingerSwipeLeftUp => ActionEvent::ThreeFingerSwipeLeftUpLong,
+            ActionEvent::ThreeFingerSwipeUp => ActionEvent::ThreeFingerSwipeUpLong,
+            ActionEvent::ThreeFingerSwipeRightUp => ActionEvent::ThreeFingerSwipeRightUpLong,
+            ActionEvent::ThreeFingerSwipeRight => ActionEvent::ThreeFingerSwipeRightLong,
+            ActionEvent::ThreeFingerSwipeRightDown => ActionEvent::ThreeFingerSwipeRightDownLong,
+            ActionEvent::ThreeFingerSwipeDown => ActionEvent::ThreeFingerSwipeDownLong,
+            ActionEvent::ThreeFingerSwipeLeftDown => ActionEvent::ThreeFingerSwipeLeftDownLong,
+            ActionEvent::FourFingerSwipeLeft => ActionEvent::FourFingerSwipeLeftLong,
+            ActionEvent::FourFingerSwipeLeftUp => ActionEvent::FourFingerSwipeLeftUpLong,
+            ActionEvent::FourFingerSwipeUp => ActionEvent::FourFingerSwipeUpLong,
+            ActionEvent::FourFingerSwipeRightUp => ActionEvent::FourFingerSwipeRightUpLong,
+            ActionEvent::FourFingerSwipeRight => ActionEvent::FourFingerSwipeRightLong,
+            ActionEvent::FourFingerSwipeRightDown => ActionEvent::FourFingerSwipeRightDownLong,
+            ActionEvent::FourFingerSwipeDown => ActionEvent::FourFingerSwipeDownLong,
+            ActionEvent::FourFingerSwipeLeftDown => ActionEvent::FourFingerSwipeLeftDownLong,
+            _ => self,
+        }
+    }
+
+    /// Return the fast variant of the event.
+    #[must_use]
+    pub fn fast(self) -> Self {
+        match self {
+            ActionEvent::ThreeFingerSwipeLeft => ActionEvent::ThreeFingerSwipeLeftFast,
+            ActionEvent::ThreeFingerSwipeLeftUp => ActionEvent::ThreeFingerSwipeLeftUpFast,
+            ActionEvent::ThreeFingerSwipeUp => ActionEvent::ThreeFingerSwipeUpFast,
+            ActionEvent::ThreeFingerSwipeRightUp => ActionEvent::ThreeFingerSwipeRightUpFast,
+            ActionEvent::ThreeFingerSwipeRight => ActionEvent::ThreeFingerSwipeRightFast,
+            ActionEvent::ThreeFingerSwipeRightDown => ActionEvent::ThreeFingerSwipeRightDownFast,
+            ActionEvent::ThreeFingerSwipeDown => ActionEvent::ThreeFingerSwipeDownFast,
+            ActionEvent::ThreeFingerSwipeLeftDown => ActionEvent::ThreeFingerSwipeLeftDownFast,
+            ActionEvent::FourFingerSwipeLeft => ActionEvent::FourFingerSwipeLeftFast,
+            ActionEvent::FourFingerSwipeLeftUp => ActionEvent::FourFingerSwipeLeftUpFast,
+            ActionEvent::FourFingerSwipeUp => ActionEvent::FourFingerSwipeUpFast,
+            ActionEvent::FourFingerSwipeRightUp => ActionEvent::FourFingerSwipeRightUpFast,
+            ActionEvent::FourFingerSwipeRight => ActionEvent::FourFingerSwipeRightFast,
+            ActionEvent::FourFingerSwipeRightDown => ActionEvent::FourFingerSwipeRightDownFast,
+            ActionEvent::FourFingerSwipeDown => ActionEvent::FourFingerSwipeDownFast,
+            ActionEvent::FourFingerSwipeLeftDown => ActionEvent::FourFingerSwipeLeftDownFast,
+            _ => self,
+        }
+    }
+
+    /// Return the hold variant of the event.
+    #[must_use]
+    pub fn hold(self) -> Self {
+        match self {
+            ActionEvent::ThreeFingerSwipeLeft => ActionEvent::ThreeFingerSwipeLeftHold,
+            ActionEvent::ThreeFingerSwipeLeftUp => ActionEvent::ThreeFingerSwipeLeftUpHold,
+            ActionEvent::ThreeFingerSwipeUp => ActionEvent::ThreeFingerSwipeUpHold,
+            ActionEvent::ThreeFingerSwipeRightUp => ActionEvent::ThreeFingerSwipeRightUpHold,
+            ActionEvent::ThreeFingerSwipeRight => ActionEvent::ThreeFingerSwipeRightHold,
+            ActionEvent::ThreeFingerSwipeRightDown => ActionEvent::ThreeFingerSwipeRightDownHold,
+            ActionEvent::ThreeFingerSwipeDown => ActionEvent::ThreeFingerSwipeDownHold,
+            ActionEvent::ThreeFingerSwipeLeftDown => ActionEvent::ThreeFingerSwipeLeftDownHold,
+            ActionEvent::FourFingerSwipeLeft => ActionEvent::FourFingerSwipeLeftHold,
+            ActionEvent::FourFingerSwipeLeftUp => ActionEvent::FourFingerSwipeLeftUpHold,
+            ActionEvent::FourFingerSwipeUp => ActionEvent::FourFingerSwipeUpHold,
+            ActionEvent::FourFingerSwipeRightUp => ActionEvent::FourFingerSwipeRightUpHold,
+            ActionEvent::FourFingerSwipeRight => ActionEvent::FourFingerSwipeRightHold,
+            ActionEvent::FourFingerSwipeRightDown => ActionEvent::FourFingerSwipeRightDownHold,
+            ActionEvent::FourFingerSwipeDown => ActionEvent::FourFingerSwipeDownHold,
+            ActionEvent::FourFingerSwipeLeftDown => ActionEvent::FourFingerSwipeLeftDownHold,
+            _ => self,
+        }
+    }
+
+    /// Return the regular variant of the event.
+    #[must_use]
+    pub fn short(self) -> Self {
+        match self {
+            ActionEvent::ThreeFingerSwipeLeftLong
+            | ActionEvent::ThreeFingerSwipeLeftFast
+            | ActionEvent::ThreeFingerSwipeLeftHold => ActionEvent::ThreeFingerSwipeLeft,
+            ActionEvent::ThreeFingerSwipeLeftUpLong
+            | ActionEvent::ThreeFingerSwipeLeftUpFast
+            | ActionEvent::ThreeFingerSwipeLeftUpHold => ActionEvent::ThreeFingerSwipeLeftUp,
+            ActionEvent::ThreeFingerSwipeUpLong
+            | ActionEvent::ThreeFingerSwipeUpFast
+            | ActionEvent::ThreeFingerSwipeUpHold => ActionEvent::ThreeFingerSwipeUp,
+            ActionEvent::ThreeFingerSwipeRightUpLong
+            | ActionEvent::ThreeFingerSwipeRightUpFast
+            | ActionEvent::ThreeFingerSwipeRightUpHold => ActionEvent::ThreeFingerSwipeRightUp,
+            ActionEvent::ThreeFingerSwipeRightLong
+            | ActionEvent::ThreeFingerSwipeRightFast
+            | ActionEvent::ThreeFingerSwipeRightHold => ActionEvent::ThreeFingerSwipeRight,
+            ActionEvent::ThreeFingerSwipeRightDownLong
+            | ActionEvent::ThreeFingerSwipeRightDownFast
+            | ActionEvent::ThreeFingerSwipeRightDownHold => ActionEvent::ThreeFingerSwipeRightDown,
+            ActionEvent::ThreeFingerSwipeDownLong
+            | ActionEvent::ThreeFingerSwipeDownFast
+            | ActionEvent::ThreeFingerSwipeDownHold => ActionEvent::ThreeFingerSwipeDown,
+            ActionEvent::ThreeFingerSwipeLeftDownLong
+            | ActionEvent::ThreeFingerSwipeLeftDownFast
+            | ActionEvent::ThreeFingerSwipeLeftDownHold => ActionEvent::ThreeFingerSwipeLeftDown,
+            ActionEvent::FourFingerSwipeLeftLong
+            | ActionEvent::FourFingerSwipeLeftFast
+            | ActionEvent::FourFingerSwipeLeftHold => ActionEvent::FourFingerSwipeLeft,
+            ActionEvent::FourFingerSwipeLeftUpLong
+            | ActionEvent::FourFingerSwipeLeftUpFast
+            | ActionEvent::FourFingerSwipeLeftUpHold => ActionEvent::FourFingerSwipeLeftUp,
+            ActionEvent::FourFingerSwipeUpLong
+            | ActionEvent::FourFingerSwipeUpFast
+            | ActionEvent::FourFingerSwipeUpHold => ActionEvent::FourFingerSwipeUp,
+            ActionEvent::FourFingerSwipeRightUpLong
+            | ActionEvent::FourFingerSwipeRightUpFast
+            | ActionEvent::FourFingerSwipeRightUpHold => ActionEvent::FourFingerSwipeRightUp,
+            ActionEvent::FourFingerSwipeRightLong
+            | ActionEvent::FourFingerSwipeRightFast
+            | ActionEvent::FourFingerSwipeRightHold => ActionEvent::FourFingerSwipeRight,
+            ActionEvent::FourFingerSwipeRightDownLong
+            | ActionEvent::FourFingerSwipeRightDownFast
+            | ActionEvent::FourFingerSwipeRightDownHold => ActionEvent::FourFingerSwipeRightDown,
+            ActionEvent::FourFingerSwipeDownLong
+            | ActionEvent::FourFingerSwipeDownFast
+            | ActionEvent::FourFingerSwipeDownHold => ActionEvent::FourFingerSwipeDown,
+            ActionEvent::FourFingerSwipeLeftDownLong
+            | ActionEvent::FourFingerSwipeLeftDownFast
+            | ActionEvent::FourFingerSwipeLeftDownHold => ActionEvent::FourFingerSwipeLeftDown,
+            _ => self,
+        }
+    }
+
+    /// Return `true` if the event is a rotation.
+    #[must_use]
+    pub fn is_rotation(self) -> bool {
+        matches!(
+            self,
+            ActionEvent::TwoFingerRotateClockwise | ActionEvent::TwoFingerRotateCounterClockwise
+        )
+    }
+
+    /// Return `true` if the event is a pinch, in any of its tiers.
+    #[must_use]
+    pub fn is_pinch(self) -> bool {
+        matches!(
+            self,
+            ActionEvent::TwoFingerPinchIn
+                | ActionEvent::TwoFingerPinchInStrong
+                | ActionEvent::TwoFingerPinchOut
+                | ActionEvent::TwoFingerPinchOutStrong
+                | ActionEvent::ThreeFingerPinchIn
+                | ActionEvent::ThreeFingerPinchInStrong
+                | ActionEvent::ThreeFingerPinchOut
+                | ActionEvent::ThreeFingerPinchOutStrong
+                | ActionEvent::FourFingerPinchIn
+                | ActionEvent::FourFingerPinchInStrong
+                | ActionEvent::FourFingerPinchOut
+                | ActionEvent::FourFingerPinchOutStrong
+        )
+    }
+
+    /// Return `true` if the event is a touchscreen edge swipe.
+    #[must_use]
+    pub fn is_edge_swipe(self) -> bool {
+        matches!(
+            self,
+            ActionEvent::EdgeSwipeFromLeft
+                | ActionEvent::EdgeSwipeFromRight
+                | ActionEvent::EdgeSwipeFromTop
+                | ActionEvent::EdgeSwipeFromBottom
+        )
+    }
+
+    /// Return `true` if the event is a switch toggle, such as closing the
+    /// lid.
+    #[must_use]
+    pub fn is_switch(self) -> bool {
+        matches!(
+            self,
+            ActionEvent::TabletModeOn
+                | ActionEvent::TabletModeOff
+                | ActionEvent::LidClosed
+                | ActionEvent::LidOpened
+        )
+    }
+
+    /// Return `true` if the event is a touchpad swipe, in any of its
+    /// variants.
+    #[must_use]
+    pub fn is_swipe(self) -> bool {
+        !(self.is_rotation() || self.is_pinch() || self.is_edge_swipe() || self.is_switch())
+    }
+
+    /// Return `true` if the event is the long variant of a swipe.
+    #[must_use]
+    pub fn is_long(self) -> bool {
+        self.short() != self && self.short().long() == self
+    }
+
+    /// Return `true` if the event is the fast variant of a swipe.
+    #[must_use]
+    pub fn is_fast(self) -> bool {
+        self.short() != self && self.short().fast() == self
+    }
+
+    /// Return `true` if the event is the hold variant of a swipe.
+    #[must_use]
+    pub fn is_hold(self) -> bool {
+        self.short() != self && self.short().hold() == self
+    }
+}
+
+/// Modifiers that can be held while performing a gesture.
+///
+/// Besides the keyboard modifiers, pointer buttons (for example, the thumb
+/// buttons of a mouse) can be held, allowing combinations such as a swipe
+/// while a side button is pressed.
+#[derive(
+    Copy,
+    Clone,
+    Display,
+    EnumIter,
+    EnumString,
+    EnumVariantNames,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Debug,
+)]
+#[strum(serialize_all = "kebab_case")]
+pub enum Modifier {
+    /// `Shift` key (left or right).
+    Shift,
+    /// `Ctrl` key (left or right).
+    Ctrl,
+    /// `Alt` key (left or right).
+    Alt,
+    /// `Super` key (left or right).
+    Super,
+    /// Left pointer button.
+    LeftButton,
+    /// Right pointer button.
+    RightButton,
+    /// Middle pointer button.
+    MiddleButton,
+    /// Side (thumb) pointer button.
+    SideButton,
+    /// Extra (thumb) pointer button.
+    ExtraButton,
+    /// Forward pointer button.
+    ForwardButton,
+    /// Back pointer button.
+    BackButton,
+}
+
+impl Modifier {
+    /// Return the [`Modifier`] corresponding to a key code, if any.
+    ///
+    /// Pointer buttons share the key code space, and are also accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - the key or button code, as reported by `libinput`.
+    #[must_use]
+    pub fn from_key(key: u32) -> Option<Self> {
+        // Key codes from `linux/input-event-codes.h`.
+        match key {
+            42 | 54 => Some(Modifier::Shift),
+            29 | 97 => Some(Modifier::Ctrl),
+            56 | 100 => Some(Modifier::Alt),
+            125 | 126 => Some(Modifier::Super),
+            0x110 => Some(Modifier::LeftButton),
+            0x111 => Some(Modifier::RightButton),
+            0x112 => Some(Modifier::MiddleButton),
+            0x113 => Some(Modifier::SideButton),
+            0x114 => Some(Modifier::ExtraButton),
+            0x115 => Some(Modifier::ForwardButton),
+            0x116 => Some(Modifier::BackButton),
+            _ => None,
+        }
+    }
+}
+
+/// Classes of devices, for enabling gestures per class.
+///
+/// The class of a device is determined via `libinput` device queries.
+#[derive(
+    Copy,
+    Clone,
+    Display,
+    EnumIter,
+    EnumString,
+    EnumVariantNames,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Debug,
+)]
+#[strum(serialize_all = "kebab_case")]
+pub enum DeviceClass {
+    /// Touchpad built into the computer.
+    InternalTouchpad,
+    /// External touchpad (for example, a USB or Bluetooth trackpad).
+    ExternalTouchpad,
+    /// Pointing stick (trackpoint).
+    Trackpoint,
+    /// Touchscreen.
+    Touchscreen,
+    /// Any other device.
+    Other,
 }
 
 /// Possible choices for finger count.
@@ -78,6 +612,216 @@ pub enum Axis {
     Y,
 }
 
+/// Possible choices for interpreting the displacement thresholds.
+#[derive(Copy, Clone, Debug, Default, Display, EnumString, EnumVariantNames, Eq, PartialEq)]
+#[strum(serialize_all = "kebab_case")]
+pub enum ThresholdMode {
+    /// Thresholds are displacements in `libinput` units.
+    #[default]
+    Absolute,
+    /// Thresholds are percentages of the width and height of the touchpad.
+    Relative,
+}
+
+/// Timing of a recognized gesture, for measuring its latency.
+///
+/// The times are expressed in microseconds of the monotonic clock, which is
+/// the clock used by `libinput` for the time of the events.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GestureTiming {
+    /// Time at which the gesture ended, as reported by the backend.
+    pub event_usec: u64,
+    /// Time at which the gesture was recognized by the processor.
+    pub recognized_usec: u64,
+}
+
+/// Context of the gesture that triggered an [`ActionEvent`], passed to the
+/// actions when executing them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GestureContext {
+    /// Event the gesture was recognized as.
+    pub action_event: ActionEvent,
+    /// Number of fingers used for the gesture, if known.
+    pub finger_count: Option<i32>,
+    /// Accumulated displacement in the `x` axis.
+    pub dx: f64,
+    /// Accumulated displacement in the `y` axis.
+    pub dy: f64,
+    /// Device that performed the gesture, if known.
+    pub device: Option<GestureDevice>,
+}
+
+impl GestureContext {
+    /// Return a new [`GestureContext`] for an event, without information
+    /// about the gesture (for example, for the events replayed from a
+    /// macro).
+    ///
+    /// # Arguments
+    ///
+    /// * `action_event` - the event.
+    #[must_use]
+    pub fn new(action_event: ActionEvent) -> Self {
+        GestureContext {
+            action_event,
+            finger_count: None,
+            dx: 0.0,
+            dy: 0.0,
+            device: None,
+        }
+    }
+}
+
+/// Device performing a gesture.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GestureDevice {
+    /// Name of the device (for example, `SynPS/2 Synaptics TouchPad`).
+    pub name: String,
+    /// System name of the device (for example, `event5`).
+    pub sysname: String,
+}
+
+impl GestureDevice {
+    /// Return `true` if the name or the system name of the device matches a
+    /// pattern.
+    ///
+    /// The comparison is case-insensitive, as the keys of the configuration
+    /// files are not guaranteed to preserve the case.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - the name or system name of the device.
+    #[must_use]
+    pub fn matches(&self, pattern: &str) -> bool {
+        self.name.eq_ignore_ascii_case(pattern) || self.sysname.eq_ignore_ascii_case(pattern)
+    }
+}
+
+/// Lists of devices whose gestures are processed or ignored.
+///
+/// Each pattern matches either the name or the system name of a device (as
+/// in [`GestureDevice::matches`]), or a `udev` property of the device when in
+/// the `KEY=VALUE` form (for example, `ID_INPUT_TABLET=1`).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DeviceFilter {
+    /// Patterns of the devices whose gestures are processed (all if empty).
+    pub allow: Vec<String>,
+    /// Patterns of the devices whose gestures are ignored, taking precedence
+    /// over the allowed ones.
+    pub deny: Vec<String>,
+}
+
+impl DeviceFilter {
+    /// Return `true` if no devices are filtered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    /// Return `true` if the gestures of a device are processed.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - the device performing the gesture.
+    /// * `property` - function returning the value of a `udev` property of
+    ///   the device, if set.
+    pub fn allows<F>(&self, device: &GestureDevice, property: F) -> bool
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let matches = |pattern: &String| match pattern.split_once('=') {
+            Some((key, value)) => property(key.trim()).is_some_and(|x| x == value.trim()),
+            None => device.matches(pattern),
+        };
+
+        (self.allow.is_empty() || self.allow.iter().any(matches)) && !self.deny.iter().any(matches)
+    }
+}
+
+/// Return the current time of the monotonic clock, in microseconds.
+#[must_use]
+pub fn monotonic_usec() -> u64 {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: the structure is valid for writing, and `CLOCK_MONOTONIC` is
+    // always supported.
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, ptr::addr_of_mut!(ts)) };
+
+    u64::try_from(ts.tv_sec).unwrap_or_default() * 1_000_000
+        + u64::try_from(ts.tv_nsec).unwrap_or_default() / 1_000
+}
+
+/// Parameters used by a [`Processor`] for recognizing gestures.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProcessorSettings {
+    /// Minimum threshold for displacement changes.
+    pub threshold: f64,
+    /// Whether positive displacement on the `X` axis should be interpreted as
+    /// "left".
+    pub invert_x: bool,
+    /// Whether positive displacement on the `Y` axis should be interpreted as
+    /// "up".
+    pub invert_y: bool,
+    /// Time after a key press during which gestures are discarded.
+    pub disable_while_typing: Option<Duration>,
+    /// Margin (in degrees) beyond its sector that the angle of a gesture can
+    /// deviate before changing its initially detected direction.
+    pub direction_margin: Option<f64>,
+    /// Minimum threshold for displacement changes of specific events,
+    /// overriding the global threshold.
+    pub event_thresholds: HashMap<ActionEvent, f64>,
+    /// Minimum threshold for displacement changes of specific events in
+    /// order to produce their long variant.
+    pub long_thresholds: HashMap<ActionEvent, f64>,
+    /// Mode for interpreting the thresholds.
+    pub threshold_mode: ThresholdMode,
+    /// Size (in millimeters) of the device performing the gestures, if
+    /// known.
+    ///
+    /// With [`ThresholdMode::Relative`], the thresholds are interpreted as
+    /// absolute displacements if the size is unknown.
+    pub device_size: Option<(f64, f64)>,
+    /// Minimum rotation (in degrees) of a pinch gesture for recognizing it as
+    /// a rotation event.
+    pub rotation_threshold: f64,
+    /// Scale thresholds for recognizing the pinch gestures.
+    pub pinch_thresholds: PinchThresholds,
+    /// Displacement between the progress events emitted while specific
+    /// swipes are in progress.
+    pub progress_steps: HashMap<ActionEvent, f64>,
+    /// Events emitted again each time the displacement reaches another
+    /// multiple of their threshold, while the swipe is in progress.
+    pub repeat_events: HashSet<ActionEvent>,
+    /// Minimum velocity (displacement per second) of specific events in
+    /// order to produce their fast variant.
+    pub fast_velocities: HashMap<ActionEvent, f64>,
+    /// Number of directions recognized for the swipes: `4` (left, up, right
+    /// and down) or `8` (including the diagonals).
+    pub directions: u8,
+    /// Width (in degrees) of the sector of each direction, starting from the
+    /// left and clock-wise, overriding the equal sectors of the `directions`.
+    ///
+    /// With `4` directions, the widths of the diagonals are expected to be
+    /// `0`, as otherwise the diagonals are recognized.
+    pub sector_widths: Option<[f64; 8]>,
+    /// Angle (in degrees) around the boundaries between directions in which
+    /// the swipes are discarded as ambiguous.
+    pub direction_deadzone: Option<f64>,
+    /// Minimum threshold for displacement changes on the `X` axis,
+    /// overriding the global threshold.
+    pub threshold_x: Option<f64>,
+    /// Minimum threshold for displacement changes on the `Y` axis,
+    /// overriding the global threshold.
+    pub threshold_y: Option<f64>,
+    /// Maximum time between the start and the end of a swipe, beyond which
+    /// it is discarded.
+    pub max_duration: Option<Duration>,
+    /// Minimum time the fingers are held still at the end of specific
+    /// events in order to produce their hold variant.
+    pub hold_times: HashMap<ActionEvent, Duration>,
+}
+
 /// Events processor, converting `libinput` events into [`ActionEvent`]s.
 pub trait Processor {
     /// Dispatch `libinput` events, converting them to [`ActionEvent`]s.
@@ -93,11 +837,15 @@ pub trait Processor {
     /// events.
     fn dispatch(&mut self, dx: &mut f64, dy: &mut f64) -> Result<Vec<ActionEvent>, LibinputError>;
 
-    /// Process a single `libinput` [`GestureEvent`].
+    /// Process a single [`SwipeEvent`].
+    ///
+    /// The events of the backend (such as `libinput`) are translated into
+    /// [`SwipeEvent`]s before being processed, allowing the recognition of
+    /// gestures to be fed directly.
     ///
     /// # Arguments
     ///
-    /// * `event` - a gesture event.
+    /// * `event` - a swipe event.
     /// * `dx` - the current position in the `x` axis.
     /// * `dy` - the current position in the `y` axis.
     ///
@@ -106,7 +854,7 @@ pub trait Processor {
     /// Returns `Err` if the processing of the event failed.
     fn process_event(
         &mut self,
-        event: GestureEvent,
+        event: SwipeEvent,
         dx: &mut f64,
         dy: &mut f64,
     ) -> Result<Option<ActionEvent>, ProcessorError>;
@@ -129,4 +877,245 @@ pub trait Processor {
         dy: f64,
         finger_count: i32,
     ) -> Result<ActionEvent, ProcessorError>;
+
+    /// Return the modifiers (keyboard modifiers and pointer buttons) held at
+    /// the start of the latest gesture.
+    fn gesture_modifiers(&self) -> BTreeSet<Modifier> {
+        BTreeSet::new()
+    }
+
+    /// Return the device that performed the latest gesture, if known.
+    fn gesture_device(&self) -> Option<GestureDevice> {
+        None
+    }
+
+    /// Return the seat that performed the latest gesture, if known.
+    fn gesture_seat(&self) -> Option<String> {
+        None
+    }
+
+    /// Return the parameters used for recognizing gestures.
+    fn settings(&self) -> ProcessorSettings;
+
+    /// Update the parameters used for recognizing gestures.
+    ///
+    /// The new parameters are used from the next processed event, without
+    /// recreating the backend (such as the `libinput` context). The size of
+    /// the device is not updated, as it is detected from the device
+    /// performing each gesture.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - the new parameters.
+    fn update_settings(&mut self, settings: ProcessorSettings);
+
+    /// Set the maximum time to wait for events during a dispatch.
+    ///
+    /// If set, [`Processor::dispatch`] returns (possibly with no events) once
+    /// the timeout expires, allowing periodic tasks to be performed even when
+    /// no input arrives.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - the maximum time to wait, or `None` to wait indefinitely.
+    fn set_timeout(&mut self, timeout: Option<Duration>);
+
+    /// Return the timing of the latest gesture recognized as an
+    /// [`ActionEvent`], removing it.
+    ///
+    /// # Arguments
+    ///
+    /// * `action_event` - the event the gesture was recognized as.
+    fn take_gesture_timing(&mut self, _action_event: ActionEvent) -> Option<GestureTiming> {
+        None
+    }
+
+    /// Return the context of the latest gesture recognized as an
+    /// [`ActionEvent`], removing it.
+    ///
+    /// # Arguments
+    ///
+    /// * `action_event` - the event the gesture was recognized as.
+    fn take_gesture_context(&mut self, _action_event: ActionEvent) -> Option<GestureContext> {
+        None
+    }
+
+    /// Return the raw swipe gestures finished since the latest call, if the
+    /// processor records them.
+    fn take_raw_gestures(&mut self) -> Vec<RawGesture> {
+        Vec::new()
+    }
+
+    /// Return the file descriptor that becomes readable when events are
+    /// pending, if the processor can be polled from an external loop.
+    fn raw_fd(&self) -> Option<RawFd> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ActionEvent, DeviceFilter, GestureDevice};
+
+    use strum::IntoEnumIterator;
+
+    #[test]
+    /// Test the opposite of each event.
+    fn test_opposite_events() {
+        assert_eq!(
+            ActionEvent::ThreeFingerSwipeLeftUp.opposite(),
+            ActionEvent::ThreeFingerSwipeRightDown
+        );
+        for action_event in ActionEvent::iter() {
+            assert_ne!(action_event.opposite(), action_event);
+            assert_eq!(action_event.opposite().opposite(), action_event);
+            assert_eq!(action_event.opposite().is_long(), action_event.is_long());
+            assert_eq!(action_event.opposite().is_fast(), action_event.is_fast());
+            assert_eq!(action_event.opposite().is_hold(), action_event.is_hold());
+        }
+    }
+
+    #[test]
+    /// Test the long and regular variants of each event.
+    fn test_long_events() {
+        assert_eq!(
+            ActionEvent::FourFingerSwipeUp.long(),
+            ActionEvent::FourFingerSwipeUpLong
+        );
+        assert_eq!(
+            ActionEvent::FourFingerSwipeUpLong.to_string(),
+            "four-finger-swipe-up-long"
+        );
+        assert_eq!(ActionEvent::iter().filter(|x| x.is_long()).count(), 16);
+        for action_event in ActionEvent::iter() {
+            assert_eq!(
+                action_event.long().is_long(),
+                action_event.is_swipe() && !(action_event.is_fast() || action_event.is_hold())
+            );
+            assert!(!action_event.short().is_long());
+            assert_eq!(action_event.long().short(), action_event.short());
+        }
+    }
+
+    #[test]
+    /// Test the fast and regular variants of each event.
+    fn test_fast_events() {
+        assert_eq!(
+            ActionEvent::ThreeFingerSwipeLeft.fast(),
+            ActionEvent::ThreeFingerSwipeLeftFast
+        );
+        assert_eq!(
+            ActionEvent::ThreeFingerSwipeLeftFast.to_string(),
+            "three-finger-swipe-left-fast"
+        );
+        assert_eq!(ActionEvent::iter().filter(|x| x.is_fast()).count(), 16);
+        for action_event in ActionEvent::iter() {
+            assert!(!(action_event.is_fast() && action_event.is_long()));
+            assert!(!action_event.short().is_fast());
+            assert_eq!(action_event.fast().short(), action_event.short());
+        }
+    }
+
+    #[test]
+    /// Test the hold and regular variants of each event.
+    fn test_hold_events() {
+        assert_eq!(
+            ActionEvent::ThreeFingerSwipeUp.hold(),
+            ActionEvent::ThreeFingerSwipeUpHold
+        );
+        assert_eq!(
+            ActionEvent::ThreeFingerSwipeUpHold.to_string(),
+            "three-finger-swipe-up-hold"
+        );
+        assert_eq!(ActionEvent::iter().filter(|x| x.is_hold()).count(), 16);
+        for action_event in ActionEvent::iter() {
+            assert!(
+                !(action_event.is_hold() && (action_event.is_long() || action_event.is_fast()))
+            );
+            assert!(!action_event.short().is_hold());
+            assert_eq!(action_event.hold().short(), action_event.short());
+        }
+    }
+
+    #[test]
+    /// Test the switch events.
+    fn test_switch_events() {
+        assert_eq!(ActionEvent::LidClosed.to_string(), "lid-closed");
+        assert_eq!(ActionEvent::TabletModeOn.to_string(), "tablet-mode-on");
+        assert_eq!(ActionEvent::iter().filter(|x| x.is_switch()).count(), 4);
+        for action_event in ActionEvent::iter().filter(|x| x.is_switch()) {
+            assert!(!action_event.is_swipe());
+            assert!(action_event.opposite().is_switch());
+            assert_eq!(action_event.short(), action_event);
+        }
+    }
+
+    #[test]
+    /// Test the pinch events.
+    fn test_pinch_events() {
+        assert_eq!(
+            ActionEvent::ThreeFingerPinchOutStrong.to_string(),
+            "three-finger-pinch-out-strong"
+        );
+        assert_eq!(
+            ActionEvent::TwoFingerPinchIn.opposite(),
+            ActionEvent::TwoFingerPinchOut
+        );
+        assert_eq!(ActionEvent::iter().filter(|x| x.is_pinch()).count(), 12);
+        for action_event in ActionEvent::iter().filter(|x| x.is_pinch()) {
+            assert!(!action_event.is_swipe());
+            assert!(action_event.opposite().is_pinch());
+            assert_eq!(action_event.short(), action_event);
+        }
+    }
+
+    #[test]
+    /// Test matching a device by its name or system name.
+    fn test_gesture_device_matches() {
+        let device = GestureDevice {
+            name: String::from("SynPS/2 Synaptics TouchPad"),
+            sysname: String::from("event5"),
+        };
+        assert!(device.matches("SynPS/2 Synaptics TouchPad"));
+        assert!(device.matches("synps/2 synaptics touchpad"));
+        assert!(device.matches("event5"));
+        assert!(!device.matches("event6"));
+    }
+
+    #[test]
+    /// Test filtering devices by name and by `udev` property.
+    fn test_device_filter() {
+        let touchpad = GestureDevice {
+            name: String::from("SynPS/2 Synaptics TouchPad"),
+            sysname: String::from("event5"),
+        };
+        let tablet = GestureDevice {
+            name: String::from("Wacom Intuos S Pad"),
+            sysname: String::from("event9"),
+        };
+        let property = |device: &GestureDevice| {
+            let is_tablet = device.sysname == "event9";
+            move |key: &str| (key == "ID_INPUT_TABLET" && is_tablet).then(|| String::from("1"))
+        };
+
+        // All the devices are allowed by default.
+        let mut filter = DeviceFilter::default();
+        assert!(filter.is_empty());
+        assert!(filter.allows(&touchpad, property(&touchpad)));
+        assert!(filter.allows(&tablet, property(&tablet)));
+
+        // Deny the devices with a `udev` property.
+        filter.deny = vec![String::from("ID_INPUT_TABLET=1")];
+        assert!(filter.allows(&touchpad, property(&touchpad)));
+        assert!(!filter.allows(&tablet, property(&tablet)));
+
+        // Allow only the devices with a name, with the denied ones taking
+        // precedence.
+        filter.allow = vec![String::from("wacom intuos s pad")];
+        filter.deny = vec![];
+        assert!(!filter.allows(&touchpad, property(&touchpad)));
+        assert!(filter.allows(&tablet, property(&tablet)));
+        filter.deny = vec![String::from("event9")];
+        assert!(!filter.allows(&tablet, property(&tablet)));
+    }
 }
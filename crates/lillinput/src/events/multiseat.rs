@@ -0,0 +1,269 @@
+//! Processing of the gestures of several seats from a single process.
+
+use std::collections::BTreeSet;
+use std::os::unix::io::AsRawFd;
+use std::time::Duration;
+
+use crate::events::errors::{LibinputError, ProcessorError};
+use crate::events::{
+    ActionEvent, DefaultProcessor, GestureContext, GestureDevice, GestureTiming, Modifier,
+    Processor, ProcessorSettings, RawGesture, SwipeEvent,
+};
+
+use filedescriptor::{poll, pollfd, POLLIN};
+
+/// [`Processor`] combining several [`DefaultProcessor`]s, one for each seat.
+///
+/// The `libinput` contexts of all the seats are polled together, and the
+/// events of each seat are processed by its own processor, keeping the state
+/// of the gestures (such as the held modifiers) separate between seats. The
+/// parameters for recognizing gestures are shared by all the seats, except
+/// for the threshold, which can be overridden for each seat.
+pub struct MultiSeatProcessor {
+    /// Processors of each seat, starting with the primary one.
+    processors: Vec<DefaultProcessor>,
+    /// Threshold overriding the shared one, for each seat.
+    thresholds: Vec<Option<f64>>,
+    /// Accumulated displacement of the current gesture of each seat.
+    positions: Vec<(f64, f64)>,
+    /// Maximum time to wait for events during a dispatch.
+    pub poll_timeout: Option<Duration>,
+    /// Index of the processor that recognized the latest event.
+    pub latest: usize,
+}
+
+impl MultiSeatProcessor {
+    /// Return a new [`MultiSeatProcessor`].
+    ///
+    /// # Arguments
+    ///
+    /// * `processor` - the processor of the primary seat, whose parameters
+    ///   are reported as the parameters of the combined processor.
+    #[must_use]
+    pub fn new(processor: DefaultProcessor) -> Self {
+        MultiSeatProcessor {
+            poll_timeout: processor.poll_timeout,
+            processors: vec![processor],
+            thresholds: vec![None],
+            positions: vec![(0.0, 0.0)],
+            latest: 0,
+        }
+    }
+
+    /// Add the processor of another seat.
+    ///
+    /// The parameters for recognizing gestures of the primary seat are
+    /// applied to the processor, except for the threshold if the seat has its
+    /// own.
+    ///
+    /// # Arguments
+    ///
+    /// * `processor` - the processor of the seat.
+    /// * `threshold` - threshold of the seat, overriding the shared one.
+    pub fn add_processor(&mut self, mut processor: DefaultProcessor, threshold: Option<f64>) {
+        processor.update_settings(seat_settings(self.settings(), threshold));
+        self.processors.push(processor);
+        self.thresholds.push(threshold);
+        self.positions.push((0.0, 0.0));
+    }
+
+    /// Return the processors of each seat, starting with the primary one.
+    #[must_use]
+    pub fn processors(&self) -> &[DefaultProcessor] {
+        &self.processors
+    }
+
+    /// Return the seat ids of the processors, in order.
+    #[must_use]
+    pub fn seat_ids(&self) -> Vec<&str> {
+        self.processors.iter().map(|x| x.seat_id.as_str()).collect()
+    }
+}
+
+impl Processor for MultiSeatProcessor {
+    fn dispatch(&mut self, dx: &mut f64, dy: &mut f64) -> Result<Vec<ActionEvent>, LibinputError> {
+        // Block until any of the descriptors is ready, or the timeout
+        // expires.
+        let mut poll_array: Vec<pollfd> = self
+            .processors
+            .iter()
+            .map(|processor| pollfd {
+                fd: processor.as_raw_fd(),
+                events: POLLIN,
+                revents: 0,
+            })
+            .collect();
+        poll(&mut poll_array, self.poll_timeout)?;
+
+        // Handle the events of the seats that are ready, each one with its
+        // own displacement.
+        let mut action_events = Vec::new();
+        for (index, ((processor, position), entry)) in self
+            .processors
+            .iter_mut()
+            .zip(&mut self.positions)
+            .zip(&poll_array)
+            .enumerate()
+        {
+            if entry.revents & POLLIN == 0 {
+                continue;
+            }
+
+            let events = processor.handle_ready(&mut position.0, &mut position.1)?;
+            if !events.is_empty() {
+                self.latest = index;
+            }
+            action_events.extend(events);
+        }
+        (*dx, *dy) = self.positions[self.latest];
+
+        Ok(action_events)
+    }
+
+    fn process_event(
+        &mut self,
+        event: SwipeEvent,
+        dx: &mut f64,
+        dy: &mut f64,
+    ) -> Result<Option<ActionEvent>, ProcessorError> {
+        self.processors[self.latest].process_event(event, dx, dy)
+    }
+
+    fn _end_event_to_action_event(
+        &mut self,
+        dx: f64,
+        dy: f64,
+        finger_count: i32,
+    ) -> Result<ActionEvent, ProcessorError> {
+        self.processors[self.latest]._end_event_to_action_event(dx, dy, finger_count)
+    }
+
+    fn gesture_modifiers(&self) -> BTreeSet<Modifier> {
+        self.processors[self.latest].gesture_modifiers()
+    }
+
+    fn gesture_device(&self) -> Option<GestureDevice> {
+        self.processors[self.latest].gesture_device()
+    }
+
+    fn gesture_seat(&self) -> Option<String> {
+        self.processors[self.latest].gesture_seat()
+    }
+
+    fn settings(&self) -> ProcessorSettings {
+        self.processors[0].settings()
+    }
+
+    fn update_settings(&mut self, settings: ProcessorSettings) {
+        for (processor, threshold) in self.processors.iter_mut().zip(&self.thresholds) {
+            processor.update_settings(seat_settings(settings.clone(), *threshold));
+        }
+    }
+
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.poll_timeout = timeout;
+    }
+
+    fn take_gesture_timing(&mut self, action_event: ActionEvent) -> Option<GestureTiming> {
+        self.processors
+            .iter_mut()
+            .find_map(|processor| processor.take_gesture_timing(action_event))
+    }
+
+    fn take_gesture_context(&mut self, action_event: ActionEvent) -> Option<GestureContext> {
+        self.processors
+            .iter_mut()
+            .find_map(|processor| processor.take_gesture_context(action_event))
+    }
+
+    fn take_raw_gestures(&mut self) -> Vec<RawGesture> {
+        self.processors
+            .iter_mut()
+            .flat_map(Processor::take_raw_gestures)
+            .collect()
+    }
+}
+
+/// Return the settings of a seat, applying its own threshold if any.
+///
+/// # Arguments
+///
+/// * `settings` - the settings shared by all the seats.
+/// * `threshold` - threshold of the seat, overriding the shared one.
+fn seat_settings(mut settings: ProcessorSettings, threshold: Option<f64>) -> ProcessorSettings {
+    if let Some(threshold) = threshold {
+        settings.threshold = threshold;
+    }
+    settings
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::MultiSeatProcessor;
+    use crate::events::{
+        ActionEvent, DefaultProcessor, Processor, SwipeBegin, SwipeEnd, SwipeEvent, SwipeUpdate,
+    };
+
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    /// Test combining the processors of several seats.
+    fn test_multi_seat_processor() {
+        let mut processor = MultiSeatProcessor::new(DefaultProcessor::default());
+        let mut settings = processor.settings();
+        settings.threshold = 20.0;
+        processor.update_settings(settings.clone());
+        processor.add_processor(DefaultProcessor::default(), None);
+        processor.add_processor(DefaultProcessor::default(), Some(40.0));
+
+        // Assert that the parameters are shared by all the seats, except for
+        // the threshold of the seats that have their own.
+        assert_eq!(processor.seat_ids(), vec!["seat0", "seat0", "seat0"]);
+        let thresholds = |processor: &MultiSeatProcessor| -> Vec<f64> {
+            processor.processors().iter().map(|x| x.threshold).collect()
+        };
+        assert_eq!(thresholds(&processor), vec![20.0, 20.0, 40.0]);
+
+        // Assert that the thresholds of the seats are kept when updating the
+        // settings.
+        settings.threshold = 10.0;
+        processor.update_settings(settings);
+        assert_eq!(thresholds(&processor), vec![10.0, 10.0, 40.0]);
+        assert_eq!(processor.gesture_seat().as_deref(), Some("seat0"));
+
+        // Assert that the events are processed by the latest processor.
+        let (mut dx, mut dy) = (0.0, 0.0);
+        let events = [
+            SwipeEvent::Begin(SwipeBegin {
+                time_usec: 0,
+                finger_count: 3,
+            }),
+            SwipeEvent::Update(SwipeUpdate {
+                time_usec: 1,
+                finger_count: 3,
+                dx: 30.0,
+                dy: 0.0,
+            }),
+            SwipeEvent::End(SwipeEnd {
+                time_usec: 2,
+                finger_count: 3,
+                cancelled: false,
+            }),
+        ];
+        let action_events: Vec<_> = events
+            .into_iter()
+            .filter_map(|event| processor.process_event(event, &mut dx, &mut dy).unwrap())
+            .collect();
+        assert_eq!(action_events, vec![ActionEvent::ThreeFingerSwipeRight]);
+        assert!(processor
+            .take_gesture_timing(ActionEvent::ThreeFingerSwipeRight)
+            .is_some());
+
+        // Assert that the dispatch returns once the timeout expires.
+        processor.set_timeout(Some(Duration::ZERO));
+        assert!(processor.dispatch(&mut dx, &mut dy).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,189 @@
+//! Recognition of pinch gestures from their scale and rotation.
+
+use strum::{Display, EnumIter, EnumString, EnumVariantNames};
+
+use crate::events::ActionEvent;
+
+/// Discrete zoom events recognized from the scale of a pinch gesture.
+#[derive(
+    Copy, Clone, Display, EnumIter, EnumString, EnumVariantNames, Eq, Hash, PartialEq, Debug,
+)]
+#[strum(serialize_all = "kebab_case")]
+pub enum PinchEvent {
+    /// Pinch with the fingers moving closer together.
+    PinchIn,
+    /// Pinch with the fingers moving closer together, beyond the strong
+    /// threshold.
+    PinchInStrong,
+    /// Pinch with the fingers moving apart.
+    PinchOut,
+    /// Pinch with the fingers moving apart, beyond the strong threshold.
+    PinchOutStrong,
+}
+
+impl PinchEvent {
+    /// Return the [`ActionEvent`] of the pinch for a number of fingers.
+    ///
+    /// # Arguments
+    ///
+    /// * `finger_count` - the number of fingers of the pinch.
+    ///
+    /// # Returns
+    ///
+    /// The event, or `None` if the number of fingers is not supported.
+    #[must_use]
+    pub fn action_event(self, finger_count: i32) -> Option<ActionEvent> {
+        Some(match (finger_count, self) {
+            (2, PinchEvent::PinchIn) => ActionEvent::TwoFingerPinchIn,
+            (2, PinchEvent::PinchInStrong) => ActionEvent::TwoFingerPinchInStrong,
+            (2, PinchEvent::PinchOut) => ActionEvent::TwoFingerPinchOut,
+            (2, PinchEvent::PinchOutStrong) => ActionEvent::TwoFingerPinchOutStrong,
+            (3, PinchEvent::PinchIn) => ActionEvent::ThreeFingerPinchIn,
+            (3, PinchEvent::PinchInStrong) => ActionEvent::ThreeFingerPinchInStrong,
+            (3, PinchEvent::PinchOut) => ActionEvent::ThreeFingerPinchOut,
+            (3, PinchEvent::PinchOutStrong) => ActionEvent::ThreeFingerPinchOutStrong,
+            (4, PinchEvent::PinchIn) => ActionEvent::FourFingerPinchIn,
+            (4, PinchEvent::PinchInStrong) => ActionEvent::FourFingerPinchInStrong,
+            (4, PinchEvent::PinchOut) => ActionEvent::FourFingerPinchOut,
+            (4, PinchEvent::PinchOutStrong) => ActionEvent::FourFingerPinchOutStrong,
+            _ => return None,
+        })
+    }
+}
+
+/// Scale thresholds for recognizing pinch gestures.
+///
+/// The scale of a pinch is the ratio between the distance of the fingers at
+/// the end and at the start of the gesture, as reported by `libinput`: scales
+/// below `1.0` correspond to pinching in, and scales above `1.0` to pinching
+/// out. The strong thresholds are an optional second tier, allowing different
+/// actions to be bound to wider pinches.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PinchThresholds {
+    /// Scale below which a pinch is recognized as [`PinchEvent::PinchIn`].
+    pub pinch_in: f64,
+    /// Scale above which a pinch is recognized as [`PinchEvent::PinchOut`].
+    pub pinch_out: f64,
+    /// Scale below which a pinch is recognized as
+    /// [`PinchEvent::PinchInStrong`], if enabled.
+    pub strong_pinch_in: Option<f64>,
+    /// Scale above which a pinch is recognized as
+    /// [`PinchEvent::PinchOutStrong`], if enabled.
+    pub strong_pinch_out: Option<f64>,
+}
+
+impl Default for PinchThresholds {
+    fn default() -> Self {
+        PinchThresholds {
+            pinch_in: 0.8,
+            pinch_out: 1.25,
+            strong_pinch_in: None,
+            strong_pinch_out: None,
+        }
+    }
+}
+
+impl PinchThresholds {
+    /// Return the [`PinchEvent`] corresponding to the final scale of a pinch.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale` - the scale at the end of the pinch.
+    ///
+    /// # Returns
+    ///
+    /// The recognized event, or `None` if the scale is not beyond the
+    /// thresholds.
+    #[must_use]
+    pub fn classify(&self, scale: f64) -> Option<PinchEvent> {
+        if self.strong_pinch_in.is_some_and(|x| scale < x) {
+            Some(PinchEvent::PinchInStrong)
+        } else if scale < self.pinch_in {
+            Some(PinchEvent::PinchIn)
+        } else if self.strong_pinch_out.is_some_and(|x| scale > x) {
+            Some(PinchEvent::PinchOutStrong)
+        } else if scale > self.pinch_out {
+            Some(PinchEvent::PinchOut)
+        } else {
+            None
+        }
+    }
+}
+
+/// Return the rotation [`ActionEvent`] corresponding to the accumulated angle
+/// of a two-finger pinch.
+///
+/// # Arguments
+///
+/// * `angle` - the accumulated angle (in degrees) of the pinch, positive for
+///   clockwise rotations as reported by `libinput`.
+/// * `threshold` - the minimum rotation (in degrees) for recognizing the
+///   pinch as a rotation.
+///
+/// # Returns
+///
+/// The recognized event, or `None` if the rotation is below the threshold.
+#[must_use]
+pub fn rotation_event(angle: f64, threshold: f64) -> Option<ActionEvent> {
+    if angle.abs() < threshold {
+        None
+    } else if angle > 0.0 {
+        Some(ActionEvent::TwoFingerRotateClockwise)
+    } else {
+        Some(ActionEvent::TwoFingerRotateCounterClockwise)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{rotation_event, PinchEvent, PinchThresholds};
+    use crate::events::ActionEvent;
+
+    #[test]
+    /// Test recognizing pinches from their scale.
+    fn test_pinch_classify() {
+        let mut thresholds = PinchThresholds::default();
+        assert_eq!(thresholds.classify(0.5), Some(PinchEvent::PinchIn));
+        assert_eq!(thresholds.classify(0.8), None);
+        assert_eq!(thresholds.classify(1.0), None);
+        assert_eq!(thresholds.classify(1.25), None);
+        assert_eq!(thresholds.classify(2.0), Some(PinchEvent::PinchOut));
+
+        // Recognize the strong pinches, if enabled.
+        thresholds.strong_pinch_in = Some(0.5);
+        thresholds.strong_pinch_out = Some(2.0);
+        assert_eq!(thresholds.classify(0.4), Some(PinchEvent::PinchInStrong));
+        assert_eq!(thresholds.classify(0.6), Some(PinchEvent::PinchIn));
+        assert_eq!(thresholds.classify(1.5), Some(PinchEvent::PinchOut));
+        assert_eq!(thresholds.classify(2.5), Some(PinchEvent::PinchOutStrong));
+    }
+
+    #[test]
+    /// Test the events of the pinches for each number of fingers.
+    fn test_pinch_action_event() {
+        assert_eq!(
+            PinchEvent::PinchIn.action_event(2),
+            Some(ActionEvent::TwoFingerPinchIn)
+        );
+        assert_eq!(
+            PinchEvent::PinchOutStrong.action_event(4),
+            Some(ActionEvent::FourFingerPinchOutStrong)
+        );
+        assert_eq!(PinchEvent::PinchOut.action_event(5), None);
+    }
+
+    #[test]
+    /// Test recognizing rotations from their accumulated angle.
+    fn test_rotation_event() {
+        assert_eq!(
+            rotation_event(45.0, 30.0),
+            Some(ActionEvent::TwoFingerRotateClockwise)
+        );
+        assert_eq!(
+            rotation_event(-30.0, 30.0),
+            Some(ActionEvent::TwoFingerRotateCounterClockwise)
+        );
+        assert_eq!(rotation_event(29.9, 30.0), None);
+        assert_eq!(rotation_event(-10.0, 30.0), None);
+    }
+}
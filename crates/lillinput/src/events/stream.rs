@@ -0,0 +1,255 @@
+//! Stream of the raw swipe gestures, for building custom policies on top of
+//! the `libinput` handling.
+//!
+//! The gestures are reported as parsed from the backend, before being mapped
+//! into [`ActionEvent`]s: the gestures below the threshold, or discarded due
+//! to other settings, are reported as well.
+//!
+//! ```no_run
+//! use lillinput::events::{DefaultProcessor, GestureStream, LibinputError};
+//!
+//! let processor = DefaultProcessor::new(5.0, "seat0", false, false)?;
+//! for raw_gesture in GestureStream::new(processor) {
+//!     let raw_gesture = raw_gesture?;
+//!     println!(
+//!         "{} fingers: ({}, {}) in {:?}",
+//!         raw_gesture.finger_count, raw_gesture.dx, raw_gesture.dy, raw_gesture.duration
+//!     );
+//! }
+//! # Ok::<(), LibinputError>(())
+//! ```
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::events::errors::LibinputError;
+use crate::events::{ActionEvent, DefaultProcessor, Processor};
+
+use filedescriptor::poll;
+
+/// Swipe gesture as parsed from the backend.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RawGesture {
+    /// Number of fingers used for the gesture.
+    pub finger_count: i32,
+    /// Accumulated displacement in the `x` axis.
+    pub dx: f64,
+    /// Accumulated displacement in the `y` axis.
+    pub dy: f64,
+    /// Time between the start and the end of the gesture.
+    pub duration: Duration,
+    /// Regular event matching the finger count and direction of the gesture,
+    /// regardless of its displacement, if any.
+    pub direction: Option<ActionEvent>,
+    /// Whether the gesture was cancelled by the backend.
+    pub cancelled: bool,
+}
+
+/// Iterator over the [`RawGesture`]s of a [`DefaultProcessor`].
+///
+/// Each call to [`Iterator::next`] blocks until a gesture is finished,
+/// dispatching the events received in the meantime (or until the timeout of
+/// the processor expires without events, returning `None`).
+pub struct GestureStream {
+    /// Processor reading the events from the backend.
+    pub processor: DefaultProcessor,
+    /// Gestures finished, pending to be yielded.
+    pending: VecDeque<RawGesture>,
+    /// Accumulated displacement of the current gesture.
+    position: (f64, f64),
+}
+
+impl GestureStream {
+    /// Return a new [`GestureStream`], recording the raw gestures of the
+    /// processor.
+    ///
+    /// # Arguments
+    ///
+    /// * `processor` - the processor reading the events from the backend.
+    #[must_use]
+    pub fn new(mut processor: DefaultProcessor) -> Self {
+        processor.raw_gestures.get_or_insert_with(Vec::new);
+        GestureStream {
+            processor,
+            pending: VecDeque::new(),
+            position: (0.0, 0.0),
+        }
+    }
+
+    /// Dispatch the pending events of the processor, returning the gestures
+    /// finished.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the dispatch of the events failed.
+    pub fn dispatch(&mut self) -> Result<Vec<RawGesture>, LibinputError> {
+        let (dx, dy) = &mut self.position;
+        self.processor.dispatch(dx, dy)?;
+        Ok(self.processor.take_raw_gestures())
+    }
+
+    /// Return the next gesture finished, dispatching the events of the
+    /// processor until one is finished.
+    ///
+    /// # Arguments
+    ///
+    /// * `dispatch` - function waiting for and dispatching the events of the
+    ///   processor, returning `false` if no events were received before the
+    ///   timeout expired.
+    ///
+    /// # Returns
+    ///
+    /// The next gesture, or `None` if no events were received before the
+    /// timeout expired.
+    fn next_with<F>(&mut self, mut dispatch: F) -> Option<Result<RawGesture, LibinputError>>
+    where
+        F: FnMut(&mut DefaultProcessor, &mut f64, &mut f64) -> Result<bool, LibinputError>,
+    {
+        // Keep dispatching, as not all the events finish a gesture (for
+        // example, the updates of a swipe or the keyboard events).
+        while self.pending.is_empty() {
+            let (dx, dy) = &mut self.position;
+            match dispatch(&mut self.processor, dx, dy) {
+                Ok(true) => self.pending.extend(self.processor.take_raw_gestures()),
+                Ok(false) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+/// Wait for the events of a processor, dispatching them if received before
+/// its timeout expires.
+///
+/// # Arguments
+///
+/// * `processor` - the processor reading the events from the backend.
+/// * `dx` - the current position in the `x` axis.
+/// * `dy` - the current position in the `y` axis.
+///
+/// # Errors
+///
+/// Returns `Err` if an error was encountered while dispatching events.
+fn dispatch_ready(
+    processor: &mut DefaultProcessor,
+    dx: &mut f64,
+    dy: &mut f64,
+) -> Result<bool, LibinputError> {
+    if poll(&mut processor.poll_array, processor.poll_timeout)? == 0 {
+        return Ok(false);
+    }
+    processor.handle_ready(dx, dy)?;
+
+    Ok(true)
+}
+
+impl Iterator for GestureStream {
+    type Item = Result<RawGesture, LibinputError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with(dispatch_ready)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::GestureStream;
+    use crate::events::{
+        ActionEvent, DefaultProcessor, Processor, SwipeBegin, SwipeEnd, SwipeEvent, SwipeUpdate,
+    };
+
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    /// Test recording the raw gestures, regardless of the threshold.
+    fn test_raw_gestures() {
+        let mut stream = GestureStream::new(DefaultProcessor::default());
+        let (mut dx, mut dy) = (0.0, 0.0);
+        for (time_usec, update_dx, cancelled) in [(0, 30.0, false), (1000, 1.0, true)] {
+            let events = [
+                SwipeEvent::Begin(SwipeBegin {
+                    time_usec,
+                    finger_count: 3,
+                }),
+                SwipeEvent::Update(SwipeUpdate {
+                    time_usec: time_usec + 100,
+                    finger_count: 3,
+                    dx: update_dx,
+                    dy: 0.0,
+                }),
+                SwipeEvent::End(SwipeEnd {
+                    time_usec: time_usec + 200,
+                    finger_count: 3,
+                    cancelled,
+                }),
+            ];
+            for event in events {
+                let _ = stream.processor.process_event(event, &mut dx, &mut dy);
+            }
+        }
+
+        let raw_gestures = stream.processor.take_raw_gestures();
+        assert_eq!(raw_gestures.len(), 2);
+        assert_eq!(raw_gestures[0].finger_count, 3);
+        assert!((raw_gestures[0].dx - 30.0).abs() < f64::EPSILON);
+        assert_eq!(raw_gestures[0].duration, Duration::from_micros(200));
+        assert_eq!(
+            raw_gestures[0].direction,
+            Some(ActionEvent::ThreeFingerSwipeRight)
+        );
+        assert!(!raw_gestures[0].cancelled);
+        assert!(raw_gestures[1].cancelled);
+        assert!(stream.processor.take_raw_gestures().is_empty());
+
+        // Assert that the stream ends once the timeout expires.
+        stream.processor.set_timeout(Some(Duration::ZERO));
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    #[serial]
+    /// Test that the stream keeps dispatching until a gesture is finished.
+    fn test_stream_waits_for_gesture() {
+        let mut stream = GestureStream::new(DefaultProcessor::default());
+        stream.processor.set_timeout(Some(Duration::from_secs(1)));
+
+        // Deliver the events of a swipe one per dispatch, followed by a
+        // timeout.
+        let mut events = vec![
+            SwipeEvent::Begin(SwipeBegin {
+                time_usec: 0,
+                finger_count: 3,
+            }),
+            SwipeEvent::Update(SwipeUpdate {
+                time_usec: 100,
+                finger_count: 3,
+                dx: 0.0,
+                dy: -30.0,
+            }),
+            SwipeEvent::End(SwipeEnd {
+                time_usec: 200,
+                finger_count: 3,
+                cancelled: false,
+            }),
+        ]
+        .into_iter();
+        let mut dispatch_count = 0;
+        let mut dispatch = |processor: &mut DefaultProcessor, dx: &mut f64, dy: &mut f64| {
+            dispatch_count += 1;
+            Ok(events
+                .next()
+                .map(|event| processor.process_event(event, dx, dy))
+                .is_some())
+        };
+
+        let raw_gesture = stream.next_with(&mut dispatch).unwrap().unwrap();
+        assert_eq!(raw_gesture.direction, Some(ActionEvent::ThreeFingerSwipeUp));
+        assert!(stream.next_with(&mut dispatch).is_none());
+        assert_eq!(dispatch_count, 4);
+    }
+}
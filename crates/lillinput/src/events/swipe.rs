@@ -0,0 +1,990 @@
+//! Swipe gestures and their recognition, independent of the `libinput` types.
+
+use std::time::Duration;
+
+use crate::events::errors::ProcessorError;
+use crate::events::{ActionEvent, FingerCount, ProcessorSettings, ThresholdMode};
+
+/// Length (in millimeters) of a `libinput` gesture unit, as the deltas are
+/// normalized to a resolution of 1000 dpi.
+const UNIT_LENGTH_MM: f64 = 0.0254;
+
+/// Names of the swipe directions, in the order of the octants: starting from
+/// the left and clock-wise.
+pub const SWIPE_DIRECTIONS: [&str; 8] = [
+    "left",
+    "left-up",
+    "up",
+    "right-up",
+    "right",
+    "right-down",
+    "down",
+    "left-down",
+];
+
+/// Three-finger swipe events, in the order of the octants.
+const THREE_FINGER_OCTANTS: [ActionEvent; 8] = [
+    ActionEvent::ThreeFingerSwipeLeft,
+    ActionEvent::ThreeFingerSwipeLeftUp,
+    ActionEvent::ThreeFingerSwipeUp,
+    ActionEvent::ThreeFingerSwipeRightUp,
+    ActionEvent::ThreeFingerSwipeRight,
+    ActionEvent::ThreeFingerSwipeRightDown,
+    ActionEvent::ThreeFingerSwipeDown,
+    ActionEvent::ThreeFingerSwipeLeftDown,
+];
+
+/// Four-finger swipe events, in the order of the octants.
+const FOUR_FINGER_OCTANTS: [ActionEvent; 8] = [
+    ActionEvent::FourFingerSwipeLeft,
+    ActionEvent::FourFingerSwipeLeftUp,
+    ActionEvent::FourFingerSwipeUp,
+    ActionEvent::FourFingerSwipeRightUp,
+    ActionEvent::FourFingerSwipeRight,
+    ActionEvent::FourFingerSwipeRightDown,
+    ActionEvent::FourFingerSwipeDown,
+    ActionEvent::FourFingerSwipeLeftDown,
+];
+
+/// Start of a swipe gesture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwipeBegin {
+    /// Time of the event, in microseconds.
+    pub time_usec: u64,
+    /// Number of fingers used for the gesture.
+    pub finger_count: i32,
+}
+
+/// Update of a swipe gesture, with the displacement since the previous event.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SwipeUpdate {
+    /// Time of the event, in microseconds.
+    pub time_usec: u64,
+    /// Number of fingers used for the gesture.
+    pub finger_count: i32,
+    /// Displacement in the `x` axis.
+    pub dx: f64,
+    /// Displacement in the `y` axis.
+    pub dy: f64,
+}
+
+/// End of a swipe gesture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwipeEnd {
+    /// Time of the event, in microseconds.
+    pub time_usec: u64,
+    /// Number of fingers used for the gesture.
+    pub finger_count: i32,
+    /// Whether the gesture was cancelled.
+    pub cancelled: bool,
+}
+
+/// Swipe gesture event, as translated from the events of a backend.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SwipeEvent {
+    /// Start of a swipe gesture.
+    Begin(SwipeBegin),
+    /// Update of a swipe gesture.
+    Update(SwipeUpdate),
+    /// End of a swipe gesture.
+    End(SwipeEnd),
+}
+
+/// Displacement of a finished swipe gesture.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SwipeSample {
+    /// Displacement in the `x` axis.
+    pub dx: f64,
+    /// Displacement in the `y` axis.
+    pub dy: f64,
+    /// Number of fingers used for the gesture.
+    pub finger_count: i32,
+}
+
+impl SwipeSample {
+    /// Return a new [`SwipeSample`].
+    ///
+    /// # Arguments
+    ///
+    /// * `dx` - displacement in the `x` axis.
+    /// * `dy` - displacement in the `y` axis.
+    /// * `finger_count` - number of fingers used for the gesture.
+    #[must_use]
+    pub fn new(dx: f64, dy: f64, finger_count: i32) -> Self {
+        SwipeSample {
+            dx,
+            dy,
+            finger_count,
+        }
+    }
+
+    /// Return the magnitude of the displacement.
+    #[must_use]
+    pub fn magnitude(&self) -> f64 {
+        self.dx.hypot(self.dy)
+    }
+
+    /// Return the components of the displacement to be compared with the
+    /// thresholds.
+    ///
+    /// With [`ThresholdMode::Relative`], each component is expressed as a
+    /// percentage of the corresponding dimension of the device, falling back
+    /// to the absolute displacement if the size of the device is unknown.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - the parameters used for recognizing gestures.
+    fn threshold_components(&self, settings: &ProcessorSettings) -> (f64, f64) {
+        match (settings.threshold_mode, settings.device_size) {
+            (ThresholdMode::Relative, Some((width, height))) if width > 0.0 && height > 0.0 => (
+                self.dx * UNIT_LENGTH_MM / width * 100.0,
+                self.dy * UNIT_LENGTH_MM / height * 100.0,
+            ),
+            _ => (self.dx, self.dy),
+        }
+    }
+
+    /// Return the magnitude of the displacement to be compared with the
+    /// thresholds.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - the parameters used for recognizing gestures.
+    #[must_use]
+    pub fn threshold_magnitude(&self, settings: &ProcessorSettings) -> f64 {
+        let (dx, dy) = self.threshold_components(settings);
+        dx.hypot(dy)
+    }
+
+    /// Return the global threshold along the direction of the swipe.
+    ///
+    /// If the thresholds of the axes are set, they are interpolated along the
+    /// direction of the swipe (as an ellipse), falling back to the global
+    /// threshold for the axes without a threshold.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - the parameters used for recognizing gestures.
+    #[must_use]
+    pub fn axis_threshold(&self, settings: &ProcessorSettings) -> f64 {
+        if settings.threshold_x.is_none() && settings.threshold_y.is_none() {
+            return settings.threshold;
+        }
+
+        let threshold_x = settings.threshold_x.unwrap_or(settings.threshold);
+        let threshold_y = settings.threshold_y.unwrap_or(settings.threshold);
+        let (dx, dy) = self.threshold_components(settings);
+        let magnitude = dx.hypot(dy);
+        if magnitude == 0.0 {
+            return threshold_x.min(threshold_y);
+        }
+
+        1.0 / (dx / magnitude / threshold_x).hypot(dy / magnitude / threshold_y)
+    }
+
+    /// Return the threshold the swipe is compared with, taking the threshold
+    /// of the event if set.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - the parameters used for recognizing gestures.
+    /// * `action_event` - the event matching the direction of the swipe.
+    #[must_use]
+    pub fn threshold(&self, settings: &ProcessorSettings, action_event: ActionEvent) -> f64 {
+        settings
+            .event_thresholds
+            .get(&action_event)
+            .copied()
+            .unwrap_or_else(|| self.axis_threshold(settings))
+    }
+
+    /// Return the velocity of the displacement (in threshold units per
+    /// second), given the duration of the swipe.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - the parameters used for recognizing gestures.
+    /// * `duration` - the time elapsed since the swipe began.
+    ///
+    /// # Returns
+    ///
+    /// The velocity, or `0` if the duration is unknown.
+    #[must_use]
+    pub fn velocity(&self, settings: &ProcessorSettings, duration: Duration) -> f64 {
+        if duration.is_zero() {
+            return 0.0;
+        }
+
+        self.threshold_magnitude(settings) / duration.as_secs_f64()
+    }
+
+    /// Return the angle of the displacement in degrees (`[0..360)`), with
+    /// `0` being the left direction and increasing clock-wise.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - the parameters used for recognizing gestures.
+    fn angle(&self, settings: &ProcessorSettings) -> f64 {
+        let dx = if settings.invert_x { -self.dx } else { self.dx };
+        let dy = if settings.invert_y { -self.dy } else { self.dy };
+        (-dy.atan2(-dx)).to_degrees().rem_euclid(360.0)
+    }
+
+    /// Return the sectors of the octants, as their start angle and width in
+    /// degrees, starting from the left direction and increasing clock-wise.
+    ///
+    /// The sectors are laid out consecutively, with the left one centered on
+    /// the horizontal axis. With `4` directions, the sectors of the diagonals
+    /// are empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - the parameters used for recognizing gestures.
+    fn sectors(settings: &ProcessorSettings) -> [(f64, f64); 8] {
+        let widths = settings
+            .sector_widths
+            .unwrap_or(if settings.directions == 4 {
+                [90.0, 0.0, 90.0, 0.0, 90.0, 0.0, 90.0, 0.0]
+            } else {
+                [45.0; 8]
+            });
+
+        let mut start = -widths[0] / 2.0;
+        widths.map(|width| {
+            let sector = (start, width);
+            start += width;
+            sector
+        })
+    }
+
+    /// Return the octant whose sector contains an angle in the `X-Y`
+    /// coordinates, with `0` being the left direction and increasing
+    /// clock-wise.
+    ///
+    /// # Arguments
+    ///
+    /// * `angle` - the angle, in degrees.
+    /// * `settings` - the parameters used for recognizing gestures.
+    fn octant(angle: f64, settings: &ProcessorSettings) -> u8 {
+        // Round the angle to the nearest of the equal sectors in use, if
+        // no widths are set, wrapping to the initial direction.
+        if settings.sector_widths.is_none() {
+            let width = if settings.directions == 4 { 90.0 } else { 45.0 };
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let octant = ((angle / width).round() * width / 45.0) as u8;
+            return octant % 8;
+        }
+
+        let sectors = Self::sectors(settings);
+        let offset = (angle - sectors[0].0).rem_euclid(360.0);
+        let octant = sectors
+            .iter()
+            .position(|(start, width)| offset < start - sectors[0].0 + width)
+            .unwrap_or_default();
+
+        u8::try_from(octant).unwrap_or_default()
+    }
+
+    /// Convert the swipe into an [`ActionEvent`].
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - the parameters used for recognizing gestures.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the finger count is not supported, or if the
+    /// displacement is below the threshold of the event.
+    pub fn to_action_event(
+        &self,
+        settings: &ProcessorSettings,
+    ) -> Result<ActionEvent, ProcessorError> {
+        self.to_action_event_from(settings, None)
+    }
+
+    /// Convert the swipe into an [`ActionEvent`], keeping the direction
+    /// initially detected for the gesture.
+    ///
+    /// If a direction margin is set, the direction of the `initial` sample
+    /// is kept unless the angle of the swipe deviates from it beyond the
+    /// margin, avoiding flipping directions due to small wobbles around the
+    /// boundaries.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - the parameters used for recognizing gestures.
+    /// * `initial` - the sample of the gesture when it first reached the
+    ///   threshold, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the finger count is not supported, or if the
+    /// displacement is below the threshold of the event.
+    pub fn to_action_event_from(
+        &self,
+        settings: &ProcessorSettings,
+        initial: Option<&SwipeSample>,
+    ) -> Result<ActionEvent, ProcessorError> {
+        let action_event = self.direction_event(settings, initial)?;
+
+        // Discard displacements below the threshold of the event.
+        let magnitude = self.threshold_magnitude(settings);
+        let threshold = self.threshold(settings, action_event);
+        if magnitude < threshold {
+            return Err(ProcessorError::DisplacementBelowThreshold {
+                dx: self.dx,
+                dy: self.dy,
+                magnitude,
+                threshold,
+                finger_count: self.finger_count,
+            });
+        };
+
+        Ok(action_event)
+    }
+
+    /// Return the [`ActionEvent`] matching the finger count and direction of
+    /// the swipe, regardless of its displacement.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - the parameters used for recognizing gestures.
+    /// * `initial` - the sample of the gesture when it first reached the
+    ///   threshold, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the finger count is not supported, or if the angle of
+    /// the swipe is within the deadzone around the boundary between two
+    /// directions.
+    pub fn direction_event(
+        &self,
+        settings: &ProcessorSettings,
+        initial: Option<&SwipeSample>,
+    ) -> Result<ActionEvent, ProcessorError> {
+        // Determine finger count.
+        let finger_count = FingerCount::try_from(self.finger_count)?;
+
+        // Determine the direction, keeping the initial one if within margin.
+        let angle = self.angle(settings);
+        let sectors = Self::sectors(settings);
+        let mut octant = Self::octant(angle, settings);
+        let mut kept = false;
+        if let (Some(margin), Some(initial)) = (settings.direction_margin, initial) {
+            let initial_octant = Self::octant(initial.angle(settings), settings);
+            let (start, width) = sectors[usize::from(initial_octant)];
+            if (angle - start + margin).rem_euclid(360.0) <= width + 2.0 * margin {
+                octant = initial_octant;
+                kept = true;
+            }
+        }
+
+        // Discard the swipes too close to the boundaries of their sector,
+        // unless their initial direction was kept.
+        if let (Some(deadzone), false) = (settings.direction_deadzone, kept) {
+            let (start, width) = sectors[usize::from(octant)];
+            let offset = (angle - start).rem_euclid(360.0);
+            if offset.min(width - offset) < deadzone / 2.0 {
+                return Err(ProcessorError::AmbiguousDirection {
+                    angle,
+                    finger_count: self.finger_count,
+                });
+            }
+        }
+
+        // Determine the `ActionEvent` for the event.
+        let octants = match finger_count {
+            FingerCount::ThreeFinger => THREE_FINGER_OCTANTS,
+            FingerCount::FourFinger => FOUR_FINGER_OCTANTS,
+        };
+
+        Ok(octants[usize::from(octant) % 8])
+    }
+
+    /// Return the event of a swipe in progress, along with the number of
+    /// progress steps completed, if the event has a progress step.
+    ///
+    /// The steps are counted once the threshold is reached, as multiples of
+    /// the progress step of the event. The events that are repeated use their
+    /// threshold as the progress step, unless a progress step is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - the parameters used for recognizing gestures.
+    /// * `initial` - the sample of the gesture when it first reached the
+    ///   threshold, or `None` if it has not reached it yet.
+    #[must_use]
+    pub fn progress(
+        &self,
+        settings: &ProcessorSettings,
+        initial: Option<&SwipeSample>,
+    ) -> Option<(ActionEvent, u32)> {
+        let action_event = self.direction_event(settings, Some(initial?)).ok()?;
+        let step = match settings.progress_steps.get(&action_event) {
+            Some(step) => *step,
+            None if settings.repeat_events.contains(&action_event) => {
+                self.threshold(settings, action_event)
+            }
+            None => return None,
+        };
+        if step <= 0.0 {
+            return None;
+        }
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let steps = (self.threshold_magnitude(settings) / step).floor() as u32;
+        Some((action_event, steps))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{HashMap, HashSet};
+    use std::time::Duration;
+
+    use super::{SwipeSample, FOUR_FINGER_OCTANTS, SWIPE_DIRECTIONS, THREE_FINGER_OCTANTS};
+    use crate::events::{
+        ActionEvent, PinchThresholds, ProcessorError, ProcessorSettings, ThresholdMode,
+    };
+
+    use proptest::prelude::*;
+
+    /// Return the settings for recognizing gestures.
+    fn settings(invert_x: bool, invert_y: bool) -> ProcessorSettings {
+        ProcessorSettings {
+            threshold: 5.0,
+            invert_x,
+            invert_y,
+            disable_while_typing: None,
+            direction_margin: None,
+            event_thresholds: HashMap::new(),
+            long_thresholds: HashMap::new(),
+            threshold_mode: ThresholdMode::Absolute,
+            device_size: None,
+            rotation_threshold: 30.0,
+            pinch_thresholds: PinchThresholds::default(),
+            progress_steps: HashMap::new(),
+            repeat_events: HashSet::new(),
+            fast_velocities: HashMap::new(),
+            directions: 8,
+            sector_widths: None,
+            direction_deadzone: None,
+            threshold_x: None,
+            threshold_y: None,
+            max_duration: None,
+            hold_times: HashMap::new(),
+        }
+    }
+
+    /// Return a sample at an angle (in degrees), with `0` being the left
+    /// direction and increasing clock-wise.
+    ///
+    /// # Arguments
+    ///
+    /// * `angle` - the angle of the displacement, in degrees.
+    /// * `magnitude` - the magnitude of the displacement.
+    /// * `finger_count` - number of fingers used for the gesture.
+    fn sample_at(angle: f64, magnitude: f64, finger_count: i32) -> SwipeSample {
+        let angle = angle.to_radians();
+        SwipeSample::new(
+            -magnitude * angle.cos(),
+            -magnitude * angle.sin(),
+            finger_count,
+        )
+    }
+
+    /// Return the samples generated by sweeping across the octants.
+    ///
+    /// For each octant, the samples cover the angles up to (but not
+    /// including) the boundaries with the neighbouring octants, and a range
+    /// of magnitudes around the threshold.
+    fn sweep(finger_count: i32) -> impl Iterator<Item = (usize, SwipeSample)> {
+        (0..8).flat_map(move |octant| {
+            (-224..=224).flat_map(move |offset| {
+                let angle = f64::from(octant * 450 + offset) / 10.0;
+                [4.99, 5.0, 5.01, 50.0, 5000.0]
+                    .into_iter()
+                    .map(move |magnitude| {
+                        #[allow(clippy::cast_sign_loss)]
+                        let octant = octant as usize;
+                        (octant, sample_at(angle, magnitude, finger_count))
+                    })
+            })
+        })
+    }
+
+    /// Return a strategy for the magnitudes, favouring the ones around the
+    /// threshold.
+    fn magnitudes() -> impl Strategy<Value = f64> {
+        prop_oneof![0.0..1000.0, 4.99..5.01]
+    }
+
+    /// Return a strategy for the sector widths, as eight positive widths
+    /// adding up to `360` degrees.
+    fn sector_widths() -> impl Strategy<Value = [f64; 8]> {
+        prop::array::uniform8(1.0..100.0).prop_map(|weights: [f64; 8]| {
+            let total: f64 = weights.iter().sum();
+            weights.map(|weight| weight / total * 360.0)
+        })
+    }
+
+    proptest! {
+        #[test]
+        /// Test the recognition of swipes across angles and magnitudes.
+        fn test_prop_directions(
+            angle in 0.0..360.0_f64,
+            magnitude in magnitudes(),
+            finger_count in 3..=4,
+        ) {
+            // Skip the swipes at the boundaries between octants.
+            prop_assume!((angle.rem_euclid(45.0) - 22.5).abs() > 1e-6);
+
+            let sample = sample_at(angle, magnitude, finger_count);
+            let result = sample.to_action_event(&settings(false, false));
+            if sample.magnitude() < 5.0 {
+                prop_assert!(
+                    matches!(
+                        result,
+                        Err(ProcessorError::DisplacementBelowThreshold { .. })
+                    ),
+                    "{:?}",
+                    sample
+                );
+            } else {
+                let octants = if finger_count == 3 {
+                    THREE_FINGER_OCTANTS
+                } else {
+                    FOUR_FINGER_OCTANTS
+                };
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let octant = (angle / 45.0).round() as usize % 8;
+                prop_assert_eq!(result.unwrap(), octants[octant], "{:?}", sample);
+            }
+        }
+
+        #[test]
+        /// Test the recognition of swipes with only `4` directions.
+        fn test_prop_four_directions(angle in 0.0..360.0_f64, magnitude in 5.01..1000.0) {
+            let settings = ProcessorSettings {
+                directions: 4,
+                ..settings(false, false)
+            };
+            let sample = sample_at(angle, magnitude, 3);
+            let angle = sample.angle(&settings);
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let octant = ((angle / 90.0).round() as usize % 4) * 2;
+            prop_assert_eq!(
+                sample.to_action_event(&settings).unwrap(),
+                THREE_FINGER_OCTANTS[octant],
+                "{:?}",
+                sample
+            );
+        }
+
+        #[test]
+        /// Test the recognition of swipes with custom sector widths.
+        fn test_prop_sector_widths(
+            widths in sector_widths(),
+            octant in 0..8_usize,
+            position in 0.01..0.99,
+            magnitude in 5.01..1000.0,
+        ) {
+            let settings = ProcessorSettings {
+                sector_widths: Some(widths),
+                ..settings(false, false)
+            };
+            // Place the swipe within the sector of the octant.
+            let start = -widths[0] / 2.0 + widths[..octant].iter().sum::<f64>();
+            let sample = sample_at(start + widths[octant] * position, magnitude, 3);
+            prop_assert_eq!(
+                sample.to_action_event(&settings).unwrap(),
+                THREE_FINGER_OCTANTS[octant],
+                "{:?}",
+                sample
+            );
+        }
+
+        #[test]
+        /// Test that inverting the axes is equivalent to mirroring the swipes.
+        fn test_prop_inversion(
+            dx in -1000.0..1000.0,
+            dy in -1000.0..1000.0,
+            invert_x in any::<bool>(),
+            invert_y in any::<bool>(),
+        ) {
+            let sample = SwipeSample::new(dx, dy, 3);
+            let mirrored = SwipeSample::new(
+                if invert_x { -dx } else { dx },
+                if invert_y { -dy } else { dy },
+                3,
+            );
+            let result = sample.to_action_event(&settings(invert_x, invert_y));
+            let expected = mirrored.to_action_event(&settings(false, false));
+            prop_assert_eq!(result.is_ok(), expected.is_ok(), "{:?}", sample);
+            if let (Ok(result), Ok(expected)) = (result, expected) {
+                prop_assert_eq!(result, expected, "{:?}", sample);
+
+                // Inverting both axes results in the opposite event.
+                if invert_x && invert_y {
+                    let original = sample.to_action_event(&settings(false, false));
+                    prop_assert_eq!(result, original.unwrap().opposite(), "{:?}", sample);
+                }
+            }
+        }
+
+        #[test]
+        /// Test that unsupported finger counts are rejected for any swipe.
+        fn test_prop_unsupported_finger_count(
+            angle in 0.0..360.0_f64,
+            magnitude in magnitudes(),
+            finger_count in prop_oneof![-10..=2, 5..=10],
+        ) {
+            let sample = sample_at(angle, magnitude, finger_count);
+            prop_assert!(
+                matches!(
+                    sample.to_action_event(&settings(false, false)),
+                    Err(ProcessorError::UnsupportedFingerCount(count)) if count == finger_count
+                ),
+                "{:?}",
+                sample
+            );
+        }
+    }
+
+    #[test]
+    /// Test the recognition of swipes with only `4` directions.
+    fn test_four_directions() {
+        let settings = ProcessorSettings {
+            directions: 4,
+            ..settings(false, false)
+        };
+        for (octant, sample) in sweep(3) {
+            if sample.magnitude() < 5.0 {
+                continue;
+            }
+
+            // The diagonals are split between their neighbouring directions.
+            let angle = sample.angle(&settings);
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let expected = THREE_FINGER_OCTANTS[((angle / 90.0).round() as usize % 4) * 2];
+            let result = sample.to_action_event(&settings).unwrap();
+            assert_eq!(result, expected, "{sample:?}");
+            if octant % 2 == 0 {
+                assert_eq!(result, THREE_FINGER_OCTANTS[octant], "{sample:?}");
+            }
+        }
+    }
+
+    #[test]
+    /// Test that the names of the directions match the events of the octants.
+    fn test_swipe_directions() {
+        for (direction, (three_finger, four_finger)) in SWIPE_DIRECTIONS
+            .iter()
+            .zip(THREE_FINGER_OCTANTS.iter().zip(FOUR_FINGER_OCTANTS))
+        {
+            assert_eq!(
+                three_finger.to_string(),
+                format!("three-finger-swipe-{direction}")
+            );
+            assert_eq!(
+                four_finger.to_string(),
+                format!("four-finger-swipe-{direction}")
+            );
+        }
+    }
+
+    #[test]
+    /// Test keeping the initial direction of a swipe within the margin.
+    fn test_direction_margin() {
+        // Return a three-finger sample at an angle, in degrees.
+        let sample = |angle: f64| {
+            SwipeSample::new(
+                -10.0 * angle.to_radians().cos(),
+                -10.0 * angle.to_radians().sin(),
+                3,
+            )
+        };
+        let margin_settings = ProcessorSettings {
+            direction_margin: Some(10.0),
+            ..settings(false, false)
+        };
+        let initial = sample(80.0);
+
+        // Small deviations keep the initial direction.
+        assert_eq!(
+            sample(115.0)
+                .to_action_event_from(&margin_settings, Some(&initial))
+                .unwrap(),
+            ActionEvent::ThreeFingerSwipeUp
+        );
+        assert_eq!(
+            sample(115.0)
+                .to_action_event_from(&settings(false, false), Some(&initial))
+                .unwrap(),
+            ActionEvent::ThreeFingerSwipeRightUp
+        );
+
+        // Deviations beyond the margin change the direction.
+        assert_eq!(
+            sample(135.0)
+                .to_action_event_from(&margin_settings, Some(&initial))
+                .unwrap(),
+            ActionEvent::ThreeFingerSwipeRightUp
+        );
+        assert_eq!(
+            sample(115.0)
+                .to_action_event_from(&margin_settings, None)
+                .unwrap(),
+            ActionEvent::ThreeFingerSwipeRightUp
+        );
+    }
+
+    #[test]
+    /// Test the recognition of swipes with custom sector widths.
+    fn test_sector_widths() {
+        let settings = ProcessorSettings {
+            sector_widths: Some([60.0, 30.0, 60.0, 30.0, 60.0, 30.0, 60.0, 30.0]),
+            ..settings(false, false)
+        };
+        // Return a three-finger sample at an angle, in degrees.
+        let sample = |angle: f64| {
+            let angle: f64 = angle.to_radians();
+            SwipeSample::new(-10.0 * angle.cos(), -10.0 * angle.sin(), 3)
+        };
+
+        for (angle, expected) in [
+            (-25.0, ActionEvent::ThreeFingerSwipeLeft),
+            (25.0, ActionEvent::ThreeFingerSwipeLeft),
+            (40.0, ActionEvent::ThreeFingerSwipeLeftUp),
+            (65.0, ActionEvent::ThreeFingerSwipeUp),
+            (115.0, ActionEvent::ThreeFingerSwipeUp),
+            (125.0, ActionEvent::ThreeFingerSwipeRightUp),
+            (200.0, ActionEvent::ThreeFingerSwipeRight),
+            (320.0, ActionEvent::ThreeFingerSwipeLeftDown),
+        ] {
+            assert_eq!(
+                sample(angle).to_action_event(&settings).unwrap(),
+                expected,
+                "{angle}"
+            );
+        }
+    }
+
+    #[test]
+    /// Test discarding the swipes within the deadzone between directions.
+    fn test_direction_deadzone() {
+        let settings = ProcessorSettings {
+            direction_deadzone: Some(10.0),
+            ..settings(false, false)
+        };
+        // Return a three-finger sample at an angle, in degrees.
+        let sample = |angle: f64| {
+            let angle: f64 = angle.to_radians();
+            SwipeSample::new(-10.0 * angle.cos(), -10.0 * angle.sin(), 3)
+        };
+
+        for angle in [18.0, 26.0, 70.0, 335.0] {
+            assert!(
+                matches!(
+                    sample(angle).to_action_event(&settings),
+                    Err(ProcessorError::AmbiguousDirection { .. })
+                ),
+                "{angle}"
+            );
+        }
+        assert_eq!(
+            sample(16.0).to_action_event(&settings).unwrap(),
+            ActionEvent::ThreeFingerSwipeLeft
+        );
+        assert_eq!(
+            sample(29.0).to_action_event(&settings).unwrap(),
+            ActionEvent::ThreeFingerSwipeLeftUp
+        );
+
+        // Swipes keeping their initial direction are not discarded.
+        let margin_settings = ProcessorSettings {
+            direction_margin: Some(10.0),
+            ..settings
+        };
+        assert_eq!(
+            sample(26.0)
+                .to_action_event_from(&margin_settings, Some(&sample(0.0)))
+                .unwrap(),
+            ActionEvent::ThreeFingerSwipeLeft
+        );
+    }
+
+    #[test]
+    /// Test the independent thresholds of the axes.
+    fn test_axis_thresholds() {
+        let settings = ProcessorSettings {
+            threshold_x: Some(20.0),
+            ..settings(false, false)
+        };
+
+        // The horizontal swipes use their threshold, and the vertical ones
+        // the global threshold.
+        assert!(matches!(
+            SwipeSample::new(15.0, 0.0, 3).to_action_event(&settings),
+            Err(ProcessorError::DisplacementBelowThreshold { threshold, .. }) if (threshold - 20.0).abs() < 1e-9
+        ));
+        assert_eq!(
+            SwipeSample::new(20.0, 0.0, 3)
+                .to_action_event(&settings)
+                .unwrap(),
+            ActionEvent::ThreeFingerSwipeRight
+        );
+        assert_eq!(
+            SwipeSample::new(0.0, 5.0, 3)
+                .to_action_event(&settings)
+                .unwrap(),
+            ActionEvent::ThreeFingerSwipeDown
+        );
+
+        // The diagonal swipes use a threshold in between.
+        let threshold = SwipeSample::new(10.0, 10.0, 3)
+            .threshold(&settings, ActionEvent::ThreeFingerSwipeRightDown);
+        assert!(threshold > 5.0 && threshold < 20.0);
+
+        // The thresholds of the events take precedence.
+        let settings = ProcessorSettings {
+            event_thresholds: HashMap::from([(ActionEvent::ThreeFingerSwipeRight, 10.0)]),
+            ..settings
+        };
+        assert_eq!(
+            SwipeSample::new(15.0, 0.0, 3)
+                .to_action_event(&settings)
+                .unwrap(),
+            ActionEvent::ThreeFingerSwipeRight
+        );
+    }
+
+    #[test]
+    /// Test overriding the threshold for specific events.
+    fn test_event_thresholds() {
+        let settings = ProcessorSettings {
+            event_thresholds: HashMap::from([
+                (ActionEvent::ThreeFingerSwipeLeft, 50.0),
+                (ActionEvent::ThreeFingerSwipeRight, 1.0),
+            ]),
+            ..settings(false, false)
+        };
+
+        assert!(matches!(
+            SwipeSample::new(-10.0, 0.0, 3).to_action_event(&settings),
+            Err(ProcessorError::DisplacementBelowThreshold { threshold, .. }) if (threshold - 50.0).abs() < f64::EPSILON
+        ));
+        assert_eq!(
+            SwipeSample::new(2.0, 0.0, 3)
+                .to_action_event(&settings)
+                .unwrap(),
+            ActionEvent::ThreeFingerSwipeRight
+        );
+        assert!(SwipeSample::new(0.0, 2.0, 3)
+            .to_action_event(&settings)
+            .is_err());
+    }
+
+    #[test]
+    /// Test the thresholds relative to the size of the device.
+    fn test_relative_threshold() {
+        // 100x50 mm device, with a threshold of 10% of each dimension.
+        let settings = ProcessorSettings {
+            threshold: 10.0,
+            threshold_mode: ThresholdMode::Relative,
+            device_size: Some((100.0, 50.0)),
+            ..settings(false, false)
+        };
+        let units = |mm: f64| mm / 0.0254;
+
+        assert!(SwipeSample::new(-units(9.0), 0.0, 3)
+            .to_action_event(&settings)
+            .is_err());
+        assert_eq!(
+            SwipeSample::new(-units(11.0), 0.0, 3)
+                .to_action_event(&settings)
+                .unwrap(),
+            ActionEvent::ThreeFingerSwipeLeft
+        );
+        assert_eq!(
+            SwipeSample::new(0.0, -units(6.0), 3)
+                .to_action_event(&settings)
+                .unwrap(),
+            ActionEvent::ThreeFingerSwipeUp
+        );
+
+        // Fall back to absolute thresholds if the size is unknown.
+        let settings = ProcessorSettings {
+            device_size: None,
+            ..settings
+        };
+        assert_eq!(
+            SwipeSample::new(-11.0, 0.0, 3)
+                .to_action_event(&settings)
+                .unwrap(),
+            ActionEvent::ThreeFingerSwipeLeft
+        );
+    }
+
+    #[test]
+    /// Test counting the progress steps of a swipe in progress.
+    fn test_progress() {
+        let settings = ProcessorSettings {
+            progress_steps: HashMap::from([(ActionEvent::ThreeFingerSwipeRight, 20.0)]),
+            ..settings(false, false)
+        };
+        let initial = SwipeSample::new(6.0, 0.0, 3);
+
+        assert_eq!(
+            SwipeSample::new(6.0, 0.0, 3).progress(&settings, None),
+            None
+        );
+        assert_eq!(
+            SwipeSample::new(19.0, 0.0, 3).progress(&settings, Some(&initial)),
+            Some((ActionEvent::ThreeFingerSwipeRight, 0))
+        );
+        assert_eq!(
+            SwipeSample::new(45.0, 0.0, 3).progress(&settings, Some(&initial)),
+            Some((ActionEvent::ThreeFingerSwipeRight, 2))
+        );
+
+        // Events without a progress step are only recognized at the end.
+        assert_eq!(
+            SwipeSample::new(-45.0, 0.0, 3).progress(&settings, Some(&initial)),
+            None
+        );
+    }
+
+    #[test]
+    /// Test repeating an event each time the threshold is reached again.
+    fn test_repeat_events() {
+        let settings = ProcessorSettings {
+            event_thresholds: HashMap::from([(ActionEvent::FourFingerSwipeLeft, 10.0)]),
+            repeat_events: HashSet::from([
+                ActionEvent::FourFingerSwipeLeft,
+                ActionEvent::FourFingerSwipeRight,
+            ]),
+            ..settings(false, false)
+        };
+        let initial = SwipeSample::new(0.0, 0.0, 4);
+
+        assert_eq!(
+            SwipeSample::new(-35.0, 0.0, 4).progress(&settings, Some(&initial)),
+            Some((ActionEvent::FourFingerSwipeLeft, 3))
+        );
+        assert_eq!(
+            SwipeSample::new(12.0, 0.0, 4).progress(&settings, Some(&initial)),
+            Some((ActionEvent::FourFingerSwipeRight, 2))
+        );
+    }
+
+    #[test]
+    /// Test the velocity of a swipe.
+    fn test_velocity() {
+        let settings = settings(false, false);
+        let sample = SwipeSample::new(30.0, 40.0, 3);
+
+        assert!((sample.velocity(&settings, Duration::from_millis(250)) - 200.0).abs() < 1e-9);
+        assert!(sample.velocity(&settings, Duration::ZERO).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,57 @@
+//! Recognition of switch toggles, such as closing the lid.
+
+use input::event::switch::{Switch, SwitchState};
+
+use crate::events::ActionEvent;
+
+/// Return the event corresponding to a switch toggle.
+///
+/// # Arguments
+///
+/// * `switch` - the switch toggled, if known.
+/// * `state` - the new state of the switch.
+///
+/// # Returns
+///
+/// The [`ActionEvent`] of the toggle, or `None` if the switch is not known.
+#[must_use]
+pub fn switch_event(switch: Option<Switch>, state: SwitchState) -> Option<ActionEvent> {
+    match (switch?, state) {
+        (Switch::Lid, SwitchState::On) => Some(ActionEvent::LidClosed),
+        (Switch::Lid, SwitchState::Off) => Some(ActionEvent::LidOpened),
+        (Switch::TabletMode, SwitchState::On) => Some(ActionEvent::TabletModeOn),
+        (Switch::TabletMode, SwitchState::Off) => Some(ActionEvent::TabletModeOff),
+        // Switch is non-exhaustive.
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use input::event::switch::{Switch, SwitchState};
+
+    use super::switch_event;
+    use crate::events::ActionEvent;
+
+    #[test]
+    /// Test the conversion of switch toggles into events.
+    fn test_switch_event() {
+        assert_eq!(
+            switch_event(Some(Switch::Lid), SwitchState::On),
+            Some(ActionEvent::LidClosed)
+        );
+        assert_eq!(
+            switch_event(Some(Switch::Lid), SwitchState::Off),
+            Some(ActionEvent::LidOpened)
+        );
+        assert_eq!(
+            switch_event(Some(Switch::TabletMode), SwitchState::On),
+            Some(ActionEvent::TabletModeOn)
+        );
+        assert_eq!(
+            switch_event(Some(Switch::TabletMode), SwitchState::Off),
+            Some(ActionEvent::TabletModeOff)
+        );
+        assert_eq!(switch_event(None, SwitchState::On), None);
+    }
+}
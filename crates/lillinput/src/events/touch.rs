@@ -0,0 +1,200 @@
+//! Recognition of edge swipes on touchscreens.
+
+use std::collections::HashSet;
+
+use crate::events::ActionEvent;
+
+/// Thresholds for recognizing edge swipes.
+///
+/// The positions of the touch points are expressed as percentages of the
+/// width and height of the touchscreen, from its top left corner.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EdgeSwipeThresholds {
+    /// Maximum distance (in percentage of the screen) from an edge for a
+    /// touch to be considered as starting at the edge.
+    pub margin: f64,
+    /// Minimum distance (in percentage of the screen) travelled away from
+    /// the edge for recognizing the swipe.
+    pub distance: f64,
+}
+
+impl Default for EdgeSwipeThresholds {
+    fn default() -> Self {
+        EdgeSwipeThresholds {
+            margin: 3.0,
+            distance: 10.0,
+        }
+    }
+}
+
+impl EdgeSwipeThresholds {
+    /// Return the edge swipe [`ActionEvent`] corresponding to a touch.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - the position at which the touch started.
+    /// * `end` - the position at which the touch ended.
+    ///
+    /// # Returns
+    ///
+    /// The recognized event, or `None` if the touch did not start at an edge
+    /// or did not travel far enough away from it.
+    #[must_use]
+    pub fn classify(&self, start: (f64, f64), end: (f64, f64)) -> Option<ActionEvent> {
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        if start.0 <= self.margin && dx >= self.distance {
+            Some(ActionEvent::EdgeSwipeFromLeft)
+        } else if start.0 >= 100.0 - self.margin && -dx >= self.distance {
+            Some(ActionEvent::EdgeSwipeFromRight)
+        } else if start.1 <= self.margin && dy >= self.distance {
+            Some(ActionEvent::EdgeSwipeFromTop)
+        } else if start.1 >= 100.0 - self.margin && -dy >= self.distance {
+            Some(ActionEvent::EdgeSwipeFromBottom)
+        } else {
+            None
+        }
+    }
+}
+
+/// Touch point tracked for recognizing an edge swipe.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct TrackedTouch {
+    /// Seat slot of the touch point.
+    slot: u32,
+    /// Position at which the touch started.
+    start: (f64, f64),
+    /// Latest position of the touch.
+    position: (f64, f64),
+}
+
+/// Tracker of the touch points of the touchscreens, for recognizing edge
+/// swipes.
+///
+/// Only single-finger swipes are recognized: the swipe is discarded if other
+/// touch points are placed while it is in progress.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EdgeSwipeTracker {
+    /// Touch point of the swipe in progress, if any.
+    touch: Option<TrackedTouch>,
+    /// Seat slots of the touch points currently down.
+    active_slots: HashSet<u32>,
+}
+
+impl EdgeSwipeTracker {
+    /// Register a touch point being placed.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot` - the seat slot of the touch point.
+    /// * `position` - the position of the touch point.
+    pub fn down(&mut self, slot: u32, position: (f64, f64)) {
+        self.touch = self.active_slots.is_empty().then_some(TrackedTouch {
+            slot,
+            start: position,
+            position,
+        });
+        self.active_slots.insert(slot);
+    }
+
+    /// Register a touch point moving.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot` - the seat slot of the touch point.
+    /// * `position` - the new position of the touch point.
+    pub fn motion(&mut self, slot: u32, position: (f64, f64)) {
+        if let Some(touch) = self.touch.as_mut().filter(|touch| touch.slot == slot) {
+            touch.position = position;
+        }
+    }
+
+    /// Register a touch point being lifted, returning the recognized edge
+    /// swipe, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot` - the seat slot of the touch point.
+    /// * `thresholds` - the thresholds for recognizing edge swipes.
+    pub fn up(&mut self, slot: u32, thresholds: &EdgeSwipeThresholds) -> Option<ActionEvent> {
+        self.active_slots.remove(&slot);
+        let touch = self.touch.take()?;
+        if touch.slot != slot || !self.active_slots.is_empty() {
+            return None;
+        }
+
+        thresholds.classify(touch.start, touch.position)
+    }
+
+    /// Return `true` if no touch points are down.
+    #[must_use]
+    pub fn is_idle(&self) -> bool {
+        self.active_slots.is_empty()
+    }
+
+    /// Discard the touch points, as cancelled by `libinput`.
+    pub fn cancel(&mut self) {
+        self.touch = None;
+        self.active_slots.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{EdgeSwipeThresholds, EdgeSwipeTracker};
+    use crate::events::ActionEvent;
+
+    #[test]
+    /// Test recognizing edge swipes from the start and end of a touch.
+    fn test_edge_swipe_classify() {
+        let thresholds = EdgeSwipeThresholds::default();
+        assert_eq!(
+            thresholds.classify((1.0, 50.0), (30.0, 55.0)),
+            Some(ActionEvent::EdgeSwipeFromLeft)
+        );
+        assert_eq!(
+            thresholds.classify((99.0, 50.0), (80.0, 50.0)),
+            Some(ActionEvent::EdgeSwipeFromRight)
+        );
+        assert_eq!(
+            thresholds.classify((50.0, 0.0), (50.0, 20.0)),
+            Some(ActionEvent::EdgeSwipeFromTop)
+        );
+        assert_eq!(
+            thresholds.classify((50.0, 100.0), (50.0, 60.0)),
+            Some(ActionEvent::EdgeSwipeFromBottom)
+        );
+
+        // Touches starting away from the edges, or not travelling far enough.
+        assert_eq!(thresholds.classify((50.0, 50.0), (90.0, 50.0)), None);
+        assert_eq!(thresholds.classify((1.0, 50.0), (5.0, 50.0)), None);
+        assert_eq!(thresholds.classify((1.0, 50.0), (0.0, 80.0)), None);
+    }
+
+    #[test]
+    /// Test tracking the touch points of an edge swipe.
+    fn test_edge_swipe_tracker() {
+        let thresholds = EdgeSwipeThresholds::default();
+        let mut tracker = EdgeSwipeTracker::default();
+
+        tracker.down(0, (1.0, 50.0));
+        tracker.motion(0, (15.0, 50.0));
+        tracker.motion(0, (40.0, 50.0));
+        assert_eq!(
+            tracker.up(0, &thresholds),
+            Some(ActionEvent::EdgeSwipeFromLeft)
+        );
+
+        // Discard the swipe if other fingers are placed.
+        tracker.down(0, (1.0, 50.0));
+        tracker.down(1, (50.0, 50.0));
+        tracker.motion(0, (40.0, 50.0));
+        assert_eq!(tracker.up(0, &thresholds), None);
+        assert_eq!(tracker.up(1, &thresholds), None);
+
+        // Discard the cancelled swipes.
+        tracker.down(2, (50.0, 99.0));
+        tracker.motion(2, (50.0, 50.0));
+        tracker.cancel();
+        assert_eq!(tracker.up(2, &thresholds), None);
+    }
+}
@@ -6,6 +6,9 @@
 //!
 //! This crate provides the library. See also the [`lillinput-cli`] crate for
 //! the commandline interface.
+//!
+//! The core traits and types are re-exported by the [`prelude`] module, which
+//! is the recommended entry point for library users.
 
 #![warn(
     missing_docs,
@@ -16,7 +19,14 @@
 )]
 
 pub mod actions;
+pub mod capabilities;
 pub mod controllers;
 pub mod events;
+pub mod prelude;
+#[cfg(feature = "serde")]
+mod serialize;
 #[cfg(test)]
-pub mod test_utils;
+mod test_utils;
+pub mod throttle;
+
+pub use crate::capabilities::capabilities;
@@ -0,0 +1,23 @@
+//! Curated re-exports of the core components of the library.
+//!
+//! The prelude provides a stable entry point for library users, allowing the
+//! traits and types needed for processing gestures and executing actions to be
+//! imported at once, regardless of the module they are defined in:
+//!
+//! ```
+//! use lillinput::prelude::*;
+//!
+//! /// Return the number of actions bound to an event.
+//! fn count_actions(controller: &DefaultController, action_event: ActionEvent) -> usize {
+//!     controller.actions.get(&action_event).map_or(0, Vec::len)
+//! }
+//! ```
+
+pub use crate::actions::{Action, ActionError, ActionType};
+pub use crate::controllers::{
+    ActionMap, Controller, ControllerError, DefaultController, EventFilter, ExecutionMode,
+};
+pub use crate::events::{
+    ActionEvent, DefaultProcessor, GestureStream, LibinputError, Processor, ProcessorError,
+    ProcessorSettings, RawGesture, ThresholdMode,
+};
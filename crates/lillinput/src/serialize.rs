@@ -0,0 +1,94 @@
+//! Serialization of the errors raised by the library.
+//!
+//! The errors are serialized as a structure with a stable `code` (the
+//! kebab-case name of the variant), the human-readable `message`, and the
+//! `source` message, if the error wraps another error.
+
+use std::error::Error;
+
+use serde::ser::{SerializeStruct, Serializer};
+use serde::Serialize;
+
+use crate::actions::ActionError;
+use crate::controllers::ControllerError;
+use crate::events::{LibinputError, ProcessorError};
+
+/// Serialize an error into its code, message and source message.
+///
+/// # Arguments
+///
+/// * `error` - the error to be serialized.
+/// * `code` - stable code of the error.
+/// * `serializer` - the serializer.
+fn serialize_error<E: Error, S: Serializer>(
+    error: &E,
+    code: &'static str,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut state = serializer.serialize_struct("Error", 3)?;
+    state.serialize_field("code", code)?;
+    state.serialize_field("message", &error.to_string())?;
+    state.serialize_field("source", &error.source().map(ToString::to_string))?;
+    state.end()
+}
+
+impl Serialize for ActionError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_error(self, self.code(), serializer)
+    }
+}
+
+impl Serialize for ControllerError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_error(self, self.code(), serializer)
+    }
+}
+
+impl Serialize for LibinputError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_error(self, self.code(), serializer)
+    }
+}
+
+impl Serialize for ProcessorError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_error(self, self.code(), serializer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::actions::ActionError;
+    use crate::controllers::ControllerError;
+    use crate::events::{ActionEvent, ProcessorError};
+
+    use serde_json::json;
+
+    #[test]
+    /// Test the serialization of errors.
+    fn test_serialize_errors() {
+        assert_eq!(
+            serde_json::to_value(ControllerError::NoActionsRegistered(
+                ActionEvent::ThreeFingerSwipeUp
+            ))
+            .unwrap(),
+            json!({
+                "code": "no-actions-registered",
+                "message": "no actions registered for event three-finger-swipe-up",
+                "source": null,
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(ControllerError::from(ProcessorError::WhileTyping)).unwrap(),
+            json!({
+                "code": "processor-error",
+                "message": "unknown error from the event processor",
+                "source": "event happened while typing",
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(ActionError::UnknownType("foo".into())).unwrap()["code"],
+            "unknown-type"
+        );
+    }
+}
@@ -8,6 +8,7 @@ use std::thread;
 use tempfile::{Builder, NamedTempFile};
 
 static MSG_COMMAND: u32 = 0;
+static MSG_GET_MARKS: u32 = 5;
 static MSG_VERSION: u32 = 7;
 
 /// Container for I3 IPC messages.
@@ -96,6 +97,8 @@ fn create_i3_reply(message_type: u32) -> Option<Vec<u8>> {
             r#"[{ "success": true }]"#.as_bytes(),
             MSG_COMMAND,
         ))
+    } else if message_type == MSG_GET_MARKS {
+        Some(create_i3_message(r#"["foo"]"#.as_bytes(), MSG_GET_MARKS))
     } else {
         None
     }
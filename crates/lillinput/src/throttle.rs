@@ -0,0 +1,168 @@
+//! Throttling of repeated log messages.
+//!
+//! Recurring identical warnings (for example, an action failing on every
+//! gesture while `i3` is not running) are emitted the first time, and then
+//! summarized at most once per interval, instead of flooding the logs.
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use log::warn;
+
+/// Throttle for the recurring warnings emitted by the library.
+pub static WARNINGS: LogThrottle = LogThrottle::new(Duration::from_secs(60));
+
+/// Occurrences of a throttled message.
+#[derive(Debug)]
+struct ThrottledMessage {
+    /// Time the message was last emitted.
+    emitted_at: Instant,
+    /// Number of occurrences suppressed since the message was last emitted.
+    suppressed: usize,
+}
+
+/// Throttle for identical log messages.
+#[derive(Debug)]
+pub struct LogThrottle {
+    /// Minimum time between the emissions of an identical message.
+    interval: Duration,
+    /// Occurrences of the messages, by their text.
+    messages: Mutex<BTreeMap<String, ThrottledMessage>>,
+}
+
+impl LogThrottle {
+    /// Return a new [`LogThrottle`].
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - minimum time between the emissions of an identical
+    ///   message.
+    #[must_use]
+    pub const fn new(interval: Duration) -> Self {
+        LogThrottle {
+            interval,
+            messages: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Register an occurrence of a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - the text of the message.
+    ///
+    /// # Returns
+    ///
+    /// The number of occurrences suppressed since the message was last
+    /// emitted if it should be emitted, or `None` if it should be suppressed.
+    pub fn check(&self, message: &str) -> Option<usize> {
+        let now = Instant::now();
+        let mut messages = self.messages.lock().unwrap_or_else(PoisonError::into_inner);
+
+        match messages.get_mut(message) {
+            Some(entry) if now.duration_since(entry.emitted_at) < self.interval => {
+                entry.suppressed += 1;
+                None
+            }
+            Some(entry) => {
+                entry.emitted_at = now;
+                Some(std::mem::take(&mut entry.suppressed))
+            }
+            None => {
+                messages.insert(
+                    message.to_string(),
+                    ThrottledMessage {
+                        emitted_at: now,
+                        suppressed: 0,
+                    },
+                );
+                Some(0)
+            }
+        }
+    }
+
+    /// Return the messages with occurrences suppressed for longer than the
+    /// interval, marking them as emitted.
+    ///
+    /// The messages without suppressed occurrences are forgotten once the
+    /// interval elapses.
+    ///
+    /// # Returns
+    ///
+    /// The messages, along with their number of suppressed occurrences.
+    pub fn flush(&self) -> Vec<(String, usize)> {
+        let now = Instant::now();
+        let mut messages = self.messages.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut summaries = vec![];
+
+        messages.retain(|message, entry| {
+            if now.duration_since(entry.emitted_at) < self.interval {
+                return true;
+            }
+            if entry.suppressed == 0 {
+                return false;
+            }
+            summaries.push((message.clone(), std::mem::take(&mut entry.suppressed)));
+            entry.emitted_at = now;
+            true
+        });
+
+        summaries
+    }
+}
+
+/// Emit the summaries of the warnings suppressed for longer than the interval.
+pub fn flush_warnings() {
+    for (message, suppressed) in WARNINGS.flush() {
+        warn!("{message} ({suppressed} identical warnings suppressed)");
+    }
+}
+
+/// Emit a warning, throttling it via [`WARNINGS`] if it is repeated.
+///
+/// Accepts the same arguments as [`log::warn!`], excluding the target.
+macro_rules! warn_throttled {
+    ($($arg:tt)+) => {{
+        let message = format!($($arg)+);
+        match $crate::throttle::WARNINGS.check(&message) {
+            Some(0) => log::warn!("{message}"),
+            Some(suppressed) => {
+                log::warn!("{message} ({suppressed} identical warnings suppressed)");
+            }
+            None => (),
+        }
+    }};
+}
+
+pub(crate) use warn_throttled;
+
+#[cfg(test)]
+mod test {
+    use std::thread;
+    use std::time::Duration;
+
+    use super::LogThrottle;
+
+    #[test]
+    /// Test throttling and summarizing repeated messages.
+    fn test_log_throttle() {
+        let throttle = LogThrottle::new(Duration::from_millis(50));
+
+        // Emit the first occurrence, suppressing the repeated ones.
+        assert_eq!(throttle.check("foo"), Some(0));
+        assert_eq!(throttle.check("foo"), None);
+        assert_eq!(throttle.check("foo"), None);
+        assert_eq!(throttle.check("bar"), Some(0));
+        assert!(throttle.flush().is_empty());
+
+        // Summarize the suppressed occurrences once the interval elapses.
+        thread::sleep(Duration::from_millis(60));
+        assert_eq!(throttle.flush(), vec![(String::from("foo"), 2)]);
+        assert_eq!(throttle.check("foo"), None);
+        assert_eq!(throttle.check("bar"), Some(0));
+
+        thread::sleep(Duration::from_millis(60));
+        assert_eq!(throttle.check("foo"), Some(1));
+    }
+}